@@ -44,12 +44,12 @@ async function loadWasm(): Promise<WasmModule | null> {
       // In Node.js: may not be available, so we catch and fallback
 
       // Dynamic import - path will be resolved at build time
-      // The WASM module is built to packages/sim-core/dist/wasm/ by wasm-pack
+      // The WASM module is built to packages/sim-core/dist/wasm/ by build.sh / build.ps1 (cargo + wasm-bindgen)
       // In browser environments, this will load the WASM module
       // In Node.js test environments, this may fail and fallback to BigInt
       try {
         // Try to import WASM module (path relative to dist/ after build)
-        // wasm-pack generates: fixed_math.js and fixed_math_bg.wasm
+        // wasm-bindgen generates: fixed_math.js and fixed_math_bg.wasm
         const wasmImport = await import('../dist/wasm/fixed_math.js');
 
         // The module has a default export that initializes the WASM binary
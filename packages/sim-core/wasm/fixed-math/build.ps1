@@ -1,15 +1,16 @@
 # Build script for fixed-math WASM module (PowerShell)
+# Extra arguments are passed to cargo, e.g. `build.ps1 --features simd`
 
 Write-Host "Building fixed-math WASM module..." -ForegroundColor Cyan
 
-# Check if wasm-pack is installed
+# Check if wasm-bindgen is installed
 try {
-    $wasmPackVersion = wasm-pack --version 2>&1
-    Write-Host "Found wasm-pack: $wasmPackVersion" -ForegroundColor Green
+    $wasmBindgenVersion = wasm-bindgen --version 2>&1
+    Write-Host "Found wasm-bindgen: $wasmBindgenVersion" -ForegroundColor Green
 } catch {
-    Write-Host "Error: wasm-pack is not installed" -ForegroundColor Red
+    Write-Host "Error: wasm-bindgen is not installed" -ForegroundColor Red
     Write-Host "Install Rust first: https://rustup.rs/" -ForegroundColor Yellow
-    Write-Host "Then install wasm-pack: cargo install wasm-pack" -ForegroundColor Yellow
+    Write-Host "Then install the version pinned in Cargo.toml: cargo install wasm-bindgen-cli --version 0.2.129" -ForegroundColor Yellow
     exit 1
 }
 
@@ -23,11 +24,22 @@ try {
     exit 1
 }
 
-# Build with wasm-pack
-# Target: web (for browser compatibility)
+# The manifest declares only an rlib so native and no_std dependents build;
+# the cdylib is requested here, for the wasm32 target only
 # Mode: release (optimized)
 Write-Host "Building WASM module..." -ForegroundColor Cyan
-wasm-pack build --target web --release --out-dir ../../dist/wasm
+cargo rustc --lib --release --target wasm32-unknown-unknown --crate-type cdylib @args
+
+# Generate the JS bindings (fixed_math.js + fixed_math_bg.wasm)
+# Target: web (for browser compatibility)
+if ($LASTEXITCODE -eq 0) {
+    wasm-bindgen --target web --out-dir ../../dist/wasm target/wasm32-unknown-unknown/release/fixed_math.wasm
+}
+
+# Shrink further when binaryen is available
+if (($LASTEXITCODE -eq 0) -and (Get-Command wasm-opt -ErrorAction SilentlyContinue)) {
+    wasm-opt -Oz ../../dist/wasm/fixed_math_bg.wasm -o ../../dist/wasm/fixed_math_bg.wasm
+}
 
 if ($LASTEXITCODE -eq 0) {
     Write-Host "WASM module built successfully!" -ForegroundColor Green
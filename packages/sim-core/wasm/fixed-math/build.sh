@@ -1,21 +1,40 @@
 #!/bin/bash
 # Build script for fixed-math WASM module
+#
+# Extra arguments are passed to cargo, e.g. `bash build.sh --features simd`
 
 set -e
 
 echo "Building fixed-math WASM module..."
 
-# Check if wasm-pack is installed
-if ! command -v wasm-pack &> /dev/null; then
-    echo "Error: wasm-pack is not installed"
-    echo "Install it with: cargo install wasm-pack"
+# The CLI must match the wasm-bindgen version pinned in Cargo.toml
+WASM_BINDGEN_VERSION=0.2.129
+
+# Check if wasm-bindgen is installed
+if ! command -v wasm-bindgen &> /dev/null; then
+    echo "Error: wasm-bindgen is not installed"
+    echo "Install it with: cargo install wasm-bindgen-cli --version $WASM_BINDGEN_VERSION"
+    exit 1
+fi
+if [ "$(wasm-bindgen --version)" != "wasm-bindgen $WASM_BINDGEN_VERSION" ]; then
+    echo "Error: found $(wasm-bindgen --version), need $WASM_BINDGEN_VERSION"
+    echo "Install it with: cargo install wasm-bindgen-cli --version $WASM_BINDGEN_VERSION"
     exit 1
 fi
 
-# Build with wasm-pack
+# The manifest declares only an rlib so native and no_std dependents build;
+# the cdylib is requested here, for the wasm32 target only
 # Target: wasm32-unknown-unknown (no stdlib, maximum compatibility)
 # Mode: release (optimized)
-wasm-pack build --target web --release --out-dir ../../dist/wasm
+cargo rustc --lib --release --target wasm32-unknown-unknown --crate-type cdylib "$@"
+
+# Generate the JS bindings (fixed_math.js + fixed_math_bg.wasm)
+wasm-bindgen --target web --out-dir ../../dist/wasm target/wasm32-unknown-unknown/release/fixed_math.wasm
+
+# Shrink further when binaryen is available
+if command -v wasm-opt &> /dev/null; then
+    wasm-opt -Oz ../../dist/wasm/fixed_math_bg.wasm -o ../../dist/wasm/fixed_math_bg.wasm
+fi
 
 echo "WASM module built successfully!"
 echo "Output: packages/sim-core/dist/wasm/"
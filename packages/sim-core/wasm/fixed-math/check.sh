@@ -0,0 +1,32 @@
+#!/bin/bash
+# Checks for fixed-math across its feature sets
+#
+# The crate must build with no features (no_std, no allocator), with
+# alloc only, with std (native dependents) and with the default wasm
+# bindings. The SIMD128 kernels only exist on wasm32, so the std,simd
+# suite also runs on wasm32-wasip1 under a WASI runner (wasmtime unless
+# WASI_RUNNER is set). Run from CI before merging changes to the crate.
+
+set -e
+
+cd "$(dirname "$0")"
+
+for features in "" "alloc" "std" "std,simd"; do
+    echo "Checking features: ${features:-<none>}"
+    cargo build --no-default-features --features "$features"
+    cargo clippy --no-default-features --features "$features" --all-targets -- -D warnings
+    cargo test --no-default-features --features "$features"
+done
+
+echo "Checking features: <default>"
+cargo build
+cargo clippy --all-targets -- -D warnings
+cargo test
+
+echo "Checking features: std,simd on wasm32-wasip1 with SIMD128"
+export CARGO_TARGET_WASM32_WASIP1_RUNNER="${WASI_RUNNER:-wasmtime}"
+export RUSTFLAGS="-C target-feature=+simd128"
+cargo clippy --target wasm32-wasip1 --no-default-features --features std,simd --all-targets -- -D warnings
+cargo test --target wasm32-wasip1 --no-default-features --features std,simd
+
+echo "All feature checks passed"
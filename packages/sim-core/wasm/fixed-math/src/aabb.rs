@@ -0,0 +1,182 @@
+//! Axis-aligned bounding boxes in Q16.16
+//!
+//! Boxes are closed: a box includes its edges, so touching boxes overlap
+//! and a point on the boundary is contained. JS broadphase culling and
+//! the sim narrowphase use these same tests, so they agree bit-for-bit.
+
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+use crate::vec2::FixedVec2;
+
+/// Axis-aligned box with min <= max on both axes
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Aabb {
+    pub min_x: i32,
+    pub min_y: i32,
+    pub max_x: i32,
+    pub max_y: i32,
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl Aabb {
+    #[cfg_attr(feature = "wasm", wasm_bindgen(constructor))]
+    pub fn new(min_x: i32, min_y: i32, max_x: i32, max_y: i32) -> Aabb {
+        Aabb { min_x, min_y, max_x, max_y }
+    }
+}
+
+/// Whether two boxes overlap (sharing an edge or corner counts)
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn aabb_overlap(a: &Aabb, b: &Aabb) -> bool {
+    a.min_x <= b.max_x && b.min_x <= a.max_x && a.min_y <= b.max_y && b.min_y <= a.max_y
+}
+
+/// Whether (x, y) lies inside the box or on its boundary
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn aabb_contains_point(b: &Aabb, x: i32, y: i32) -> bool {
+    b.min_x <= x && x <= b.max_x && b.min_y <= y && y <= b.max_y
+}
+
+/// Smallest box containing both boxes
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn aabb_union(a: &Aabb, b: &Aabb) -> Aabb {
+    Aabb {
+        min_x: a.min_x.min(b.min_x),
+        min_y: a.min_y.min(b.min_y),
+        max_x: a.max_x.max(b.max_x),
+        max_y: a.max_y.max(b.max_y),
+    }
+}
+
+/// Shrink [lo, hi] by -margin, collapsing to the midpoint once it would
+/// invert
+fn expand_axis(lo: i32, hi: i32, margin: i32) -> (i32, i32) {
+    let lo2 = lo.saturating_sub(margin);
+    let hi2 = hi.saturating_add(margin);
+    if lo2 <= hi2 {
+        return (lo2, hi2);
+    }
+    let mid = ((lo as i64 + hi as i64) >> 1) as i32;
+    (mid, mid)
+}
+
+/// Grow the box by `margin` on every side
+///
+/// Edges saturate at the i32 range instead of wrapping. A negative
+/// margin shrinks the box; once it exceeds half an extent that axis
+/// collapses to its (floored) midpoint rather than inverting.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn aabb_expand(b: &Aabb, margin: i32) -> Aabb {
+    let (min_x, max_x) = expand_axis(b.min_x, b.max_x, margin);
+    let (min_y, max_y) = expand_axis(b.min_y, b.max_y, margin);
+    Aabb { min_x, min_y, max_x, max_y }
+}
+
+/// Result of `ray_aabb`
+///
+/// When `hit` is false both t values are 0.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RayHit {
+    pub hit: bool,
+    /// Ray parameter where the ray enters the box (Q16.16); negative when
+    /// the origin is already inside
+    pub t_enter: i32,
+    /// Ray parameter where the ray leaves the box (Q16.16)
+    pub t_exit: i32,
+}
+
+/// Entry and exit parameters of one slab, Q16.16 in i128
+///
+/// None when the ray is parallel to the slab and outside it.
+fn slab(origin: i32, dir: i32, lo: i32, hi: i32) -> Option<(i128, i128)> {
+    if dir == 0 {
+        return if lo <= origin && origin <= hi { Some((i128::MIN, i128::MAX)) } else { None };
+    }
+    let t1 = ((lo as i128 - origin as i128) << 16) / dir as i128;
+    let t2 = ((hi as i128 - origin as i128) << 16) / dir as i128;
+    Some((t1.min(t2), t1.max(t2)))
+}
+
+/// Ray against box slab test
+///
+/// The ray is origin + dir * t for t >= 0, so t is in units of |dir|
+/// (pass a unit dir to get distances). Per-axis slab parameters are one
+/// 128-bit division each, truncated toward zero; the hit test compares
+/// those same values, so entry and exit are always consistent with
+/// `hit`. Edges count as inside (a ray grazing a face hits). Reported t
+/// values saturate at the i32 range.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn ray_aabb(origin: &FixedVec2, dir: &FixedVec2, b: &Aabb) -> RayHit {
+    let miss = RayHit { hit: false, t_enter: 0, t_exit: 0 };
+    let Some((x_near, x_far)) = slab(origin.x, dir.x, b.min_x, b.max_x) else {
+        return miss;
+    };
+    let Some((y_near, y_far)) = slab(origin.y, dir.y, b.min_y, b.max_y) else {
+        return miss;
+    };
+
+    let t_enter = x_near.max(y_near);
+    let t_exit = x_far.min(y_far);
+    if t_enter > t_exit || t_exit < 0 {
+        return miss;
+    }
+    let clamp = |t: i128| t.clamp(i32::MIN as i128, i32::MAX as i128) as i32;
+    RayHit { hit: true, t_enter: clamp(t_enter), t_exit: clamp(t_exit) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ONE: i32 = 1 << 16;
+
+    fn b(min_x: i32, min_y: i32, max_x: i32, max_y: i32) -> Aabb {
+        Aabb::new(min_x * ONE, min_y * ONE, max_x * ONE, max_y * ONE)
+    }
+
+    #[test]
+    fn overlap_and_containment_include_edges() {
+        let a = b(0, 0, 2, 2);
+        assert!(aabb_overlap(&a, &b(1, 1, 3, 3)));
+        assert!(aabb_overlap(&a, &b(2, 2, 3, 3)));
+        assert!(!aabb_overlap(&a, &b(2, 3, 3, 4)));
+        assert!(aabb_contains_point(&a, 0, 2 * ONE));
+        assert!(!aabb_contains_point(&a, -1, ONE));
+    }
+
+    #[test]
+    fn union_and_expand() {
+        assert_eq!(aabb_union(&b(0, 0, 1, 1), &b(3, -2, 4, 0)), b(0, -2, 4, 1));
+        assert_eq!(aabb_expand(&b(0, 0, 2, 2), ONE), b(-1, -1, 3, 3));
+        // Saturates instead of wrapping
+        let wide = aabb_expand(&Aabb::new(i32::MIN + 1, 0, i32::MAX - 1, 0), 5);
+        assert_eq!((wide.min_x, wide.max_x), (i32::MIN, i32::MAX));
+        // Over-shrinking collapses to the midpoint
+        assert_eq!(aabb_expand(&b(0, 0, 2, 6), -2 * ONE), b(1, 2, 1, 4));
+    }
+
+    #[test]
+    fn ray_enters_and_exits() {
+        let o = FixedVec2::new(-2 * ONE, ONE);
+        let h = ray_aabb(&o, &FixedVec2::new(ONE, 0), &b(0, 0, 4, 2));
+        assert_eq!(h, RayHit { hit: true, t_enter: 2 * ONE, t_exit: 6 * ONE });
+        // Diagonal direction: t is in units of |dir|
+        let h = ray_aabb(&FixedVec2::new(0, 0), &FixedVec2::new(2 * ONE, 2 * ONE), &b(2, 2, 4, 4));
+        assert_eq!((h.t_enter, h.t_exit), (ONE, 2 * ONE));
+    }
+
+    #[test]
+    fn ray_misses_and_origin_inside() {
+        let inside = ray_aabb(&FixedVec2::new(ONE, ONE), &FixedVec2::new(ONE, 0), &b(0, 0, 4, 2));
+        assert_eq!((inside.hit, inside.t_enter, inside.t_exit), (true, -ONE, 3 * ONE));
+        // Box behind the ray
+        assert!(!ray_aabb(&FixedVec2::new(5 * ONE, ONE), &FixedVec2::new(ONE, 0), &b(0, 0, 4, 2)).hit);
+        // Parallel and outside the slab
+        assert!(!ray_aabb(&FixedVec2::new(0, 3 * ONE), &FixedVec2::new(ONE, 0), &b(1, 0, 4, 2)).hit);
+        // Grazing a face hits
+        assert!(ray_aabb(&FixedVec2::new(0, 2 * ONE), &FixedVec2::new(ONE, 0), &b(1, 0, 4, 2)).hit);
+    }
+}
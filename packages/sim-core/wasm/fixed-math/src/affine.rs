@@ -0,0 +1,200 @@
+//! 2D affine transforms for Q16.16
+//!
+//! `Mat2x3` maps (x, y) to (a x + c y + tx, b x + d y + ty), the same
+//! layout as the canvas `setTransform(a, b, c, d, e, f)`. Products are
+//! summed in 128-bit and truncated once per component, so chains of
+//! attachment points resolve identically on every client.
+
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+use crate::trig::sincos_q30;
+use crate::vec2::FixedVec2;
+use crate::{q30_to_q16, saturate_i128, truncate_i128};
+
+/// 1.0 in Q16.16
+const ONE: i32 = 1 << 16;
+
+/// 2x3 affine matrix with Q16.16 entries
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Mat2x3 {
+    pub a: i32,
+    pub b: i32,
+    pub c: i32,
+    pub d: i32,
+    pub tx: i32,
+    pub ty: i32,
+}
+
+/// Sum of Q16.16 products plus a Q16.16 offset, truncated once
+fn dot2(p: i32, q: i32, r: i32, s: i32, offset: i32) -> i32 {
+    let sum = p as i128 * q as i128 + r as i128 * s as i128 + ((offset as i128) << 16);
+    truncate_i128(sum >> 16)
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl Mat2x3 {
+    #[cfg_attr(feature = "wasm", wasm_bindgen(constructor))]
+    pub fn new(a: i32, b: i32, c: i32, d: i32, tx: i32, ty: i32) -> Mat2x3 {
+        Mat2x3 { a, b, c, d, tx, ty }
+    }
+
+    /// The identity transform
+    pub fn identity() -> Mat2x3 {
+        Mat2x3::new(ONE, 0, 0, ONE, 0, 0)
+    }
+
+    /// Translation by (tx, ty)
+    pub fn translation(tx: i32, ty: i32) -> Mat2x3 {
+        Mat2x3::new(ONE, 0, 0, ONE, tx, ty)
+    }
+
+    /// Counter-clockwise rotation by a Q16.16 angle in radians
+    ///
+    /// Entries are the CORDIC sine and cosine rounded to Q16.16, so they
+    /// match `sin` and `cos`.
+    pub fn rotation(angle: i32) -> Mat2x3 {
+        let (c, s) = sincos_q30(angle);
+        let (c, s) = (q30_to_q16(c), q30_to_q16(s));
+        Mat2x3::new(c, s, -s, c, 0, 0)
+    }
+
+    /// Non-uniform scale by (sx, sy)
+    pub fn scaling(sx: i32, sy: i32) -> Mat2x3 {
+        Mat2x3::new(sx, 0, 0, sy, 0, 0)
+    }
+
+    /// self * other: the transform that applies `other` first, then `self`
+    ///
+    /// For a turret on a tower on a platform:
+    /// `platform.compose(tower).compose(turret)` maps turret-local points
+    /// to world space.
+    pub fn compose(&self, other: &Mat2x3) -> Mat2x3 {
+        Mat2x3 {
+            a: dot2(self.a, other.a, self.c, other.b, 0),
+            b: dot2(self.b, other.a, self.d, other.b, 0),
+            c: dot2(self.a, other.c, self.c, other.d, 0),
+            d: dot2(self.b, other.c, self.d, other.d, 0),
+            tx: dot2(self.a, other.tx, self.c, other.ty, self.tx),
+            ty: dot2(self.b, other.tx, self.d, other.ty, self.ty),
+        }
+    }
+
+    /// Inverse transform
+    ///
+    /// The determinant is exact in Q32.32 and each entry is one 128-bit
+    /// division, truncated toward zero and saturated. A singular matrix
+    /// (determinant 0) goes through the `DivByZeroPolicy` like every
+    /// other division (raising `StatusFlag::DivByZero`, trapping under
+    /// `Trap`) and returns the all-zero matrix.
+    pub fn invert(&self) -> Mat2x3 {
+        let (a, b, c, d) = (self.a as i128, self.b as i128, self.c as i128, self.d as i128);
+        let (tx, ty) = (self.tx as i128, self.ty as i128);
+        let det = a * d - b * c;
+        if det == 0 {
+            crate::div_by_zero(false);
+            return Mat2x3::new(0, 0, 0, 0, 0, 0);
+        }
+
+        let q = |n: i128| saturate_i128((n << 32) / det);
+        let t = |n: i128| saturate_i128((n << 16) / det);
+        Mat2x3 {
+            a: q(d),
+            b: q(-b),
+            c: q(-c),
+            d: q(a),
+            tx: t(c * ty - d * tx),
+            ty: t(b * tx - a * ty),
+        }
+    }
+
+    /// Transform a point (rotation/scale plus translation)
+    pub fn transform_point(&self, p: &FixedVec2) -> FixedVec2 {
+        FixedVec2 {
+            x: dot2(self.a, p.x, self.c, p.y, self.tx),
+            y: dot2(self.b, p.x, self.d, p.y, self.ty),
+        }
+    }
+
+    /// Transform a direction (rotation/scale only, no translation)
+    pub fn transform_vector(&self, v: &FixedVec2) -> FixedVec2 {
+        FixedVec2 {
+            x: dot2(self.a, v.x, self.c, v.y, 0),
+            y: dot2(self.b, v.x, self.d, v.y, 0),
+        }
+    }
+}
+
+/// Apply a transform to packed `[x0, y0, x1, y1, ...]` points in place
+///
+/// Bit-identical to calling `transform_point` on each pair, but one
+/// call for the whole array (the JS `Int32Array` is copied into wasm
+/// memory and back). A trailing odd value is left unchanged.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn transform_points(points: &mut [i32], transform: &Mat2x3) {
+    for p in points.chunks_exact_mut(2) {
+        let (x, y) = (p[0], p[1]);
+        p[0] = dot2(transform.a, x, transform.c, y, transform.tx);
+        p[1] = dot2(transform.b, x, transform.d, y, transform.ty);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::consts::HALF_PI;
+    use crate::flags::{clear_flags, get_flags, StatusFlag};
+
+    fn p(x: i32, y: i32) -> FixedVec2 {
+        FixedVec2::new(x * ONE, y * ONE)
+    }
+
+    #[test]
+    fn basic_transforms() {
+        assert_eq!(Mat2x3::identity().transform_point(&p(3, -2)), p(3, -2));
+        assert_eq!(Mat2x3::translation(ONE, 2 * ONE).transform_point(&p(3, -2)), p(4, 0));
+        assert_eq!(Mat2x3::translation(ONE, 2 * ONE).transform_vector(&p(3, -2)), p(3, -2));
+        assert_eq!(Mat2x3::scaling(2 * ONE, -ONE).transform_point(&p(3, -2)), p(6, 2));
+        let r = Mat2x3::rotation(HALF_PI).transform_point(&p(1, 0));
+        assert!(r.x.abs() <= 1 && (r.y - ONE).abs() <= 1);
+    }
+
+    #[test]
+    fn compose_applies_the_right_operand_first() {
+        let t = Mat2x3::translation(10 * ONE, 0);
+        let s = Mat2x3::scaling(2 * ONE, 2 * ONE);
+        assert_eq!(t.compose(&s).transform_point(&p(1, 1)), p(12, 2));
+        assert_eq!(s.compose(&t).transform_point(&p(1, 1)), p(22, 2));
+    }
+
+    #[test]
+    fn invert_round_trips() {
+        let m = Mat2x3::translation(5 * ONE, -3 * ONE)
+            .compose(&Mat2x3::scaling(2 * ONE, 4 * ONE));
+        let inv = m.invert();
+        assert_eq!(inv.transform_point(&m.transform_point(&p(7, 1))), p(7, 1));
+        assert_eq!(m.compose(&inv), Mat2x3::identity());
+    }
+
+    #[test]
+    fn singular_matrix_inverts_to_zero() {
+        clear_flags();
+        let m = Mat2x3::scaling(ONE, 0);
+        assert_eq!(m.invert(), Mat2x3::new(0, 0, 0, 0, 0, 0));
+        assert_eq!(get_flags(), StatusFlag::DivByZero as u32);
+    }
+
+    #[test]
+    fn batch_transform_matches_transform_point() {
+        let m = Mat2x3::rotation(12_345).compose(&Mat2x3::translation(3 * ONE, -ONE));
+        let mut points = [ONE, 2 * ONE, -7 * ONE, ONE / 3, i32::MAX, i32::MIN, 99];
+        let before = points;
+        transform_points(&mut points, &m);
+        for (i, pair) in before[..6].chunks_exact(2).enumerate() {
+            let q = m.transform_point(&FixedVec2::new(pair[0], pair[1]));
+            assert_eq!([points[2 * i], points[2 * i + 1]], [q.x, q.y]);
+        }
+        assert_eq!(points[6], 99);
+    }
+}
@@ -0,0 +1,91 @@
+//! Angle utilities for Q16.16 radians
+//!
+//! Wraparound is done once, here, in Q2.30 with the same 2π constant as
+//! `trig`, so turret rotation and homing code agree to the last bit on
+//! where an angle wraps.
+
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+use crate::trig::{PI_Q30, TAU_Q30};
+use crate::{q30_to_q16, Q30_FRAC_BITS};
+
+/// Normalize a Q2.30 angle into (-π, π] and round it to Q16.16
+fn normalize_q30(a30: i64) -> i32 {
+    let mut r = a30.rem_euclid(TAU_Q30);
+    if r > PI_Q30 {
+        r -= TAU_Q30;
+    }
+    q30_to_q16(r)
+}
+
+/// Wrap a Q16.16 angle into (-π, π]
+///
+/// Reduction happens in Q2.30, so large angles wrap with the precise 2π
+/// rather than the rounded Q16.16 one. In-range inputs are returned
+/// unchanged, so the function is idempotent. Output lies in
+/// [-205887, 205887] (±π rounded to Q16.16).
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn normalize_angle(angle: i32) -> i32 {
+    normalize_q30((angle as i64) << (Q30_FRAC_BITS - 16))
+}
+
+/// Shortest signed rotation from angle a to angle b
+///
+/// Formula: normalize(b - a), with the difference taken in 64-bit.
+/// Result in (-π, π]; positive means b is counter-clockwise from a.
+/// An exact half turn returns +π.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn delta_angle(a: i32, b: i32) -> i32 {
+    normalize_q30((b as i64 - a as i64) << (Q30_FRAC_BITS - 16))
+}
+
+/// Interpolate between two angles along the shortest arc
+///
+/// Formula: normalize(a + delta_angle(a, b) * t)
+/// t = 0 gives normalize(a), t = 1.0 gives normalize(b). Result in (-π, π].
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn lerp_angle(a: i32, b: i32, t: i32) -> i32 {
+    let delta = delta_angle(a, b) as i64;
+    let angle = a as i64 + ((delta * t as i64) >> 16);
+    normalize_q30(angle << (Q30_FRAC_BITS - 16))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::consts::{HALF_PI, PI, TAU};
+
+    #[test]
+    fn normalize_wraps_into_half_open_range() {
+        assert_eq!(normalize_angle(0), 0);
+        assert_eq!(normalize_angle(PI), PI);
+        // PI is π rounded down, so -PI is already inside the range
+        assert_eq!(normalize_angle(-PI), -PI);
+        assert_eq!(normalize_angle(-PI - 1), PI);
+        assert_eq!(normalize_angle(HALF_PI + TAU), HALF_PI);
+        for a in (i32::MIN..i32::MAX).step_by(3_333_331) {
+            let n = normalize_angle(a);
+            assert!((-PI..=PI).contains(&n));
+            assert_eq!(normalize_angle(n), n);
+        }
+    }
+
+    #[test]
+    fn delta_angle_takes_the_short_way() {
+        assert_eq!(delta_angle(0, HALF_PI), HALF_PI);
+        // The exact 2π used for wrapping is 0.83 LSB more than 2 * PI
+        assert_eq!(delta_angle(PI - 100, -PI + 100), 201);
+        assert_eq!(delta_angle(-PI + 100, PI - 100), -201);
+        assert_eq!(delta_angle(0, PI), PI);
+    }
+
+    #[test]
+    fn lerp_angle_crosses_the_seam() {
+        const ONE: i32 = 1 << 16;
+        assert_eq!(lerp_angle(PI - 100, -PI + 100, 0), PI - 100);
+        assert_eq!(lerp_angle(PI - 100, -PI + 100, ONE), -PI + 100);
+        assert_eq!(lerp_angle(PI - 100, -PI + 100, ONE / 2), PI);
+        assert_eq!(lerp_angle(0, HALF_PI, ONE / 2), HALF_PI / 2);
+    }
+}
@@ -0,0 +1,234 @@
+//! Persistent i32 buffers in wasm linear memory
+//!
+//! Passing a typed array to a kernel copies it into wasm memory and back
+//! on every call. An arena buffer is allocated once and lives in linear
+//! memory until freed: JS writes entity state straight into it through
+//! an `Int32Array` view and then runs any number of `*_buffers` kernels
+//! on it with no copies at all.
+//!
+//! Buffers are identified by their address in linear memory, which is
+//! also the byteOffset for the view:
+//!
+//! ```js
+//! const ptr = alloc_i32_buffer(n);
+//! const view = new Int32Array(wasm_memory().buffer, ptr, n);
+//! ```
+//!
+//! Growing wasm memory (any allocation may) detaches existing views, so
+//! recreate them after allocating. Kernels validate every address against
+//! the arena; an unknown address, or a buffer that a kernel writes being
+//! passed twice, raises `StatusFlag::Domain` and leaves every buffer
+//! unchanged.
+
+use std::cell::RefCell;
+use alloc::collections::BTreeMap;
+
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+use crate::batch;
+use crate::flags::{self, StatusFlag};
+use crate::physics;
+
+thread_local! {
+    static BUFFERS: RefCell<BTreeMap<usize, Box<[i32]>>> = const { RefCell::new(BTreeMap::new()) };
+}
+
+/// Allocate a zeroed buffer of `len` i32 values and return its address
+///
+/// The address is a byte offset into `wasm_memory().buffer`. A zero-length
+/// request still gets a distinct buffer (of one element, so the address is
+/// unique).
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn alloc_i32_buffer(len: u32) -> usize {
+    let mut buf = vec![0i32; len.max(1) as usize].into_boxed_slice();
+    let ptr = buf.as_mut_ptr() as usize;
+    BUFFERS.with(|b| b.borrow_mut().insert(ptr, buf));
+    ptr
+}
+
+/// Release a buffer; false if `ptr` is not a live arena buffer
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn free_i32_buffer(ptr: usize) -> bool {
+    BUFFERS.with(|b| b.borrow_mut().remove(&ptr).is_some())
+}
+
+/// Length of a buffer in i32 elements (0 if `ptr` is not a live buffer)
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn i32_buffer_len(ptr: usize) -> u32 {
+    BUFFERS.with(|b| b.borrow().get(&ptr).map_or(0, |buf| buf.len() as u32))
+}
+
+/// The wasm `Memory` object, for building views onto arena buffers
+#[cfg(feature = "wasm")]
+#[wasm_bindgen]
+pub fn wasm_memory() -> JsValue {
+    wasm_bindgen::memory()
+}
+
+/// Run f with mutable access to the `write` buffers and shared access to
+/// the `read` buffers
+///
+/// Written buffers are taken out of the arena for the call, so a buffer
+/// that is written and also passed elsewhere is not found. Any missing
+/// buffer raises Domain and skips f, with nothing modified.
+fn with_buffers(write: &[usize], read: &[usize], f: impl FnOnce(&mut [Box<[i32]>], &[&[i32]])) {
+    BUFFERS.with(|b| {
+        let mut map = b.borrow_mut();
+        let mut ptrs = Vec::with_capacity(write.len());
+        let mut bufs = Vec::with_capacity(write.len());
+        for &ptr in write {
+            let Some(buf) = map.remove(&ptr) else { break };
+            ptrs.push(ptr);
+            bufs.push(buf);
+        }
+
+        let reads: Option<Vec<&[i32]>> = read.iter().map(|ptr| map.get(ptr).map(|buf| &**buf)).collect();
+        match reads {
+            Some(reads) if bufs.len() == write.len() => f(&mut bufs, &reads),
+            _ => flags::raise(StatusFlag::Domain),
+        }
+
+        for (ptr, buf) in ptrs.into_iter().zip(bufs) {
+            map.insert(ptr, buf);
+        }
+    });
+}
+
+/// `add_arrays` on arena buffers
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn add_buffers(dst: usize, src: usize) {
+    with_buffers(&[dst], &[src], |w, r| batch::add_arrays(&mut w[0], r[0]));
+}
+
+/// `mul_arrays` on arena buffers
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn mul_buffers(dst: usize, src: usize) {
+    with_buffers(&[dst], &[src], |w, r| batch::mul_arrays(&mut w[0], r[0]));
+}
+
+/// `scale_array` on an arena buffer
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn scale_buffer(values: usize, factor: i32) {
+    with_buffers(&[values], &[], |w, _| batch::scale_array(&mut w[0], factor));
+}
+
+/// `min_array` on arena buffers
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn min_buffers(dst: usize, src: usize) {
+    with_buffers(&[dst], &[src], |w, r| batch::min_array(&mut w[0], r[0]));
+}
+
+/// `clamp_array` on an arena buffer
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn clamp_buffer(values: usize, lo: i32, hi: i32) {
+    with_buffers(&[values], &[], |w, _| batch::clamp_array(&mut w[0], lo, hi));
+}
+
+/// `physics::integrate` on arena buffers
+///
+/// The four state buffers must be distinct; the two acceleration buffers
+/// may be the same buffer.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[allow(clippy::too_many_arguments)]
+pub fn integrate_buffers(xs: usize, ys: usize, vxs: usize, vys: usize, axs: usize, ays: usize, dt: i32, count: u32) {
+    with_buffers(&[xs, ys, vxs, vys], &[axs, ays], |w, r| {
+        let [x, y, vx, vy] = w else { return };
+        physics::integrate(x, y, vx, vy, r[0], r[1], dt, count);
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::flags::{clear_flags, get_flags};
+
+    const ONE: i32 = 1 << 16;
+
+    /// Fresh buffer holding `values`
+    fn buffer(values: &[i32]) -> usize {
+        let ptr = alloc_i32_buffer(values.len() as u32);
+        BUFFERS.with(|b| b.borrow_mut().get_mut(&ptr).unwrap()[..values.len()].copy_from_slice(values));
+        ptr
+    }
+
+    fn contents(ptr: usize) -> Vec<i32> {
+        BUFFERS.with(|b| b.borrow()[&ptr].to_vec())
+    }
+
+    #[test]
+    fn alloc_and_free() {
+        let a = alloc_i32_buffer(5);
+        let b = alloc_i32_buffer(0);
+        assert_ne!(a, b);
+        assert_eq!(i32_buffer_len(a), 5);
+        assert_eq!(i32_buffer_len(b), 1);
+        assert_eq!(contents(a), [0; 5]);
+        assert!(free_i32_buffer(a));
+        assert!(!free_i32_buffer(a));
+        assert_eq!(i32_buffer_len(a), 0);
+        assert!(free_i32_buffer(b));
+    }
+
+    #[test]
+    fn kernels_match_the_array_versions() {
+        let (a, b) = ([ONE, -2 * ONE, 3 * ONE, i32::MAX, 5], [2 * ONE, ONE / 2, -ONE, 1, 7]);
+        let (dst, src) = (buffer(&a), buffer(&b));
+        clear_flags();
+        add_buffers(dst, src);
+        mul_buffers(dst, src);
+        min_buffers(dst, src);
+        scale_buffer(dst, 3 * ONE);
+        clamp_buffer(dst, -ONE, 4 * ONE);
+
+        let mut want = a;
+        batch::add_arrays(&mut want, &b);
+        batch::mul_arrays(&mut want, &b);
+        batch::min_array(&mut want, &b);
+        batch::scale_array(&mut want, 3 * ONE);
+        batch::clamp_array(&mut want, -ONE, 4 * ONE);
+        assert_eq!(contents(dst), want);
+        assert_eq!(contents(src), b);
+        assert_eq!(get_flags(), 0);
+        free_i32_buffer(dst);
+        free_i32_buffer(src);
+    }
+
+    #[test]
+    fn integrate_matches_physics() {
+        let state = [[0, ONE], [ONE, 0], [ONE, -ONE], [0, 0]];
+        let accel = [0, -10 * ONE];
+        let ptrs: Vec<usize> = state.iter().map(|s| buffer(s)).collect();
+        let acc = buffer(&accel);
+        // One shared buffer for both accelerations
+        integrate_buffers(ptrs[0], ptrs[1], ptrs[2], ptrs[3], acc, acc, ONE / 10, 2);
+
+        let [mut x, mut y, mut vx, mut vy] = state;
+        physics::integrate(&mut x, &mut y, &mut vx, &mut vy, &accel, &accel, ONE / 10, 2);
+        for (ptr, want) in ptrs.iter().zip([x, y, vx, vy]) {
+            assert_eq!(contents(*ptr), want);
+        }
+    }
+
+    #[test]
+    fn bad_addresses_raise_domain_and_change_nothing() {
+        let (dst, src) = (buffer(&[1, 2]), buffer(&[3, 4]));
+        let freed = buffer(&[5, 6]);
+        free_i32_buffer(freed);
+
+        for run in [
+            Box::new(|| add_buffers(dst, freed)) as Box<dyn Fn()>,
+            Box::new(|| add_buffers(freed, src)),
+            Box::new(|| add_buffers(dst, dst)),
+            Box::new(|| scale_buffer(dst + 4, ONE)),
+            Box::new(|| integrate_buffers(dst, src, dst, src, src, src, ONE, 2)),
+        ] {
+            clear_flags();
+            run();
+            assert_eq!(get_flags(), StatusFlag::Domain as u32);
+            assert_eq!((contents(dst), contents(src)), (vec![1, 2], vec![3, 4]));
+        }
+        free_i32_buffer(dst);
+        free_i32_buffer(src);
+    }
+}
@@ -0,0 +1,88 @@
+//! Reductions over packed Q16.16 arrays
+//!
+//! One wasm call per array instead of one per element, with the running
+//! total kept wide and truncated to i32 only once at the end.
+
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+use crate::truncate_i128;
+
+/// Dot product of two Q16.16 arrays
+///
+/// Sum of a[i] * b[i] accumulated in 128-bit (a single i32 product
+/// already needs 63 bits, so an i64 total could overflow after two
+/// terms), shifted back to Q16.16 and truncated once. Extra elements of
+/// the longer array are ignored.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn dot_i32(a: &[i32], b: &[i32]) -> i32 {
+    let sum: i128 = a.iter().zip(b).map(|(&x, &y)| x as i128 * y as i128).sum();
+    truncate_i128(sum >> 16)
+}
+
+/// Exact sum of an i32 array in i64 (BigInt in JS)
+///
+/// Accumulated in 64-bit, which holds the sum of any array wasm memory
+/// can contain. Works for Q16.16 and plain integers alike.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn sum_i32(values: &[i32]) -> i64 {
+    values.iter().map(|&v| v as i64).sum()
+}
+
+/// Mean of a Q16.16 array
+///
+/// Exact 64-bit sum divided once, rounding toward -∞ (the mean of
+/// i32 values always fits). An empty array returns 0.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn mean_fp(values: &[i32]) -> i32 {
+    if values.is_empty() {
+        return 0;
+    }
+    let sum: i64 = values.iter().map(|&v| v as i64).sum();
+    sum.div_euclid(values.len() as i64) as i32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ONE: i32 = 1 << 16;
+
+    #[test]
+    fn dot_of_arrays() {
+        assert_eq!(dot_i32(&[], &[]), 0);
+        assert_eq!(dot_i32(&[ONE, 2 * ONE, 3 * ONE], &[4 * ONE, -5 * ONE, 6 * ONE]), 12 * ONE);
+        // The longer array's tail is ignored
+        assert_eq!(dot_i32(&[ONE, ONE], &[ONE, ONE, ONE]), 2 * ONE);
+    }
+
+    #[test]
+    fn dot_truncates_once() {
+        // Each product is half an LSB; per-term truncation would give 0
+        assert_eq!(dot_i32(&[1, 1, 1, 1], &[ONE / 2; 4]), 2);
+        // 2^63 - (2^63 - 2^32) + 3 * 2^32: the first two terms alone overflow i64
+        let a = [i32::MIN, i32::MIN, i32::MAX, i32::MAX, ONE];
+        let b = [i32::MIN, i32::MIN, i32::MIN, i32::MIN, 3 * ONE];
+        assert_eq!(dot_i32(&a, &b), 4 * ONE);
+    }
+
+    #[test]
+    fn sum_is_exact() {
+        assert_eq!(sum_i32(&[]), 0);
+        assert_eq!(sum_i32(&[ONE, -3 * ONE]), -2 * ONE as i64);
+        // Well past the i32 range in both directions
+        assert_eq!(sum_i32(&[i32::MAX, i32::MAX, i32::MAX]), 3 * i32::MAX as i64);
+        assert_eq!(sum_i32(&[i32::MIN; 3]), 3 * i32::MIN as i64);
+    }
+
+    #[test]
+    fn mean_rounds_toward_negative_infinity() {
+        assert_eq!(mean_fp(&[]), 0);
+        assert_eq!(mean_fp(&[ONE, 2 * ONE]), ONE + ONE / 2);
+        assert_eq!(mean_fp(&[-1, 0]), -1);
+        assert_eq!(mean_fp(&[1, 0]), 0);
+        // The sum leaves i32 but the mean does not
+        assert_eq!(mean_fp(&[i32::MAX, i32::MAX, i32::MAX - 3]), i32::MAX - 1);
+        assert_eq!(mean_fp(&[i32::MIN, i32::MIN]), i32::MIN);
+    }
+}
@@ -0,0 +1,113 @@
+//! Ballistic arcs for Q16.16
+//!
+//! Flat-ground projectile formulas under constant gravity (a positive
+//! magnitude pulling toward -y). Speeds, distances and gravity share
+//! one unit system; angles are Q16.16 radians above the horizontal.
+
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+use crate::trig::{asin, sincos_q30};
+use crate::{saturate_i128, Q30_FRAC_BITS};
+
+/// Launch angle that lands a projectile `distance` away
+///
+/// From R = v² sin(2θ) / g: θ = asin(g R / v²) / 2, with g R / v² formed
+/// in 128-bit and the angle floored after halving. This is the low
+/// (flat) arc; the high (lob) arc for the same range is π/2 minus it.
+/// Out-of-reach distances (g R > v², including speed 0) clamp to the
+/// maximum-range angle π/4 and raise `StatusFlag::Domain`, like `asin`.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn launch_angle_for_range(speed: i32, gravity: i32, distance: i32) -> i32 {
+    let v2 = speed as i128 * speed as i128;
+    let gr = gravity as i128 * distance as i128;
+    let ratio = if v2 == 0 {
+        if gr < 0 { i32::MIN } else { i32::MAX }
+    } else {
+        saturate_i128((gr << 16) / v2)
+    };
+    asin(ratio) >> 1
+}
+
+/// Vertical launch speed v sin θ, Q16.16 in i128
+fn vertical_speed(speed: i32, angle: i32) -> i128 {
+    let (_, s) = sincos_q30(angle);
+    (speed as i128 * s as i128) >> Q30_FRAC_BITS
+}
+
+/// Highest point of the arc above the launch height, (v sin θ)² / 2g
+///
+/// Non-positive gravity never comes down and goes through the
+/// `DivByZeroPolicy` (i32::MAX by default).
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn apex_height(speed: i32, gravity: i32, angle: i32) -> i32 {
+    if gravity <= 0 {
+        return crate::div_by_zero(false);
+    }
+    let vy = vertical_speed(speed, angle);
+    saturate_i128(vy * vy / (2 * gravity as i128))
+}
+
+/// Time until the projectile returns to launch height, 2 v sin θ / g
+///
+/// Negative for downward launches. Non-positive gravity goes through
+/// the `DivByZeroPolicy`, like `apex_height`.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn time_of_flight(speed: i32, gravity: i32, angle: i32) -> i32 {
+    if gravity <= 0 {
+        return crate::div_by_zero(false);
+    }
+    saturate_i128((vertical_speed(speed, angle) << 17) / gravity as i128)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::consts::{HALF_PI, PI};
+    use crate::flags::{clear_flags, get_flags, StatusFlag};
+
+    const ONE: i32 = 1 << 16;
+
+    fn near(a: i32, b: f64, tol: i32) -> bool {
+        (a - (b * ONE as f64).round() as i32).abs() <= tol
+    }
+
+    #[test]
+    fn launch_angle_hits_the_range() {
+        clear_flags();
+        let theta = launch_angle_for_range(10 * ONE, 10 * ONE, 5 * ONE);
+        assert!(near(theta, core::f64::consts::PI / 12.0, 2));
+        assert!((launch_angle_for_range(10 * ONE, 10 * ONE, 10 * ONE) - PI / 4).abs() <= 1);
+        assert_eq!(launch_angle_for_range(10 * ONE, 10 * ONE, 0), 0);
+        assert_eq!(get_flags(), 0);
+    }
+
+    #[test]
+    fn out_of_reach_clamps_to_max_range_angle() {
+        clear_flags();
+        assert!((launch_angle_for_range(10 * ONE, 10 * ONE, 11 * ONE) - PI / 4).abs() <= 1);
+        assert_eq!(get_flags(), StatusFlag::Domain as u32);
+        clear_flags();
+        assert!((launch_angle_for_range(0, 10 * ONE, ONE) - PI / 4).abs() <= 1);
+        assert_eq!(get_flags(), StatusFlag::Domain as u32);
+    }
+
+    #[test]
+    fn apex_and_flight_time() {
+        assert!(near(apex_height(10 * ONE, 10 * ONE, HALF_PI), 5.0, 2));
+        assert!(near(time_of_flight(10 * ONE, 10 * ONE, HALF_PI), 2.0, 2));
+        // PI / 6 is 2/3 LSB short of 30°, a few LSB once scaled by v
+        let theta = PI / 6;
+        assert!(near(apex_height(10 * ONE, 10 * ONE, theta), 1.25, 4));
+        assert!(near(time_of_flight(10 * ONE, 10 * ONE, theta), 1.0, 2));
+        assert!(time_of_flight(10 * ONE, 10 * ONE, -theta) < 0);
+    }
+
+    #[test]
+    fn non_positive_gravity_uses_div_policy() {
+        clear_flags();
+        assert_eq!(apex_height(ONE, 0, HALF_PI), i32::MAX);
+        assert_eq!(time_of_flight(ONE, -ONE, HALF_PI), i32::MAX);
+        assert_eq!(get_flags(), StatusFlag::DivByZero as u32);
+    }
+}
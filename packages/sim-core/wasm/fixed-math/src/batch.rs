@@ -0,0 +1,150 @@
+//! Elementwise kernels over packed Q16.16 arrays
+//!
+//! Each kernel applies one scalar op to a whole array in a single wasm
+//! call, writing into the first array in place. Elements are computed
+//! with the scalar functions themselves, so `mul_arrays` gives exactly
+//! the values of calling `mul` per element (Overflow flag included).
+//! Where two arrays differ in length, only the common prefix is touched.
+//!
+//! With the `simd` feature on a simd128 wasm build, the leading blocks of
+//! four elements go through `core::arch::wasm32` lanes instead. Those
+//! compute the same integer formulas, so results (and the Overflow flag)
+//! are bit-identical to the scalar path on every build.
+
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+use crate::{mul, simd_prefix};
+use crate::scalar::clamp;
+
+/// dst[i] = dst[i] + src[i], wrapping like `FP.add`
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn add_arrays(dst: &mut [i32], src: &[i32]) {
+    let start = simd_prefix!(crate::simd::add_arrays(dst, src));
+    for (d, &s) in dst[start..].iter_mut().zip(&src[start..]) {
+        *d = d.wrapping_add(s);
+    }
+}
+
+/// dst[i] = mul(dst[i], src[i])
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn mul_arrays(dst: &mut [i32], src: &[i32]) {
+    let start = simd_prefix!(crate::simd::mul_arrays(dst, src));
+    for (d, &s) in dst[start..].iter_mut().zip(&src[start..]) {
+        *d = mul(*d, s);
+    }
+}
+
+/// values[i] = mul(values[i], factor)
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn scale_array(values: &mut [i32], factor: i32) {
+    let start = simd_prefix!(crate::simd::scale_array(values, factor));
+    for v in values[start..].iter_mut() {
+        *v = mul(*v, factor);
+    }
+}
+
+/// dst[i] = min(dst[i], src[i])
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn min_array(dst: &mut [i32], src: &[i32]) {
+    let start = simd_prefix!(crate::simd::min_array(dst, src));
+    for (d, &s) in dst[start..].iter_mut().zip(&src[start..]) {
+        *d = (*d).min(s);
+    }
+}
+
+/// values[i] = clamp(values[i], lo, hi)
+///
+/// Same comparison order as `clamp` (lower bound first).
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn clamp_array(values: &mut [i32], lo: i32, hi: i32) {
+    let start = simd_prefix!(crate::simd::clamp_array(values, lo, hi));
+    for v in values[start..].iter_mut() {
+        *v = clamp(*v, lo, hi);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::flags::{clear_flags, get_flags, StatusFlag};
+
+    /// Pseudo-random values covering the whole i32 range, ends included
+    fn values(n: usize, seed: u32) -> Vec<i32> {
+        let mut seed = seed;
+        let mut out = vec![i32::MIN, i32::MAX, 0, -1];
+        while out.len() < n {
+            seed = seed.wrapping_mul(1664525).wrapping_add(1013904223);
+            out.push(seed as i32 >> (seed % 17));
+        }
+        out.truncate(n);
+        out
+    }
+
+    #[test]
+    fn kernels_match_the_scalar_ops() {
+        // Lengths around the four-lane blocks of the simd path
+        for n in 0..=13 {
+            let (a, b) = (values(n, 1 + n as u32), values(n, 100 + n as u32));
+
+            let mut d = a.clone();
+            add_arrays(&mut d, &b);
+            let want: Vec<i32> = a.iter().zip(&b).map(|(&x, &y)| x.wrapping_add(y)).collect();
+            assert_eq!(d, want, "add {n}");
+
+            let mut d = a.clone();
+            mul_arrays(&mut d, &b);
+            let want: Vec<i32> = a.iter().zip(&b).map(|(&x, &y)| mul(x, y)).collect();
+            assert_eq!(d, want, "mul {n}");
+
+            let mut d = a.clone();
+            scale_array(&mut d, -3 << 15);
+            let want: Vec<i32> = a.iter().map(|&x| mul(x, -3 << 15)).collect();
+            assert_eq!(d, want, "scale {n}");
+
+            let mut d = a.clone();
+            min_array(&mut d, &b);
+            let want: Vec<i32> = a.iter().zip(&b).map(|(&x, &y)| x.min(y)).collect();
+            assert_eq!(d, want, "min {n}");
+
+            let mut d = a.clone();
+            clamp_array(&mut d, -1 << 20, 1 << 24);
+            let want: Vec<i32> = a.iter().map(|&x| clamp(x, -1 << 20, 1 << 24)).collect();
+            assert_eq!(d, want, "clamp {n}");
+        }
+    }
+
+    #[test]
+    fn clamp_checks_the_lower_bound_first() {
+        // With lo > hi, values below lo become lo and the rest hi
+        let mut v = [-5, 0, 5, 10];
+        clamp_array(&mut v, 3, 1);
+        assert_eq!(v, [3, 3, 1, 1]);
+    }
+
+    #[test]
+    fn only_the_common_prefix_is_touched() {
+        let mut d = [1, 2, 3, 4, 5, 6];
+        add_arrays(&mut d, &[10, 10, 10, 10, 10]);
+        assert_eq!(d, [11, 12, 13, 14, 15, 6]);
+        let mut d = [1, 2];
+        min_array(&mut d, &[0, 0, 0, 0, 0]);
+        assert_eq!(d, [0, 0]);
+    }
+
+    #[test]
+    fn overflow_flag_matches_scalar() {
+        // A single overflowing element in the last lane of a block
+        let mut d = [0, 0, 0, i32::MAX, 0];
+        clear_flags();
+        mul_arrays(&mut d, &[1, 1, 1, 4 << 16, 1]);
+        assert_eq!(d[3], mul(i32::MAX, 4 << 16));
+        assert_eq!(get_flags(), StatusFlag::Overflow as u32);
+
+        clear_flags();
+        let mut d = values(9, 7);
+        scale_array(&mut d, 1 << 16);
+        add_arrays(&mut d, &[i32::MAX; 9]);
+        assert_eq!(get_flags(), 0);
+    }
+}
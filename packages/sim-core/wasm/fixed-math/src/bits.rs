@@ -0,0 +1,212 @@
+//! Bit-level serialization
+//!
+//! `BitWriter` and `BitReader` pack values into a byte buffer with a
+//! fixed layout: bit n of the stream is bit n % 8 of byte n / 8, and
+//! every value is written least significant bit first. Save games and
+//! network snapshots built with them are byte-identical everywhere.
+
+use alloc::vec::Vec;
+
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+use crate::flags::{self, StatusFlag};
+
+/// Widest single field
+const MAX_BITS: u32 = 32;
+
+/// Clamp a requested field width to 0..=32, raising Domain if it was wider
+fn field_width(bits: u32) -> u32 {
+    if bits > MAX_BITS {
+        flags::raise(StatusFlag::Domain);
+        return MAX_BITS;
+    }
+    bits
+}
+
+/// Append-only bit stream
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct BitWriter {
+    bytes: Vec<u8>,
+    /// Bits written so far
+    len: u64,
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl BitWriter {
+    #[cfg_attr(feature = "wasm", wasm_bindgen(constructor))]
+    pub fn new() -> BitWriter {
+        BitWriter::default()
+    }
+
+    /// Append the low `bits` bits of value (0..=32)
+    ///
+    /// Higher bits of value are ignored. Widths above 32 write 32 bits
+    /// and raise `StatusFlag::Domain`.
+    pub fn write_bits(&mut self, value: u32, bits: u32) {
+        let bits = field_width(bits);
+        for i in 0..bits {
+            if self.len.is_multiple_of(8) {
+                self.bytes.push(0);
+            }
+            let bit = ((value >> i) & 1) as u8;
+            *self.bytes.last_mut().unwrap() |= bit << (self.len % 8);
+            self.len += 1;
+        }
+    }
+
+    pub fn write_bool(&mut self, value: bool) {
+        self.write_bits(value as u32, 1);
+    }
+
+    /// Append a two's complement field of `bits` bits (1..=32)
+    ///
+    /// Fixed-point values go through here unchanged. Values outside the
+    /// field's range are saturated to it and raise
+    /// `StatusFlag::Overflow`.
+    pub fn write_signed(&mut self, value: i32, bits: u32) {
+        let bits = field_width(bits).max(1);
+        let (lo, hi) = (-(1i64 << (bits - 1)), (1i64 << (bits - 1)) - 1);
+        let v = value as i64;
+        if v < lo || v > hi {
+            flags::raise(StatusFlag::Overflow);
+        }
+        self.write_bits(v.clamp(lo, hi) as u32, bits);
+    }
+
+    /// Bits written so far
+    pub fn bit_len(&self) -> u64 {
+        self.len
+    }
+
+    /// The buffer, with the last byte zero-padded
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.bytes.clone()
+    }
+}
+
+/// Sequential reader over a buffer produced by `BitWriter`
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BitReader {
+    bytes: Vec<u8>,
+    /// Next bit to read
+    pos: u64,
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl BitReader {
+    #[cfg_attr(feature = "wasm", wasm_bindgen(constructor))]
+    pub fn new(bytes: &[u8]) -> BitReader {
+        BitReader { bytes: bytes.to_vec(), pos: 0 }
+    }
+
+    /// Read a `bits`-bit field (0..=32) as an unsigned value
+    ///
+    /// Bits past the end of the buffer read as 0 and raise
+    /// `StatusFlag::Domain`; widths above 32 read 32 bits and raise it
+    /// too.
+    pub fn read_bits(&mut self, bits: u32) -> u32 {
+        let bits = field_width(bits);
+        let mut value = 0u32;
+        for i in 0..bits {
+            match self.bytes.get((self.pos / 8) as usize) {
+                Some(&byte) => value |= (((byte >> (self.pos % 8)) & 1) as u32) << i,
+                None => flags::raise(StatusFlag::Domain),
+            }
+            self.pos += 1;
+        }
+        value
+    }
+
+    pub fn read_bool(&mut self) -> bool {
+        self.read_bits(1) == 1
+    }
+
+    /// Read a two's complement field of `bits` bits (1..=32), sign-extended
+    pub fn read_signed(&mut self, bits: u32) -> i32 {
+        let bits = field_width(bits).max(1);
+        let raw = self.read_bits(bits);
+        let shift = MAX_BITS - bits;
+        ((raw << shift) as i32) >> shift
+    }
+
+    /// Bits left before the end of the buffer
+    pub fn remaining_bits(&self) -> u64 {
+        (self.bytes.len() as u64 * 8).saturating_sub(self.pos)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::flags::{clear_flags, get_flags};
+
+    #[test]
+    fn layout_is_lsb_first() {
+        let mut w = BitWriter::new();
+        w.write_bits(0b101, 3);
+        w.write_bool(true);
+        w.write_bits(0xFF, 2);
+        w.write_bits(0xABC, 12);
+        assert_eq!(w.bit_len(), 18);
+        assert_eq!(w.to_bytes(), [0b0011_1101, 0xAF, 0b10]);
+    }
+
+    #[test]
+    fn round_trip_mixed_fields() {
+        clear_flags();
+        let mut w = BitWriter::new();
+        w.write_bits(u32::MAX, 32);
+        w.write_signed(-5, 4);
+        w.write_signed(-(1 << 16) * 3, 20);
+        w.write_bool(false);
+        w.write_signed(i32::MIN, 32);
+        w.write_bits(7, 0);
+        let mut r = BitReader::new(&w.to_bytes());
+        assert_eq!(r.read_bits(32), u32::MAX);
+        assert_eq!(r.read_signed(4), -5);
+        assert_eq!(r.read_signed(20), -(1 << 16) * 3);
+        assert!(!r.read_bool());
+        assert_eq!(r.read_signed(32), i32::MIN);
+        assert_eq!(r.read_bits(0), 0);
+        assert_eq!(r.remaining_bits(), 8 * 12 - 89);
+        assert_eq!(get_flags(), 0);
+    }
+
+    #[test]
+    fn signed_fields_saturate() {
+        clear_flags();
+        let mut w = BitWriter::new();
+        w.write_signed(8, 4);
+        w.write_signed(-9, 4);
+        assert_eq!(get_flags(), StatusFlag::Overflow as u32);
+        let mut r = BitReader::new(&w.to_bytes());
+        assert_eq!((r.read_signed(4), r.read_signed(4)), (7, -8));
+    }
+
+    #[test]
+    fn reading_past_the_end_yields_zero_bits() {
+        clear_flags();
+        let mut r = BitReader::new(&[0xFF]);
+        assert_eq!(r.read_bits(6), 0b11_1111);
+        assert_eq!(get_flags(), 0);
+        assert_eq!(r.read_bits(4), 0b11);
+        assert_eq!(get_flags(), StatusFlag::Domain as u32);
+        assert_eq!(r.remaining_bits(), 0);
+    }
+
+    #[test]
+    fn oversized_widths_clamp_to_32() {
+        clear_flags();
+        let mut w = BitWriter::new();
+        w.write_bits(1, 40);
+        assert_eq!(w.bit_len(), 32);
+        assert_eq!(get_flags(), StatusFlag::Domain as u32);
+        clear_flags();
+        let mut r = BitReader::new(&w.to_bytes());
+        assert_eq!(r.read_bits(33), 1);
+        assert_eq!(get_flags(), StatusFlag::Domain as u32);
+    }
+}
@@ -0,0 +1,192 @@
+//! Stat modifier stacking
+//!
+//! Buffs and debuffs are Q16.16 modifiers: flat amounts added to the base
+//! stat, or fractions (0.25 = +25%) that either sum into one additive
+//! multiplier or each multiply in separately. Every function applies its
+//! modifiers in a fixed order (flat, then additive, then multiplicative,
+//! each in slice order) on a Q32.32 accumulator in 128-bit, and floors to
+//! Q16.16 exactly once at the end, so the result does not depend on how
+//! the caller grouped the steps. Pass each slice in a stable order (e.g.
+//! sorted by buff id): multiplicative steps round at 2^-32, so reordering
+//! them can change the last bit in rare cases.
+//!
+//! `soft_cap` then applies diminishing returns to the stacked stat.
+
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+use crate::flags::{self, StatusFlag};
+use crate::saturate_i128;
+
+/// 1.0 in Q16.16
+const ONE: i128 = 1 << 16;
+
+/// Accumulator magnitude limit, keeping acc * factor inside i128
+const ACC_LIMIT: i128 = 1 << 94;
+
+/// Q16.16 sum of a slice, exact in 128-bit
+fn sum(values: &[i32]) -> i128 {
+    values.iter().map(|&v| v as i128).sum()
+}
+
+/// Multiply the Q32.32 accumulator by a Q16.16 factor, flooring at 2^-32
+///
+/// Raises `StatusFlag::Overflow` and clamps if the accumulator leaves
+/// ±2^94 (far outside anything that narrows back into Q16.16).
+fn scale_acc(acc: i128, factor: i128) -> i128 {
+    let r = (acc * factor) >> 16;
+    if r.abs() > ACC_LIMIT {
+        flags::raise(StatusFlag::Overflow);
+        return r.clamp(-ACC_LIMIT, ACC_LIMIT);
+    }
+    r
+}
+
+/// Narrow the Q32.32 accumulator to Q16.16 (floor, saturating)
+fn finish(acc: i128) -> i32 {
+    saturate_i128(acc >> 16)
+}
+
+/// Run the flat → additive → multiplicative pipeline from base
+fn pipeline(base: i128, flat: &[i32], additive: &[i32], multiplicative: &[i32]) -> i32 {
+    let mut acc = (base + sum(flat)) << 16;
+    acc = scale_acc(acc, ONE + sum(additive));
+    for &m in multiplicative {
+        acc = scale_acc(acc, ONE + m as i128);
+    }
+    finish(acc)
+}
+
+/// Combined additive multiplier: 1.0 + Σ mods
+///
+/// Exact sum, saturated once. An empty slice gives 1.0.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn stack_additive(mods: &[i32]) -> i32 {
+    saturate_i128(ONE + sum(mods))
+}
+
+/// Combined multiplicative multiplier: Π (1.0 + m) in slice order
+///
+/// An empty slice gives 1.0. Factors below zero (m < -1.0) are applied
+/// as given.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn stack_multiplicative(mods: &[i32]) -> i32 {
+    pipeline(ONE, &[], &[], mods)
+}
+
+/// Final stat after all modifiers
+///
+/// Formula: (base + Σ flat) * (1.0 + Σ additive) * Π (1.0 + m)
+/// Evaluated in exactly that order with one floor to Q16.16 at the end,
+/// so it can differ by an LSB from `mul`-ing the stat by `stack_additive`
+/// and `stack_multiplicative`, which rounds at each step.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn apply_modifiers(base: i32, flat: &[i32], additive: &[i32], multiplicative: &[i32]) -> i32 {
+    pipeline(base as i128, flat, additive, multiplicative)
+}
+
+/// Diminishing returns toward `cap`
+///
+/// Formula: cap * v / (curvature * cap + |v|), keeping the sign of v
+/// A rational curve with slope 1 / curvature at 0 that approaches ±cap
+/// but never reaches it: curvature 1.0 tracks v closely while it is small
+/// and gives cap / 2 at v = cap; smaller values bend toward the cap
+/// sooner, larger ones later. Exact in 128-bit, truncated toward zero.
+/// cap and curvature must be positive; otherwise returns 0 and raises
+/// `StatusFlag::Domain`.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn soft_cap(value: i32, cap: i32, curvature: i32) -> i32 {
+    if cap <= 0 || curvature <= 0 {
+        flags::raise(StatusFlag::Domain);
+        return 0;
+    }
+    let (v, cap) = (value as i128, cap as i128);
+    let den = cap * curvature as i128 + v.abs() * ONE;
+    (cap * v * ONE / den) as i32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::flags::{clear_flags, get_flags};
+
+    const ONE: i32 = 1 << 16;
+
+    #[test]
+    fn stacking_multipliers() {
+        assert_eq!(stack_additive(&[]), ONE);
+        assert_eq!(stack_additive(&[ONE / 4, ONE / 4, -ONE / 10]), ONE + ONE / 2 - ONE / 10);
+        assert_eq!(stack_multiplicative(&[]), ONE);
+        // 1.5 * 1.5 * 0.5
+        assert_eq!(stack_multiplicative(&[ONE / 2, ONE / 2, -ONE / 2]), 9 * ONE / 8);
+        // A factor below zero is applied as given
+        assert_eq!(stack_multiplicative(&[-3 * ONE]), -2 * ONE);
+        clear_flags();
+        assert_eq!(stack_additive(&[i32::MAX, i32::MAX]), i32::MAX);
+        assert_eq!(get_flags(), StatusFlag::Overflow as u32);
+    }
+
+    #[test]
+    fn modifiers_apply_in_a_fixed_order() {
+        // (100 + 20) * (1 + 0.25 + 0.25) * 1.1 * 0.8
+        let r = apply_modifiers(100 * ONE, &[20 * ONE], &[ONE / 4, ONE / 4], &[ONE / 10, -ONE / 5]);
+        let mut acc = 120i128 << 32;
+        for factor in [3 * ONE / 2, ONE + ONE / 10, ONE - ONE / 5] {
+            acc = (acc * factor as i128) >> 16;
+        }
+        assert_eq!(r as i128, acc >> 16);
+        assert_eq!(apply_modifiers(7 * ONE, &[], &[], &[]), 7 * ONE);
+        // Flat and additive entries can be given in any order
+        let a = apply_modifiers(3 * ONE, &[ONE, 5, -ONE / 3], &[ONE / 7, ONE / 3], &[ONE / 9]);
+        let b = apply_modifiers(3 * ONE, &[-ONE / 3, ONE, 5], &[ONE / 3, ONE / 7], &[ONE / 9]);
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn modifiers_truncate_once() {
+        // 1 LSB scaled by 1.5 twice is 2.25 LSB: one floor keeps 2,
+        // flooring each step would give 1
+        assert_eq!(apply_modifiers(1, &[], &[], &[ONE / 2, ONE / 2]), 2);
+        assert_eq!(crate::mul(crate::mul(1, 3 * ONE / 2), 3 * ONE / 2), 1);
+        // Negative results floor toward -∞
+        assert_eq!(apply_modifiers(-1, &[], &[-ONE / 2], &[]), -1);
+    }
+
+    #[test]
+    fn huge_stacks_saturate() {
+        clear_flags();
+        let many = [i32::MAX; 8];
+        assert_eq!(apply_modifiers(i32::MAX, &[], &[], &many), i32::MAX);
+        assert_eq!(apply_modifiers(i32::MIN, &[], &[], &many), i32::MIN);
+        assert_eq!(get_flags(), StatusFlag::Overflow as u32);
+    }
+
+    #[test]
+    fn soft_cap_approaches_but_never_reaches_the_cap() {
+        let cap = 100 * ONE;
+        assert_eq!(soft_cap(0, cap, ONE), 0);
+        // Half the cap at v = cap with curvature 1.0
+        assert_eq!(soft_cap(cap, cap, ONE), cap / 2);
+        assert_eq!(soft_cap(-cap, cap, ONE), -cap / 2);
+        // Small values track v with slope 1 / curvature
+        assert_eq!(soft_cap(ONE, cap, ONE), 100 * ONE / 101);
+        assert_eq!(soft_cap(ONE, cap, 2 * ONE), 100 * ONE / 201);
+        assert!(soft_cap(i32::MAX, cap, ONE) < cap);
+        assert!(soft_cap(i32::MIN, cap, ONE) > -cap);
+
+        let mut last = 0;
+        for v in (0..1000).map(|i| i * 7 * ONE) {
+            let r = soft_cap(v, cap, ONE / 2);
+            assert!(r >= last && r < cap && r <= 2 * v.max(1));
+            last = r;
+        }
+    }
+
+    #[test]
+    fn soft_cap_rejects_non_positive_parameters() {
+        clear_flags();
+        assert_eq!(soft_cap(ONE, 0, ONE), 0);
+        assert_eq!(soft_cap(ONE, ONE, -ONE), 0);
+        assert_eq!(get_flags(), StatusFlag::Domain as u32);
+    }
+}
@@ -0,0 +1,94 @@
+//! Checked arithmetic for Q16.16
+//!
+//! Same formulas as the plain ops, but the 64-bit intermediate is
+//! range-checked before truncation. On overflow the op returns
+//! `CHECKED_SENTINEL` and raises `StatusFlag::Overflow` in the status
+//! register (see `flags`), so formulas that silently wrap as the economy
+//! grows can be located.
+
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+use crate::flags::{self, StatusFlag};
+
+/// Value returned by a checked op whose result does not fit in i32
+///
+/// i32::MIN is also a legal result, so always confirm with `get_flags()`.
+pub const CHECKED_SENTINEL: i32 = i32::MIN;
+
+/// Narrow a 64-bit intermediate, or raise the flag and return the sentinel
+fn narrow(v: i64) -> i32 {
+    match i32::try_from(v) {
+        Ok(r) => r,
+        Err(_) => {
+            flags::raise(StatusFlag::Overflow);
+            CHECKED_SENTINEL
+        }
+    }
+}
+
+/// Checked addition of two fixed-point numbers
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn checked_add(a: i32, b: i32) -> i32 {
+    narrow(a as i64 + b as i64)
+}
+
+/// Checked subtraction of two fixed-point numbers (a - b)
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn checked_sub(a: i32, b: i32) -> i32 {
+    narrow(a as i64 - b as i64)
+}
+
+/// Checked multiply of two fixed-point numbers (Q16.16)
+///
+/// Formula: (a * b) >> 16, same rounding as `mul`
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn checked_mul(a: i32, b: i32) -> i32 {
+    narrow((a as i64 * b as i64) >> 16)
+}
+
+/// Checked divide of two fixed-point numbers (Q16.16)
+///
+/// Formula: (a << 16) / b, same rounding as `div`
+/// Division by zero has no representable result: returns the sentinel
+/// and raises `StatusFlag::DivByZero`.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn checked_div(a: i32, b: i32) -> i32 {
+    if b == 0 {
+        flags::raise(StatusFlag::DivByZero);
+        return CHECKED_SENTINEL;
+    }
+    narrow(((a as i64) << 16) / b as i64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::flags::{clear_flags, get_flags};
+
+    const ONE: i32 = 1 << 16;
+
+    #[test]
+    fn in_range_results_match_plain_ops() {
+        clear_flags();
+        assert_eq!(checked_add(ONE, 2 * ONE), 3 * ONE);
+        assert_eq!(checked_sub(ONE, 2 * ONE), -ONE);
+        assert_eq!(checked_mul(-3 * ONE, ONE / 2), crate::mul(-3 * ONE, ONE / 2));
+        assert_eq!(checked_div(ONE, 3 * ONE), crate::div(ONE, 3 * ONE));
+        assert_eq!(checked_add(i32::MIN, 0), CHECKED_SENTINEL);
+        assert_eq!(get_flags(), 0);
+    }
+
+    #[test]
+    fn overflow_returns_the_sentinel() {
+        clear_flags();
+        assert_eq!(checked_add(i32::MAX, 1), CHECKED_SENTINEL);
+        assert_eq!(checked_sub(i32::MIN, 1), CHECKED_SENTINEL);
+        assert_eq!(checked_mul(i32::MAX, 2 * ONE), CHECKED_SENTINEL);
+        assert_eq!(get_flags(), StatusFlag::Overflow as u32);
+
+        clear_flags();
+        assert_eq!(checked_div(ONE, 0), CHECKED_SENTINEL);
+        assert_eq!(get_flags(), StatusFlag::DivByZero as u32);
+    }
+}
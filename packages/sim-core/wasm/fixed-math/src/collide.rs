@@ -0,0 +1,537 @@
+//! Narrowphase collision tests for Q16.16 shapes
+//!
+//! Overlap tests compare exact 128-bit squared distances; penetration
+//! depth and normal are derived afterwards with one truncation each.
+//! Shapes that just touch count as overlapping (with depth 0), matching
+//! the closed boxes of `aabb`.
+
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+use crate::aabb::Aabb;
+#[cfg(feature = "alloc")]
+use crate::saturate_i128;
+use crate::saturate_i64;
+use crate::vec2::FixedVec2;
+
+/// 1.0 in Q16.16
+const ONE: i32 = 1 << 16;
+
+/// Result of a narrowphase test
+///
+/// The normal is a unit vector (Q16.16) pointing from the first shape
+/// toward the second; moving the second shape by `normal * depth`
+/// (or the first by the opposite) separates them. When `hit` is false
+/// the other fields are 0.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Contact {
+    pub hit: bool,
+    /// Penetration depth (Q16.16)
+    pub depth: i32,
+    pub normal_x: i32,
+    pub normal_y: i32,
+}
+
+impl Contact {
+    fn miss() -> Contact {
+        Contact { hit: false, depth: 0, normal_x: 0, normal_y: 0 }
+    }
+
+    /// Contact along (dx, dy) at distance `dist` (both Q16.16 raw)
+    ///
+    /// A zero direction uses the deterministic fallback normal (1, 0),
+    /// like `FP.normalize2D`.
+    fn along(dx: i64, dy: i64, dist: i64, depth: i64) -> Contact {
+        let (normal_x, normal_y) = if dist == 0 {
+            (ONE, 0)
+        } else {
+            (((dx << 16) / dist) as i32, ((dy << 16) / dist) as i32)
+        };
+        Contact { hit: true, depth: saturate_i64(depth), normal_x, normal_y }
+    }
+}
+
+/// Circle against circle
+///
+/// Overlap when |b - a|² <= (ra + rb)², compared exactly; depth is
+/// ra + rb - |b - a| and the normal points from a to b (coincident centers
+/// give (1, 0)).
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn circle_circle(a: &FixedVec2, ra: i32, b: &FixedVec2, rb: i32) -> Contact {
+    let dx = b.x as i64 - a.x as i64;
+    let dy = b.y as i64 - a.y as i64;
+    let reach = ra as i64 + rb as i64;
+    let d2 = dx as i128 * dx as i128 + dy as i128 * dy as i128;
+    if reach < 0 || d2 > reach as i128 * reach as i128 {
+        return Contact::miss();
+    }
+    let dist = (d2 as u128).isqrt() as i64;
+    Contact::along(dx, dy, dist, reach - dist)
+}
+
+/// Circle against box
+///
+/// The closest box point to the center decides overlap (exact squared
+/// comparison) and, for a center outside the box, gives the normal from
+/// the circle toward the box and depth r - distance. A center inside the
+/// box is pushed out through the nearest face (ties in -x, +x, -y, +y
+/// order): the normal is that face's inward axis, depth is r plus the
+/// distance to the face.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn circle_aabb(c: &FixedVec2, r: i32, b: &Aabb) -> Contact {
+    let px = c.x.clamp(b.min_x, b.max_x.max(b.min_x));
+    let py = c.y.clamp(b.min_y, b.max_y.max(b.min_y));
+    let dx = px as i64 - c.x as i64;
+    let dy = py as i64 - c.y as i64;
+    let r = r as i64;
+    let d2 = dx as i128 * dx as i128 + dy as i128 * dy as i128;
+    if r < 0 || d2 > r as i128 * r as i128 {
+        return Contact::miss();
+    }
+
+    if d2 != 0 {
+        let dist = (d2 as u128).isqrt() as i64;
+        return Contact::along(dx, dy, dist, r - dist);
+    }
+
+    // Center inside (or on) the box: leave through the nearest face
+    let faces = [
+        (c.x as i64 - b.min_x as i64, ONE, 0),
+        (b.max_x as i64 - c.x as i64, -ONE, 0),
+        (c.y as i64 - b.min_y as i64, 0, ONE),
+        (b.max_y as i64 - c.y as i64, 0, -ONE),
+    ];
+    let mut best = faces[0];
+    for f in &faces[1..] {
+        if f.0 < best.0 {
+            best = *f;
+        }
+    }
+    Contact { hit: true, depth: saturate_i64(r + best.0), normal_x: best.1, normal_y: best.2 }
+}
+
+/// Result of a swept test
+///
+/// `t` is the time of impact as a fraction of the tick (Q16.16, 0 to
+/// 1.0). The normal is a unit vector from the first shape toward the
+/// second at that moment. When `hit` is false the other fields are 0.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Impact {
+    pub hit: bool,
+    pub t: i32,
+    pub normal_x: i32,
+    pub normal_y: i32,
+}
+
+impl Impact {
+    fn miss() -> Impact {
+        Impact { hit: false, t: 0, normal_x: 0, normal_y: 0 }
+    }
+}
+
+/// Unit vector of a Q32.32 direction, (1, 0) for zero
+pub(crate) fn unit_q32(gx: i128, gy: i128) -> (i32, i32) {
+    let len = ((gx * gx + gy * gy) as u128).isqrt() as i128;
+    if len == 0 {
+        return (ONE, 0);
+    }
+    (((gx << 16) / len) as i32, ((gy << 16) / len) as i32)
+}
+
+/// Earliest tick fraction t at which |d + w t| <= reach
+///
+/// d (gap) and w (its change over the tick) are Q16.16 and t runs on the
+/// Q16.16 grid in [0, 1.0]. The gap is convex in t, so the first contact
+/// lies before its minimum and is found by bisection with the squared
+/// gap evaluated exactly in 128-bit. The result is the first grid t at
+/// which the shapes touch or overlap; contacts shorter than one grid
+/// step can be missed. Returns the t and the gap direction there.
+fn sweep_gap(dx: i64, dy: i64, wx: i64, wy: i64, reach: i64) -> Option<(i32, i32, i32)> {
+    let one = ONE as i128;
+    let (dx, dy, wx, wy) = (dx as i128, dy as i128, wx as i128, wy as i128);
+    let reach2 = (reach as i128 * one) * (reach as i128 * one);
+    let gap = |t: i128| (dx * one + wx * t, dy * one + wy * t);
+    let overlaps = |t: i128| {
+        let (gx, gy) = gap(t);
+        gx * gx + gy * gy <= reach2
+    };
+    let hit_at = |t: i128| {
+        let (gx, gy) = gap(t);
+        let (nx, ny) = unit_q32(gx, gy);
+        Some((t as i32, nx, ny))
+    };
+
+    if reach < 0 {
+        return None;
+    }
+    if overlaps(0) {
+        return hit_at(0);
+    }
+    let ww = wx * wx + wy * wy;
+    let dw = dx * wx + dy * wy;
+    if ww == 0 || dw >= 0 {
+        // Not moving, or moving apart
+        return None;
+    }
+
+    // Closest approach, then its grid neighbor (the gap is convex)
+    let t_min = ((-dw << 16) / ww).min(one);
+    let mut hi = if overlaps(t_min) {
+        t_min
+    } else if t_min < one && overlaps(t_min + 1) {
+        t_min + 1
+    } else {
+        return None;
+    };
+    let mut lo = 0;
+    while hi - lo > 1 {
+        let mid = lo + (hi - lo) / 2;
+        if overlaps(mid) {
+            hi = mid;
+        } else {
+            lo = mid;
+        }
+    }
+    hit_at(hi)
+}
+
+/// Time of impact of two moving circles over one tick
+///
+/// `va` and `vb` are the displacements over the tick (not per-second
+/// velocities). Circles already touching at the start hit at t = 0;
+/// see `sweep_gap` for how t is chosen on the Q16.16 grid. The normal
+/// points from a to b at impact.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn sweep_circle_circle(a: &FixedVec2, va: &FixedVec2, ra: i32, b: &FixedVec2, vb: &FixedVec2, rb: i32) -> Impact {
+    let hit = sweep_gap(
+        b.x as i64 - a.x as i64,
+        b.y as i64 - a.y as i64,
+        vb.x as i64 - va.x as i64,
+        vb.y as i64 - va.y as i64,
+        ra as i64 + rb as i64,
+    );
+    match hit {
+        Some((t, normal_x, normal_y)) => Impact { hit: true, t, normal_x, normal_y },
+        None => Impact::miss(),
+    }
+}
+
+/// ceil(n / d) for d > 0
+fn ceil_div(n: i128, d: i128) -> i128 {
+    -((-n).div_euclid(d))
+}
+
+/// First t at which a circle moving along one axis reaches a face plane
+///
+/// `gap` is the distance still to close (Q16.16) and `speed` how much
+/// of it the tick covers; None if it is not approaching or is already
+/// past the plane.
+fn face_toi(gap: i64, speed: i64) -> Option<i128> {
+    if speed <= 0 || gap < 0 {
+        return None;
+    }
+    let t = ceil_div((gap as i128) << 16, speed as i128);
+    (t <= ONE as i128).then_some(t)
+}
+
+/// Time of impact of a moving circle against a static box over one tick
+///
+/// `v` is the circle's displacement over the tick. The box inflated by
+/// r (a rounded rectangle) is tested as four face bands and four corner
+/// circles; the earliest contact wins (ties keep the first found in
+/// -x, +x, -y, +y face, then corner order). Face times are rounded up
+/// to the Q16.16 grid and corners use the same bisection as
+/// `sweep_circle_circle`, so t is always the first grid time at which
+/// the shapes touch. A circle already touching the box hits at t = 0
+/// with the `circle_aabb` normal. The normal points from the circle
+/// toward the box.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn sweep_circle_aabb(c: &FixedVec2, v: &FixedVec2, r: i32, b: &Aabb) -> Impact {
+    let start = circle_aabb(c, r, b);
+    if start.hit {
+        return Impact { hit: true, t: 0, normal_x: start.normal_x, normal_y: start.normal_y };
+    }
+
+    let (cx, cy, vx, vy, r) = (c.x as i64, c.y as i64, v.x as i64, v.y as i64, r as i64);
+    let (min_x, min_y, max_x, max_y) = (b.min_x as i64, b.min_y as i64, b.max_x as i64, b.max_y as i64);
+    let at = |p: i64, vp: i64, t: i128| p as i128 + ((vp as i128 * t) >> 16);
+
+    let mut best: Option<(i128, i32, i32)> = None;
+    let mut consider = |t: i128, nx: i32, ny: i32| {
+        if best.is_none_or(|(bt, _, _)| t < bt) {
+            best = Some((t, nx, ny));
+        }
+    };
+
+    // Faces: the leading edge reaches the plane inside the face's span
+    let y_in = |t: i128| (min_y as i128..=max_y as i128).contains(&at(cy, vy, t));
+    let x_in = |t: i128| (min_x as i128..=max_x as i128).contains(&at(cx, vx, t));
+    if let Some(t) = face_toi(min_x - r - cx, vx).filter(|&t| y_in(t)) {
+        consider(t, ONE, 0);
+    }
+    if let Some(t) = face_toi(cx - r - max_x, -vx).filter(|&t| y_in(t)) {
+        consider(t, -ONE, 0);
+    }
+    if let Some(t) = face_toi(min_y - r - cy, vy).filter(|&t| x_in(t)) {
+        consider(t, 0, ONE);
+    }
+    if let Some(t) = face_toi(cy - r - max_y, -vy).filter(|&t| x_in(t)) {
+        consider(t, 0, -ONE);
+    }
+
+    // Corners: the circle center against each corner point
+    for (px, py) in [(min_x, min_y), (max_x, min_y), (min_x, max_y), (max_x, max_y)] {
+        if let Some((t, nx, ny)) = sweep_gap(px - cx, py - cy, -vx, -vy, r) {
+            consider(t as i128, nx, ny);
+        }
+    }
+
+    match best {
+        Some((t, normal_x, normal_y)) => Impact { hit: true, t: t as i32, normal_x, normal_y },
+        None => Impact::miss(),
+    }
+}
+
+/// Packed `[x0, y0, ...]` vertices as i128 pairs (trailing odd value dropped)
+#[cfg(feature = "alloc")]
+fn vertex_list(poly: &[i32]) -> Vec<(i128, i128)> {
+    poly.chunks_exact(2).map(|p| (p[0] as i128, p[1] as i128)).collect()
+}
+
+/// Unit edge normals (Q16.16) of a polygon, skipping zero-length edges
+#[cfg(feature = "alloc")]
+fn edge_axes(v: &[(i128, i128)], axes: &mut Vec<(i128, i128)>) {
+    for i in 0..v.len() {
+        let (ax, ay) = v[i];
+        let (bx, by) = v[(i + 1) % v.len()];
+        if (ax, ay) != (bx, by) {
+            let (nx, ny) = unit_q32(ay - by, bx - ax);
+            axes.push((nx as i128, ny as i128));
+        }
+    }
+}
+
+/// (min, max) of the vertices projected on a unit axis, Q32.32
+#[cfg(feature = "alloc")]
+fn project_onto(v: &[(i128, i128)], (ux, uy): (i128, i128)) -> (i128, i128) {
+    v.iter().fold((i128::MAX, i128::MIN), |(lo, hi), &(x, y)| {
+        let p = x * ux + y * uy;
+        (lo.min(p), hi.max(p))
+    })
+}
+
+/// Sum of the vertices and their count, for the A → B orientation
+#[cfg(feature = "alloc")]
+fn vertex_sum(v: &[(i128, i128)]) -> (i128, i128, i128) {
+    let (sx, sy) = v.iter().fold((0, 0), |(sx, sy), &(x, y)| (sx + x, sy + y));
+    (sx, sy, v.len() as i128)
+}
+
+/// Minimum-overlap axis over `axes`, or None if one of them separates
+///
+/// `proj_a` / `proj_b` give each shape's Q32.32 interval on an axis.
+/// Axes are tested in order and the first smallest overlap wins, so the
+/// result does not depend on anything but the input order.
+#[cfg(feature = "alloc")]
+fn sat(
+    axes: &[(i128, i128)],
+    proj_a: impl Fn((i128, i128)) -> (i128, i128),
+    proj_b: impl Fn((i128, i128)) -> (i128, i128),
+) -> Option<(i128, (i128, i128))> {
+    let mut best: Option<(i128, (i128, i128))> = None;
+    for &axis in axes {
+        let (a_lo, a_hi) = proj_a(axis);
+        let (b_lo, b_hi) = proj_b(axis);
+        let overlap = a_hi.min(b_hi) - a_lo.max(b_lo);
+        if overlap < 0 {
+            return None;
+        }
+        if best.is_none_or(|(o, _)| overlap < o) {
+            best = Some((overlap, axis));
+        }
+    }
+    best
+}
+
+/// Contact from the SAT winner, with the normal flipped to point from A
+/// toward B (by the vertex means, or a single center for circles)
+#[cfg(feature = "alloc")]
+fn sat_contact(best: Option<(i128, (i128, i128))>, (ax, ay, an): (i128, i128, i128), (bx, by, bn): (i128, i128, i128)) -> Contact {
+    let Some((overlap, (ux, uy))) = best else {
+        return Contact::miss();
+    };
+    // (mean_b - mean_a) · u, scaled by an * bn to stay exact
+    let toward = (bx * an - ax * bn) * ux + (by * an - ay * bn) * uy;
+    let s = if toward < 0 { -1 } else { 1 };
+    Contact {
+        hit: true,
+        depth: saturate_i128(overlap >> 16),
+        normal_x: (ux * s) as i32,
+        normal_y: (uy * s) as i32,
+    }
+}
+
+/// Separating-axis test between two convex polygons
+///
+/// Vertices are packed `[x0, y0, x1, y1, ...]` in either winding order.
+/// Candidate axes are the unit edge normals of a, then of b (rounded to
+/// Q16.16 once); projections are exact on those axes. Returns the
+/// minimum translation: depth along the axis of least overlap (first
+/// one wins ties) and a normal from a toward b. Touching polygons hit
+/// with depth 0. Results are meaningless for concave input.
+#[cfg(feature = "alloc")]
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn polygon_polygon(a: &[i32], b: &[i32]) -> Contact {
+    let (va, vb) = (vertex_list(a), vertex_list(b));
+    if va.is_empty() || vb.is_empty() {
+        return Contact::miss();
+    }
+    let mut axes = Vec::with_capacity(va.len() + vb.len());
+    edge_axes(&va, &mut axes);
+    edge_axes(&vb, &mut axes);
+
+    let best = sat(&axes, |u| project_onto(&va, u), |u| project_onto(&vb, u));
+    sat_contact(best, vertex_sum(&va), vertex_sum(&vb))
+}
+
+/// Separating-axis test between a convex polygon and a circle
+///
+/// Axes are the polygon's unit edge normals plus the axis from the
+/// polygon vertex nearest the center (first one on ties) to the
+/// center. The normal points from the polygon toward the circle.
+#[cfg(feature = "alloc")]
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn polygon_circle(poly: &[i32], center: &FixedVec2, r: i32) -> Contact {
+    let v = vertex_list(poly);
+    if v.is_empty() || r < 0 {
+        return Contact::miss();
+    }
+    let (cx, cy, r) = (center.x as i128, center.y as i128, r as i128);
+
+    let mut axes = Vec::with_capacity(v.len() + 1);
+    edge_axes(&v, &mut axes);
+    let mut nearest = v[0];
+    let mut nearest_d2 = i128::MAX;
+    for &(x, y) in &v {
+        let d2 = (x - cx) * (x - cx) + (y - cy) * (y - cy);
+        if d2 < nearest_d2 {
+            nearest = (x, y);
+            nearest_d2 = d2;
+        }
+    }
+    if nearest_d2 != 0 {
+        let (nx, ny) = unit_q32(cx - nearest.0, cy - nearest.1);
+        axes.push((nx as i128, ny as i128));
+    }
+
+    let circle = |(ux, uy): (i128, i128)| {
+        let p = cx * ux + cy * uy;
+        (p - (r << 16), p + (r << 16))
+    };
+    let best = sat(&axes, |u| project_onto(&v, u), circle);
+    sat_contact(best, vertex_sum(&v), (cx, cy, 1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn v(x: i32, y: i32) -> FixedVec2 {
+        FixedVec2::new(x * ONE, y * ONE)
+    }
+
+    fn hit(depth: i32, normal_x: i32, normal_y: i32) -> Contact {
+        Contact { hit: true, depth, normal_x, normal_y }
+    }
+
+    #[test]
+    fn circle_circle_contacts() {
+        assert_eq!(circle_circle(&v(0, 0), 2 * ONE, &v(3, 0), 2 * ONE), hit(ONE, ONE, 0));
+        assert_eq!(circle_circle(&v(0, 0), ONE, &v(0, -2), ONE), hit(0, 0, -ONE));
+        assert!(!circle_circle(&v(0, 0), ONE, &v(3, 4), 3 * ONE + ONE / 2).hit);
+        // Coincident centers push along +x
+        assert_eq!(circle_circle(&v(1, 1), ONE, &v(1, 1), ONE), hit(2 * ONE, ONE, 0));
+    }
+
+    #[test]
+    fn circle_aabb_outside_and_inside() {
+        let b = Aabb::new(0, 0, 4 * ONE, 2 * ONE);
+        // Left of the box, normal from the circle toward the box
+        assert_eq!(circle_aabb(&v(-1, 1), 2 * ONE, &b), hit(ONE, ONE, 0));
+        assert!(!circle_aabb(&v(-3, 5), 2 * ONE, &b).hit);
+        // Center inside, nearest face is +y
+        assert_eq!(circle_aabb(&FixedVec2::new(2 * ONE, 3 * ONE / 2), ONE, &b), hit(3 * ONE / 2, 0, -ONE));
+    }
+
+    #[test]
+    fn sweep_circles_find_first_contact() {
+        // Gap of 6 closed at 10 per tick: touch at t = 0.6
+        let i = sweep_circle_circle(&v(0, 0), &v(10, 0), ONE, &v(8, 0), &v(0, 0), ONE);
+        assert!(i.hit);
+        assert!((i.t - 3 * ONE / 5).abs() <= 1);
+        assert_eq!((i.normal_x, i.normal_y), (ONE, 0));
+        // Already touching
+        let i = sweep_circle_circle(&v(0, 0), &v(1, 0), ONE, &v(2, 0), &v(0, 0), ONE);
+        assert_eq!((i.hit, i.t), (true, 0));
+        // Moving apart or passing wide
+        assert!(!sweep_circle_circle(&v(0, 0), &v(-5, 0), ONE, &v(3, 0), &v(0, 0), ONE).hit);
+        assert!(!sweep_circle_circle(&v(0, 0), &v(10, 0), ONE, &v(5, 3), &v(0, 0), ONE).hit);
+    }
+
+    #[test]
+    fn sweep_circle_against_box() {
+        let b = Aabb::new(4 * ONE, -ONE, 6 * ONE, ONE);
+        let i = sweep_circle_aabb(&v(0, 0), &v(8, 0), ONE, &b);
+        assert_eq!(i, Impact { hit: true, t: 3 * ONE / 8, normal_x: ONE, normal_y: 0 });
+        // Above the -x face band, so it first touches the corner at (4, 1):
+        // (4 - x)² + 1 = 1.5² at x = 4 - √1.25
+        let i = sweep_circle_aabb(&v(0, 2), &v(8, 0), 3 * ONE / 2, &b);
+        let want = (4.0 - 1.25f64.sqrt()) / 8.0 * 65536.0;
+        assert!(i.hit && (i.t as f64 - want).abs() <= 2.0);
+        assert!(i.normal_x > 0 && i.normal_y < 0);
+        assert!(!sweep_circle_aabb(&v(0, 3), &v(8, 0), ONE, &b).hit);
+    }
+
+    /// Axis-aligned square of side 2 centered at (cx, cy), counter-clockwise
+    #[cfg(feature = "alloc")]
+    fn square(cx: i32, cy: i32) -> [i32; 8] {
+        let (l, r, b, t) = ((cx - 1) * ONE, (cx + 1) * ONE, (cy - 1) * ONE, (cy + 1) * ONE);
+        [l, b, r, b, r, t, l, t]
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn polygon_polygon_minimum_translation() {
+        assert_eq!(polygon_polygon(&square(0, 0), &square(1, 0)), hit(ONE, ONE, 0));
+        assert_eq!(polygon_polygon(&square(1, 0), &square(0, 0)), hit(ONE, -ONE, 0));
+        assert_eq!(polygon_polygon(&square(0, 0), &square(0, 2)), hit(0, 0, ONE));
+        assert!(!polygon_polygon(&square(0, 0), &square(3, 0)).hit);
+        // Bounding boxes overlap at the corner (1, 1), but the diamond's
+        // edge normal separates them
+        let (near, mid, far) = (3 * ONE / 5, 2 * ONE, 17 * ONE / 5);
+        let diamond = [mid, near, far, mid, mid, far, near, mid];
+        assert!(!polygon_polygon(&square(0, 0), &diamond).hit);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn polygon_winding_does_not_matter() {
+        let ccw = square(1, 0);
+        let cw = [ccw[6], ccw[7], ccw[4], ccw[5], ccw[2], ccw[3], ccw[0], ccw[1]];
+        assert_eq!(polygon_polygon(&square(0, 0), &cw), polygon_polygon(&square(0, 0), &ccw));
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn polygon_circle_contacts() {
+        assert_eq!(polygon_circle(&square(0, 0), &v(2, 0), 2 * ONE), hit(ONE, ONE, 0));
+        assert!(!polygon_circle(&square(0, 0), &v(3, 3), 2 * ONE).hit);
+        // Near a corner the vertex axis separates what the face axes cannot
+        assert!(!polygon_circle(&square(0, 0), &FixedVec2::new(2 * ONE, 2 * ONE), ONE).hit);
+    }
+}
@@ -0,0 +1,173 @@
+//! Damage mitigation formulas
+//!
+//! The one shared implementation of how armor, penetration and resists
+//! turn incoming damage into damage taken, so the client preview, the sim
+//! and the server agree to the bit. Armor and k are Q16.16 stat values;
+//! reductions and percentages are Q16.16 fractions (1.0 = 100%).
+//!
+//! Armor follows the hyperbolic curve armor / (armor + k): k armor halves
+//! damage and no amount reaches full immunity. Negative armor mirrors it
+//! as amplification, damage * (2 - k / (k - armor)), so it never more
+//! than doubles damage.
+
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+use crate::flags::{self, StatusFlag};
+use crate::{saturate_i128, saturate_i64};
+
+/// 1.0 in Q16.16
+const ONE: i64 = 1 << 16;
+
+/// Fraction of damage removed by armor, Q16.16 in (-1.0, 1.0)
+///
+/// Formula: armor / (k + |armor|), truncated toward zero
+/// Positive for armor > 0, negative (an amplification) for armor < 0.
+/// k must be positive; otherwise returns 0 and raises
+/// `StatusFlag::Domain`.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn armor_reduction(armor: i32, k: i32) -> i32 {
+    if k <= 0 {
+        flags::raise(StatusFlag::Domain);
+        return 0;
+    }
+    let armor = armor as i64;
+    ((armor << 16) / (k as i64 + armor.abs())) as i32
+}
+
+/// Damage taken after armor
+///
+/// Formula: damage * (k + |armor| - armor) / (k + |armor|)
+/// That is damage * k / (k + armor) for armor >= 0 and
+/// damage * (k - 2 armor) / (k - armor) below zero, formed exactly in
+/// 128-bit and truncated toward zero once (so it does not equal
+/// subtracting `mul(damage, armor_reduction(..))`, which rounds twice).
+/// k must be positive; otherwise damage is returned unchanged and
+/// `StatusFlag::Domain` is raised.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn mitigate(damage: i32, armor: i32, k: i32) -> i32 {
+    if k <= 0 {
+        flags::raise(StatusFlag::Domain);
+        return damage;
+    }
+    let (armor, k) = (armor as i128, k as i128);
+    let den = k + armor.abs();
+    saturate_i128(damage as i128 * (den - armor) / den)
+}
+
+/// Armor left after penetration
+///
+/// Percentage penetration applies first, then flat:
+/// max(armor - mul(armor, percent_pen) - flat_pen, 0). percent_pen is
+/// clamped to [0, 1.0] and negative flat_pen counts as 0. Penetration
+/// never pushes armor below zero, and armor that is already zero or
+/// negative is returned unchanged.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn apply_penetration(armor: i32, percent_pen: i32, flat_pen: i32) -> i32 {
+    if armor <= 0 {
+        return armor;
+    }
+    let armor = armor as i64;
+    let pct = (percent_pen as i64).clamp(0, ONE);
+    let after_pct = armor - ((armor * pct) >> 16);
+    saturate_i64((after_pct - flat_pen.max(0) as i64).max(0))
+}
+
+/// Piecewise linear resist curve through packed `[x0, y0, x1, y1, ...]`
+///
+/// The x values must be ascending. Below x0 the curve holds y0 and past
+/// the last point it holds the last y; between two points it is
+/// y0 + (y1 - y0) * (x - x0) / (x1 - x0) in 128-bit, floored once. At a
+/// repeated x (a step) the earlier segment wins. A trailing odd value is
+/// ignored and an empty curve returns 0.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn resist_curve(points: &[i32], x: i32) -> i32 {
+    let n = points.len() / 2;
+    if n == 0 {
+        return 0;
+    }
+    let px = |i: usize| points[2 * i];
+    let py = |i: usize| points[2 * i + 1];
+    if x <= px(0) {
+        return py(0);
+    }
+    for i in 1..n {
+        if x <= px(i) {
+            let (x0, y0) = (px(i - 1) as i128, py(i - 1) as i128);
+            let (x1, y1) = (px(i) as i128, py(i) as i128);
+            // x0 < x <= x1, so the span is positive
+            return (y0 + ((y1 - y0) * (x as i128 - x0)).div_euclid(x1 - x0)) as i32;
+        }
+    }
+    py(n - 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::flags::{clear_flags, get_flags};
+
+    const ONE: i32 = 1 << 16;
+
+    #[test]
+    fn armor_reduction_is_hyperbolic() {
+        assert_eq!(armor_reduction(100 * ONE, 100 * ONE), ONE / 2);
+        assert_eq!(armor_reduction(-100 * ONE, 100 * ONE), -ONE / 2);
+        assert_eq!(armor_reduction(0, ONE), 0);
+        // Never full immunity, never worse than doubling
+        assert!(armor_reduction(i32::MAX, 1) < ONE);
+        assert!(armor_reduction(i32::MIN, 1) > -ONE);
+    }
+
+    #[test]
+    fn mitigate_matches_the_closed_forms() {
+        assert_eq!(mitigate(100 * ONE, 100 * ONE, 100 * ONE), 50 * ONE);
+        assert_eq!(mitigate(100 * ONE, -100 * ONE, 100 * ONE), 150 * ONE);
+        assert_eq!(mitigate(100 * ONE, 0, 100 * ONE), 100 * ONE);
+        // Truncated toward zero once: -7 * 2/3
+        assert_eq!(mitigate(-7, 1, 2), -4);
+        for armor in [-300 * ONE, -ONE, 1, 37 * ONE, 500 * ONE] {
+            let (d, a, k) = (1234 * ONE + 5, armor as i128, 60 * ONE as i128);
+            let want = if armor >= 0 { d as i128 * k / (k + a) } else { d as i128 * (k - 2 * a) / (k - a) };
+            assert_eq!(mitigate(d, armor, 60 * ONE) as i128, want, "{armor}");
+        }
+        assert!(mitigate(100 * ONE, i32::MIN, ONE) < 200 * ONE);
+        assert!(mitigate(100 * ONE, i32::MAX, ONE) > 0);
+    }
+
+    #[test]
+    fn non_positive_k_is_a_domain_error() {
+        clear_flags();
+        assert_eq!(armor_reduction(ONE, 0), 0);
+        assert_eq!(mitigate(42 * ONE, ONE, -ONE), 42 * ONE);
+        assert_eq!(get_flags(), StatusFlag::Domain as u32);
+    }
+
+    #[test]
+    fn penetration_applies_percent_then_flat() {
+        assert_eq!(apply_penetration(100 * ONE, ONE / 4, 10 * ONE), 65 * ONE);
+        assert_eq!(apply_penetration(100 * ONE, 2 * ONE, 0), 0);
+        assert_eq!(apply_penetration(100 * ONE, -ONE, -10 * ONE), 100 * ONE);
+        assert_eq!(apply_penetration(10 * ONE, 0, 50 * ONE), 0);
+        assert_eq!(apply_penetration(-5 * ONE, ONE / 2, 10 * ONE), -5 * ONE);
+        assert_eq!(apply_penetration(i32::MAX, 0, 0), i32::MAX);
+    }
+
+    #[test]
+    fn resist_curve_interpolates_and_holds_the_ends() {
+        let curve = [0, 0, 10 * ONE, ONE / 2, 20 * ONE, ONE / 2];
+        assert_eq!(resist_curve(&curve, -ONE), 0);
+        assert_eq!(resist_curve(&curve, 5 * ONE), ONE / 4);
+        assert_eq!(resist_curve(&curve, 10 * ONE), ONE / 2);
+        assert_eq!(resist_curve(&curve, i32::MAX), ONE / 2);
+        // A falling segment floors
+        assert_eq!(resist_curve(&[0, 1, 3, 0], 1), 0);
+        // A step: the earlier segment owns the repeated x
+        let step = [0, 0, 5, 0, 5, ONE, 10, ONE];
+        assert_eq!(resist_curve(&step, 5), 0);
+        assert_eq!(resist_curve(&step, 6), ONE);
+        assert_eq!(resist_curve(&[0, 5, 9], 100), 5);
+        assert_eq!(resist_curve(&[7], 0), 0);
+        assert_eq!(resist_curve(&[i32::MIN, i32::MIN, i32::MAX, i32::MAX], 0), 0);
+    }
+}
@@ -0,0 +1,170 @@
+//! Canonical Q16.16 constants and angle unit conversions
+//!
+//! Every constant is the nearest Q16.16 value to the real number, so all
+//! consumers share the exact same bit patterns. JS reads them through the
+//! getter functions below (wasm-bindgen cannot export plain constants).
+//! Conversions multiply by a Q2.30 factor and round once, so they are
+//! more precise than `mul(x, DEG_TO_RAD)`.
+
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+use crate::trig::TAU_Q30;
+use crate::truncate_i64;
+
+/// π
+pub const PI: i32 = 205_887;
+/// π/2
+pub const HALF_PI: i32 = 102_944;
+/// 2π
+pub const TAU: i32 = 411_775;
+/// Euler's number e
+pub const E: i32 = 178_145;
+/// √2
+pub const SQRT2: i32 = 92_682;
+/// 1/√2
+pub const FRAC_1_SQRT2: i32 = 46_341;
+/// ln(2)
+pub const LN2: i32 = 45_426;
+/// ln(10)
+pub const LN10: i32 = 150_902;
+/// π/180 (radians per degree)
+pub const DEG_TO_RAD: i32 = 1_144;
+/// 180/π (degrees per radian)
+pub const RAD_TO_DEG: i32 = 3_754_936;
+
+/// π/180 in Q2.30
+const DEG_TO_RAD_Q30: i64 = 18_740_330;
+/// 180/π in Q2.30
+const RAD_TO_DEG_Q30: i64 = 61_520_874_802;
+
+/// Multiply a Q16.16 value by a Q2.30 factor, rounding to nearest
+fn scale_q30(fp: i32, factor_q30: i64) -> i32 {
+    let product = fp as i128 * factor_q30 as i128;
+    truncate_i64(((product + (1 << 29)) >> 30) as i64)
+}
+
+/// π in Q16.16
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn pi() -> i32 {
+    PI
+}
+
+/// π/2 in Q16.16
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn half_pi() -> i32 {
+    HALF_PI
+}
+
+/// 2π in Q16.16
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn tau() -> i32 {
+    TAU
+}
+
+/// e in Q16.16
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn e() -> i32 {
+    E
+}
+
+/// √2 in Q16.16
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn sqrt2() -> i32 {
+    SQRT2
+}
+
+/// 1/√2 in Q16.16
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn frac_1_sqrt2() -> i32 {
+    FRAC_1_SQRT2
+}
+
+/// ln(2) in Q16.16
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn ln2() -> i32 {
+    LN2
+}
+
+/// ln(10) in Q16.16
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn ln10() -> i32 {
+    LN10
+}
+
+/// Radians per degree in Q16.16
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn deg_to_rad_factor() -> i32 {
+    DEG_TO_RAD
+}
+
+/// Degrees per radian in Q16.16
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn rad_to_deg_factor() -> i32 {
+    RAD_TO_DEG
+}
+
+/// Convert Q16.16 degrees to Q16.16 radians (rounded to nearest)
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn deg_to_rad(deg: i32) -> i32 {
+    scale_q30(deg, DEG_TO_RAD_Q30)
+}
+
+/// Convert Q16.16 radians to Q16.16 degrees (rounded to nearest)
+///
+/// Overflows past ±572 radians (wraps and raises `StatusFlag::Overflow`).
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn rad_to_deg(rad: i32) -> i32 {
+    scale_q30(rad, RAD_TO_DEG_Q30)
+}
+
+/// Convert Q16.16 turns (1.0 = full circle) to Q16.16 radians
+///
+/// Rounded to nearest; overflows past ±5215 turns.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn turns_to_rad(turns: i32) -> i32 {
+    scale_q30(turns, TAU_Q30)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::f64::consts;
+
+    const ONE: i32 = 1 << 16;
+
+    fn fp(x: f64) -> i32 {
+        (x * 65536.0).round() as i32
+    }
+
+    #[test]
+    fn constants_are_rounded_to_nearest() {
+        assert_eq!(PI, fp(consts::PI));
+        assert_eq!(HALF_PI, fp(consts::FRAC_PI_2));
+        assert_eq!(TAU, fp(consts::TAU));
+        assert_eq!(E, fp(consts::E));
+        assert_eq!(SQRT2, fp(consts::SQRT_2));
+        assert_eq!(FRAC_1_SQRT2, fp(consts::FRAC_1_SQRT_2));
+        assert_eq!(LN2, fp(consts::LN_2));
+        assert_eq!(LN10, fp(consts::LN_10));
+        assert_eq!(DEG_TO_RAD, fp(consts::PI / 180.0));
+        assert_eq!(RAD_TO_DEG, fp(180.0 / consts::PI));
+    }
+
+    #[test]
+    fn q30_factors_match_the_constants() {
+        assert_eq!(DEG_TO_RAD_Q30, (consts::PI / 180.0 * (1u64 << 30) as f64).round() as i64);
+        assert_eq!(RAD_TO_DEG_Q30, (180.0 / consts::PI * (1u64 << 30) as f64).round() as i64);
+    }
+
+    #[test]
+    fn unit_conversions() {
+        assert_eq!(deg_to_rad(180 * ONE), PI);
+        assert_eq!(deg_to_rad(-90 * ONE), -HALF_PI);
+        assert_eq!(rad_to_deg(ONE), RAD_TO_DEG);
+        // PI is 0.42 LSB short of π, scaled by ~57.3
+        assert_eq!(rad_to_deg(PI), 180 * ONE - 24);
+        assert_eq!(turns_to_rad(ONE), TAU);
+        assert_eq!(turns_to_rad(ONE / 4), HALF_PI);
+    }
+}
@@ -0,0 +1,204 @@
+//! Curve evaluation for Q16.16 control points
+//!
+//! Control points are packed `[x0, y0, x1, y1, ...]` i32 arrays and
+//! results come back as a `FixedVec2`. Each axis is evaluated as one
+//! weighted sum in 128-bit and truncated once, so projectile arcs and
+//! lane paths are bit-identical everywhere.
+
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+use crate::truncate_i128;
+use crate::vec2::FixedVec2;
+
+/// 1.0 in Q16.16
+const ONE: i128 = 1 << 16;
+
+/// Quadratic Bezier position on one axis, Q16.16
+fn quad_axis(p0: i32, p1: i32, p2: i32, t: i128) -> i32 {
+    let u = ONE - t;
+    let sum = u * u * p0 as i128 + 2 * u * t * p1 as i128 + t * t * p2 as i128;
+    truncate_i128(sum >> 32)
+}
+
+/// Quadratic Bezier derivative on one axis, Q16.16
+fn quad_tangent_axis(p0: i32, p1: i32, p2: i32, t: i128) -> i32 {
+    let u = ONE - t;
+    let sum = 2 * (u * (p1 as i128 - p0 as i128) + t * (p2 as i128 - p1 as i128));
+    truncate_i128(sum >> 16)
+}
+
+/// Cubic Bezier position on one axis, Q16.16
+fn cubic_axis(p0: i32, p1: i32, p2: i32, p3: i32, t: i128) -> i32 {
+    let u = ONE - t;
+    let sum = u * u * u * p0 as i128
+        + 3 * u * u * t * p1 as i128
+        + 3 * u * t * t * p2 as i128
+        + t * t * t * p3 as i128;
+    truncate_i128(sum >> 48)
+}
+
+/// Cubic Bezier derivative on one axis, Q16.16
+fn cubic_tangent_axis(p0: i32, p1: i32, p2: i32, p3: i32, t: i128) -> i32 {
+    let u = ONE - t;
+    let sum = 3
+        * (u * u * (p1 as i128 - p0 as i128)
+            + 2 * u * t * (p2 as i128 - p1 as i128)
+            + t * t * (p3 as i128 - p2 as i128));
+    truncate_i128(sum >> 32)
+}
+
+/// Point on a quadratic Bezier curve
+///
+/// `points` holds `[x0, y0, x1, y1, x2, y2]`; returns the point at t
+/// (t = 0 gives P0, t = 1.0 gives P2; not clamped).
+/// Panics if fewer than 6 values are given.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn bezier2(points: &[i32], t: i32) -> FixedVec2 {
+    let t = t as i128;
+    FixedVec2::new(
+        quad_axis(points[0], points[2], points[4], t),
+        quad_axis(points[1], points[3], points[5], t),
+    )
+}
+
+/// Tangent (derivative with respect to t) of a quadratic Bezier curve
+///
+/// Same layout as `bezier2`; returns (dx, dy), not normalized.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn bezier2_tangent(points: &[i32], t: i32) -> FixedVec2 {
+    let t = t as i128;
+    FixedVec2::new(
+        quad_tangent_axis(points[0], points[2], points[4], t),
+        quad_tangent_axis(points[1], points[3], points[5], t),
+    )
+}
+
+/// Point on a cubic Bezier curve
+///
+/// `points` holds `[x0, y0, x1, y1, x2, y2, x3, y3]`; returns the point
+/// at t (not clamped). Panics if fewer than 8 values are given.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn bezier3(points: &[i32], t: i32) -> FixedVec2 {
+    let t = t as i128;
+    FixedVec2::new(
+        cubic_axis(points[0], points[2], points[4], points[6], t),
+        cubic_axis(points[1], points[3], points[5], points[7], t),
+    )
+}
+
+/// Tangent (derivative with respect to t) of a cubic Bezier curve
+///
+/// Same layout as `bezier3`; returns (dx, dy), not normalized.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn bezier3_tangent(points: &[i32], t: i32) -> FixedVec2 {
+    let t = t as i128;
+    FixedVec2::new(
+        cubic_tangent_axis(points[0], points[2], points[4], points[6], t),
+        cubic_tangent_axis(points[1], points[3], points[5], points[7], t),
+    )
+}
+
+/// Uniform Catmull-Rom position on one axis, Q16.16
+///
+/// 0.5 * (2 P1 + (P2 - P0) t + (2 P0 - 5 P1 + 4 P2 - P3) t²
+/// + (3 P1 - P0 - 3 P2 + P3) t³), summed in Q64 and truncated once.
+fn catmull_rom_axis(p0: i32, p1: i32, p2: i32, p3: i32, t: i128) -> i32 {
+    let (p0, p1, p2, p3) = (p0 as i128, p1 as i128, p2 as i128, p3 as i128);
+    let c1 = p2 - p0;
+    let c2 = 2 * p0 - 5 * p1 + 4 * p2 - p3;
+    let c3 = 3 * p1 - p0 - 3 * p2 + p3;
+    let sum = ((((2 * p1 * ONE + c1 * t) * ONE) + c2 * t * t) * ONE) + c3 * t * t * t;
+    truncate_i128(sum >> 49)
+}
+
+/// Uniform Catmull-Rom derivative on one axis, Q16.16
+fn catmull_rom_tangent_axis(p0: i32, p1: i32, p2: i32, p3: i32, t: i128) -> i32 {
+    let (p0, p1, p2, p3) = (p0 as i128, p1 as i128, p2 as i128, p3 as i128);
+    let c1 = p2 - p0;
+    let c2 = 2 * p0 - 5 * p1 + 4 * p2 - p3;
+    let c3 = 3 * p1 - p0 - 3 * p2 + p3;
+    let sum = (c1 * ONE + 2 * c2 * t) * ONE + 3 * c3 * t * t;
+    truncate_i128(sum >> 33)
+}
+
+/// Point on a uniform Catmull-Rom segment
+///
+/// `points` holds `[x0, y0, x1, y1, x2, y2, x3, y3]`; the segment runs
+/// from P1 (t = 0) to P2 (t = 1.0) with P0 and P3 shaping the tangents.
+/// For a waypoint list, evaluate each window of four consecutive points
+/// (duplicate the first and last waypoint to reach the ends). t is not
+/// clamped. Panics if fewer than 8 values are given.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn catmull_rom(points: &[i32], t: i32) -> FixedVec2 {
+    let t = t as i128;
+    FixedVec2::new(
+        catmull_rom_axis(points[0], points[2], points[4], points[6], t),
+        catmull_rom_axis(points[1], points[3], points[5], points[7], t),
+    )
+}
+
+/// Tangent (derivative with respect to t) of a uniform Catmull-Rom segment
+///
+/// Same layout as `catmull_rom`; returns (dx, dy), not normalized.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn catmull_rom_tangent(points: &[i32], t: i32) -> FixedVec2 {
+    let t = t as i128;
+    FixedVec2::new(
+        catmull_rom_tangent_axis(points[0], points[2], points[4], points[6], t),
+        catmull_rom_tangent_axis(points[1], points[3], points[5], points[7], t),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ONE_FP: i32 = 1 << 16;
+    const HALF: i32 = ONE_FP / 2;
+
+    fn pts(coords: &[i32]) -> [i32; 8] {
+        let mut out = [0; 8];
+        for (o, c) in out.iter_mut().zip(coords) {
+            *o = c * ONE_FP;
+        }
+        out
+    }
+
+    #[test]
+    fn bezier_endpoints_and_midpoints() {
+        let quad = pts(&[0, 0, 2, 4, 4, 0]);
+        assert_eq!(bezier2(&quad, 0), FixedVec2::new(0, 0));
+        assert_eq!(bezier2(&quad, ONE_FP), FixedVec2::new(4 * ONE_FP, 0));
+        assert_eq!(bezier2(&quad, HALF), FixedVec2::new(2 * ONE_FP, 2 * ONE_FP));
+        assert_eq!(bezier2_tangent(&quad, HALF), FixedVec2::new(4 * ONE_FP, 0));
+
+        let cubic = pts(&[0, 0, 0, 4, 4, 4, 4, 0]);
+        assert_eq!(bezier3(&cubic, 0), FixedVec2::new(0, 0));
+        assert_eq!(bezier3(&cubic, ONE_FP), FixedVec2::new(4 * ONE_FP, 0));
+        assert_eq!(bezier3(&cubic, HALF), FixedVec2::new(2 * ONE_FP, 3 * ONE_FP));
+        assert_eq!(bezier3_tangent(&cubic, 0), FixedVec2::new(0, 12 * ONE_FP));
+    }
+
+    #[test]
+    fn straight_control_points_give_a_line() {
+        let cubic = pts(&[0, 0, 1, 1, 2, 2, 3, 3]);
+        for t in (0..=ONE_FP).step_by(4099) {
+            let p = bezier3(&cubic, t);
+            assert_eq!(p.x, p.y);
+            assert!((p.x - 3 * t).abs() <= 1);
+        }
+    }
+
+    #[test]
+    fn catmull_rom_passes_through_inner_points() {
+        let p = pts(&[0, 0, 1, 2, 3, 3, 4, 1]);
+        assert_eq!(catmull_rom(&p, 0), FixedVec2::new(ONE_FP, 2 * ONE_FP));
+        assert_eq!(catmull_rom(&p, ONE_FP), FixedVec2::new(3 * ONE_FP, 3 * ONE_FP));
+        // Tangent at P1 is (P2 - P0) / 2
+        assert_eq!(catmull_rom_tangent(&p, 0), FixedVec2::new(3 * ONE_FP / 2, 3 * ONE_FP / 2));
+        // Evenly spaced collinear points are traversed uniformly
+        let line = pts(&[0, 0, 1, 0, 2, 0, 3, 0]);
+        assert_eq!(catmull_rom(&line, HALF), FixedVec2::new(3 * HALF, 0));
+    }
+}
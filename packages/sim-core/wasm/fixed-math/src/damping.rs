@@ -0,0 +1,169 @@
+//! Frame-rate-independent smoothing for Q16.16
+//!
+//! Closed-form and critically damped updates whose result depends only on
+//! the elapsed time, not on how the caller splits it into ticks.
+
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+use crate::exp::exp_nonpos_q30;
+use crate::{truncate_i128, Q30_FRAC_BITS};
+
+/// exp(-rate * dt) as a Q2.30 factor in [0, 1.0]
+///
+/// rate * dt is formed in Q32.32 and narrowed to Q2.30 before the exp, so
+/// the factor for a given product is identical whichever of the two
+/// carries the magnitude. Non-positive products give 1.0.
+pub(crate) fn decay_factor_q30(rate: i32, dt: i32) -> i64 {
+    let x = (rate as i64 * dt as i64) >> (32 - Q30_FRAC_BITS);
+    if x <= 0 {
+        return 1 << Q30_FRAC_BITS;
+    }
+    exp_nonpos_q30(-x)
+}
+
+/// Exponential decay of a value over dt
+///
+/// Formula: value * exp(-rate * dt)
+/// rate is per unit time, dt in the same unit (both Q16.16). The product
+/// is truncated toward zero, so repeated decay always reaches exactly 0
+/// for either sign of value. A non-positive rate * dt leaves value
+/// unchanged (this never grows a value).
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn decay(value: i32, rate: i32, dt: i32) -> i32 {
+    let factor = decay_factor_q30(rate, dt);
+    ((value as i64 * factor) / (1 << Q30_FRAC_BITS)) as i32
+}
+
+/// 1.0 in Q16.16
+const ONE: i128 = 1 << 16;
+
+/// Smallest smooth time accepted by `smooth_damp` (~0.0001)
+const MIN_SMOOTH_TIME: i32 = 7;
+
+/// Largest omega * dt used by `smooth_damp`
+///
+/// At 64.0 the decay factor is already below one Q16.16 LSB; clamping
+/// keeps the cubic in the denominator well inside 128-bit.
+const MAX_OMEGA_DT: i128 = 64 << 16;
+
+/// A value together with its rate of change, returned by damped updates
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DampedValue {
+    /// New value (Q16.16)
+    pub value: i32,
+    /// New velocity in value units per time unit (Q16.16)
+    pub velocity: i32,
+}
+
+/// Critically damped move of current toward target (Unity's SmoothDamp)
+///
+/// smooth_time is roughly the time to reach the target; velocity is the
+/// caller-held velocity from the previous call. Uses the same cubic
+/// approximation of exp(-omega * dt) as Unity,
+/// 1 / (1 + x + 0.48x² + 0.235x³), in Q16.16 with 128-bit intermediates
+/// and one truncation per output. The result never overshoots the
+/// target: if it would, value snaps to target and velocity becomes 0.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn smooth_damp(current: i32, target: i32, velocity: i32, smooth_time: i32, dt: i32) -> DampedValue {
+    let smooth_time = smooth_time.max(MIN_SMOOTH_TIME) as i128;
+    let omega = (2 * ONE * ONE) / smooth_time;
+    let x = ((omega * dt as i128) >> 16).clamp(0, MAX_OMEGA_DT);
+    let x2 = (x * x) >> 16;
+    let x3 = (x2 * x) >> 16;
+    // 0.48 and 0.235 in Q16.16
+    let denom = ONE + x + ((31_457 * x2) >> 16) + ((15_401 * x3) >> 16);
+    let factor = (ONE * ONE) / denom;
+
+    let target = target as i128;
+    let change = current as i128 - target;
+    let temp = ((velocity as i128 + ((omega * change) >> 16)) * dt as i128) >> 16;
+    let mut new_velocity = ((velocity as i128 - ((omega * temp) >> 16)) * factor) >> 16;
+    let mut output = target + (((change + temp) * factor) >> 16);
+
+    // Prevent overshooting the target
+    if (-change > 0) == (output > target) {
+        output = target;
+        new_velocity = 0;
+    }
+
+    DampedValue {
+        value: truncate_i128(output),
+        velocity: truncate_i128(new_velocity),
+    }
+}
+
+/// One semi-implicit Euler step of a damped spring toward target
+///
+/// Formula: a = stiffness * (target - pos) - damping * vel
+///          vel' = vel + a * dt
+///          pos' = pos + vel' * dt
+/// The acceleration stays in Q32.32 until it is scaled by dt, and pos'
+/// uses the already-truncated vel', so the returned pair is the complete
+/// state for the next call. Symplectic, so energy does not drift; stable
+/// while dt < 2 / sqrt(stiffness).
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn spring(pos: i32, vel: i32, target: i32, stiffness: i32, damping: i32, dt: i32) -> DampedValue {
+    let accel = stiffness as i128 * (target as i128 - pos as i128) - damping as i128 * vel as i128;
+    let velocity = truncate_i128(vel as i128 + ((accel * dt as i128) >> 32));
+    let value = truncate_i128(pos as i128 + ((velocity as i128 * dt as i128) >> 16));
+    DampedValue { value, velocity }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ONE_FP: i32 = 1 << 16;
+
+    #[test]
+    fn decay_matches_closed_form() {
+        assert_eq!(decay(100 * ONE_FP, ONE_FP, 0), 100 * ONE_FP);
+        assert_eq!(decay(100 * ONE_FP, -ONE_FP, ONE_FP), 100 * ONE_FP);
+        for dt in [ONE_FP / 60, ONE_FP / 2, ONE_FP, 3 * ONE_FP] {
+            let want = 100.0 * (-(dt as f64) / 65536.0).exp() * 65536.0;
+            assert!((decay(100 * ONE_FP, ONE_FP, dt) as f64 - want).abs() <= 2.0);
+        }
+    }
+
+    #[test]
+    fn decay_is_symmetric_and_reaches_zero() {
+        let (mut up, mut down) = (1000 * ONE_FP, -1000 * ONE_FP);
+        for _ in 0..2000 {
+            up = decay(up, 4 * ONE_FP, ONE_FP / 10);
+            down = decay(down, 4 * ONE_FP, ONE_FP / 10);
+            assert_eq!(down, -up);
+        }
+        assert_eq!(up, 0);
+    }
+
+    #[test]
+    fn smooth_damp_converges_without_overshoot() {
+        let (mut value, mut velocity) = (0, 0);
+        let target = 10 * ONE_FP;
+        for _ in 0..600 {
+            let s = smooth_damp(value, target, velocity, ONE_FP / 2, ONE_FP / 60);
+            assert!(s.value <= target);
+            assert!(s.value >= value);
+            value = s.value;
+            velocity = s.velocity;
+        }
+        // Truncation stalls the last few dozen LSB short of the target
+        assert!(target - value < 64);
+    }
+
+    #[test]
+    fn spring_settles_on_target() {
+        let (mut pos, mut vel) = (0, 0);
+        let target = 5 * ONE_FP;
+        for _ in 0..2000 {
+            let s = spring(pos, vel, target, 100 * ONE_FP, 20 * ONE_FP, ONE_FP / 60);
+            pos = s.value;
+            vel = s.velocity;
+        }
+        // Truncated steps leave a small dead band around the target
+        assert!((pos - target).abs() < 64);
+        assert!(vel.abs() < 64);
+    }
+}
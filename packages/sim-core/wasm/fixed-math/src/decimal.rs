@@ -0,0 +1,187 @@
+//! Decimal strings to and from Q16.16
+//!
+//! Balance values exported from spreadsheets arrive as decimal text, and
+//! debug output should show what the sim actually holds. Both directions
+//! are done with exact integer arithmetic on the decimal digits, rounding
+//! to nearest with ties toward +∞ (the `quantize` rule), so a given
+//! string always parses to the same value on every platform.
+
+#[cfg(feature = "alloc")]
+use alloc::{format, string::String};
+
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+use crate::flags::{self, StatusFlag};
+use crate::saturate_i128;
+
+/// Fractional digits kept exactly when parsing; later digits only break ties
+const MAX_PARSE_DIGITS: u32 = 20;
+
+/// Integer part beyond which every result saturates
+const MAX_INT_PART: u128 = 1 << 20;
+
+/// Fractional digits needed to print any Q16.16 value exactly
+#[cfg(feature = "alloc")]
+const MAX_FORMAT_DIGITS: u32 = 16;
+
+/// Nearest Q16.16 value to a decimal string, ties toward +∞
+///
+/// Accepts an optional sign, digits, and an optional '.' with more digits
+/// ("12", "-0.25", "+.5", "3."), with surrounding ASCII whitespace
+/// ignored. The exact decimal value is rounded once; digits past the 20th
+/// decimal place only decide exact ties. Values outside the Q16.16 range
+/// saturate with `StatusFlag::Overflow`. Anything else (empty, no digits,
+/// exponents, stray characters) returns 0 and raises `StatusFlag::Domain`.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn parse_fp(s: &str) -> i32 {
+    let s = s.trim_ascii();
+    let (negative, body) = match s.as_bytes().first() {
+        Some(b'-') => (true, &s[1..]),
+        Some(b'+') => (false, &s[1..]),
+        _ => (false, s),
+    };
+    let (int_digits, frac_digits) = body.split_once('.').unwrap_or((body, ""));
+    let all_digits = |d: &str| d.bytes().all(|c| c.is_ascii_digit());
+    if int_digits.len() + frac_digits.len() == 0 || !all_digits(int_digits) || !all_digits(frac_digits) {
+        flags::raise(StatusFlag::Domain);
+        return 0;
+    }
+
+    let mut int_part: u128 = 0;
+    for c in int_digits.bytes() {
+        int_part = (int_part * 10 + (c - b'0') as u128).min(MAX_INT_PART);
+    }
+
+    let kept = frac_digits.len().min(MAX_PARSE_DIGITS as usize);
+    let mut frac_part: u128 = 0;
+    for c in frac_digits[..kept].bytes() {
+        frac_part = frac_part * 10 + (c - b'0') as u128;
+    }
+    let sticky = frac_digits[kept..].bytes().any(|c| c != b'0');
+
+    // value = ±(m + δ) / 10^kept with 0 < δ < 1 when sticky; δ = 1/2
+    // stands in for the dropped digits, since no rounding boundary lies
+    // strictly inside that last decimal place
+    let scale = 10u128.pow(kept as u32);
+    let m2 = ((int_part * scale + frac_part) * 2 + sticky as u128) as i128;
+    let den = 2 * scale as i128;
+    let num = if negative { -m2 } else { m2 } << 16;
+
+    // floor(num / den + 1/2)
+    saturate_i128((2 * num + den).div_euclid(2 * den))
+}
+
+/// Decimal text of a Q16.16 value rounded to `decimals` places
+///
+/// The exact value fp / 65536 is rounded to nearest with ties toward +∞,
+/// and never prints "-0". Sixteen places show any value exactly, so
+/// larger counts are clamped to 16; five or more places always parse
+/// back to the same value with `parse_fp`.
+#[cfg(feature = "alloc")]
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn format_fp(fp: i32, decimals: u32) -> String {
+    let decimals = decimals.min(MAX_FORMAT_DIGITS);
+    let scale = 10i128.pow(decimals);
+
+    // floor(fp * 10^d / 2^16 + 1/2)
+    let scaled = ((fp as i128 * scale) * 2 + (1 << 16)).div_euclid(1 << 17);
+    let sign = if scaled < 0 { "-" } else { "" };
+    let mag = scaled.unsigned_abs();
+    let int_part = mag / scale as u128;
+    if decimals == 0 {
+        return format!("{sign}{int_part}");
+    }
+    let frac_part = mag % scale as u128;
+    format!("{sign}{int_part}.{frac_part:0width$}", width = decimals as usize)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::flags::{clear_flags, get_flags};
+
+    const ONE: i32 = 1 << 16;
+
+    #[test]
+    fn parse_accepts_plain_decimals() {
+        assert_eq!(parse_fp("1.5"), ONE + ONE / 2);
+        assert_eq!(parse_fp("-0.25"), -ONE / 4);
+        assert_eq!(parse_fp("+.5"), ONE / 2);
+        assert_eq!(parse_fp("3."), 3 * ONE);
+        assert_eq!(parse_fp(" \t12\n"), 12 * ONE);
+        assert_eq!(parse_fp("0.0000152587890625"), 1);
+        assert_eq!(parse_fp("-0"), 0);
+    }
+
+    #[test]
+    fn parse_rounds_half_lsb_ties_up() {
+        // Half an LSB is exactly 0.00000762939453125
+        assert_eq!(parse_fp("0.00000762939453125"), 1);
+        assert_eq!(parse_fp("-0.00000762939453125"), 0);
+        assert_eq!(parse_fp("0.0000076293945312"), 0);
+        // Digits past the 20th place still break the tie
+        assert_eq!(parse_fp("0.0000076293945312499999999"), 0);
+        assert_eq!(parse_fp("-0.0000076293945312500000001"), -1);
+        assert_eq!(parse_fp("0.00000762939453125000000000"), 1);
+    }
+
+    #[test]
+    fn parse_saturates_out_of_range() {
+        clear_flags();
+        assert_eq!(parse_fp("-32768"), i32::MIN);
+        assert_eq!(parse_fp("32767.99998"), i32::MAX);
+        assert_eq!(get_flags(), 0);
+        assert_eq!(parse_fp("32768"), i32::MAX);
+        assert_eq!(parse_fp("-99999999999999999999999.5"), i32::MIN);
+        assert_eq!(get_flags(), StatusFlag::Overflow as u32);
+    }
+
+    #[test]
+    fn parse_rejects_malformed_text() {
+        for s in ["", " ", "-", ".", "+.", "1e5", "1.2.3", "abc", "- 1", "1,5", "0x10", "--1"] {
+            clear_flags();
+            assert_eq!(parse_fp(s), 0, "{s:?}");
+            assert_eq!(get_flags(), StatusFlag::Domain as u32, "{s:?}");
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn format_rounds_to_the_requested_places() {
+        assert_eq!(format_fp(ONE + ONE / 2, 2), "1.50");
+        // Ties go toward +∞ on both sides of zero
+        assert_eq!(format_fp(ONE / 4, 1), "0.3");
+        assert_eq!(format_fp(-ONE / 4, 1), "-0.2");
+        assert_eq!(format_fp(ONE / 2, 0), "1");
+        assert_eq!(format_fp(-ONE / 2, 0), "0");
+        assert_eq!(format_fp(-3 * ONE / 2, 0), "-1");
+        assert_eq!(format_fp(-1, 5), "-0.00002");
+        // Rounds to zero without printing "-0"
+        assert_eq!(format_fp(-1, 4), "0.0000");
+        assert_eq!(format_fp(1, 16), "0.0000152587890625");
+        assert_eq!(format_fp(1, 40), "0.0000152587890625");
+        assert_eq!(format_fp(i32::MIN, 3), "-32768.000");
+        assert_eq!(format_fp(i32::MAX, 16), "32767.9999847412109375");
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn format_parse_round_trip_from_five_places() {
+        let mut seed = 3u32;
+        let mut values = vec![i32::MIN, i32::MIN + 1, -ONE, -1, 0, 1, ONE / 3, i32::MAX - 1, i32::MAX];
+        for _ in 0..2000 {
+            seed = seed.wrapping_mul(1664525).wrapping_add(1013904223);
+            values.push(seed as i32);
+        }
+        clear_flags();
+        for d in 5..=16 {
+            for &fp in &values {
+                assert_eq!(parse_fp(&format_fp(fp, d)), fp, "{fp} at {d} places");
+            }
+        }
+        assert_eq!(get_flags(), 0);
+        // Four places are not enough: 1 and 2 LSB both print 0.0000
+        assert_eq!(parse_fp(&format_fp(2, 4)), 0);
+    }
+}
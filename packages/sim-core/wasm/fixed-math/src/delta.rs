@@ -0,0 +1,136 @@
+//! Snapshot delta compression
+//!
+//! Per-element differences between two i32 state snapshots, zigzag
+//! mapped (small negative and positive changes both get small codes) and
+//! written as LEB128 varints: an unchanged field costs one byte, a small
+//! change one or two. Differences wrap, so any pair of snapshots round
+//! trips exactly.
+
+use alloc::vec::Vec;
+
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+use crate::flags::{self, StatusFlag};
+
+/// Longest varint for a 32-bit value
+const MAX_VARINT_BYTES: u32 = 5;
+
+fn zigzag(v: i32) -> u32 {
+    ((v << 1) ^ (v >> 31)) as u32
+}
+
+fn unzigzag(z: u32) -> i32 {
+    (z >> 1) as i32 ^ -((z & 1) as i32)
+}
+
+/// Encode `curr` relative to `prev`
+///
+/// One varint per element of `curr`; elements past the end of `prev`
+/// are encoded relative to 0. The element count is implied by the
+/// byte stream, so no header is written.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn delta_encode(prev: &[i32], curr: &[i32]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(curr.len());
+    for (i, &c) in curr.iter().enumerate() {
+        let base = prev.get(i).copied().unwrap_or(0);
+        let mut z = zigzag(c.wrapping_sub(base));
+        while z >= 0x80 {
+            out.push((z as u8 & 0x7F) | 0x80);
+            z >>= 7;
+        }
+        out.push(z as u8);
+    }
+    out
+}
+
+/// Rebuild a snapshot from `prev` and the output of `delta_encode`
+///
+/// A truncated or over-long varint ends decoding there and raises
+/// `StatusFlag::Domain`; the elements decoded so far are returned.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn delta_decode(prev: &[i32], bytes: &[u8]) -> Vec<i32> {
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut z = 0u32;
+    let mut shift = 0u32;
+    for &b in bytes {
+        if shift == 7 * (MAX_VARINT_BYTES - 1) && b > 0x0F {
+            flags::raise(StatusFlag::Domain);
+            return out;
+        }
+        z |= ((b & 0x7F) as u32) << shift;
+        if b & 0x80 != 0 {
+            shift += 7;
+            continue;
+        }
+        let base = prev.get(out.len()).copied().unwrap_or(0);
+        out.push(base.wrapping_add(unzigzag(z)));
+        z = 0;
+        shift = 0;
+    }
+    if shift != 0 {
+        flags::raise(StatusFlag::Domain);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::flags::{clear_flags, get_flags};
+
+    #[test]
+    fn zigzag_maps_small_magnitudes_to_small_codes() {
+        let pairs = [(0, 0), (-1, 1), (1, 2), (-2, 3), (i32::MAX, u32::MAX - 1), (i32::MIN, u32::MAX)];
+        for (v, z) in pairs {
+            assert_eq!(zigzag(v), z);
+            assert_eq!(unzigzag(z), v);
+        }
+    }
+
+    #[test]
+    fn encoding_sizes() {
+        assert_eq!(delta_encode(&[5, 5], &[5, 5]), [0, 0]);
+        assert_eq!(delta_encode(&[5], &[4]), [1]);
+        assert_eq!(delta_encode(&[0], &[64]), [0x80, 0x01]);
+        assert_eq!(delta_encode(&[], &[i32::MIN]), [0xFF, 0xFF, 0xFF, 0xFF, 0x0F]);
+        assert!(delta_encode(&[1, 2], &[]).is_empty());
+    }
+
+    #[test]
+    fn round_trips_any_snapshots() {
+        clear_flags();
+        let mut s = 3u32;
+        let mut next = || {
+            s = s.wrapping_mul(1664525).wrapping_add(1013904223);
+            s as i32
+        };
+        let prev: Vec<i32> = (0..200).map(|_| next()).collect();
+        let small: Vec<i32> = prev.iter().map(|&p| p.wrapping_add(next() >> 26)).collect();
+        let wild: Vec<i32> = (0..230).map(|_| next()).collect();
+        for curr in [&small, &wild, &prev[..50].to_vec(), &vec![i32::MIN, i32::MAX, 0]] {
+            assert_eq!(&delta_decode(&prev, &delta_encode(&prev, curr)), curr);
+        }
+        assert!(delta_encode(&prev, &small).len() < 2 * small.len());
+        assert_eq!(get_flags(), 0);
+    }
+
+    #[test]
+    fn truncated_varint_stops_decoding() {
+        clear_flags();
+        let mut bytes = delta_encode(&[0, 0, 0], &[1, 2, 1 << 20]);
+        bytes.pop();
+        assert_eq!(delta_decode(&[0, 0, 0], &bytes), [1, 2]);
+        assert_eq!(get_flags(), StatusFlag::Domain as u32);
+    }
+
+    #[test]
+    fn over_long_varint_is_rejected() {
+        clear_flags();
+        assert_eq!(delta_decode(&[], &[4, 0xFF, 0xFF, 0xFF, 0xFF, 0x1F, 0]), [2]);
+        assert_eq!(get_flags(), StatusFlag::Domain as u32);
+        clear_flags();
+        assert_eq!(delta_decode(&[], &[0x80, 0x80, 0x80, 0x80, 0x80, 0x00]), []);
+        assert_eq!(get_flags(), StatusFlag::Domain as u32);
+    }
+}
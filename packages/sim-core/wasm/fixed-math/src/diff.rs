@@ -0,0 +1,66 @@
+//! State divergence diagnostics
+//!
+//! When two clients' digests disagree, dumping both snapshots and
+//! running these over them names the exact fields that differ.
+
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+/// Index of the first element where two snapshots differ, or -1
+///
+/// When one array is a prefix of the other, the shorter length is the
+/// first divergence. Equal arrays return -1. Indices beyond i32::MAX
+/// saturate.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn first_divergence(a: &[i32], b: &[i32]) -> i32 {
+    let common = a.len().min(b.len());
+    match a.iter().zip(b).position(|(x, y)| x != y) {
+        Some(i) => i.min(i32::MAX as usize) as i32,
+        None if a.len() != b.len() => common.min(i32::MAX as usize) as i32,
+        None => -1,
+    }
+}
+
+/// The first `limit` mismatches, packed `[index, a_value, b_value, ...]`
+///
+/// Compares the common prefix in ascending index order; a length
+/// difference is not reported here (see `first_divergence`).
+#[cfg(feature = "alloc")]
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn divergences(a: &[i32], b: &[i32], limit: u32) -> Vec<i32> {
+    a.iter()
+        .zip(b)
+        .enumerate()
+        .filter(|(_, (x, y))| x != y)
+        .take(limit as usize)
+        .flat_map(|(i, (&x, &y))| [i.min(i32::MAX as usize) as i32, x, y])
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn first_divergence_finds_mismatch_or_length() {
+        assert_eq!(first_divergence(&[1, 2, 3], &[1, 2, 3]), -1);
+        assert_eq!(first_divergence(&[], &[]), -1);
+        assert_eq!(first_divergence(&[1, 2, 3], &[1, 5, 4]), 1);
+        assert_eq!(first_divergence(&[1, 2], &[1, 2, 3]), 2);
+        assert_eq!(first_divergence(&[7], &[]), 0);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn divergences_lists_common_prefix_mismatches() {
+        let a = [0, 1, 2, 3, 4, 5];
+        let b = [0, 9, 2, -3, 4, 8, 100];
+        assert_eq!(divergences(&a, &b, 10), [1, 1, 9, 3, 3, -3, 5, 5, 8]);
+        assert_eq!(divergences(&a, &b, 2), [1, 1, 9, 3, 3, -3]);
+        assert!(divergences(&a, &b, 0).is_empty());
+        assert!(divergences(&a, &a[..3], 10).is_empty());
+    }
+}
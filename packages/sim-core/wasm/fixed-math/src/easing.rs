@@ -0,0 +1,303 @@
+//! Easing curves for Q16.16
+//!
+//! The standard easings.net set evaluated in fixed point, so animation
+//! timings that gate gameplay (build and upgrade progress) come from the
+//! deterministic core. Input t is clamped to [0, 1.0]; outputs are 0 at
+//! t = 0 and exactly 1.0 at t = 1.0. Back and elastic curves overshoot
+//! outside [0, 1.0] in between, as usual.
+
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+use crate::exp::{exp_q30, LN2_Q30};
+use crate::trig::sin;
+
+/// 1.0 in Q16.16
+const ONE: i64 = 1 << 16;
+/// 0.5 in Q16.16
+const HALF: i64 = 1 << 15;
+
+/// Back overshoot c1 = 1.70158
+const BACK_C1: i64 = 111_515;
+/// c3 = c1 + 1
+const BACK_C3: i64 = 177_051;
+/// In-out overshoot c2 = c1 * 1.525
+const BACK_C2: i64 = 170_060;
+/// Elastic angular step c4 = 2π/3
+const ELASTIC_C4: i64 = 137_258;
+/// In-out elastic angular step c5 = 2π/4.5
+const ELASTIC_C5: i64 = 91_506;
+/// Bounce scale 2.75 (the bounce constant 7.5625 is 2.75²)
+const BOUNCE_D1: i64 = 180_224;
+
+/// Easing curve selector for `ease`
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Easing {
+    Linear = 0,
+    QuadIn = 1,
+    QuadOut = 2,
+    QuadInOut = 3,
+    CubicIn = 4,
+    CubicOut = 5,
+    CubicInOut = 6,
+    QuartIn = 7,
+    QuartOut = 8,
+    QuartInOut = 9,
+    BackIn = 10,
+    BackOut = 11,
+    BackInOut = 12,
+    ElasticIn = 13,
+    ElasticOut = 14,
+    ElasticInOut = 15,
+    BounceIn = 16,
+    BounceOut = 17,
+    BounceInOut = 18,
+}
+
+/// Q16.16 multiply on i64 (floors like `mul`)
+#[inline]
+fn m(a: i64, b: i64) -> i64 {
+    (a * b) >> 16
+}
+
+/// t^n * 2^k for a Q16.16 t in [-2, 2], truncated once
+///
+/// The power is formed exactly in 128-bit, so the only rounding is the
+/// final shift back to Q16.16.
+fn pk(t: i64, n: u32, k: u32) -> i64 {
+    let power = (0..n).fold(1i128, |acc, _| acc * t as i128);
+    ((power << k) >> (16 * (n - 1))) as i64
+}
+
+/// t^n for a Q16.16 t in [-2, 2], truncated once
+fn p(t: i64, n: u32) -> i64 {
+    pk(t, n, 0)
+}
+
+/// 2^x for a Q16.16 exponent (x * ln2 through `exp`)
+fn exp2(x: i64) -> i64 {
+    exp_q30((x * LN2_Q30) >> 16) as i64
+}
+
+/// sin of a Q16.16 angle held in i64
+fn sin64(angle: i64) -> i64 {
+    sin(angle as i32) as i64
+}
+
+fn power_in(t: i64, n: u32) -> i64 {
+    p(t, n)
+}
+
+fn power_out(t: i64, n: u32) -> i64 {
+    ONE - p(ONE - t, n)
+}
+
+/// In-out power curve: 2^(n-1) t^n, then 1 - (2 - 2t)^n / 2
+fn power_in_out(t: i64, n: u32) -> i64 {
+    if t < HALF {
+        pk(t, n, n - 1)
+    } else {
+        ONE - p(2 * ONE - 2 * t, n) / 2
+    }
+}
+
+fn back_in(t: i64) -> i64 {
+    m(BACK_C3, p(t, 3)) - m(BACK_C1, p(t, 2))
+}
+
+fn back_out(t: i64) -> i64 {
+    let u = t - ONE;
+    ONE + m(BACK_C3, p(u, 3)) + m(BACK_C1, p(u, 2))
+}
+
+fn back_in_out(t: i64) -> i64 {
+    if t < HALF {
+        let u = 2 * t;
+        m(p(u, 2), m(BACK_C2 + ONE, u) - BACK_C2) / 2
+    } else {
+        let u = 2 * t - 2 * ONE;
+        (m(p(u, 2), m(BACK_C2 + ONE, u) + BACK_C2) + 2 * ONE) / 2
+    }
+}
+
+fn elastic_in(t: i64) -> i64 {
+    if t == 0 || t == ONE {
+        return t;
+    }
+    let u = 10 * t;
+    -m(exp2(u - 10 * ONE), sin64(m(u - 704_512, ELASTIC_C4)))
+}
+
+fn elastic_out(t: i64) -> i64 {
+    if t == 0 || t == ONE {
+        return t;
+    }
+    let u = 10 * t;
+    m(exp2(-u), sin64(m(u - 49_152, ELASTIC_C4))) + ONE
+}
+
+fn elastic_in_out(t: i64) -> i64 {
+    if t == 0 || t == ONE {
+        return t;
+    }
+    let u = 20 * t;
+    let s = sin64(m(u - 729_088, ELASTIC_C5));
+    if t < HALF {
+        -m(exp2(u - 10 * ONE), s) / 2
+    } else {
+        m(exp2(10 * ONE - u), s) / 2 + ONE
+    }
+}
+
+/// Piecewise-parabolic bounce, written in u = 2.75 t
+fn bounce_out(t: i64) -> i64 {
+    let u = m(t, BOUNCE_D1);
+    if u < ONE {
+        p(u, 2)
+    } else if u < 2 * ONE {
+        p(u - 98_304, 2) + 49_152
+    } else if u < 163_840 {
+        p(u - 147_456, 2) + 61_440
+    } else {
+        p(u - 172_032, 2) + 64_512
+    }
+}
+
+fn bounce_in(t: i64) -> i64 {
+    ONE - bounce_out(ONE - t)
+}
+
+fn bounce_in_out(t: i64) -> i64 {
+    if t < HALF {
+        (ONE - bounce_out(ONE - 2 * t)) / 2
+    } else {
+        (ONE + bounce_out(2 * t - ONE)) / 2
+    }
+}
+
+/// Evaluate an easing curve at t
+///
+/// t is Q16.16 and clamped to [0, 1.0]. Powers are formed exactly and
+/// truncated once; the remaining products use `mul`-style truncation in a
+/// fixed order, and the elastic curves use the crate's `exp` and `sin`,
+/// so the whole suite is bit-exact.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn ease(kind: Easing, t: i32) -> i32 {
+    let t = (t as i64).clamp(0, ONE);
+    let y = match kind {
+        Easing::Linear => t,
+        Easing::QuadIn => power_in(t, 2),
+        Easing::QuadOut => power_out(t, 2),
+        Easing::QuadInOut => power_in_out(t, 2),
+        Easing::CubicIn => power_in(t, 3),
+        Easing::CubicOut => power_out(t, 3),
+        Easing::CubicInOut => power_in_out(t, 3),
+        Easing::QuartIn => power_in(t, 4),
+        Easing::QuartOut => power_out(t, 4),
+        Easing::QuartInOut => power_in_out(t, 4),
+        Easing::BackIn => back_in(t),
+        Easing::BackOut => back_out(t),
+        Easing::BackInOut => back_in_out(t),
+        Easing::ElasticIn => elastic_in(t),
+        Easing::ElasticOut => elastic_out(t),
+        Easing::ElasticInOut => elastic_in_out(t),
+        Easing::BounceIn => bounce_in(t),
+        Easing::BounceOut => bounce_out(t),
+        Easing::BounceInOut => bounce_in_out(t),
+    };
+    y as i32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALL: [Easing; 19] = [
+        Easing::Linear,
+        Easing::QuadIn,
+        Easing::QuadOut,
+        Easing::QuadInOut,
+        Easing::CubicIn,
+        Easing::CubicOut,
+        Easing::CubicInOut,
+        Easing::QuartIn,
+        Easing::QuartOut,
+        Easing::QuartInOut,
+        Easing::BackIn,
+        Easing::BackOut,
+        Easing::BackInOut,
+        Easing::ElasticIn,
+        Easing::ElasticOut,
+        Easing::ElasticInOut,
+        Easing::BounceIn,
+        Easing::BounceOut,
+        Easing::BounceInOut,
+    ];
+
+    /// easings.net reference formulas
+    fn reference(kind: Easing, t: f64) -> f64 {
+        let bounce_out = |t: f64| {
+            let (n1, d1) = (7.5625, 2.75);
+            if t < 1.0 / d1 {
+                n1 * t * t
+            } else if t < 2.0 / d1 {
+                let t = t - 1.5 / d1;
+                n1 * t * t + 0.75
+            } else if t < 2.5 / d1 {
+                let t = t - 2.25 / d1;
+                n1 * t * t + 0.9375
+            } else {
+                let t = t - 2.625 / d1;
+                n1 * t * t + 0.984375
+            }
+        };
+        let (c1, c3) = (1.70158, 2.70158);
+        match kind {
+            Easing::QuadInOut if t < 0.5 => 2.0 * t * t,
+            Easing::QuadInOut => 1.0 - (-2.0 * t + 2.0).powi(2) / 2.0,
+            Easing::CubicOut => 1.0 - (1.0 - t).powi(3),
+            Easing::BackIn => c3 * t * t * t - c1 * t * t,
+            Easing::BackOut => 1.0 + c3 * (t - 1.0).powi(3) + c1 * (t - 1.0).powi(2),
+            Easing::BounceOut => bounce_out(t),
+            Easing::BounceIn => 1.0 - bounce_out(1.0 - t),
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn every_curve_hits_both_endpoints() {
+        for kind in ALL {
+            assert_eq!(ease(kind, 0), 0, "{kind:?}");
+            assert_eq!(ease(kind, ONE as i32), ONE as i32, "{kind:?}");
+            // t is clamped
+            assert_eq!(ease(kind, -5), 0, "{kind:?}");
+            assert_eq!(ease(kind, 3 * ONE as i32), ONE as i32, "{kind:?}");
+        }
+    }
+
+    #[test]
+    fn curves_match_reference_formulas() {
+        let kinds = [
+            Easing::QuadInOut,
+            Easing::CubicOut,
+            Easing::BackIn,
+            Easing::BackOut,
+            Easing::BounceOut,
+            Easing::BounceIn,
+        ];
+        for kind in kinds {
+            for t in (0..=ONE as i32).step_by(331) {
+                let want = reference(kind, t as f64 / 65536.0) * 65536.0;
+                assert!((ease(kind, t) as f64 - want).abs() <= 8.0, "{kind:?} at {t}");
+            }
+        }
+    }
+
+    #[test]
+    fn back_and_elastic_overshoot() {
+        assert!(ease(Easing::BackIn, (ONE / 4) as i32) < 0);
+        assert!(ease(Easing::BackOut, (3 * ONE / 4) as i32) > ONE as i32);
+        assert!(ease(Easing::ElasticOut, (ONE / 10) as i32) > ONE as i32);
+    }
+}
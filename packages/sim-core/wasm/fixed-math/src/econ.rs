@@ -0,0 +1,357 @@
+//! Economy curves: upgrade costs, idle income and XP levels
+//!
+//! Purchase k (counting from 0) of an upgrade costs base * growth^k.
+//! Costs are Q16.16 values held in i64 (BigInt in JS), since base times a
+//! large growth factor outgrows the i32 range long before the factor
+//! itself does. Bulk totals are defined as the sum of the single-purchase
+//! costs, so "buy 10" and "buy max" always charge exactly what ten
+//! separate purchases would. Idle income is summed in closed form with
+//! the same exact integer result as ticking one step at a time.
+//!
+//! XP curves use the same summed-cost model: the XP needed for a level is
+//! the sum of the per-level costs before it, so the level for a total is
+//! simply how many costs it covers and the inverse can never disagree
+//! with the forward curve.
+
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+use crate::exp::powi;
+use crate::flags::{self, StatusFlag};
+
+/// Cost of purchase n: (base * powi(growth, n)) >> 16
+///
+/// growth^n comes from `powi`, so it saturates (raising
+/// `StatusFlag::Overflow`) beyond the Q16.16 range; the product with base
+/// is exact in i64 and floored once.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn geom_cost(base: i32, growth: i32, n: u32) -> i64 {
+    let factor = powi(growth, n.min(i32::MAX as u32) as i32);
+    (base as i64 * factor as i64) >> 16
+}
+
+/// First purchase from which growth^n is pinned
+///
+/// `powi` squares growth repeatedly, and the squares settle on one of
+/// their fixed points (the 2^31 clamp for |growth| > 1.0, 0 below 1.0,
+/// or 1.0 itself) within 20 steps. Once the square for bit j has
+/// settled, every n >= 2^j gets the same factor up to the sign of
+/// growth^n, so from there purchase costs repeat with period 2.
+fn settled_from(growth: i32) -> u32 {
+    let limit = 1i64 << 31;
+    let mut b = growth as i64;
+    for j in 0..32 {
+        let next = ((b * b) >> 16).clamp(-limit, limit);
+        if next == b {
+            return 1 << j;
+        }
+        b = next;
+    }
+    u32::MAX
+}
+
+/// Sum of `geom_cost` over lo..hi when the costs repeat with period 2
+fn repeating_sum(base: i32, growth: i32, lo: u32, hi: u32) -> i128 {
+    if lo >= hi {
+        return 0;
+    }
+    let m = (hi - lo) as i128;
+    let first = geom_cost(base, growth, lo) as i128;
+    let second = if m > 1 { geom_cost(base, growth, lo + 1) as i128 } else { 0 };
+    first * ((m + 1) / 2) + second * (m / 2)
+}
+
+/// Total cost of purchases from..to (to exclusive)
+///
+/// Exactly the sum of `geom_cost` over the range, so it never drifts from
+/// buying one at a time. Purchases are summed one `powi` at a time only
+/// until growth^n settles (at most 2^20 of them); the repeating costs
+/// after that are summed in closed form. An empty or inverted range
+/// costs 0. A total beyond the i64 range saturates and raises
+/// `StatusFlag::Overflow`.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn geom_sum(base: i32, growth: i32, from: u32, to: u32) -> i64 {
+    let settled = settled_from(growth).clamp(from, to.max(from));
+    let mut total: i128 = (from..settled).map(|k| geom_cost(base, growth, k) as i128).sum();
+    // geom_cost pins n at i32::MAX, which ends the alternation
+    let pinned = 1u32 << 31;
+    total += repeating_sum(base, growth, settled, to.min(pinned).max(settled));
+    total += repeating_sum(base, growth, settled.max(pinned), to);
+    match i64::try_from(total) {
+        Ok(t) => t,
+        Err(_) => {
+            flags::raise(StatusFlag::Overflow);
+            if total < 0 { i64::MIN } else { i64::MAX }
+        }
+    }
+}
+
+/// Resources earned over elapsed_ticks of idle time
+///
+/// Tick k (from 0) earns rate_fp + k * growth_fp, i.e. the reference loop
+/// `total += rate; rate += growth` starting from rate_fp. The closed form
+/// n * rate_fp + growth_fp * n(n - 1) / 2 is evaluated in 128-bit, so it
+/// equals that loop exactly for any tick count; only a total beyond the
+/// i64 range differs, saturating and raising `StatusFlag::Overflow`.
+/// Q16.16 result in i64 (BigInt).
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn integrate_idle(rate_fp: i32, growth_fp: i32, elapsed_ticks: u32) -> i64 {
+    let n = elapsed_ticks as i128;
+    let total = n * rate_fp as i128 + growth_fp as i128 * (n * (n - 1) / 2);
+    match i64::try_from(total) {
+        Ok(t) => t,
+        Err(_) => {
+            flags::raise(StatusFlag::Overflow);
+            if total < 0 { i64::MIN } else { i64::MAX }
+        }
+    }
+}
+
+/// Highest level an `XpCurve` can reach
+///
+/// Bounds the per-level walk in `xp_for_level` and `level_for_xp` at
+/// 65536 costs; a larger `max_level` is clamped to it.
+pub const MAX_XP_LEVEL: u32 = 1 << 16;
+
+/// Designer parameters for an XP curve (all Q16.16)
+///
+/// Going from level k + 1 to k + 2 (from 0) costs
+/// max(geom_cost(base, growth, k) + linear * k + quadratic * k², 0),
+/// so growth = 1.0 with linear or quadratic terms gives polynomial totals
+/// and growth > 1.0 an exponential curve.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct XpCurve {
+    /// Cost of the first level-up
+    pub base: i32,
+    /// Per-level multiplier on base (1.0 = none)
+    pub growth: i32,
+    /// Extra cost added per level
+    pub linear: i32,
+    /// Extra cost added per level squared
+    pub quadratic: i32,
+    /// Highest reachable level (at least 1, at most `MAX_XP_LEVEL`)
+    pub max_level: u32,
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl XpCurve {
+    #[cfg_attr(feature = "wasm", wasm_bindgen(constructor))]
+    pub fn new(base: i32, growth: i32, linear: i32, quadratic: i32, max_level: u32) -> XpCurve {
+        XpCurve { base, growth, linear, quadratic, max_level }
+    }
+}
+
+impl XpCurve {
+    /// Cost of level-up k (from 0), never negative
+    fn level_cost(&self, k: u32) -> i128 {
+        let k = k as i128;
+        let cost = geom_cost(self.base, self.growth, k as u32) as i128
+            + self.linear as i128 * k
+            + self.quadratic as i128 * k * k;
+        cost.max(0)
+    }
+
+    fn top(&self) -> u32 {
+        self.max_level.clamp(1, MAX_XP_LEVEL)
+    }
+}
+
+/// Narrow a 128-bit XP total to i64, raising Overflow when it clamps
+fn saturate_xp(total: i128) -> i64 {
+    match i64::try_from(total) {
+        Ok(t) => t,
+        Err(_) => {
+            flags::raise(StatusFlag::Overflow);
+            i64::MAX
+        }
+    }
+}
+
+/// Total XP needed to reach `level` (Q16.16 in i64)
+///
+/// Level 1 (and 0) needs 0; levels above `max_level` (or `MAX_XP_LEVEL`)
+/// are clamped to it.
+/// Costs are never negative, so the total never decreases with level.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn xp_for_level(curve: &XpCurve, level: u32) -> i64 {
+    let level = level.min(curve.top());
+    let total: i128 = (0..level.saturating_sub(1)).map(|k| curve.level_cost(k)).sum();
+    saturate_xp(total)
+}
+
+/// Level reached with `total` XP: the largest level whose
+/// `xp_for_level` is at most total, capped at `max_level` (and
+/// `MAX_XP_LEVEL`)
+///
+/// Walks the same per-level costs as `xp_for_level`, so a level-up
+/// happens at exactly the XP the forward curve reports. Always at least
+/// 1, even for a negative total.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn level_for_xp(curve: &XpCurve, total: i64) -> u32 {
+    let total = total as i128;
+    let mut need = 0i128;
+    let mut level = 1;
+    while level < curve.top() {
+        need += curve.level_cost(level - 1);
+        // Compare the total as xp_for_level reports it (saturated)
+        if need.min(i64::MAX as i128) > total {
+            break;
+        }
+        level += 1;
+    }
+    level
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::flags::{clear_flags, get_flags};
+
+    const ONE: i32 = 1 << 16;
+    const ONE64: i64 = 1 << 16;
+
+    #[test]
+    fn geom_cost_grows_by_powi() {
+        assert_eq!(geom_cost(10 * ONE, ONE, 50), 10 * ONE64);
+        assert_eq!(geom_cost(10 * ONE, 2 * ONE, 0), 10 * ONE64);
+        assert_eq!(geom_cost(10 * ONE, 2 * ONE, 3), 80 * ONE64);
+        // Past the i32 range the cost is still exact
+        assert_eq!(geom_cost(1000 * ONE, 2 * ONE, 14), 16_384_000 * ONE64);
+        for n in 0..20 {
+            let want = (10 * ONE as i64 * powi(3 * ONE / 2, n) as i64) >> 16;
+            assert_eq!(geom_cost(10 * ONE, 3 * ONE / 2, n as u32), want);
+        }
+        clear_flags();
+        assert_eq!(geom_cost(ONE, 2 * ONE, 15), i32::MAX as i64);
+        assert_eq!(get_flags(), StatusFlag::Overflow as u32);
+    }
+
+    #[test]
+    fn geom_sum_equals_single_purchases() {
+        assert_eq!(geom_sum(10 * ONE, 2 * ONE, 0, 4), 150 * ONE64);
+        assert_eq!(geom_sum(10 * ONE, 2 * ONE, 4, 4), 0);
+        assert_eq!(geom_sum(10 * ONE, 2 * ONE, 5, 2), 0);
+        let (base, growth) = (7 * ONE + 123, ONE + ONE / 7);
+        let single: i64 = (3..30).map(|k| geom_cost(base, growth, k)).sum();
+        assert_eq!(geom_sum(base, growth, 3, 30), single);
+        // Splitting a bulk buy never changes the total
+        assert_eq!(geom_sum(base, growth, 3, 12) + geom_sum(base, growth, 12, 30), single);
+    }
+
+    #[test]
+    fn geom_sum_saturates() {
+        clear_flags();
+        assert_eq!(geom_sum(i32::MAX, i32::MAX, 0, 1 << 18), i64::MAX);
+        assert_eq!(geom_sum(i32::MIN, i32::MAX, 0, 1 << 18), i64::MIN);
+        assert_eq!(get_flags(), StatusFlag::Overflow as u32);
+    }
+
+    #[test]
+    fn geom_sum_closed_form_tail_matches_single_purchases() {
+        let base = 3 * ONE + 5;
+        for growth in [ONE + ONE / 16, ONE - ONE / 16, -(ONE + ONE / 16), -ONE, ONE, -ONE / 2, 0, 3 * ONE] {
+            let settled = settled_from(growth);
+            let (lo, hi) = (settled.saturating_sub(40), settled + 41);
+            let single: i64 = (lo..hi).map(|k| geom_cost(base, growth, k)).sum();
+            assert_eq!(geom_sum(base, growth, lo, hi), single, "growth {growth}");
+        }
+        // geom_cost pins n at i32::MAX, so the alternation stops at 2^31
+        let (lo, hi) = ((1 << 31) - 5, (1 << 31) + 5);
+        let single: i64 = (lo..hi).map(|k| geom_cost(base, -3 * ONE, k)).sum();
+        assert_eq!(geom_sum(base, -3 * ONE, lo, hi), single);
+        // The whole u32 range without a per-purchase loop
+        assert_eq!(geom_sum(ONE, ONE, 0, u32::MAX), u32::MAX as i64 * ONE64);
+        assert_eq!(geom_sum(ONE, -ONE, 0, u32::MAX), -((1 << 31) - 1) * ONE64);
+    }
+
+    #[test]
+    fn idle_income_matches_the_reference_loop() {
+        for (rate, growth) in [(ONE, 0), (3 * ONE / 2, ONE / 3), (-ONE, 7), (i32::MAX, i32::MAX), (i32::MIN, -1)] {
+            let (mut total, mut r) = (0i64, rate as i64);
+            for n in 0..200u32 {
+                assert_eq!(integrate_idle(rate, growth, n), total, "{rate} {growth} {n}");
+                total += r;
+                r += growth as i64;
+            }
+        }
+        // One day of 60 Hz ticks at 2.5/tick, growing 1 LSB per tick
+        let n = 60 * 60 * 60 * 24i64;
+        assert_eq!(integrate_idle(5 * ONE / 2, 1, n as u32), n * (5 * ONE64 / 2) + n * (n - 1) / 2);
+    }
+
+    #[test]
+    fn idle_income_saturates() {
+        clear_flags();
+        assert_eq!(integrate_idle(ONE, ONE, 0), 0);
+        assert_eq!(get_flags(), 0);
+        assert_eq!(integrate_idle(i32::MAX, i32::MAX, u32::MAX), i64::MAX);
+        assert_eq!(integrate_idle(i32::MIN, i32::MIN, u32::MAX), i64::MIN);
+        assert_eq!(get_flags(), StatusFlag::Overflow as u32);
+    }
+
+    #[test]
+    fn xp_totals_sum_the_level_costs() {
+        // Linear: level-up k costs 100 + 10k
+        let curve = XpCurve::new(100 * ONE, ONE, 10 * ONE, 0, 50);
+        assert_eq!(xp_for_level(&curve, 0), 0);
+        assert_eq!(xp_for_level(&curve, 1), 0);
+        assert_eq!(xp_for_level(&curve, 2), 100 * ONE64);
+        assert_eq!(xp_for_level(&curve, 4), 330 * ONE64);
+        // Clamped to max_level
+        assert_eq!(xp_for_level(&curve, 99), xp_for_level(&curve, 50));
+
+        // Exponential with a quadratic term
+        let curve = XpCurve::new(50 * ONE, 6 * ONE / 5, 0, ONE / 2, 30);
+        let mut total = 0i64;
+        for level in 1..=30 {
+            assert_eq!(xp_for_level(&curve, level), total);
+            let k = level as i64 - 1;
+            total += geom_cost(50 * ONE, 6 * ONE / 5, k as u32) + (ONE64 / 2) * k * k;
+        }
+    }
+
+    #[test]
+    fn level_for_xp_inverts_xp_for_level() {
+        let curves = [
+            XpCurve::new(100 * ONE, ONE, 10 * ONE, 0, 50),
+            XpCurve::new(50 * ONE, 6 * ONE / 5, 0, ONE / 2, 30),
+            // Negative terms clamp each cost to 0, giving free levels
+            XpCurve::new(10 * ONE, ONE, -4 * ONE, 0, 8),
+        ];
+        for curve in curves {
+            for level in 1..=curve.max_level {
+                let need = xp_for_level(&curve, level);
+                let top = (level..=curve.max_level).rev().find(|&l| xp_for_level(&curve, l) == need).unwrap();
+                assert_eq!(level_for_xp(&curve, need), top);
+                if need > 0 {
+                    assert!(level_for_xp(&curve, need - 1) < level);
+                }
+            }
+            assert_eq!(level_for_xp(&curve, i64::MAX), curve.max_level);
+            assert_eq!(level_for_xp(&curve, -5), 1);
+        }
+    }
+
+    #[test]
+    fn xp_curve_edge_cases() {
+        // max_level 0 behaves as 1
+        let flat = XpCurve::new(ONE, ONE, 0, 0, 0);
+        assert_eq!(xp_for_level(&flat, 10), 0);
+        assert_eq!(level_for_xp(&flat, 1000), 1);
+
+        // Totals beyond i64 saturate, and the inverse compares against the
+        // saturated totals
+        let steep = XpCurve::new(i32::MAX, i32::MAX, i32::MAX, i32::MAX, 1 << 16);
+        clear_flags();
+        assert_eq!(xp_for_level(&steep, 1 << 16), i64::MAX);
+        assert_eq!(get_flags(), StatusFlag::Overflow as u32);
+        assert_eq!(level_for_xp(&steep, i64::MAX), 1 << 16);
+        assert!(level_for_xp(&steep, i64::MAX - 1) < 1 << 16);
+
+        // max_level beyond MAX_XP_LEVEL is clamped
+        let long = XpCurve::new(ONE, ONE, 0, 0, u32::MAX);
+        assert_eq!(xp_for_level(&long, u32::MAX), (MAX_XP_LEVEL - 1) as i64 * ONE64);
+        assert_eq!(level_for_xp(&long, i64::MAX), MAX_XP_LEVEL);
+    }
+}
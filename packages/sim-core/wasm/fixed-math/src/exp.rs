@@ -0,0 +1,432 @@
+//! Exponential and logarithm for Q16.16
+//!
+//! Both functions split the argument into a power of two and a small
+//! remainder, evaluate a short series on the remainder in Q2.30 with
+//! 64-bit intermediates, and round back to Q16.16 once at the end.
+
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+use crate::flags::{self, StatusFlag};
+use crate::{q16_to_q30, q30_to_q16, saturate_i64, Q30_FRAC_BITS};
+
+/// 1.0 in Q16.16
+const ONE_Q16: i64 = 1 << 16;
+
+/// 1.0 in Q2.30
+const ONE_Q30: i64 = 1 << Q30_FRAC_BITS;
+
+/// ln(2) in Q2.30
+pub(crate) const LN2_Q30: i64 = 744_261_118;
+
+/// log10(2) in Q2.30
+const LOG10_2_Q30: i64 = 323_228_497;
+
+/// Number of Taylor terms for exp(r), r in [0, ln 2)
+///
+/// ln(2)^14 / 14! < 2^-40, far below one Q2.30 LSB.
+const EXP_TERMS: i64 = 14;
+
+/// Highest odd power used by the atanh series for ln(m), m in [1, 2)
+///
+/// s = (m - 1) / (m + 1) < 1/3, so s^23 / 23 < 2^-40.
+const LN_MAX_POWER: i64 = 23;
+
+/// exp(r) for r in [0, ln 2) in Q2.30, result in [1, 2)
+fn exp_reduced_q30(r: i64) -> i64 {
+    let mut term = ONE_Q30;
+    let mut sum = ONE_Q30;
+    for n in 1..=EXP_TERMS {
+        term = ((term * r) >> Q30_FRAC_BITS) / n;
+        sum += term;
+    }
+    sum
+}
+
+/// ln(m) for m in [1, 2) given in Q2.30, result in Q2.30
+///
+/// Uses ln(m) = 2 * atanh(s) = 2 * (s + s³/3 + s⁵/5 + ...),
+/// s = (m - 1) / (m + 1).
+pub(crate) fn ln_mantissa_q30(m: i64) -> i64 {
+    let s = ((m - ONE_Q30) << Q30_FRAC_BITS) / (m + ONE_Q30);
+    let s2 = (s * s) >> Q30_FRAC_BITS;
+
+    let mut power = s;
+    let mut acc = 0i64;
+    let mut n = 1;
+    while n <= LN_MAX_POWER {
+        acc += power / n;
+        power = (power * s2) >> Q30_FRAC_BITS;
+        n += 2;
+    }
+    acc * 2
+}
+
+/// Split a positive Q16.16 value into (e, m) with x = m * 2^e
+///
+/// m is returned in Q2.30 and lies in [1, 2); e is the unbiased binary
+/// exponent taken from the leading-zero count.
+pub(crate) fn split_mantissa(fp: i32) -> (i32, i64) {
+    let top_bit = 31 - (fp as u32).leading_zeros() as i32;
+    let m = (fp as i64) << (Q30_FRAC_BITS as i32 - top_bit);
+    (top_bit - 16, m)
+}
+
+/// e^x for a Q2.30 argument, rounded to nearest Q16.16
+///
+/// Shared by `exp` and `pow` so both use the same reduction and the
+/// same final rounding step.
+pub(crate) fn exp_q30(x30: i64) -> i32 {
+    let k = x30.div_euclid(LN2_Q30);
+    let r = x30 - k * LN2_Q30;
+
+    // e^r * 2^k >= 2^15 does not fit in Q16.16
+    if k >= 15 {
+        flags::raise(StatusFlag::Overflow);
+        return i32::MAX;
+    }
+
+    let sum = exp_reduced_q30(r);
+    let shift = (Q30_FRAC_BITS as i64 - 16) - k;
+    if shift > 32 {
+        return 0;
+    }
+    if shift <= 0 {
+        return saturate_i64(sum << -shift);
+    }
+    ((sum + (1 << (shift - 1))) >> shift) as i32
+}
+
+/// Natural exponential e^x of a Q16.16 value
+///
+/// Range reduction: x = k * ln2 + r with 0 <= r < ln2, then
+/// e^x = e^r * 2^k. The result is rounded to nearest.
+///
+/// Saturation: results above the Q16.16 range (x > ~10.3972) return
+/// i32::MAX and raise `StatusFlag::Overflow`; results below half an LSB
+/// (x < ~-11.78) return 0.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn exp(x: i32) -> i32 {
+    exp_q30(q16_to_q30(x))
+}
+
+/// Result of a logarithm of a non-positive value
+fn log_domain_error() -> i32 {
+    flags::raise(StatusFlag::Domain);
+    i32::MIN
+}
+
+/// ln(x) of a positive Q16.16 value in Q2.30
+pub(crate) fn ln_q30(x: i32) -> i64 {
+    let (e, m) = split_mantissa(x);
+    e as i64 * LN2_Q30 + ln_mantissa_q30(m)
+}
+
+/// Natural logarithm ln(x) of a Q16.16 value
+///
+/// x = m * 2^e with m in [1, 2) from the leading-zero count, then
+/// ln(x) = e * ln2 + ln(m). The result is rounded to nearest.
+///
+/// Domain: x <= 0 returns i32::MIN (saturated -∞) and raises
+/// `StatusFlag::Domain`.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn ln(x: i32) -> i32 {
+    if x <= 0 {
+        return log_domain_error();
+    }
+
+    q30_to_q16(ln_q30(x))
+}
+
+/// log2(x) of a positive Q16.16 value in Q2.30
+///
+/// Integer part from the leading-zero count; fractional bits by
+/// repeated squaring of the mantissa (each square that lands in [2, 4)
+/// contributes one bit and is halved back into [1, 2)).
+fn log2_q30(x: i32) -> i64 {
+    let (e, mut m) = split_mantissa(x);
+
+    let mut frac = 0i64;
+    for bit in (0..Q30_FRAC_BITS).rev() {
+        m = (m * m) >> Q30_FRAC_BITS;
+        if m >= 2 * ONE_Q30 {
+            m >>= 1;
+            frac |= 1 << bit;
+        }
+    }
+
+    ((e as i64) << Q30_FRAC_BITS) + frac
+}
+
+/// Base-2 logarithm of a Q16.16 value
+///
+/// Exact for powers of two. The result is rounded to nearest.
+///
+/// Domain: x <= 0 returns i32::MIN (saturated -∞) and raises
+/// `StatusFlag::Domain`.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn log2(x: i32) -> i32 {
+    if x <= 0 {
+        return log_domain_error();
+    }
+
+    q30_to_q16(log2_q30(x))
+}
+
+/// Base-10 logarithm of a Q16.16 value
+///
+/// Computed as log2(x) * log10(2) from two Q2.30 factors; the Q4.60
+/// product is rounded to nearest Q16.16 once (ties toward +∞), with no
+/// intermediate Q2.30 rounding.
+///
+/// Domain: x <= 0 returns i32::MIN (saturated -∞) and raises
+/// `StatusFlag::Domain`.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn log10(x: i32) -> i32 {
+    if x <= 0 {
+        return log_domain_error();
+    }
+
+    // |log2| < 2^35 and log10(2) < 2^29 in Q2.30, so the product fits i64
+    let shift = 2 * Q30_FRAC_BITS - 16;
+    ((log2_q30(x) * LOG10_2_Q30 + (1 << (shift - 1))) >> shift) as i32
+}
+
+/// Largest |x| in Q2.30 passed to `exp_q30` from `pow`
+///
+/// Anything beyond ±64 has already saturated to i32::MAX or 0, and
+/// clamping keeps the range reduction free of i64 overflow.
+const POW_EXP_LIMIT_Q30: i64 = 64 << Q30_FRAC_BITS;
+
+/// Raise a Q16.16 base to a Q16.16 exponent
+///
+/// base^e = exp(e * ln(base)), with ln kept in Q2.30 and the product
+/// formed in 128 bits so only the final exp rounds to Q16.16.
+///
+/// Defined results:
+/// - pow(x, 0) = 1.0 for every x (including 0^0)
+/// - pow(0, e > 0) = 0, pow(0, e < 0) is a division by zero (i32::MAX
+///   under the default policy)
+/// - negative base with an integral exponent uses `powi`
+/// - negative base with a fractional exponent has no real result,
+///   returns 0 and raises `StatusFlag::Domain`
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn pow(base: i32, exponent: i32) -> i32 {
+    if exponent == 0 {
+        return ONE_Q16 as i32;
+    }
+    if base == 0 {
+        return if exponent > 0 { 0 } else { crate::div_by_zero(false) };
+    }
+    if base < 0 {
+        if exponent & 0xFFFF == 0 {
+            return powi(base, exponent >> 16);
+        }
+        flags::raise(StatusFlag::Domain);
+        return 0;
+    }
+
+    let y = (ln_q30(base) as i128 * exponent as i128) >> 16;
+    let y = y.clamp(-POW_EXP_LIMIT_Q30 as i128, POW_EXP_LIMIT_Q30 as i128) as i64;
+    exp_q30(y)
+}
+
+/// Raise a Q16.16 base to an integer power
+///
+/// Exponentiation by squaring with 64-bit intermediates; every multiply
+/// truncates like `mul`, in the fixed right-to-left bit order, so the
+/// result is fully deterministic. Magnitudes are clamped to 2^31 between
+/// steps (an overflowed intermediate can only grow), and the final value
+/// saturates to i32::MAX/MIN with the sign of base^n.
+///
+/// Negative n returns 1 / base^|n| via `div` (same division-by-zero
+/// saturation). powi(x, 0) = 1.0.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn powi(base: i32, n: i32) -> i32 {
+    if n == 0 {
+        return ONE_Q16 as i32;
+    }
+
+    let limit = 1i64 << 31;
+    let mut remaining = n.unsigned_abs();
+    let mut acc = ONE_Q16;
+    let mut b = base as i64;
+    loop {
+        if remaining & 1 == 1 {
+            acc = ((acc * b) >> 16).clamp(-limit, limit);
+        }
+        remaining >>= 1;
+        if remaining == 0 {
+            break;
+        }
+        b = ((b * b) >> 16).clamp(-limit, limit);
+    }
+
+    let result = saturate_i64(acc);
+    if n < 0 {
+        crate::div(ONE_Q16 as i32, result)
+    } else {
+        result
+    }
+}
+
+/// e^x for x <= 0 with the result kept in Q2.30
+///
+/// Used where Q16.16 output precision is not enough for the next step
+/// (tanh, sigmoid). Rounded to nearest at the 2^k shift.
+pub(crate) fn exp_nonpos_q30(x30: i64) -> i64 {
+    let k = x30.div_euclid(LN2_Q30);
+    let r = x30 - k * LN2_Q30;
+    let shift = -k;
+    if shift >= 62 {
+        return 0;
+    }
+
+    let sum = exp_reduced_q30(r);
+    if shift == 0 {
+        return sum;
+    }
+    (sum + (1 << (shift - 1))) >> shift
+}
+
+/// Hyperbolic tangent of a Q16.16 value
+///
+/// tanh(|x|) = (1 - e^(-2|x|)) / (1 + e^(-2|x|)) in Q2.30, rounded to
+/// nearest, then the sign applied, so tanh(-x) == -tanh(x) exactly.
+/// Result lies in [-1.0, 1.0].
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn tanh(x: i32) -> i32 {
+    let ax = q16_to_q30(x).abs();
+    let e = exp_nonpos_q30(-2 * ax);
+    let magnitude = q30_to_q16(((ONE_Q30 - e) << Q30_FRAC_BITS) / (ONE_Q30 + e));
+    if x < 0 {
+        -magnitude
+    } else {
+        magnitude
+    }
+}
+
+/// Logistic sigmoid 1 / (1 + e^(-x)) of a Q16.16 value
+///
+/// Evaluated for |x| in Q2.30 and rounded to nearest; negative inputs
+/// use sigmoid(-x) = 1 - sigmoid(x) on the rounded value, so the curve is
+/// exactly point-symmetric around (0, 0.5). Result lies in [0, 1.0].
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn sigmoid(x: i32) -> i32 {
+    let ax = q16_to_q30(x).abs();
+    let e = exp_nonpos_q30(-ax);
+    let upper = q30_to_q16((ONE_Q30 << Q30_FRAC_BITS) / (ONE_Q30 + e));
+    if x < 0 {
+        ONE_Q16 as i32 - upper
+    } else {
+        upper
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::flags::{clear_flags, get_flags};
+
+    const ONE: i32 = ONE_Q16 as i32;
+
+    fn f(x: i32) -> f64 {
+        x as f64 / 65536.0
+    }
+
+    /// Error in LSB, relative to the result once it passes 1.0
+    fn scaled_error(got: i32, want: f64) -> f64 {
+        (got as f64 - want * 65536.0).abs() / want.max(1.0)
+    }
+
+    #[test]
+    fn exp_matches_f64() {
+        assert_eq!(exp(0), ONE);
+        for x in (-11 * ONE..10 * ONE).step_by(97) {
+            assert!(scaled_error(exp(x), f(x).exp()) <= 1.0, "exp({})", f(x));
+        }
+    }
+
+    #[test]
+    fn exp_saturates() {
+        clear_flags();
+        assert_eq!(exp(-12 * ONE), 0);
+        assert_eq!(get_flags(), 0);
+        assert_eq!(exp(11 * ONE), i32::MAX);
+        assert_eq!(get_flags(), StatusFlag::Overflow as u32);
+    }
+
+    #[test]
+    fn logs_match_f64() {
+        for x in (1..i32::MAX).step_by(99_991) {
+            assert!((ln(x) as f64 - f(x).ln() * 65536.0).abs() <= 1.0);
+            assert!((log2(x) as f64 - f(x).log2() * 65536.0).abs() <= 1.0);
+            assert!((log10(x) as f64 - f(x).log10() * 65536.0).abs() <= 1.0);
+        }
+    }
+
+    #[test]
+    fn logs_are_exact_at_powers() {
+        assert_eq!(ln(ONE), 0);
+        assert_eq!(log2(8 * ONE), 3 * ONE);
+        assert_eq!(log2(ONE / 4), -2 * ONE);
+        assert_eq!(log10(100 * ONE), 2 * ONE);
+    }
+
+    #[test]
+    fn log_of_non_positive_is_a_domain_error() {
+        clear_flags();
+        assert_eq!(ln(0), i32::MIN);
+        assert_eq!(log2(-ONE), i32::MIN);
+        assert_eq!(get_flags(), StatusFlag::Domain as u32);
+    }
+
+    #[test]
+    fn pow_matches_f64() {
+        for b in (1..20 * ONE).step_by(7919) {
+            for e in (-3 * ONE..3 * ONE).step_by(6151) {
+                let want = f(b).powf(f(e));
+                if want * 65536.0 < i32::MAX as f64 {
+                    assert!(scaled_error(pow(b, e), want) <= 1.0, "pow({}, {})", f(b), f(e));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn pow_special_cases() {
+        assert_eq!(pow(0, 0), ONE);
+        assert_eq!(pow(0, ONE), 0);
+        assert_eq!(pow(-2 * ONE, 3 * ONE), -8 * ONE);
+
+        clear_flags();
+        assert_eq!(pow(-2 * ONE, ONE / 2), 0);
+        assert_eq!(get_flags(), StatusFlag::Domain as u32);
+    }
+
+    #[test]
+    fn powi_squares_deterministically() {
+        assert_eq!(powi(3 * ONE, 0), ONE);
+        assert_eq!(powi(3 * ONE, 4), 81 * ONE);
+        assert_eq!(powi(-2 * ONE, 5), -32 * ONE);
+        assert_eq!(powi(2 * ONE, -2), ONE / 4);
+        assert_eq!(powi(ONE / 2, 17), 0);
+
+        clear_flags();
+        assert_eq!(powi(2 * ONE, 100), i32::MAX);
+        assert_eq!(powi(-2 * ONE, 101), i32::MIN);
+        assert_eq!(get_flags(), StatusFlag::Overflow as u32);
+    }
+
+    #[test]
+    fn tanh_and_sigmoid_are_symmetric() {
+        assert_eq!(tanh(0), 0);
+        assert_eq!(sigmoid(0), ONE / 2);
+        for x in (-12 * ONE..12 * ONE).step_by(313) {
+            assert_eq!(tanh(-x), -tanh(x));
+            assert_eq!(sigmoid(-x), ONE - sigmoid(x));
+            assert!((tanh(x) as f64 - f(x).tanh() * 65536.0).abs() <= 1.0);
+            assert!((sigmoid(x) as f64 - 65536.0 / (1.0 + (-f(x)).exp())).abs() <= 1.0);
+        }
+    }
+}
@@ -0,0 +1,114 @@
+//! Status register
+//!
+//! Math ops OR a bit into this register when something noteworthy
+//! happens (a result did not fit in i32, a division by zero, an input
+//! outside the function's domain). Bits are sticky until `clear_flags`,
+//! so a desync investigation can poll once per tick instead of every op
+//! returning a Result-like struct.
+//!
+//! Wrapping ops never raise flags: their wraparound is intentional.
+//!
+//! Without the `std` feature there are no threads to keep apart, so the
+//! register (and the division-by-zero policy) is a single global instead.
+
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+/// Bits of the status register returned by `get_flags`
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StatusFlag {
+    /// A result was outside the i32 range (wrapped, saturated or sentinel)
+    Overflow = 1,
+    /// A division (or reciprocal) by zero
+    DivByZero = 2,
+    /// An input outside the function's domain (ln of a non-positive
+    /// value, asin of |x| > 1, ...) was clamped or mapped to a sentinel
+    Domain = 4,
+}
+
+/// Define a u32 register `$name` with accessors `$get` and `$set`
+///
+/// std builds keep one value per thread. no_std builds keep one global
+/// that only uses atomic load and store, which every target with 32-bit
+/// atomics supports (unlike read-modify-write). Test builds always link
+/// std and keep the per-thread register, so parallel tests never see
+/// each other's flags.
+macro_rules! register {
+    ($name:ident, $get:ident, $set:ident, $init:expr) => {
+        #[cfg(any(feature = "std", test))]
+        thread_local! {
+            static $name: core::cell::Cell<u32> = const { core::cell::Cell::new($init) };
+        }
+
+        #[cfg(any(feature = "std", test))]
+        fn $get() -> u32 {
+            $name.with(|r| r.get())
+        }
+
+        #[cfg(any(feature = "std", test))]
+        fn $set(v: u32) {
+            $name.with(|r| r.set(v));
+        }
+
+        #[cfg(not(any(feature = "std", test)))]
+        static $name: core::sync::atomic::AtomicU32 = core::sync::atomic::AtomicU32::new($init);
+
+        #[cfg(not(any(feature = "std", test)))]
+        fn $get() -> u32 {
+            $name.load(core::sync::atomic::Ordering::Relaxed)
+        }
+
+        #[cfg(not(any(feature = "std", test)))]
+        fn $set(v: u32) {
+            $name.store(v, core::sync::atomic::Ordering::Relaxed);
+        }
+    };
+}
+pub(crate) use register;
+
+register!(FLAGS, load_flags, store_flags, 0);
+
+/// Set a bit in the status register
+#[inline]
+pub(crate) fn raise(flag: StatusFlag) {
+    store_flags(load_flags() | flag as u32);
+}
+
+/// Current status register (bitwise OR of `StatusFlag` values)
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn get_flags() -> u32 {
+    load_flags()
+}
+
+/// Reset every bit of the status register
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn clear_flags() {
+    store_flags(0);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flags_are_sticky_until_cleared() {
+        clear_flags();
+        raise(StatusFlag::Domain);
+        raise(StatusFlag::Overflow);
+        raise(StatusFlag::Domain);
+        assert_eq!(get_flags(), StatusFlag::Domain as u32 | StatusFlag::Overflow as u32);
+        clear_flags();
+        assert_eq!(get_flags(), 0);
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", ignore = "wasm32 has no threads")]
+    fn register_is_per_thread() {
+        clear_flags();
+        raise(StatusFlag::DivByZero);
+        let other = std::thread::spawn(get_flags).join().unwrap();
+        assert_eq!(other, 0);
+        assert_eq!(get_flags(), StatusFlag::DivByZero as u32);
+    }
+}
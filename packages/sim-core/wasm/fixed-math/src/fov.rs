@@ -0,0 +1,222 @@
+//! Field of view by shadowcasting
+//!
+//! Albert Ford's symmetric shadowcasting: each quadrant is scanned row
+//! by row, and a wall splits the scan recursively into the slope ranges
+//! that remain lit. Slopes are exact fractions of integers, so the
+//! result is bit-identical everywhere and symmetric (if a sees b on open
+//! floor, b sees a), which keeps fog of war consistent between the
+//! authoritative sim and replays.
+
+use alloc::{vec, vec::Vec};
+
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+/// Exact slope num / den with den > 0
+#[derive(Clone, Copy, Debug)]
+struct Slope {
+    num: i64,
+    den: i64,
+}
+
+/// Row at `depth` from the origin, lit between two slopes
+#[derive(Clone, Copy, Debug)]
+struct Row {
+    depth: i64,
+    start: Slope,
+    end: Slope,
+}
+
+impl Row {
+    /// First column: depth * start rounded half up
+    fn min_col(&self) -> i64 {
+        (2 * self.depth * self.start.num + self.start.den).div_euclid(2 * self.start.den)
+    }
+
+    /// Last column: depth * end rounded half down
+    fn max_col(&self) -> i64 {
+        -(self.end.den - 2 * self.depth * self.end.num).div_euclid(2 * self.end.den)
+    }
+
+    /// Whether the column's center lies inside the lit slope range
+    fn is_symmetric(&self, col: i64) -> bool {
+        col * self.start.den >= self.depth * self.start.num && col * self.end.den <= self.depth * self.end.num
+    }
+
+    fn next(&self) -> Row {
+        Row { depth: self.depth + 1, ..*self }
+    }
+}
+
+/// Grid state shared by the scans
+struct Fov<'a> {
+    grid: &'a [u8],
+    width: i64,
+    height: i64,
+    origin: (i64, i64),
+    radius_sq: i64,
+    max_depth: i64,
+    visible: Vec<u32>,
+}
+
+impl Fov<'_> {
+    /// Tile for (depth, col) in one of the four quadrants
+    fn transform(&self, quadrant: u8, depth: i64, col: i64) -> (i64, i64) {
+        let (x, y) = self.origin;
+        match quadrant {
+            0 => (x + col, y - depth),
+            1 => (x + depth, y + col),
+            2 => (x + col, y + depth),
+            _ => (x - depth, y + col),
+        }
+    }
+
+    fn in_bounds(&self, (x, y): (i64, i64)) -> bool {
+        x >= 0 && y >= 0 && x < self.width && y < self.height
+    }
+
+    /// Opaque grid cell; everything outside the grid is opaque too
+    fn is_wall(&self, tile: (i64, i64)) -> bool {
+        !self.in_bounds(tile) || self.grid.get((tile.1 * self.width + tile.0) as usize).is_none_or(|&c| c != 0)
+    }
+
+    fn reveal(&mut self, (x, y): (i64, i64)) {
+        let (dx, dy) = (x - self.origin.0, y - self.origin.1);
+        if self.in_bounds((x, y)) && dx * dx + dy * dy <= self.radius_sq {
+            let i = (y * self.width + x) as usize;
+            self.visible[i / 32] |= 1 << (i % 32);
+        }
+    }
+
+    fn scan(&mut self, quadrant: u8, mut row: Row) {
+        if row.depth > self.max_depth {
+            return;
+        }
+        let mut prev_wall: Option<bool> = None;
+        for col in row.min_col()..=row.max_col() {
+            let tile = self.transform(quadrant, row.depth, col);
+            let wall = self.is_wall(tile);
+            if wall || row.is_symmetric(col) {
+                self.reveal(tile);
+            }
+            let slope = Slope { num: 2 * col - 1, den: 2 * row.depth };
+            if prev_wall == Some(true) && !wall {
+                row.start = slope;
+            }
+            if prev_wall == Some(false) && wall {
+                self.scan(quadrant, Row { end: slope, ..row.next() });
+            }
+            prev_wall = Some(wall);
+        }
+        if prev_wall == Some(false) {
+            self.scan(quadrant, row.next());
+        }
+    }
+}
+
+/// Tiles visible from (ox, oy) within `radius`, as a bitset
+///
+/// `grid` is row-major `width` × `height`, non-zero meaning opaque. Bit
+/// i of the result (word i / 32, bit i % 32) is set when tile
+/// i = y * width + x is visible: the origin, open tiles whose center is
+/// in view, and walls that bound the view. Tiles count when dx² + dy² <=
+/// radius². An origin outside the grid sees nothing.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn field_of_view(grid: &[u8], width: u32, height: u32, ox: i32, oy: i32, radius: u32) -> Vec<u32> {
+    let tiles = width as usize * height as usize;
+    let mut fov = Fov {
+        grid,
+        width: width as i64,
+        height: height as i64,
+        origin: (ox as i64, oy as i64),
+        radius_sq: radius as i64 * radius as i64,
+        max_depth: (radius as i64).min(width.max(height) as i64),
+        visible: vec![0; tiles.div_ceil(32)],
+    };
+    if !fov.in_bounds(fov.origin) {
+        return fov.visible;
+    }
+    fov.reveal(fov.origin);
+    for quadrant in 0..4 {
+        let first = Row {
+            depth: 1,
+            start: Slope { num: -1, den: 1 },
+            end: Slope { num: 1, den: 1 },
+        };
+        fov.scan(quadrant, first);
+    }
+    fov.visible
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn seen(bits: &[u32], i: usize) -> bool {
+        bits[i / 32] & (1 << (i % 32)) != 0
+    }
+
+    #[test]
+    fn open_floor_sees_the_radius_disc() {
+        let (w, h) = (15u32, 11u32);
+        let grid = vec![0u8; (w * h) as usize];
+        let bits = field_of_view(&grid, w, h, 7, 5, 4);
+        assert_eq!(bits.len(), ((w * h) as usize).div_ceil(32));
+        for y in 0..h as i32 {
+            for x in 0..w as i32 {
+                let (dx, dy) = (x - 7, y - 5);
+                assert_eq!(seen(&bits, (y * w as i32 + x) as usize), dx * dx + dy * dy <= 16, "({x}, {y})");
+            }
+        }
+    }
+
+    #[test]
+    fn walls_cast_shadows_and_are_lit() {
+        #[rustfmt::skip]
+        let grid = [
+            0, 0, 0, 0, 0,
+            0, 0, 0, 0, 0,
+            0, 0, 1, 0, 0,
+            0, 0, 0, 0, 0,
+            0, 0, 0, 0, 0,
+        ];
+        let bits = field_of_view(&grid, 5, 5, 2, 4, 10);
+        assert!(seen(&bits, 2 * 5 + 2));
+        assert!(!seen(&bits, 5 + 2));
+        assert!(!seen(&bits, 2));
+        assert!(seen(&bits, 5 + 1) && seen(&bits, 5 + 3));
+        assert!(seen(&bits, 4 * 5 + 2));
+    }
+
+    #[test]
+    fn origin_outside_grid_sees_nothing() {
+        let grid = [0u8; 9];
+        assert_eq!(field_of_view(&grid, 3, 3, 3, 0, 5), [0]);
+        assert_eq!(field_of_view(&grid, 3, 3, 0, -1, 5), [0]);
+        assert_eq!(field_of_view(&grid, 3, 3, 1, 1, 0), [1 << 4]);
+    }
+
+    #[test]
+    fn visibility_is_symmetric_on_open_tiles() {
+        let (w, h) = (16u32, 12u32);
+        let mut s = 7u32;
+        let grid: Vec<u8> = (0..w * h)
+            .map(|_| {
+                s = s.wrapping_mul(1664525).wrapping_add(1013904223);
+                ((s >> 24) < 60) as u8
+            })
+            .collect();
+        let open: Vec<usize> = (0..grid.len()).filter(|&i| grid[i] == 0).collect();
+        let views: Vec<Vec<u32>> = (0..grid.len())
+            .map(|i| field_of_view(&grid, w, h, (i % w as usize) as i32, (i / w as usize) as i32, 8))
+            .collect();
+        let mut pairs = 0;
+        for &a in &open {
+            for &b in &open {
+                assert_eq!(seen(&views[a], b), seen(&views[b], a), "{a} {b}");
+                pairs += seen(&views[a], b) as u32;
+            }
+        }
+        assert!(pairs > 1000);
+    }
+}
@@ -0,0 +1,488 @@
+//! 2D geometry helpers for Q16.16
+//!
+//! Orientation tests, projections, closest-point queries and triangle
+//! helpers on plain coordinates and `FixedVec2`. Products are formed in 128-bit and every
+//! result is truncated once.
+
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+use crate::flags::{self, StatusFlag};
+use crate::polygon::point_in_polygon;
+use crate::trig::atan2;
+use crate::vec2::FixedVec2;
+use crate::{saturate_i128, truncate_i128};
+
+/// ax * by - ay * bx in Q32.32
+fn cross_q32(ax: i32, ay: i32, bx: i32, by: i32) -> i128 {
+    ax as i128 * by as i128 - ay as i128 * bx as i128
+}
+
+/// ax * bx + ay * by in Q32.32
+fn dot_q32(ax: i32, ay: i32, bx: i32, by: i32) -> i128 {
+    ax as i128 * bx as i128 + ay as i128 * by as i128
+}
+
+/// 2D cross product ax * by - ay * bx (the z of the 3D cross)
+///
+/// Positive when b turns counter-clockwise from a, negative when
+/// clockwise, 0 when parallel. Both products are exact and the
+/// difference is floored once, like `mul`.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn cross2(ax: i32, ay: i32, bx: i32, by: i32) -> i32 {
+    truncate_i128(cross_q32(ax, ay, bx, by) >> 16)
+}
+
+/// Perpendicular of (x, y): the vector rotated 90° counter-clockwise,
+/// (-y, x)
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn perp(x: i32, y: i32) -> FixedVec2 {
+    FixedVec2 { x: y.wrapping_neg(), y: x }
+}
+
+/// `atan2` of wide inputs, shifted down together (atan2 is
+/// scale-invariant) until both fit its i32 arguments
+pub(crate) fn atan2_wide(mut y: i128, mut x: i128) -> i32 {
+    let limit = i32::MAX as i128;
+    while y.abs() > limit || x.abs() > limit {
+        y >>= 1;
+        x >>= 1;
+    }
+    atan2(y as i32, x as i32)
+}
+
+/// Signed angle from a to b in (-π, π], positive counter-clockwise
+///
+/// atan2(cross, dot) on the exact Q32.32 cross and dot products via
+/// `atan2_wide`. A zero vector gives 0.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn angle_between(ax: i32, ay: i32, bx: i32, by: i32) -> i32 {
+    atan2_wide(cross_q32(ax, ay, bx, by), dot_q32(ax, ay, bx, by))
+}
+
+/// Projection of a onto the line through b: b * (a · b) / (b · b)
+///
+/// Both dot products stay exact in Q32.32 and each component is one
+/// 128-bit division, truncated toward zero; b need not be normalized.
+/// Projecting onto the zero vector gives the zero vector.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn project(a: &FixedVec2, b: &FixedVec2) -> FixedVec2 {
+    let len_sq = dot_q32(b.x, b.y, b.x, b.y);
+    if len_sq == 0 {
+        return FixedVec2::zero();
+    }
+    let d = dot_q32(a.x, a.y, b.x, b.y);
+    FixedVec2 {
+        x: truncate_i128(b.x as i128 * d / len_sq),
+        y: truncate_i128(b.y as i128 * d / len_sq),
+    }
+}
+
+/// Reflection of v about a surface with normal n: v - 2 n (v · n) / (n · n)
+///
+/// For ricochets off walls: n is the wall normal (either side, any
+/// length). Like `project`, the dot products are exact and each
+/// component is truncated once. A zero normal returns v unchanged.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn reflect(v: &FixedVec2, n: &FixedVec2) -> FixedVec2 {
+    let len_sq = dot_q32(n.x, n.y, n.x, n.y);
+    if len_sq == 0 {
+        return *v;
+    }
+    let d = dot_q32(v.x, v.y, n.x, n.y);
+    FixedVec2 {
+        x: truncate_i128(v.x as i128 - 2 * n.x as i128 * d / len_sq),
+        y: truncate_i128(v.y as i128 - 2 * n.y as i128 * d / len_sq),
+    }
+}
+
+/// Nearest point on a segment, returned by `closest_point_on_segment`
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ClosestPoint {
+    /// Point on the segment (Q16.16)
+    pub x: i32,
+    pub y: i32,
+    /// Position along the segment, 0 at a to 1.0 at b (Q16.16)
+    pub t: i32,
+    /// Distance from the query point, as `distance` (Q16.16)
+    pub distance: i32,
+}
+
+/// Closest point to p on the segment from a to b
+///
+/// The projection parameter (p - a) · (b - a) / |b - a|² is kept as an
+/// exact Q32.32 ratio and clamped to the segment before the single
+/// division per component, so the ends are hit exactly. Differences are
+/// taken in 64-bit, so segments spanning the whole range do not wrap. A
+/// degenerate segment (a == b) returns a.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn closest_point_on_segment(p: &FixedVec2, a: &FixedVec2, b: &FixedVec2) -> ClosestPoint {
+    let (abx, aby) = (b.x as i128 - a.x as i128, b.y as i128 - a.y as i128);
+    let (apx, apy) = (p.x as i128 - a.x as i128, p.y as i128 - a.y as i128);
+    let num = apx * abx + apy * aby;
+    let den = abx * abx + aby * aby;
+
+    let (x, y, t) = if den == 0 || num <= 0 {
+        (a.x, a.y, 0)
+    } else if num >= den {
+        (b.x, b.y, 1 << 16)
+    } else {
+        (
+            (a.x as i128 + abx * num / den) as i32,
+            (a.y as i128 + aby * num / den) as i32,
+            ((num << 16) / den) as i32,
+        )
+    };
+    ClosestPoint { x, y, t, distance: crate::distance(p.x, p.y, x, y) }
+}
+
+/// Result of `segments_intersect`
+///
+/// When `hit` is false the other fields are 0.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SegmentHit {
+    pub hit: bool,
+    /// Intersection point (Q16.16)
+    pub x: i32,
+    pub y: i32,
+    /// Position along a0 → a1, 0 to 1.0 (Q16.16)
+    pub t: i32,
+    /// Position along b0 → b1, 0 to 1.0 (Q16.16)
+    pub u: i32,
+}
+
+impl SegmentHit {
+    fn miss() -> SegmentHit {
+        SegmentHit { hit: false, x: 0, y: 0, t: 0, u: 0 }
+    }
+}
+
+/// Parameter of (x, y) along b0 → b1 as Q16.16; 0 for a point segment
+fn param_along(x: i32, y: i32, b0: &FixedVec2, sx: i128, sy: i128) -> i32 {
+    let ss = sx * sx + sy * sy;
+    if ss == 0 {
+        return 0;
+    }
+    let d = (x as i128 - b0.x as i128) * sx + (y as i128 - b0.y as i128) * sy;
+    ((d << 16) / ss) as i32
+}
+
+/// Whether and where segment a0 → a1 crosses segment b0 → b1
+///
+/// Segments are closed: touching at an endpoint counts as a hit. The
+/// test itself uses exact 128-bit orientation products, so it never
+/// disagrees with itself under swapped or reversed inputs; only the
+/// reported point and parameters are truncated (once each).
+///
+/// Tie-breaking:
+/// - parallel, non-collinear segments never hit
+/// - collinear overlapping segments report the overlap point nearest a0
+/// - a zero-length segment hits if its point lies on the other segment
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn segments_intersect(a0: &FixedVec2, a1: &FixedVec2, b0: &FixedVec2, b1: &FixedVec2) -> SegmentHit {
+    let (rx, ry) = (a1.x as i128 - a0.x as i128, a1.y as i128 - a0.y as i128);
+    let (sx, sy) = (b1.x as i128 - b0.x as i128, b1.y as i128 - b0.y as i128);
+    let (qx, qy) = (b0.x as i128 - a0.x as i128, b0.y as i128 - a0.y as i128);
+
+    let denom = rx * sy - ry * sx;
+    if denom != 0 {
+        // Keep the denominator positive so the range checks are plain
+        let sign = denom.signum();
+        let denom = denom * sign;
+        let t_num = (qx * sy - qy * sx) * sign;
+        let u_num = (qx * ry - qy * rx) * sign;
+        if t_num < 0 || t_num > denom || u_num < 0 || u_num > denom {
+            return SegmentHit::miss();
+        }
+        return SegmentHit {
+            hit: true,
+            x: (a0.x as i128 + rx * t_num / denom) as i32,
+            y: (a0.y as i128 + ry * t_num / denom) as i32,
+            t: ((t_num << 16) / denom) as i32,
+            u: ((u_num << 16) / denom) as i32,
+        };
+    }
+
+    // Parallel: only collinear segments can touch
+    if qx * ry - qy * rx != 0 || qx * sy - qy * sx != 0 {
+        return SegmentHit::miss();
+    }
+
+    let rr = rx * rx + ry * ry;
+    if rr == 0 {
+        // a is a single point on b's line; it must lie within b
+        let ss = sx * sx + sy * sy;
+        let d = -(qx * sx + qy * sy);
+        if (ss == 0 && (qx != 0 || qy != 0)) || d < 0 || d > ss {
+            return SegmentHit::miss();
+        }
+        let u = param_along(a0.x, a0.y, b0, sx, sy);
+        return SegmentHit { hit: true, x: a0.x, y: a0.y, t: 0, u };
+    }
+
+    // Overlap of b's projection onto a with a itself, in units of rr
+    let t0 = qx * rx + qy * ry;
+    let t1 = t0 + sx * rx + sy * ry;
+    let lo = t0.min(t1).max(0);
+    let hi = t0.max(t1).min(rr);
+    if lo > hi {
+        return SegmentHit::miss();
+    }
+    let x = (a0.x as i128 + rx * lo / rr) as i32;
+    let y = (a0.y as i128 + ry * lo / rr) as i32;
+    SegmentHit { hit: true, x, y, t: ((lo << 16) / rr) as i32, u: param_along(x, y, b0, sx, sy) }
+}
+
+/// (b - a) x (p - a) in Q32.32, with the differences in 64-bit
+fn orient(a: &FixedVec2, b: &FixedVec2, p: &FixedVec2) -> i128 {
+    let (abx, aby) = (b.x as i128 - a.x as i128, b.y as i128 - a.y as i128);
+    let (apx, apy) = (p.x as i128 - a.x as i128, p.y as i128 - a.y as i128);
+    abx * apy - aby * apx
+}
+
+/// Signed area of triangle abc (Q16.16), positive for counter-clockwise
+///
+/// Half the exact 128-bit cross product (b - a) x (c - a), floored once;
+/// equal to `polygon_area` on the three vertices. Saturates (raising
+/// `StatusFlag::Overflow`) past the Q16.16 range.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn triangle_area(a: &FixedVec2, b: &FixedVec2, c: &FixedVec2) -> i32 {
+    saturate_i128(orient(a, b, c) >> 17)
+}
+
+/// Whether p lies inside triangle abc or on its boundary
+///
+/// Three exact 128-bit orientation tests, so the answer is the same for
+/// either winding and for any vertex order. A degenerate (zero-area)
+/// triangle contains only the points on its edges, as in
+/// `point_in_polygon`.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn point_in_triangle(p: &FixedVec2, a: &FixedVec2, b: &FixedVec2, c: &FixedVec2) -> bool {
+    if orient(a, b, c) == 0 {
+        return point_in_polygon(&[a.x, a.y, b.x, b.y, c.x, c.y], p.x, p.y);
+    }
+    let d = [orient(a, b, p), orient(b, c, p), orient(c, a, p)];
+    !(d.iter().any(|&v| v < 0) && d.iter().any(|&v| v > 0))
+}
+
+/// Barycentric weights of p with respect to triangle abc
+///
+/// p = u a + v b + w c with u + v + w = 1.0 exactly: u and v are ratios
+/// of exact 128-bit areas truncated toward zero, and w = 1.0 - u - v
+/// takes up the rounding. All three lie in [0, 1.0] when p is inside;
+/// outside, weights go negative (saturating, with `StatusFlag::Overflow`,
+/// far from a small triangle).
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Barycentric {
+    pub u: i32,
+    pub v: i32,
+    pub w: i32,
+}
+
+/// Barycentric coordinates of p in triangle abc
+///
+/// A degenerate (zero-area) triangle returns all zeros and raises
+/// `StatusFlag::Domain`.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn barycentric(p: &FixedVec2, a: &FixedVec2, b: &FixedVec2, c: &FixedVec2) -> Barycentric {
+    let area = orient(a, b, c);
+    if area == 0 {
+        flags::raise(StatusFlag::Domain);
+        return Barycentric { u: 0, v: 0, w: 0 };
+    }
+    let u = (orient(b, c, p) << 16) / area;
+    let v = (orient(c, a, p) << 16) / area;
+    let w = (1 << 16) - u - v;
+    Barycentric {
+        u: saturate_i128(u),
+        v: saturate_i128(v),
+        w: saturate_i128(w),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::consts::{HALF_PI, PI};
+
+    const ONE: i32 = 1 << 16;
+
+    fn v(x: i32, y: i32) -> FixedVec2 {
+        FixedVec2::new(x * ONE, y * ONE)
+    }
+
+    #[test]
+    fn cross_sign_gives_turn_direction() {
+        assert_eq!(cross2(ONE, 0, 0, ONE), ONE);
+        assert_eq!(cross2(0, ONE, ONE, 0), -ONE);
+        assert_eq!(cross2(2 * ONE, ONE, 4 * ONE, 2 * ONE), 0);
+        assert_eq!(perp(3 * ONE, 2 * ONE), v(-2, 3));
+    }
+
+    #[test]
+    fn angle_between_is_signed() {
+        assert_eq!(angle_between(ONE, 0, 0, ONE), HALF_PI);
+        assert_eq!(angle_between(0, ONE, ONE, 0), -HALF_PI);
+        assert_eq!(angle_between(ONE, 0, -ONE, 0), PI);
+        assert_eq!(angle_between(0, 0, ONE, 0), 0);
+        // Scale does not matter, even for inputs whose products leave i64
+        assert_eq!(angle_between(i32::MAX, 0, 0, i32::MAX), HALF_PI);
+        // π/4 rounded to nearest
+        assert_eq!(angle_between(3 * ONE, 3 * ONE, 0, 5), 51_472);
+    }
+
+    #[test]
+    fn project_and_reflect() {
+        assert_eq!(project(&v(3, 4), &v(2, 0)), v(3, 0));
+        assert_eq!(project(&v(3, 4), &v(1, 1)), FixedVec2::new(7 * ONE / 2, 7 * ONE / 2));
+        assert_eq!(project(&v(3, 4), &FixedVec2::zero()), FixedVec2::zero());
+        // Off a floor, with the normal pointing either way and any length
+        assert_eq!(reflect(&v(2, -3), &v(0, 1)), v(2, 3));
+        assert_eq!(reflect(&v(2, -3), &v(0, -5)), v(2, 3));
+        assert_eq!(reflect(&v(2, -3), &FixedVec2::zero()), v(2, -3));
+    }
+
+    #[test]
+    fn closest_point_clamps_to_the_ends() {
+        let (a, b) = (v(0, 0), v(4, 0));
+        let c = closest_point_on_segment(&v(1, 3), &a, &b);
+        assert_eq!(c, ClosestPoint { x: ONE, y: 0, t: ONE / 4, distance: 3 * ONE });
+        let c = closest_point_on_segment(&v(-3, 4), &a, &b);
+        assert_eq!(c, ClosestPoint { x: 0, y: 0, t: 0, distance: 5 * ONE });
+        let c = closest_point_on_segment(&v(7, -4), &a, &b);
+        assert_eq!(c, ClosestPoint { x: 4 * ONE, y: 0, t: ONE, distance: 5 * ONE });
+        // Degenerate segment
+        let c = closest_point_on_segment(&v(3, 4), &a, &a);
+        assert_eq!((c.x, c.y, c.distance), (0, 0, 5 * ONE));
+    }
+
+    #[test]
+    fn closest_point_on_a_full_range_segment() {
+        let (a, b) = (FixedVec2::new(i32::MIN, i32::MIN), FixedVec2::new(i32::MAX, i32::MAX));
+        let c = closest_point_on_segment(&FixedVec2::new(0, 0), &a, &b);
+        assert!(c.x.abs() <= 1 && c.y.abs() <= 1);
+        assert!((c.t - ONE / 2).abs() <= 1);
+    }
+
+    #[test]
+    fn crossing_segments() {
+        let h = segments_intersect(&v(0, 0), &v(4, 4), &v(0, 4), &v(4, 0));
+        assert_eq!(h, SegmentHit { hit: true, x: 2 * ONE, y: 2 * ONE, t: ONE / 2, u: ONE / 2 });
+        // Same answer with either segment reversed or the pair swapped
+        for (a0, a1, b0, b1) in [(v(4, 4), v(0, 0), v(0, 4), v(4, 0)), (v(0, 4), v(4, 0), v(0, 0), v(4, 4))] {
+            let h = segments_intersect(&a0, &a1, &b0, &b1);
+            assert!(h.hit);
+            assert_eq!((h.x, h.y), (2 * ONE, 2 * ONE));
+        }
+    }
+
+    #[test]
+    fn touching_parallel_and_disjoint() {
+        // Touching at an endpoint counts
+        let h = segments_intersect(&v(0, 0), &v(2, 0), &v(2, 0), &v(2, 5));
+        assert_eq!((h.hit, h.x, h.t, h.u), (true, 2 * ONE, ONE, 0));
+        // Parallel, not collinear
+        assert!(!segments_intersect(&v(0, 0), &v(2, 0), &v(0, 1), &v(2, 1)).hit);
+        // Would cross if extended
+        assert_eq!(segments_intersect(&v(0, 0), &v(1, 1), &v(3, 0), &v(2, 1)), SegmentHit::miss());
+    }
+
+    #[test]
+    fn collinear_overlap_reports_the_point_nearest_a0() {
+        let h = segments_intersect(&v(0, 0), &v(4, 0), &v(6, 0), &v(2, 0));
+        assert!(h.hit);
+        assert_eq!((h.x, h.y, h.t), (2 * ONE, 0, ONE / 2));
+        assert_eq!(h.u, ONE);
+        // A point segment on the other one
+        let h = segments_intersect(&v(1, 1), &v(1, 1), &v(0, 0), &v(2, 2));
+        assert!(h.hit);
+    }
+
+    #[test]
+    fn triangle_area_is_signed() {
+        let (a, b, c) = (v(0, 0), v(4, 0), v(0, 3));
+        assert_eq!(triangle_area(&a, &b, &c), 6 * ONE);
+        assert_eq!(triangle_area(&a, &c, &b), -6 * ONE);
+        assert_eq!(triangle_area(&a, &b, &v(8, 0)), 0);
+        // One LSB legs: half an LSB² area floors toward -∞
+        let (p, q, r) = (FixedVec2::new(0, 0), FixedVec2::new(1, 0), FixedVec2::new(0, 1));
+        assert_eq!(triangle_area(&p, &q, &r), 0);
+        assert_eq!(triangle_area(&p, &r, &q), -1);
+        assert_eq!(triangle_area(&a, &b, &c), crate::polygon::polygon_area(&[0, 0, 4 * ONE, 0, 0, 3 * ONE]));
+
+        flags::clear_flags();
+        let big = triangle_area(&FixedVec2::new(i32::MIN, i32::MIN), &FixedVec2::new(i32::MAX, 0), &FixedVec2::new(0, i32::MAX));
+        assert_eq!(big, i32::MAX);
+        assert_eq!(flags::get_flags(), StatusFlag::Overflow as u32);
+    }
+
+    #[test]
+    fn point_in_triangle_ignores_winding_and_order() {
+        let (a, b, c) = (v(0, 0), v(4, 0), v(0, 4));
+        let orders = [(a, b, c), (a, c, b), (b, c, a), (b, a, c), (c, a, b), (c, b, a)];
+        for p in [v(1, 1), v(0, 0), v(2, 0), v(2, 2), v(0, 3), v(3, 1)] {
+            for (x, y, z) in orders {
+                assert!(point_in_triangle(&p, &x, &y, &z), "{p:?}");
+            }
+        }
+        for p in [v(3, 3), v(-1, 1), v(1, -1), FixedVec2::new(2 * ONE + 1, 2 * ONE)] {
+            for (x, y, z) in orders {
+                assert!(!point_in_triangle(&p, &x, &y, &z), "{p:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn degenerate_triangles_contain_only_their_edges() {
+        let (a, b, c) = (v(0, 0), v(2, 2), v(4, 4));
+        assert!(point_in_triangle(&v(1, 1), &a, &b, &c));
+        assert!(point_in_triangle(&v(4, 4), &a, &c, &b));
+        assert!(!point_in_triangle(&v(5, 5), &a, &b, &c));
+        assert!(!point_in_triangle(&v(1, 0), &a, &b, &c));
+        assert!(point_in_triangle(&a, &a, &a, &a));
+        assert!(!point_in_triangle(&b, &a, &a, &a));
+    }
+
+    #[test]
+    fn barycentric_weights_sum_to_one() {
+        let (a, b, c) = (v(0, 0), v(6, 0), v(0, 6));
+        assert_eq!(barycentric(&a, &a, &b, &c), Barycentric { u: ONE, v: 0, w: 0 });
+        assert_eq!(barycentric(&b, &a, &b, &c), Barycentric { u: 0, v: ONE, w: 0 });
+        assert_eq!(barycentric(&c, &a, &b, &c), Barycentric { u: 0, v: 0, w: ONE });
+        // The centroid: u and v truncate, w takes up the rounding
+        assert_eq!(barycentric(&v(2, 2), &a, &b, &c), Barycentric { u: 21845, v: 21845, w: 21846 });
+        // Outside, the weight of the far vertex goes negative
+        assert_eq!(barycentric(&v(6, 6), &a, &b, &c), Barycentric { u: -ONE, v: ONE, w: ONE });
+
+        let mut seed = 13u32;
+        let mut next = || {
+            seed = seed.wrapping_mul(1664525).wrapping_add(1013904223);
+            (seed as i32) >> 12
+        };
+        for _ in 0..200 {
+            let (a, b, c) = (FixedVec2::new(next(), next()), FixedVec2::new(next(), next()), FixedVec2::new(next(), next()));
+            let p = FixedVec2::new(next(), next());
+            if triangle_area(&a, &b, &c).abs() < ONE {
+                continue;
+            }
+            let w = barycentric(&p, &a, &b, &c);
+            assert_eq!(w.u as i64 + w.v as i64 + w.w as i64, ONE as i64);
+            let inside = w.u >= 0 && w.v >= 0 && w.w >= 0;
+            // Truncated weights can misplace points within an LSB of an edge
+            if w.u.abs() > 1 && w.v.abs() > 1 && w.w.abs() > 1 {
+                assert_eq!(inside, point_in_triangle(&p, &a, &b, &c));
+            }
+        }
+    }
+
+    #[test]
+    fn barycentric_of_a_degenerate_triangle_is_a_domain_error() {
+        flags::clear_flags();
+        let w = barycentric(&v(1, 1), &v(0, 0), &v(1, 1), &v(2, 2));
+        assert_eq!(w, Barycentric { u: 0, v: 0, w: 0 });
+        assert_eq!(flags::get_flags(), StatusFlag::Domain as u32);
+    }
+}
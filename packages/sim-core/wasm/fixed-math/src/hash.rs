@@ -0,0 +1,334 @@
+//! Non-cryptographic state hashing
+//!
+//! XXH64 over wasm-resident buffers, for per-tick lockstep digests: each
+//! client hashes its state and publishes the digest, and any mismatch
+//! flags a desync. Digests are returned as u64 (BigInt in JS), so they
+//! compare and log as a single value.
+//!
+//! FNV-1a maps content names to stable 32-bit ids, identical in the sim,
+//! the save format and the replay verifier.
+
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+const PRIME64_1: u64 = 0x9E37_79B1_85EB_CA87;
+const PRIME64_2: u64 = 0xC2B2_AE3D_27D4_EB4F;
+const PRIME64_3: u64 = 0x1656_67B1_9E37_79F9;
+const PRIME64_4: u64 = 0x85EB_CA77_C2B2_AE63;
+const PRIME64_5: u64 = 0x27D4_EB2F_1656_67C5;
+
+/// Little-endian view of the input to `xxh64`
+///
+/// Lets an i32 buffer be hashed as its byte image in place, with no
+/// copy (and so no alloc).
+trait LeBytes {
+    fn byte_len(&self) -> usize;
+    fn read_u64(&self, at: usize) -> u64;
+    fn read_u32(&self, at: usize) -> u32;
+    fn read_u8(&self, at: usize) -> u8;
+}
+
+impl LeBytes for [u8] {
+    fn byte_len(&self) -> usize {
+        self.len()
+    }
+
+    fn read_u64(&self, at: usize) -> u64 {
+        u64::from_le_bytes(self[at..at + 8].try_into().unwrap())
+    }
+
+    fn read_u32(&self, at: usize) -> u32 {
+        u32::from_le_bytes(self[at..at + 4].try_into().unwrap())
+    }
+
+    fn read_u8(&self, at: usize) -> u8 {
+        self[at]
+    }
+}
+
+/// Words as little-endian bytes; `xxh64` only reads lanes at multiples
+/// of 4 bytes, so every u32/u64 read is word-aligned
+impl LeBytes for [i32] {
+    fn byte_len(&self) -> usize {
+        4 * self.len()
+    }
+
+    fn read_u64(&self, at: usize) -> u64 {
+        self.read_u32(at) as u64 | (self.read_u32(at + 4) as u64) << 32
+    }
+
+    fn read_u32(&self, at: usize) -> u32 {
+        self[at / 4] as u32
+    }
+
+    fn read_u8(&self, at: usize) -> u8 {
+        (self[at / 4] as u32 >> (8 * (at % 4))) as u8
+    }
+}
+
+fn round(acc: u64, lane: u64) -> u64 {
+    acc.wrapping_add(lane.wrapping_mul(PRIME64_2)).rotate_left(31).wrapping_mul(PRIME64_1)
+}
+
+fn merge_round(acc: u64, v: u64) -> u64 {
+    (acc ^ round(0, v)).wrapping_mul(PRIME64_1).wrapping_add(PRIME64_4)
+}
+
+/// XXH64 of a byte buffer (reference algorithm, little-endian lanes)
+fn xxh64<D: LeBytes + ?Sized>(data: &D, seed: u64) -> u64 {
+    let len = data.byte_len();
+    let mut at = 0;
+    let mut h = if len >= 32 {
+        let mut v = [
+            seed.wrapping_add(PRIME64_1).wrapping_add(PRIME64_2),
+            seed.wrapping_add(PRIME64_2),
+            seed,
+            seed.wrapping_sub(PRIME64_1),
+        ];
+        while len - at >= 32 {
+            for (i, lane) in v.iter_mut().enumerate() {
+                *lane = round(*lane, data.read_u64(at + 8 * i));
+            }
+            at += 32;
+        }
+        let mut h = v[0]
+            .rotate_left(1)
+            .wrapping_add(v[1].rotate_left(7))
+            .wrapping_add(v[2].rotate_left(12))
+            .wrapping_add(v[3].rotate_left(18));
+        for lane in v {
+            h = merge_round(h, lane);
+        }
+        h
+    } else {
+        seed.wrapping_add(PRIME64_5)
+    };
+    h = h.wrapping_add(len as u64);
+
+    while len - at >= 8 {
+        h ^= round(0, data.read_u64(at));
+        h = h.rotate_left(27).wrapping_mul(PRIME64_1).wrapping_add(PRIME64_4);
+        at += 8;
+    }
+    if len - at >= 4 {
+        h ^= (data.read_u32(at) as u64).wrapping_mul(PRIME64_1);
+        h = h.rotate_left(23).wrapping_mul(PRIME64_2).wrapping_add(PRIME64_3);
+        at += 4;
+    }
+    for i in at..len {
+        h ^= (data.read_u8(i) as u64).wrapping_mul(PRIME64_5);
+        h = h.rotate_left(11).wrapping_mul(PRIME64_1);
+    }
+
+    h ^= h >> 33;
+    h = h.wrapping_mul(PRIME64_2);
+    h ^= h >> 29;
+    h = h.wrapping_mul(PRIME64_3);
+    h ^ (h >> 32)
+}
+
+/// XXH64 digest of a byte buffer
+///
+/// Matches the reference XXH64 for the same seed, so digests can be
+/// checked against any other implementation.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn hash_state(data: &[u8], seed: u64) -> u64 {
+    xxh64(data, seed)
+}
+
+/// XXH64 digest of an i32 buffer
+///
+/// Hashes the values as little-endian bytes, so it equals `hash_state`
+/// over the same memory viewed as a Uint8Array.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn hash_state_i32(data: &[i32], seed: u64) -> u64 {
+    xxh64(data, seed)
+}
+
+/// Fletcher-64 modulus (2^32 - 1)
+const FLETCHER_MOD: u64 = 0xFFFF_FFFF;
+
+/// Incremental Fletcher-64 checksum over 32-bit words
+///
+/// `update` can be fed the state in any number of pieces; the digest
+/// depends only on the concatenated words, not on how they were split.
+/// Cheap enough to run over the mutable parts of the state every tick.
+/// As with any Fletcher sum, a word of all ones (-1) adds the same as 0;
+/// use `hash_state` where that matters.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RollingChecksum {
+    /// Sum of words mod 2^32 - 1
+    a: u64,
+    /// Sum of running `a` values mod 2^32 - 1
+    b: u64,
+    /// Words consumed so far
+    count: u64,
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl RollingChecksum {
+    #[cfg_attr(feature = "wasm", wasm_bindgen(constructor))]
+    pub fn new() -> RollingChecksum {
+        RollingChecksum::default()
+    }
+
+    /// Feed i32 words (as their u32 bit patterns)
+    pub fn update(&mut self, words: &[i32]) {
+        for &w in words {
+            self.a = (self.a + w as u32 as u64) % FLETCHER_MOD;
+            self.b = (self.b + self.a) % FLETCHER_MOD;
+        }
+        self.count += words.len() as u64;
+    }
+
+    /// Feed bytes, packed little-endian into words
+    ///
+    /// A trailing partial word is zero-padded, so byte input should come
+    /// in multiples of 4 for splits not to matter.
+    pub fn update_bytes(&mut self, bytes: &[u8]) {
+        for c in bytes.chunks(4) {
+            let mut w = [0u8; 4];
+            w[..c.len()].copy_from_slice(c);
+            self.update(&[i32::from_le_bytes(w)]);
+        }
+    }
+
+    /// Checksum `b << 32 | a` of everything fed so far
+    pub fn digest(&self) -> u64 {
+        self.b << 32 | self.a
+    }
+
+    /// Number of words fed so far
+    pub fn word_count(&self) -> u64 {
+        self.count
+    }
+
+    pub fn reset(&mut self) {
+        *self = RollingChecksum::default();
+    }
+}
+
+/// FNV-1a 32-bit offset basis
+const FNV_OFFSET: u32 = 0x811C_9DC5;
+
+/// FNV-1a 32-bit prime
+const FNV_PRIME: u32 = 0x0100_0193;
+
+/// 32-bit FNV-1a of a string's UTF-8 bytes
+///
+/// Matches the reference FNV-1a (e.g. `""` hashes to 0x811C9DC5), so ids
+/// can be precomputed by any other implementation.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn hash_str_fnv1a(name: &str) -> u32 {
+    name.bytes().fold(FNV_OFFSET, |h, b| (h ^ b as u32).wrapping_mul(FNV_PRIME))
+}
+
+/// Mix two 32-bit ids into one (order matters)
+///
+/// Formula: a ^ (b + 0x9E3779B9 + (a << 6) + (a >> 2)), wrapping, the
+/// Boost `hash_combine` step. Chain it left to right to build ids for
+/// composite keys such as (tower type, modifier).
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn hash_combine(a: u32, b: u32) -> u32 {
+    a ^ b.wrapping_add(0x9E37_79B9).wrapping_add(a << 6).wrapping_add(a >> 2)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn xxh64_reference_vectors() {
+        assert_eq!(hash_state(b"", 0), 0xef46db3751d8e999);
+        assert_eq!(hash_state(b"a", 0), 0xd24ec4f1a98c6e5b);
+        assert_eq!(hash_state(b"abc", 0), 0x44bc2cf5ad770999);
+        // Long enough for the four-lane loop plus every tail step
+        assert_eq!(hash_state(b"Nobody inspects the spammish repetition", 0), 0xfbcea83c8a378bf1);
+        assert_ne!(hash_state(b"abc", 1), hash_state(b"abc", 0));
+    }
+
+    #[test]
+    fn i32_digest_hashes_the_byte_image() {
+        let words = [1, -2, 0x0403_0201, i32::MIN];
+        let bytes = [1, 0, 0, 0, 0xfe, 0xff, 0xff, 0xff, 1, 2, 3, 4, 0, 0, 0, 0x80];
+        assert_eq!(hash_state_i32(&words, 7), hash_state(&bytes, 7));
+        assert_eq!(hash_state_i32(&[], 0), 0xef46db3751d8e999);
+        // 11 words cover the four-lane loop, an 8-byte lane and a 4-byte tail
+        let mut long = [0i32; 11];
+        let mut long_bytes = [0u8; 44];
+        let mut x: u32 = 5;
+        for (i, w) in long.iter_mut().enumerate() {
+            x = x.wrapping_mul(1664525).wrapping_add(1013904223);
+            *w = x as i32;
+            long_bytes[4 * i..4 * i + 4].copy_from_slice(&w.to_le_bytes());
+        }
+        assert_eq!(hash_state_i32(&long, 3), hash_state(&long_bytes, 3));
+    }
+
+    #[test]
+    fn rolling_checksum_is_fletcher64() {
+        let mut c = RollingChecksum::new();
+        c.update(&[1, 2, 3]);
+        assert_eq!((c.a, c.b, c.word_count()), (6, 10, 3));
+        // -1 is 2^32 - 1, which is 0 mod the Fletcher modulus
+        let mut d = RollingChecksum::new();
+        d.update(&[1, 2, -1, 3]);
+        let mut z = RollingChecksum::new();
+        z.update(&[1, 2, 0, 3]);
+        assert_eq!((d.a, d.b), (z.a, z.b));
+        c.reset();
+        assert_eq!(c, RollingChecksum::new());
+    }
+
+    #[test]
+    fn rolling_checksum_ignores_splits() {
+        let words: Vec<i32> = (0..100).map(|i| i * 0x0123_4567 - 77).collect();
+        let mut whole = RollingChecksum::new();
+        whole.update(&words);
+        let mut pieces = RollingChecksum::new();
+        for chunk in words.chunks(7) {
+            pieces.update(chunk);
+        }
+        assert_eq!(whole, pieces);
+        let bytes: Vec<u8> = words.iter().flat_map(|w| w.to_le_bytes()).collect();
+        let mut from_bytes = RollingChecksum::new();
+        for chunk in bytes.chunks(12) {
+            from_bytes.update_bytes(chunk);
+        }
+        assert_eq!(from_bytes, whole);
+        // A trailing partial word is zero-padded
+        let mut partial = RollingChecksum::new();
+        partial.update_bytes(&[1, 2, 3, 4, 5]);
+        let mut padded = RollingChecksum::new();
+        padded.update(&[0x0403_0201, 5]);
+        assert_eq!(partial, padded);
+    }
+
+    #[test]
+    fn rolling_digest_is_b_then_a() {
+        let mut c = RollingChecksum::new();
+        c.update(&[1, 2, 3]);
+        assert_eq!(c.digest(), 10 << 32 | 6);
+    }
+
+    #[test]
+    fn fnv1a_reference_vectors() {
+        assert_eq!(hash_str_fnv1a(""), 0x811c9dc5);
+        assert_eq!(hash_str_fnv1a("a"), 0xe40c292c);
+        assert_eq!(hash_str_fnv1a("foobar"), 0xbf9cf968);
+        // Hashes the UTF-8 bytes, not UTF-16 code units
+        assert_eq!(hash_str_fnv1a("é"), 0x1e9de8c1);
+    }
+
+    #[test]
+    fn hash_combine_is_the_boost_step() {
+        assert_eq!(hash_combine(0, 0), 0x9e3779b9);
+        assert_eq!(hash_combine(1, 2), 0x9e3779fa);
+        let (a, b) = (hash_str_fnv1a("a"), hash_str_fnv1a("foobar"));
+        assert_eq!(hash_combine(a, b), 0x7dede140);
+        // Order matters
+        assert_ne!(hash_combine(a, b), hash_combine(b, a));
+        assert_ne!(hash_combine(hash_combine(a, b), 7), hash_combine(hash_combine(a, 7), b));
+    }
+}
@@ -0,0 +1,291 @@
+//! Hex grid coordinates
+//!
+//! Axial coordinates (q, r) with the implied cube coordinate
+//! s = -q - r, following the usual Red Blob Games conventions. Pixel to
+//! hex conversion forms the fractional cube coordinate in Q32.32 from
+//! exact 128-bit products and rounds it with a fixed tie rule, so every
+//! client picks the same hex for a point on an edge or corner.
+
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+use crate::flags::{self, StatusFlag};
+use crate::truncate_i128;
+use crate::vec2::FixedVec2;
+
+/// √3 in Q32.32
+const SQRT3_Q32: i128 = 7_439_101_574;
+
+/// Axial offsets of the six neighbours, counter-clockwise from +q
+const DIRECTIONS: [(i32, i32); 6] = [(1, 0), (1, -1), (0, -1), (-1, 0), (-1, 1), (0, 1)];
+
+/// Hex tile in axial coordinates
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Hex {
+    pub q: i32,
+    pub r: i32,
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl Hex {
+    #[cfg_attr(feature = "wasm", wasm_bindgen(constructor))]
+    pub fn new(q: i32, r: i32) -> Hex {
+        Hex { q, r }
+    }
+
+    /// Hex from cube coordinates
+    ///
+    /// Raises `StatusFlag::Domain` when q + r + s != 0; q and r are kept
+    /// either way.
+    pub fn from_cube(q: i32, r: i32, s: i32) -> Hex {
+        if q as i64 + r as i64 + s as i64 != 0 {
+            flags::raise(StatusFlag::Domain);
+        }
+        Hex { q, r }
+    }
+
+    /// Third cube coordinate, -q - r
+    pub fn s(&self) -> i32 {
+        self.q.wrapping_neg().wrapping_sub(self.r)
+    }
+}
+
+/// Hex layout for pixel conversions
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HexOrientation {
+    /// Vertex at the top, rows offset horizontally
+    PointyTop = 0,
+    /// Edge at the top, columns offset vertically
+    FlatTop = 1,
+}
+
+impl Hex {
+    fn offset(self, dq: i32, dr: i32) -> Hex {
+        Hex::new(self.q.wrapping_add(dq), self.r.wrapping_add(dr))
+    }
+}
+
+/// Number of steps between two hexes
+///
+/// max(|dq|, |dr|, |ds|), formed in 64-bit and saturated.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn hex_distance(a: &Hex, b: &Hex) -> i32 {
+    let dq = a.q as i64 - b.q as i64;
+    let dr = a.r as i64 - b.r as i64;
+    let ds = -dq - dr;
+    crate::saturate_i64(dq.abs().max(dr.abs()).max(ds.abs()))
+}
+
+/// Neighbour in direction 0..6, counter-clockwise from +q
+///
+/// Directions wrap modulo 6.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn hex_neighbor(h: &Hex, direction: u32) -> Hex {
+    let (dq, dr) = DIRECTIONS[(direction % 6) as usize];
+    h.offset(dq, dr)
+}
+
+/// Append the ring at exactly `radius` steps, in a fixed walk order
+#[cfg(feature = "alloc")]
+fn push_ring(out: &mut Vec<i32>, center: Hex, radius: u32) {
+    if radius == 0 {
+        out.extend([center.q, center.r]);
+        return;
+    }
+    let (dq, dr) = DIRECTIONS[4];
+    let mut h = center.offset(dq.wrapping_mul(radius as i32), dr.wrapping_mul(radius as i32));
+    for &(dq, dr) in &DIRECTIONS {
+        for _ in 0..radius {
+            out.extend([h.q, h.r]);
+            h = h.offset(dq, dr);
+        }
+    }
+}
+
+/// Hexes exactly `radius` steps from center, packed `[q0, r0, ...]`
+///
+/// 6 * radius hexes, starting radius steps along direction 4 and walking
+/// counter-clockwise; radius 0 gives the center alone.
+#[cfg(feature = "alloc")]
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn hex_ring(center: &Hex, radius: u32) -> Vec<i32> {
+    let mut out = Vec::with_capacity(12 * radius.max(1) as usize);
+    push_ring(&mut out, *center, radius);
+    out
+}
+
+/// Every hex within `radius` steps, packed, center first then ring by ring
+#[cfg(feature = "alloc")]
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn hex_spiral(center: &Hex, radius: u32) -> Vec<i32> {
+    let mut out = Vec::new();
+    for k in 0..=radius {
+        push_ring(&mut out, *center, k);
+    }
+    out
+}
+
+/// Round a fractional Q32.32 cube coordinate to the nearest hex
+///
+/// Each coordinate is rounded half up; the one with the largest rounding
+/// error is then rebuilt from the other two. Equal errors resolve in the
+/// order q, r, s (q is only rebuilt when its error is strictly largest),
+/// so edge and corner points always land on the same hex.
+fn cube_round(q: i128, r: i128) -> Hex {
+    let s = -q - r;
+    let round = |v: i128| (v + (1 << 31)) >> 32;
+    let (mut rq, mut rr, rs) = (round(q), round(r), round(s));
+    let (dq, dr, ds) = ((q - (rq << 32)).abs(), (r - (rr << 32)).abs(), (s - (rs << 32)).abs());
+    if dq > dr && dq > ds {
+        rq = -rr - rs;
+    } else if dr > ds {
+        rr = -rq - rs;
+    }
+    Hex::new(truncate_i128(rq), truncate_i128(rr))
+}
+
+/// Round a fractional axial coordinate (Q16.16 q and r) to a hex
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn hex_round(q: i32, r: i32) -> Hex {
+    cube_round((q as i128) << 16, (r as i128) << 16)
+}
+
+/// Center of a hex in pixels, for hexes of circumradius `size`
+///
+/// Pointy-top: x = size √3 (q + r/2), y = size 3/2 r. Flat-top swaps the
+/// roles. Products are exact in 128-bit and floor once.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn hex_to_pixel(h: &Hex, size: i32, orientation: HexOrientation) -> FixedVec2 {
+    let (q, r, size) = (h.q as i128, h.r as i128, size as i128);
+    let (along, across) = match orientation {
+        HexOrientation::PointyTop => (2 * q + r, r),
+        HexOrientation::FlatTop => (2 * r + q, q),
+    };
+    let slanted = truncate_i128((size * SQRT3_Q32 * along) >> 33);
+    let straight = truncate_i128((size * 3 * across) >> 1);
+    match orientation {
+        HexOrientation::PointyTop => FixedVec2::new(slanted, straight),
+        HexOrientation::FlatTop => FixedVec2::new(straight, slanted),
+    }
+}
+
+/// Hex containing a pixel position, for hexes of circumradius `size`
+///
+/// Inverse of `hex_to_pixel`: the fractional axial coordinate is formed
+/// in Q32.32 and rounded with `cube_round`'s tie rule. A non-positive
+/// size returns hex (0, 0) and raises `StatusFlag::Domain`.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn pixel_to_hex(p: &FixedVec2, size: i32, orientation: HexOrientation) -> Hex {
+    if size <= 0 {
+        flags::raise(StatusFlag::Domain);
+        return Hex::new(0, 0);
+    }
+    let (x, y, d) = (p.x as i128, p.y as i128, 3 * size as i128);
+    let (q, r) = match orientation {
+        HexOrientation::PointyTop => ((SQRT3_Q32 * x - (y << 32)).div_euclid(d), (y << 33).div_euclid(d)),
+        HexOrientation::FlatTop => ((x << 33).div_euclid(d), (SQRT3_Q32 * y - (x << 32)).div_euclid(d)),
+    };
+    cube_round(q, r)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::flags::{clear_flags, get_flags};
+
+    const ONE: i32 = 1 << 16;
+
+    #[test]
+    fn cube_coordinates() {
+        clear_flags();
+        let h = Hex::from_cube(2, -5, 3);
+        assert_eq!((h, h.s()), (Hex::new(2, -5), 3));
+        assert_eq!(get_flags(), 0);
+        assert_eq!(Hex::from_cube(1, 1, 1), Hex::new(1, 1));
+        assert_eq!(get_flags(), StatusFlag::Domain as u32);
+    }
+
+    #[test]
+    fn distance_and_neighbors() {
+        let o = Hex::new(0, 0);
+        assert_eq!(hex_distance(&o, &Hex::new(3, -1)), 3);
+        assert_eq!(hex_distance(&Hex::new(-2, 4), &Hex::new(1, -1)), 5);
+        assert_eq!(hex_distance(&Hex::new(i32::MIN, 0), &Hex::new(i32::MAX, 0)), i32::MAX);
+        for d in 0..6 {
+            let n = hex_neighbor(&o, d);
+            assert_eq!(hex_distance(&o, &n), 1);
+            assert_eq!(hex_neighbor(&n, d + 3), o);
+            assert_eq!(hex_neighbor(&o, d + 6), n);
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn rings_and_spirals() {
+        let c = Hex::new(4, -2);
+        assert_eq!(hex_ring(&c, 0), [4, -2]);
+        for radius in 1..5u32 {
+            let ring = hex_ring(&c, radius);
+            assert_eq!(ring.len(), 12 * radius as usize);
+            let mut hexes: Vec<Hex> = ring.chunks(2).map(|h| Hex::new(h[0], h[1])).collect();
+            assert!(hexes.iter().all(|h| hex_distance(h, &c) == radius as i32));
+            // Consecutive hexes are neighbours, closing the loop
+            for (i, h) in hexes.iter().enumerate() {
+                assert_eq!(hex_distance(h, &hexes[(i + 1) % hexes.len()]), 1);
+            }
+            hexes.sort_unstable();
+            hexes.dedup();
+            assert_eq!(hexes.len(), 6 * radius as usize);
+        }
+        let spiral = hex_spiral(&c, 3);
+        assert_eq!(spiral.len(), 2 * 37);
+        assert_eq!(spiral[..2], [4, -2]);
+        assert_eq!(spiral[2 + 12..2 + 12 + 24], hex_ring(&c, 2)[..]);
+    }
+
+    #[test]
+    fn pixel_round_trip() {
+        for orientation in [HexOrientation::PointyTop, HexOrientation::FlatTop] {
+            for q in -6..6 {
+                for r in -6..6 {
+                    let h = Hex::new(q, r);
+                    let p = hex_to_pixel(&h, 3 * ONE, orientation);
+                    assert_eq!(pixel_to_hex(&p, 3 * ONE, orientation), h);
+                    let nudged = FixedVec2::new(p.x + ONE, p.y - ONE);
+                    assert_eq!(pixel_to_hex(&nudged, 3 * ONE, orientation), h);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn pixel_centers() {
+        let p = hex_to_pixel(&Hex::new(1, 0), ONE, HexOrientation::PointyTop);
+        assert_eq!(p, FixedVec2::new(113_511, 0));
+        let p = hex_to_pixel(&Hex::new(0, 2), ONE, HexOrientation::PointyTop);
+        assert_eq!(p, FixedVec2::new(113_511, 3 * ONE));
+        let p = hex_to_pixel(&Hex::new(2, 0), ONE, HexOrientation::FlatTop);
+        assert_eq!(p, FixedVec2::new(3 * ONE, 113_511));
+    }
+
+    #[test]
+    fn rounding_ties_are_fixed() {
+        assert_eq!(hex_round(ONE / 2, 0), Hex::new(1, 0));
+        assert_eq!(hex_round(-ONE / 2, 0), Hex::new(0, 0));
+        assert_eq!(hex_round(ONE / 3, ONE / 3), Hex::new(0, 0));
+        assert_eq!(hex_round(2 * ONE / 3 + 1, -ONE / 3), Hex::new(1, 0));
+        assert_eq!(hex_round(5 * ONE + 100, -3 * ONE - 100), Hex::new(5, -3));
+    }
+
+    #[test]
+    fn non_positive_size_is_a_domain_error() {
+        clear_flags();
+        assert_eq!(pixel_to_hex(&FixedVec2::new(ONE, ONE), 0, HexOrientation::FlatTop), Hex::new(0, 0));
+        assert_eq!(get_flags(), StatusFlag::Domain as u32);
+    }
+}
@@ -0,0 +1,124 @@
+//! Influence map kernels
+//!
+//! In-place batch updates for row-major i32 grids (threat, aggro,
+//! territory) that tower AI samples every tick. Each pass reads a
+//! snapshot of the previous values, so the result does not depend on
+//! cell visiting order.
+
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+use crate::damping::decay_factor_q30;
+#[cfg(feature = "alloc")]
+use crate::saturate_i64;
+use crate::Q30_FRAC_BITS;
+
+/// Spread influence to the four orthogonal neighbours, in place
+///
+/// Each cell moves toward the mean of its in-grid neighbours by rate
+/// (Q16.16, clamped to [0, 1.0]): v + rate * (mean - v). The mean uses
+/// only neighbours inside the grid, so nothing leaks out at the edges.
+/// All cells are computed from the values before the call; the mean
+/// rounds toward -∞ and the rate product truncates like `mul`. Grids
+/// shorter than width * height are processed up to their last full row.
+#[cfg(feature = "alloc")]
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn influence_diffuse(grid: &mut [i32], width: u32, height: u32, rate: i32) {
+    let (w, h) = (width as usize, height as usize);
+    let rows = h.min(grid.len() / w.max(1));
+    if w == 0 || rows == 0 {
+        return;
+    }
+    let rate = rate.clamp(0, 1 << 16) as i64;
+    let before = grid[..w * rows].to_vec();
+    for y in 0..rows {
+        for x in 0..w {
+            let mut sum = 0i64;
+            let mut count = 0i64;
+            let neighbours = [(x > 0, -1isize), (x + 1 < w, 1), (y > 0, -(w as isize)), (y + 1 < rows, w as isize)];
+            let i = y * w + x;
+            for (inside, offset) in neighbours {
+                if inside {
+                    sum += before[(i as isize + offset) as usize] as i64;
+                    count += 1;
+                }
+            }
+            if count == 0 {
+                continue;
+            }
+            let v = before[i] as i64;
+            let mean = sum.div_euclid(count);
+            grid[i] = saturate_i64(v + ((rate * (mean - v)) >> 16));
+        }
+    }
+}
+
+/// Exponential decay of every cell over dt, in place
+///
+/// v * exp(-rate * dt) with one shared Q2.30 factor (the same one
+/// `decay` and `apply_drag` use), truncated toward zero so influence
+/// always fades to exactly 0. Non-positive rate * dt leaves the grid
+/// unchanged.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn influence_decay(grid: &mut [i32], rate: i32, dt: i32) {
+    let factor = decay_factor_q30(rate, dt);
+    for v in grid.iter_mut() {
+        *v = ((*v as i64 * factor) / (1 << Q30_FRAC_BITS)) as i32;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ONE: i32 = 1 << 16;
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn diffuse_moves_toward_the_neighbour_mean() {
+        let mut grid = [0, 0, 0, 0, 8 * ONE, 0, 0, 0, 0];
+        influence_diffuse(&mut grid, 3, 3, ONE / 2);
+        // Edge cells see 8 among 3 neighbours, the centre sees 0s
+        assert_eq!(grid, [0, 87381, 0, 87381, 4 * ONE, 87381, 0, 87381, 0]);
+        let mut full = [ONE; 6];
+        influence_diffuse(&mut full, 3, 2, ONE);
+        assert_eq!(full, [ONE; 6]);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn diffuse_is_order_independent_and_clamps_rate() {
+        let mut a = [5, -7 * ONE, 3 * ONE, 11, 0, ONE, -ONE, 9 * ONE];
+        let mut b = a;
+        influence_diffuse(&mut a, 4, 2, 2 * ONE);
+        influence_diffuse(&mut b, 4, 2, ONE);
+        assert_eq!(a, b);
+        // Mirroring the grid mirrors the result
+        let mut m = [11, 3 * ONE, -7 * ONE, 5, 9 * ONE, -ONE, ONE, 0];
+        influence_diffuse(&mut m, 4, 2, ONE);
+        for (row, mrow) in a.chunks(4).zip(m.chunks(4)) {
+            assert!(row.iter().eq(mrow.iter().rev()));
+        }
+        let mut c = [1, 2, 3];
+        influence_diffuse(&mut c, 4, 2, ONE);
+        assert_eq!(c, [1, 2, 3]);
+        influence_diffuse(&mut c, 3, 1, -ONE);
+        assert_eq!(c, [1, 2, 3]);
+    }
+
+    #[test]
+    fn decay_fades_to_zero() {
+        let mut grid = [10 * ONE, -10 * ONE, 1, 0];
+        influence_decay(&mut grid, ONE, ONE);
+        let e = (-1f64).exp() * 10.0 * ONE as f64;
+        assert!((grid[0] as f64 - e).abs() < 4.0 && (grid[1] as f64 + e).abs() < 4.0);
+        assert_eq!(grid[2..], [0, 0]);
+        for _ in 0..100 {
+            influence_decay(&mut grid, 4 * ONE, ONE);
+        }
+        assert_eq!(grid, [0; 4]);
+        let mut kept = [3, -3];
+        influence_decay(&mut kept, 0, ONE);
+        assert_eq!(kept, [3, -3]);
+    }
+}
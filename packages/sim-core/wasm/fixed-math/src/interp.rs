@@ -0,0 +1,206 @@
+//! Interpolation helpers for Q16.16
+//!
+//! Differences and products are formed in 64-bit (or 128-bit where two
+//! full-range differences are multiplied), so hand-rolled
+//! `add(a, mul(sub(b, a), t))` chains no longer overflow at the
+//! intermediate step. Each function truncates once at the end.
+
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+use crate::flags::{self, StatusFlag};
+use crate::vec2::FixedVec2;
+use crate::{truncate_i128, truncate_i64};
+
+/// 1.0 in Q16.16
+const ONE: i64 = 1 << 16;
+
+/// Linear interpolation from a to b
+///
+/// Formula: a + ((b - a) * t) >> 16
+/// t = 0 gives a, t = 1.0 gives b; t outside [0, 1.0] extrapolates.
+/// Bit-identical to `FP.lerp` whenever b - a fits in i32.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn lerp(a: i32, b: i32, t: i32) -> i32 {
+    let delta = b as i64 - a as i64;
+    truncate_i64(a as i64 + ((delta * t as i64) >> 16))
+}
+
+/// Where v lies between a and b, as a Q16.16 fraction
+///
+/// Formula: ((v - a) << 16) / (b - a), truncated toward zero
+/// Not clamped: values outside [a, b] give results outside [0, 1.0].
+/// A degenerate range (a == b) returns 0 and raises `StatusFlag::Domain`.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn inverse_lerp(a: i32, b: i32, v: i32) -> i32 {
+    let range = b as i64 - a as i64;
+    if range == 0 {
+        flags::raise(StatusFlag::Domain);
+        return 0;
+    }
+    truncate_i64(((v as i64 - a as i64) << 16) / range)
+}
+
+/// Map v from [in_lo, in_hi] onto [out_lo, out_hi]
+///
+/// Formula: out_lo + (v - in_lo) * (out_hi - out_lo) / (in_hi - in_lo)
+/// The product is formed in 128-bit and divided once (truncated toward
+/// zero), so no precision is lost to an intermediate `inverse_lerp`.
+/// Not clamped. A degenerate input range returns out_lo and raises
+/// `StatusFlag::Domain`.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn remap(v: i32, in_lo: i32, in_hi: i32, out_lo: i32, out_hi: i32) -> i32 {
+    let in_range = in_hi as i128 - in_lo as i128;
+    if in_range == 0 {
+        flags::raise(StatusFlag::Domain);
+        return out_lo;
+    }
+    let out_range = out_hi as i128 - out_lo as i128;
+    let offset = (v as i128 - in_lo as i128) * out_range / in_range;
+    truncate_i128(out_lo as i128 + offset)
+}
+
+/// Clamped position of x between edge0 and edge1, in [0, 1.0]
+///
+/// A zero-width edge behaves as a step at edge0.
+fn step_t(edge0: i32, edge1: i32, x: i32) -> i64 {
+    let range = edge1 as i64 - edge0 as i64;
+    if range == 0 {
+        return if x < edge0 { 0 } else { ONE };
+    }
+    (((x as i64 - edge0 as i64) << 16) / range).clamp(0, ONE)
+}
+
+/// Hermite smoothstep between two edges
+///
+/// Formula: t² * (3 - 2t), t = clamp01((x - edge0) / (edge1 - edge0))
+/// The cubic is formed in Q16.48 and truncated once. Result in [0, 1.0];
+/// edge1 < edge0 gives the mirrored curve.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn smoothstep(edge0: i32, edge1: i32, x: i32) -> i32 {
+    let t = step_t(edge0, edge1, x);
+    ((t * t * (3 * ONE - 2 * t)) >> 32) as i32
+}
+
+/// Perlin's smootherstep between two edges
+///
+/// Formula: t³ * (t * (6t - 15) + 10), t as in `smoothstep`
+/// Zero first and second derivatives at both edges. The quintic is formed
+/// in 128-bit and truncated once. Result in [0, 1.0].
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn smootherstep(edge0: i32, edge1: i32, x: i32) -> i32 {
+    let t = step_t(edge0, edge1, x) as i128;
+    let one = ONE as i128;
+    let inner = t * (6 * t - 15 * one) + 10 * one * one;
+    ((t * t * t * inner) >> 64) as i32
+}
+
+/// Move current toward target by at most max_delta
+///
+/// Reaches target exactly (no overshoot) once it is within max_delta.
+/// max_delta <= 0 leaves current unchanged. The difference is taken in
+/// 64-bit, so far-apart values spanning the whole i32 range still work.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn move_toward(current: i32, target: i32, max_delta: i32) -> i32 {
+    if max_delta <= 0 {
+        return current;
+    }
+    let delta = target as i64 - current as i64;
+    if delta.abs() <= max_delta as i64 {
+        return target;
+    }
+    (current as i64 + delta.signum() * max_delta as i64) as i32
+}
+
+/// Move the point (cx, cy) toward (tx, ty) by at most max_delta
+///
+/// Steps along the straight line: each axis moves by
+/// d * max_delta / |d|, with |d| the truncated length (as in `hypot`)
+/// and the division truncated toward zero. Snaps exactly to the
+/// target once it is within max_delta. max_delta <= 0 leaves the point
+/// unchanged.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn move_toward_vec2(cx: i32, cy: i32, tx: i32, ty: i32, max_delta: i32) -> FixedVec2 {
+    if max_delta <= 0 {
+        return FixedVec2::new(cx, cy);
+    }
+    // Differences span up to 2^32, so square and scale in 128-bit
+    let dx = tx as i128 - cx as i128;
+    let dy = ty as i128 - cy as i128;
+    let dist = ((dx * dx + dy * dy) as u128).isqrt() as i128;
+    if dist <= max_delta as i128 {
+        return FixedVec2::new(tx, ty);
+    }
+    let step = max_delta as i128;
+    FixedVec2::new(
+        (cx as i128 + dx * step / dist) as i32,
+        (cy as i128 + dy * step / dist) as i32,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::flags::{clear_flags, get_flags};
+
+    const ONE: i32 = 1 << 16;
+
+    #[test]
+    fn lerp_endpoints_and_extrapolation() {
+        assert_eq!(lerp(2 * ONE, 6 * ONE, 0), 2 * ONE);
+        assert_eq!(lerp(2 * ONE, 6 * ONE, ONE), 6 * ONE);
+        assert_eq!(lerp(2 * ONE, 6 * ONE, ONE / 4), 3 * ONE);
+        assert_eq!(lerp(2 * ONE, 6 * ONE, 2 * ONE), 10 * ONE);
+        assert_eq!(lerp(i32::MIN, i32::MAX, ONE / 2), -1);
+    }
+
+    #[test]
+    fn inverse_lerp_and_remap() {
+        assert_eq!(inverse_lerp(2 * ONE, 6 * ONE, 3 * ONE), ONE / 4);
+        assert_eq!(inverse_lerp(2 * ONE, 6 * ONE, 0), -ONE / 2);
+        assert_eq!(remap(5 * ONE, 0, 10 * ONE, 100 * ONE, 200 * ONE), 150 * ONE);
+        assert_eq!(remap(ONE, 0, 3 * ONE, 0, ONE), ONE / 3);
+
+        clear_flags();
+        assert_eq!(inverse_lerp(ONE, ONE, 2 * ONE), 0);
+        assert_eq!(remap(ONE, 2 * ONE, 2 * ONE, 7, 9), 7);
+        assert_eq!(get_flags(), StatusFlag::Domain as u32);
+    }
+
+    #[test]
+    fn smoothsteps_are_clamped_and_symmetric() {
+        for f in [smoothstep, smootherstep] {
+            assert_eq!(f(0, ONE, -ONE), 0);
+            assert_eq!(f(0, ONE, 0), 0);
+            assert_eq!(f(0, ONE, ONE / 2), ONE / 2);
+            assert_eq!(f(0, ONE, ONE), ONE);
+            assert_eq!(f(0, ONE, 2 * ONE), ONE);
+            let mut prev = 0;
+            for x in (0..=ONE).step_by(257) {
+                let y = f(0, ONE, x);
+                assert!(y >= prev);
+                prev = y;
+            }
+        }
+        assert_eq!(smoothstep(0, ONE, ONE / 4), 10240);
+        // Zero-width edge is a step
+        assert_eq!(smoothstep(ONE, ONE, ONE - 1), 0);
+        assert_eq!(smoothstep(ONE, ONE, ONE), ONE);
+    }
+
+    #[test]
+    fn move_toward_does_not_overshoot() {
+        assert_eq!(move_toward(0, 10 * ONE, 3 * ONE), 3 * ONE);
+        assert_eq!(move_toward(0, -10 * ONE, 3 * ONE), -3 * ONE);
+        assert_eq!(move_toward(9 * ONE, 10 * ONE, 3 * ONE), 10 * ONE);
+        assert_eq!(move_toward(ONE, 10 * ONE, -ONE), ONE);
+        assert_eq!(move_toward(i32::MIN, i32::MAX, i32::MAX), -1);
+    }
+
+    #[test]
+    fn move_toward_vec2_steps_along_the_line() {
+        assert_eq!(move_toward_vec2(0, 0, 6 * ONE, 8 * ONE, 5 * ONE), FixedVec2::new(3 * ONE, 4 * ONE));
+        assert_eq!(move_toward_vec2(0, 0, 6 * ONE, 8 * ONE, 10 * ONE), FixedVec2::new(6 * ONE, 8 * ONE));
+        assert_eq!(move_toward_vec2(ONE, ONE, 6 * ONE, 8 * ONE, 0), FixedVec2::new(ONE, ONE));
+    }
+}
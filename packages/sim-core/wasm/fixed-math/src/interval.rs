@@ -0,0 +1,215 @@
+//! Interval arithmetic over Q16.16
+//!
+//! A `FixedInterval` is the closed range [lo, hi] of values a quantity can
+//! take. The interval ops return exactly the range of results the scalar
+//! op produces over every input in the operand ranges, so balance tooling
+//! can push stat caps through a damage formula and read off the worst
+//! case. The scalar ops are monotone in each argument (floor and
+//! truncation preserve order), so the bounds come from the corners.
+//!
+//! Reachability is reported through the status flags: an op raises
+//! `StatusFlag::Overflow` when some input in range would overflow the
+//! scalar op (the bound is then saturated to the i32 range),
+//! `StatusFlag::DivByZero` when the divisor range contains zero, and
+//! `StatusFlag::Domain` when `sqrt` could see a negative. Clear the
+//! flags, evaluate the formula on intervals, and an empty `get_flags()`
+//! proves the formula is safe for those caps.
+
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+use crate::flags::{self, StatusFlag};
+use crate::saturate_i64;
+
+/// Closed Q16.16 range [lo, hi]
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FixedInterval {
+    pub lo: i32,
+    pub hi: i32,
+}
+
+/// Smallest and largest of the four corner values, saturated to i32
+fn corners(v: [i64; 4]) -> FixedInterval {
+    let lo = v.iter().copied().min().unwrap();
+    let hi = v.iter().copied().max().unwrap();
+    FixedInterval {
+        lo: saturate_i64(lo),
+        hi: saturate_i64(hi),
+    }
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl FixedInterval {
+    /// Range between a and b (in either order)
+    #[cfg_attr(feature = "wasm", wasm_bindgen(constructor))]
+    pub fn new(a: i32, b: i32) -> FixedInterval {
+        FixedInterval {
+            lo: a.min(b),
+            hi: a.max(b),
+        }
+    }
+
+    /// The single value v
+    pub fn point(v: i32) -> FixedInterval {
+        FixedInterval { lo: v, hi: v }
+    }
+
+    /// Whether v lies in the range (bounds included)
+    pub fn contains(&self, v: i32) -> bool {
+        self.lo <= v && v <= self.hi
+    }
+
+    /// hi - lo as a Q16.16 amount, saturating
+    pub fn width(&self) -> i32 {
+        saturate_i64(self.hi as i64 - self.lo as i64)
+    }
+
+    /// Smallest range containing both
+    pub fn union(&self, other: &FixedInterval) -> FixedInterval {
+        FixedInterval {
+            lo: self.lo.min(other.lo),
+            hi: self.hi.max(other.hi),
+        }
+    }
+
+    /// Range of `add` (Overflow when any sum would wrap)
+    pub fn add(&self, other: &FixedInterval) -> FixedInterval {
+        FixedInterval {
+            lo: saturate_i64(self.lo as i64 + other.lo as i64),
+            hi: saturate_i64(self.hi as i64 + other.hi as i64),
+        }
+    }
+
+    /// Range of `sub` (Overflow when any difference would wrap)
+    pub fn sub(&self, other: &FixedInterval) -> FixedInterval {
+        FixedInterval {
+            lo: saturate_i64(self.lo as i64 - other.hi as i64),
+            hi: saturate_i64(self.hi as i64 - other.lo as i64),
+        }
+    }
+
+    /// Range of `mul`: (a * b) >> 16 at the four corners
+    pub fn mul(&self, other: &FixedInterval) -> FixedInterval {
+        let (a, b) = (self.lo as i64, self.hi as i64);
+        let (c, d) = (other.lo as i64, other.hi as i64);
+        corners([(a * c) >> 16, (a * d) >> 16, (b * c) >> 16, (b * d) >> 16])
+    }
+
+    /// Range of `div`: (a << 16) / b at the four corners
+    ///
+    /// A divisor range containing zero raises `StatusFlag::DivByZero` and
+    /// gives the whole i32 range, since the divisor can get arbitrarily
+    /// close to zero (and zero itself follows the policy).
+    pub fn div(&self, other: &FixedInterval) -> FixedInterval {
+        if other.contains(0) {
+            flags::raise(StatusFlag::DivByZero);
+            return FixedInterval { lo: i32::MIN, hi: i32::MAX };
+        }
+        let (a, b) = ((self.lo as i64) << 16, (self.hi as i64) << 16);
+        let (c, d) = (other.lo as i64, other.hi as i64);
+        corners([a / c, a / d, b / c, b / d])
+    }
+
+    /// Range of `sqrt`
+    ///
+    /// A range reaching below zero raises `StatusFlag::Domain`; the
+    /// negative part maps to 0 like the scalar op.
+    pub fn sqrt(&self) -> FixedInterval {
+        if self.lo < 0 {
+            flags::raise(StatusFlag::Domain);
+        }
+        FixedInterval {
+            lo: crate::sqrt(self.lo.max(0)),
+            hi: crate::sqrt(self.hi.max(0)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::flags::{clear_flags, get_flags};
+
+    const ONE: i32 = 1 << 16;
+
+    fn iv(lo: i32, hi: i32) -> FixedInterval {
+        FixedInterval::new(lo * ONE, hi * ONE)
+    }
+
+    #[test]
+    fn construction_and_queries() {
+        assert_eq!(FixedInterval::new(3, -2), FixedInterval { lo: -2, hi: 3 });
+        assert_eq!(FixedInterval::point(7), FixedInterval { lo: 7, hi: 7 });
+        let r = iv(-1, 2);
+        assert!(r.contains(-ONE) && r.contains(2 * ONE) && !r.contains(2 * ONE + 1));
+        assert_eq!(r.width(), 3 * ONE);
+        assert_eq!(r.union(&iv(5, 6)), iv(-1, 6));
+        clear_flags();
+        assert_eq!(FixedInterval::new(i32::MIN, i32::MAX).width(), i32::MAX);
+        assert_eq!(get_flags(), StatusFlag::Overflow as u32);
+    }
+
+    #[test]
+    fn arithmetic_bounds() {
+        let (a, b) = (iv(-1, 2), iv(3, 4));
+        assert_eq!(a.add(&b), iv(2, 6));
+        assert_eq!(a.sub(&b), iv(-5, -1));
+        assert_eq!(a.mul(&b), iv(-4, 8));
+        assert_eq!(iv(-3, -2).mul(&iv(-5, 1)), iv(-3, 15));
+        assert_eq!(iv(6, 12).div(&iv(2, 3)), iv(2, 6));
+        assert_eq!(iv(4, 9).sqrt(), iv(2, 3));
+    }
+
+    #[test]
+    fn ops_bound_every_scalar_result() {
+        let mut seed = 21u32;
+        let mut next = || {
+            seed = seed.wrapping_mul(1664525).wrapping_add(1013904223);
+            (seed as i32) >> 10
+        };
+        for _ in 0..200 {
+            let a = FixedInterval::new(next(), next());
+            let b = FixedInterval::new(next(), next());
+            let (sum, diff, prod) = (a.add(&b), a.sub(&b), a.mul(&b));
+            let quot = (!b.contains(0)).then(|| a.div(&b));
+            let root = a.sqrt();
+            let mut hit = [false; 2];
+            for i in 0..=8 {
+                let x = a.lo + ((a.hi as i64 - a.lo as i64) * i / 8) as i32;
+                assert!(root.contains(crate::sqrt(x.max(0))));
+                for j in 0..=8 {
+                    let y = b.lo + ((b.hi as i64 - b.lo as i64) * j / 8) as i32;
+                    assert!(sum.contains(x.wrapping_add(y)));
+                    assert!(diff.contains(x.wrapping_sub(y)));
+                    assert!(prod.contains(crate::mul(x, y)));
+                    if let Some(q) = quot {
+                        assert!(q.contains(crate::div(x, y)));
+                    }
+                    // Corners reach the bounds exactly
+                    hit[0] |= crate::mul(x, y) == prod.lo;
+                    hit[1] |= crate::mul(x, y) == prod.hi;
+                }
+            }
+            assert!(hit[0] && hit[1]);
+        }
+    }
+
+    #[test]
+    fn flags_report_reachable_faults() {
+        clear_flags();
+        let safe = iv(0, 100).mul(&iv(1, 2)).div(&iv(1, 4)).sqrt();
+        assert_eq!(safe, FixedInterval::new(0, crate::sqrt(200 * ONE)));
+        assert_eq!(get_flags(), 0);
+
+        assert_eq!(iv(1, 2).div(&iv(-1, 1)), FixedInterval { lo: i32::MIN, hi: i32::MAX });
+        assert_eq!(get_flags(), StatusFlag::DivByZero as u32);
+        clear_flags();
+        assert_eq!(iv(-4, 9).sqrt(), iv(0, 3));
+        assert_eq!(get_flags(), StatusFlag::Domain as u32);
+        clear_flags();
+        assert_eq!(iv(0, 30000).add(&iv(0, 30000)).hi, i32::MAX);
+        assert_eq!(iv(-200, 200).mul(&iv(-200, 200)), FixedInterval { lo: i32::MIN, hi: i32::MAX });
+        assert_eq!(get_flags(), StatusFlag::Overflow as u32);
+    }
+}
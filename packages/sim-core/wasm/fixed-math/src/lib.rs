@@ -1,19 +1,128 @@
+//! Q16.16 Fixed-Point Math Operations
+//! 
+//! All operations use 64-bit intermediate values to avoid overflow
+//! and ensure deterministic results across platforms.
+//! 
+//! Format: Q16.16 (16 bits integer, 16 bits fractional)
+//! - FP.ONE = 65536 (represents 1.0)
+//! - Precision: 1/65536 ≈ 0.0000153
+//!
+//! Every function is plain Rust; the `wasm` feature (on by default) adds
+//! the `wasm_bindgen` exports on top. Native consumers (the game server,
+//! the headless replay checker) depend on the crate with
+//! `default-features = false, features = ["std"]` and link the same
+//! arithmetic directly.
+//!
+//! Without `std` the crate is `#![no_std]`. The scalar ops need nothing
+//! else; functions that return or build collections also need `alloc`
+//! (implied by `std`).
+
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+#[cfg(feature = "wasm")]
 use wasm_bindgen::prelude::*;
 
-/// Q16.16 Fixed-Point Math Operations
-/// 
-/// All operations use 64-bit intermediate values to avoid overflow
-/// and ensure deterministic results across platforms.
-/// 
-/// Format: Q16.16 (16 bits integer, 16 bits fractional)
-/// - FP.ONE = 65536 (represents 1.0)
-/// - Precision: 1/65536 ≈ 0.0000153
+use flags::StatusFlag;
+
+pub mod aabb;
+pub mod affine;
+pub mod angle;
+#[cfg(feature = "std")]
+pub mod arena;
+pub mod array;
+pub mod ballistics;
+pub mod batch;
+#[cfg(feature = "alloc")]
+pub mod bits;
+pub mod buff;
+pub mod checked;
+pub mod collide;
+pub mod combat;
+pub mod consts;
+pub mod curve;
+pub mod damping;
+pub mod decimal;
+#[cfg(feature = "alloc")]
+pub mod delta;
+pub mod diff;
+pub mod easing;
+pub mod econ;
+pub mod exp;
+pub mod flags;
+#[cfg(feature = "alloc")]
+pub mod fov;
+pub mod geom;
+pub mod hash;
+pub mod hex;
+pub mod influence;
+pub mod interp;
+pub mod interval;
+pub mod morton;
+pub mod noise;
+#[cfg(feature = "alloc")]
+pub mod path;
+#[cfg(feature = "alloc")]
+pub mod pathfind;
+pub mod physics;
+#[cfg(feature = "alloc")]
+pub mod poisson;
+pub mod poly;
+pub mod polygon;
+pub mod prd;
+pub mod q24;
+pub mod q32;
+pub mod qformat;
+#[cfg(feature = "alloc")]
+pub mod quadtree;
+#[cfg(feature = "std")]
+pub mod quantize;
+pub mod raster;
+pub mod rng;
+pub mod round;
+pub mod saturating;
+pub mod scalar;
+pub mod sort;
+#[cfg(feature = "alloc")]
+pub mod spatial;
+pub mod stats;
+pub mod steering;
+pub mod targeting;
+pub mod tick;
+pub mod trig;
+pub mod vec2;
+pub mod vec3;
+pub mod wave;
+#[cfg(feature = "alloc")]
+pub mod weighted;
+pub mod wrapping;
+
+#[cfg(all(feature = "simd", target_arch = "wasm32", target_feature = "simd128"))]
+mod simd;
+
+/// Number of leading elements a SIMD kernel handled, or 0 when the
+/// `simd` path is not compiled in
+///
+/// Callers run their scalar loop from the returned index, so scalar and
+/// SIMD builds share one tail and one definition of each formula.
+macro_rules! simd_prefix {
+    ($call:expr) => {{
+        #[cfg(all(feature = "simd", target_arch = "wasm32", target_feature = "simd128"))]
+        let done: usize = $call;
+        #[cfg(not(all(feature = "simd", target_arch = "wasm32", target_feature = "simd128")))]
+        let done: usize = 0;
+        done
+    }};
+}
+pub(crate) use simd_prefix;
 
 /// Multiply two fixed-point numbers (Q16.16)
 /// 
 /// Formula: (a * b) >> 16
 /// Uses 64-bit intermediate to avoid overflow
-#[wasm_bindgen]
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
 pub fn mul(a: i32, b: i32) -> i32 {
     // Convert to i64 for 64-bit arithmetic
     let a64 = a as i64;
@@ -23,18 +132,18 @@ pub fn mul(a: i32, b: i32) -> i32 {
     let result = (a64 * b64) >> 16;
     
     // Truncate to i32 (standardized truncation moment)
-    result as i32
+    truncate_i64(result)
 }
 
 /// Divide two fixed-point numbers (Q16.16)
 /// 
 /// Formula: (a << 16) / b
 /// Uses 64-bit intermediate to avoid overflow when shifting
-#[wasm_bindgen]
+/// Division by zero is handled by the `DivByZeroPolicy` (saturates by default)
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
 pub fn div(a: i32, b: i32) -> i32 {
     if b == 0 {
-        // Division by zero: return MAX for positive, MIN for negative
-        return if a >= 0 { i32::MAX } else { i32::MIN };
+        return div_by_zero(a < 0);
     }
     
     // Convert to i64 for 64-bit arithmetic
@@ -45,16 +154,179 @@ pub fn div(a: i32, b: i32) -> i32 {
     let result = (a64 << 16) / b64;
     
     // Truncate to i32 (standardized truncation moment)
-    result as i32
+    truncate_i64(result)
+}
+
+/// Multiply two fixed-point numbers with round-half-to-even (Q16.16)
+/// 
+/// Formula: round_half_even((a * b) / 2^16)
+/// `mul` floors via the shift, which biases long formula chains
+/// downward; this variant centers the error. Ties (exactly half an LSB)
+/// go to the even neighbour.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn mul_round(a: i32, b: i32) -> i32 {
+    let product = a as i64 * b as i64;
+    
+    // Floor quotient and the discarded 16 bits (always non-negative)
+    let mut result = product >> 16;
+    let rem = product & 0xFFFF;
+    if rem > 0x8000 || (rem == 0x8000 && result & 1 == 1) {
+        result += 1;
+    }
+    
+    // Truncate to i32 (standardized truncation moment)
+    truncate_i64(result)
+}
+
+/// Divide two fixed-point numbers with round-half-to-even (Q16.16)
+/// 
+/// Formula: round_half_even((a << 16) / b)
+/// Division by zero follows the same policy as `div`.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn div_round(a: i32, b: i32) -> i32 {
+    if b == 0 {
+        return div_by_zero(a < 0);
+    }
+    
+    // Work on magnitudes so rounding is symmetric around zero
+    let n = (a.unsigned_abs() as u64) << 16;
+    let d = b.unsigned_abs() as u64;
+    let mut q = n / d;
+    let r = n % d;
+    if 2 * r > d || (2 * r == d && q & 1 == 1) {
+        q += 1;
+    }
+    
+    let result = if (a < 0) != (b < 0) { -(q as i64) } else { q as i64 };
+    
+    // Truncate to i32 (standardized truncation moment)
+    truncate_i64(result)
+}
+
+/// Multiply then divide with the full 64-bit product (Q16.16)
+/// 
+/// Formula: (a * b) / c
+/// The Q32.32 product divided by a Q16.16 divisor is already Q16.16, so
+/// unlike `div(mul(a, b), c)` none of the low product bits are dropped
+/// before dividing. Quotient truncates toward zero like `div`.
+/// Division by zero follows the same policy as `div`, using the sign of a * b.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn mul_div(a: i32, b: i32, c: i32) -> i32 {
+    let product = a as i64 * b as i64;
+    if c == 0 {
+        return div_by_zero(product < 0);
+    }
+    
+    let result = product / c as i64;
+    
+    // Truncate to i32 (standardized truncation moment)
+    truncate_i64(result)
+}
+
+/// Fused multiply-add of fixed-point numbers (Q16.16)
+/// 
+/// Formula: (a * b + (c << 16)) >> 16
+/// The addition happens on the 64-bit Q32.32 product before the single
+/// truncation, so a product outside the i32 range that `c` brings back
+/// into range does not wrap (as `mul(a, b) + c` would), and it costs one
+/// boundary call instead of two.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn fma(a: i32, b: i32, c: i32) -> i32 {
+    let sum = a as i64 * b as i64 + ((c as i64) << 16);
+    let result = sum >> 16;
+    
+    // Truncate to i32 (standardized truncation moment)
+    truncate_i64(result)
+}
+
+/// Quotient and remainder returned by `div_rem`
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DivRem {
+    /// Integer quotient (plain i32, not Q16.16)
+    pub quot: i32,
+    /// Remainder in the same Q16.16 scale as the inputs
+    pub rem: i32,
+}
+
+/// Integer quotient and remainder of two fixed-point numbers
+/// 
+/// Formula: quot = trunc(a / b), rem = a - quot * b
+/// Both come from one 64-bit division, returned together so tile
+/// conversion needs a single boundary call. Signs follow truncating
+/// division (rem has the sign of a); see `rem_euclid` for a
+/// non-negative remainder.
+/// Division by zero: quot follows the same policy as `div`, rem = 0.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn div_rem(a: i32, b: i32) -> DivRem {
+    if b == 0 {
+        return DivRem { quot: div_by_zero(a < 0), rem: 0 };
+    }
+    
+    // 64-bit so i32::MIN / -1 cannot trap
+    let a64 = a as i64;
+    let b64 = b as i64;
+    
+    // Truncate to i32 (standardized truncation moment)
+    DivRem {
+        quot: truncate_i64(a64 / b64),
+        rem: (a64 % b64) as i32,
+    }
+}
+
+/// Euclidean remainder of two fixed-point numbers (Q16.16)
+/// 
+/// Result is always in [0, |b|), regardless of the signs of a and b,
+/// so negative coordinates wrap the same way as positive ones.
+/// b == 0 returns 0 (same remainder as `div_rem`).
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn rem_euclid(a: i32, b: i32) -> i32 {
+    if b == 0 {
+        return 0;
+    }
+    
+    // 64-bit so i32::MIN % -1 cannot trap
+    (a as i64).rem_euclid(b as i64) as i32
+}
+
+/// Wrap a fixed-point value into the half-open range [min, max)
+/// 
+/// Formula: min + rem_euclid(a - min, max - min)
+/// Differences are taken in 64-bit so ranges spanning more than half the
+/// i32 space still wrap correctly. An empty range (max <= min) returns min.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn wrap(a: i32, min: i32, max: i32) -> i32 {
+    if max <= min {
+        return min;
+    }
+    
+    let span = max as i64 - min as i64;
+    let offset = (a as i64 - min as i64).rem_euclid(span);
+    (min as i64 + offset) as i32
+}
+
+/// Reciprocal of a fixed-point number (Q16.16)
+/// 
+/// Formula: (1 << 32) / fp
+/// Defined as `div(ONE, fp)`, so it is bit-identical to that call and
+/// follows the same division-by-zero policy (recip(0) = i32::MAX by default).
+/// Precompute once and `mul` in hot loops instead of dividing each time.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn recip(fp: i32) -> i32 {
+    div(1 << 16, fp)
 }
 
 /// Square root of a fixed-point number (Q16.16)
 /// 
 /// Uses Newton's method with 64-bit intermediate precision
 /// Input and output are both Q16.16 format
-#[wasm_bindgen]
+/// Negative inputs return 0 and raise `StatusFlag::Domain`
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
 pub fn sqrt(fp: i32) -> i32 {
     if fp <= 0 {
+        if fp < 0 {
+            flags::raise(StatusFlag::Domain);
+        }
         return 0;
     }
     
@@ -74,8 +346,421 @@ pub fn sqrt(fp: i32) -> i32 {
     
     // Scale result to fixed-point: multiply by 2^8 (sqrt of 2^16)
     // This maintains Q16.16 format in the result
-    let result = (x << 8) as i64;
+    let result = x << 8;
     
     // Truncate to i32 (standardized truncation moment)
     result as i32
 }
+
+/// Cube root of a fixed-point number (Q16.16)
+/// 
+/// Uses integer Newton's method with 64-bit intermediate precision,
+/// same as `sqrt`. Negative inputs return the negative real root.
+/// Input and output are both Q16.16 format
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn cbrt(fp: i32) -> i32 {
+    if fp == 0 {
+        return 0;
+    }
+    
+    // cbrt(fp / 2^16) * 2^16 = cbrt(fp * 2^32), so scale up by 2^32 first
+    // |fp| <= 2^31, so the scaled value always fits in u64
+    let n = (fp.unsigned_abs() as u64) << 32;
+    
+    // Initial guess: a power of two guaranteed to be >= the root
+    let bits = 64 - n.leading_zeros();
+    let mut x = 1u64 << bits.div_ceil(3);
+    let mut y = (2 * x + n / (x * x)) / 3;
+    
+    // Newton's method iteration (decreases monotonically to the floor root)
+    while y < x {
+        x = y;
+        y = (2 * x + n / (x * x)) / 3;
+    }
+    
+    // Truncate to i32 (standardized truncation moment)
+    let result = x as i32;
+    if fp < 0 { -result } else { result }
+}
+
+/// 1/sqrt(m) in Q2.30 sampled at m = 1 + 3i/64 for i = 0..=64 (m in [1, 4])
+const RSQRT_TABLE_Q30: [i64; 65] = [
+    1_073_741_824, 1_049_427_536, 1_026_693_558, 1_005_375_799, 985_333_074,
+    966_443_148, 948_599_586, 931_709_222, 915_690_104, 900_469_818,
+    885_984_104, 872_175_715, 858_993_459, 846_391_405, 834_328_203,
+    822_766_514, 811_672_525, 801_015_531, 790_767_575, 780_903_145,
+    771_398_898, 762_233_438, 753_387_102, 744_841_792, 736_580_814,
+    728_588_743, 720_851_298, 713_355_240, 706_088_274, 699_038_963,
+    692_196_655, 685_551_414, 679_093_957, 672_815_603, 666_708_225,
+    660_764_199, 654_976_372, 649_338_020, 643_842_818, 638_484_810,
+    633_258_380, 628_158_229, 623_179_354, 618_317_022, 613_566_757,
+    608_924_318, 604_385_689, 599_947_056, 595_604_800, 591_355_485,
+    587_195_840, 583_122_756, 579_133_272, 575_224_566, 571_393_950,
+    567_638_856, 563_956_835, 560_345_548, 556_802_759, 553_326_329,
+    549_914_212, 546_564_450, 543_275_165, 540_044_561, 536_870_912,
+];
+
+/// Reciprocal square root of a fixed-point number (Q16.16)
+/// 
+/// Normalizes the input to m * 4^e with m in [1, 4), takes an initial
+/// estimate by linear interpolation in a 65-entry table, then applies a
+/// single Newton refinement y = y * (3 - m * y²) / 2 in Q2.30.
+/// No division and no iteration count that depends on the input, so it is
+/// both faster than `div(ONE, sqrt(x))` and deterministic. Accurate to
+/// within 1 LSB of the rounded result.
+/// 
+/// rsqrt(0) is treated as a division by zero (i32::MAX by default);
+/// negative inputs return 0 and raise `StatusFlag::Domain`, like `sqrt`.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn rsqrt(fp: i32) -> i32 {
+    if fp < 0 {
+        flags::raise(StatusFlag::Domain);
+        return 0;
+    }
+    if fp == 0 {
+        return div_by_zero(false);
+    }
+    
+    // Even bit position so that the mantissa lands in [1, 4)
+    let top_bit = (31 - fp.leading_zeros()) & !1;
+    let m = (fp as i64) << (Q30_FRAC_BITS - top_bit);
+    
+    // Table lookup with linear interpolation (64 segments over [1, 4))
+    let segment = 3i64 << 24;
+    let offset = m - (1 << Q30_FRAC_BITS);
+    let idx = (offset / segment) as usize;
+    let t = offset % segment;
+    let lo = RSQRT_TABLE_Q30[idx];
+    let hi = RSQRT_TABLE_Q30[idx + 1];
+    let y0 = lo + (hi - lo) * t / segment;
+    
+    // One Newton step in Q2.30
+    let y0_sq = (y0 * y0) >> Q30_FRAC_BITS;
+    let m_y0_sq = (m * y0_sq) >> Q30_FRAC_BITS;
+    let y1 = (y0 * ((3 << Q30_FRAC_BITS) - m_y0_sq)) >> (Q30_FRAC_BITS + 1);
+    
+    // rsqrt(x) = rsqrt(m) * 2^((16 - top_bit) / 2); shift back to Q16.16
+    let shift = (Q30_FRAC_BITS - 16) as i32 - (16 - top_bit as i32) / 2;
+    ((y1 + (1 << (shift - 1))) >> shift) as i32
+}
+
+/// Length of the vector (x, y) in fixed-point (Q16.16)
+/// 
+/// Formula: sqrt(x² + y²)
+/// The squared sum is kept in unsigned 64-bit (no intermediate `mul`
+/// truncation and no i32 overflow), and the integer square root of the
+/// raw sum is already in Q16.16 scale. Result is truncated and saturates
+/// to i32::MAX if the length exceeds the Q16.16 range.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn hypot(x: i32, y: i32) -> i32 {
+    let xx = (x as i64 * x as i64) as u64;
+    let yy = (y as i64 * y as i64) as u64;
+    saturate_i64((xx + yy).isqrt() as i64)
+}
+
+/// Length of the vector (x, y, z) in fixed-point (Q16.16)
+/// 
+/// Same as `hypot`, with three squares summed in unsigned 64-bit
+/// (3 * 2^62 still fits).
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn hypot3(x: i32, y: i32, z: i32) -> i32 {
+    let xx = (x as i64 * x as i64) as u64;
+    let yy = (y as i64 * y as i64) as u64;
+    let zz = (z as i64 * z as i64) as u64;
+    saturate_i64((xx + yy + zz).isqrt() as i64)
+}
+
+/// Length of the 2D vector (x, y) in fixed-point (Q16.16)
+/// 
+/// Overflow-safe replacement for `FP.length2D`: the squares are summed
+/// in 64-bit instead of through `mul` + `add`, which wraps once |v|
+/// passes ~181.0. Bit-identical to `hypot(x, y)`.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn length2(x: i32, y: i32) -> i32 {
+    hypot(x, y)
+}
+
+/// Squared distance between two points, Q32.32 raw in u128
+/// 
+/// The differences are taken in 64-bit, so they never wrap.
+fn distance_sq_q32(x1: i32, y1: i32, x2: i32, y2: i32) -> u128 {
+    let dx = (x2 as i64 - x1 as i64).unsigned_abs() as u128;
+    let dy = (y2 as i64 - y1 as i64).unsigned_abs() as u128;
+    dx * dx + dy * dy
+}
+
+/// Distance between (x1, y1) and (x2, y2) in fixed-point (Q16.16)
+/// 
+/// Overflow-safe replacement for `FP.dist`. Result is truncated and
+/// saturates to i32::MAX if the distance exceeds the Q16.16 range.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn distance(x1: i32, y1: i32, x2: i32, y2: i32) -> i32 {
+    saturate_i64(distance_sq_q32(x1, y1, x2, y2).isqrt() as i64)
+}
+
+/// Squared distance between (x1, y1) and (x2, y2), Q16.16 in i64
+/// 
+/// Overflow-safe replacement for `FP.distSq` for range checks: compare
+/// against a squared radius computed the same way (e.g. `r * r >> 16` in
+/// 64-bit). Floored to Q16.16; the widest possible result (2^49) fits
+/// easily, so it never saturates. Crosses to JS as a BigInt.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn distance_sq_i64(x1: i32, y1: i32, x2: i32, y2: i32) -> i64 {
+    (distance_sq_q32(x1, y1, x2, y2) >> 16) as i64
+}
+
+/// What divisions return when the divisor is zero
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DivByZeroPolicy {
+    /// MAX for a non-negative numerator, MIN for a negative one (default)
+    Saturate = 0,
+    /// Always 0
+    Zero = 1,
+    /// Panic, which traps the wasm instance (for dev builds)
+    Trap = 2,
+}
+
+flags::register!(DIV_BY_ZERO_POLICY, load_policy, store_policy, DivByZeroPolicy::Saturate as u32);
+
+/// Select how every division in the crate handles a zero divisor
+/// 
+/// Applies to `div`, `div_round`, `mul_div`, `recip`, the saturating and
+/// wrapping divides and everything built on them. Release builds should
+/// keep the default `Saturate`; dev builds can use `Trap` to catch the
+/// offending call. `StatusFlag::DivByZero` is raised under every policy.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn set_div_by_zero_policy(policy: DivByZeroPolicy) {
+    store_policy(policy as u32);
+}
+
+/// Currently selected division-by-zero policy
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn get_div_by_zero_policy() -> DivByZeroPolicy {
+    match load_policy() {
+        1 => DivByZeroPolicy::Zero,
+        2 => DivByZeroPolicy::Trap,
+        _ => DivByZeroPolicy::Saturate,
+    }
+}
+
+/// Result of dividing by zero
+/// 
+/// Single policy point for every division in the crate: raises
+/// `StatusFlag::DivByZero`, then applies the `DivByZeroPolicy`.
+#[inline]
+pub(crate) fn div_by_zero(numerator_negative: bool) -> i32 {
+    flags::raise(StatusFlag::DivByZero);
+    match get_div_by_zero_policy() {
+        DivByZeroPolicy::Saturate => {
+            if numerator_negative { i32::MIN } else { i32::MAX }
+        }
+        DivByZeroPolicy::Zero => 0,
+        DivByZeroPolicy::Trap => panic!("fixed-math: division by zero"),
+    }
+}
+
+/// The standardized truncation moment: keep the low 32 bits
+/// 
+/// Wraps exactly like `as i32` (and `| 0` on the JS side), but raises
+/// `StatusFlag::Overflow` when the value did not fit.
+#[inline]
+pub(crate) fn truncate_i64(v: i64) -> i32 {
+    let r = v as i32;
+    if r as i64 != v {
+        flags::raise(StatusFlag::Overflow);
+    }
+    r
+}
+
+/// The standardized truncation moment for 128-bit intermediates
+/// 
+/// Same as `truncate_i64`, checked against the full 128-bit value.
+#[inline]
+pub(crate) fn truncate_i128(v: i128) -> i32 {
+    let r = v as i32;
+    if r as i128 != v {
+        flags::raise(StatusFlag::Overflow);
+    }
+    r
+}
+
+/// Clamp a 64-bit intermediate into the i32 range
+///
+/// Shared saturation point for operations that pin at i32::MAX/MIN
+/// instead of wrapping. Raises `StatusFlag::Overflow` when it clamps.
+#[inline]
+pub(crate) fn saturate_i64(v: i64) -> i32 {
+    match i32::try_from(v) {
+        Ok(r) => r,
+        Err(_) => {
+            flags::raise(StatusFlag::Overflow);
+            if v < 0 { i32::MIN } else { i32::MAX }
+        }
+    }
+}
+
+/// `saturate_i64` for 128-bit intermediates
+#[inline]
+pub(crate) fn saturate_i128(v: i128) -> i32 {
+    saturate_i64(v.clamp(i64::MIN as i128, i64::MAX as i128) as i64)
+}
+
+/// Fractional bits of the internal high-precision representation (Q2.30)
+///
+/// Transcendental functions run in Q2.30 on i64 and round back to
+/// Q16.16 exactly once at the end.
+pub(crate) const Q30_FRAC_BITS: u32 = 30;
+
+/// Convert a Q16.16 value to Q2.30 (exact, 64-bit)
+#[inline]
+pub(crate) fn q16_to_q30(fp: i32) -> i64 {
+    (fp as i64) << (Q30_FRAC_BITS - 16)
+}
+
+/// Convert a Q2.30 value back to Q16.16, rounding to nearest
+/// (ties toward +∞: adds half an LSB before the shift)
+#[inline]
+pub(crate) fn q30_to_q16(v: i64) -> i32 {
+    let shift = Q30_FRAC_BITS - 16;
+    ((v + (1 << (shift - 1))) >> shift) as i32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::flags::{clear_flags, get_flags};
+
+    const ONE: i32 = 1 << 16;
+
+    #[test]
+    fn mul_and_div_basics() {
+        assert_eq!(mul(3 * ONE, ONE / 2), 3 * ONE / 2);
+        assert_eq!(mul(-ONE, ONE / 4), -ONE / 4);
+        // Floors: -1 LSB * 0.5 rounds toward -∞
+        assert_eq!(mul(-1, ONE / 2), -1);
+        assert_eq!(div(3 * ONE, 2 * ONE), 3 * ONE / 2);
+        assert_eq!(div(-ONE, 3 * ONE), -21845);
+    }
+
+    #[test]
+    fn recip_matches_div() {
+        assert_eq!(recip(2 * ONE), ONE / 2);
+        assert_eq!(recip(-4 * ONE), -ONE / 4);
+        for x in (1..i32::MAX).step_by(7_654_321) {
+            assert_eq!(recip(x), div(ONE, x));
+            assert_eq!(recip(-x), div(ONE, -x));
+        }
+    }
+
+    #[test]
+    fn div_by_zero_follows_policy() {
+        clear_flags();
+        assert_eq!(div(ONE, 0), i32::MAX);
+        assert_eq!(div(-ONE, 0), i32::MIN);
+        assert_eq!(recip(0), i32::MAX);
+        assert_eq!(get_flags(), StatusFlag::DivByZero as u32);
+
+        set_div_by_zero_policy(DivByZeroPolicy::Zero);
+        assert_eq!(div(ONE, 0), 0);
+        assert_eq!(mul_div(ONE, ONE, 0), 0);
+        set_div_by_zero_policy(DivByZeroPolicy::Saturate);
+        assert_eq!(get_div_by_zero_policy(), DivByZeroPolicy::Saturate);
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", ignore = "panics abort on wasm32")]
+    fn trap_policy_panics() {
+        set_div_by_zero_policy(DivByZeroPolicy::Trap);
+        let trapped = std::panic::catch_unwind(|| div(ONE, 0)).is_err();
+        set_div_by_zero_policy(DivByZeroPolicy::Saturate);
+        assert!(trapped);
+    }
+
+    #[test]
+    fn overflow_is_flagged() {
+        clear_flags();
+        mul(i32::MAX, 2 * ONE);
+        assert_eq!(get_flags(), StatusFlag::Overflow as u32);
+    }
+
+    #[test]
+    fn rounding_variants_tie_to_even() {
+        // 1 LSB * 0.5 and 3 LSB * 0.5 are exact ties
+        assert_eq!(mul_round(1, ONE / 2), 0);
+        assert_eq!(mul_round(3, ONE / 2), 2);
+        assert_eq!(mul_round(-3, ONE / 2), -2);
+        assert_eq!(div_round(1, 2 * ONE), 0);
+        assert_eq!(div_round(3, 2 * ONE), 2);
+        assert_eq!(div_round(-3, 2 * ONE), -2);
+        assert_eq!(div_round(2 * ONE, 3 * ONE), 43691);
+    }
+
+    #[test]
+    fn mul_div_keeps_the_full_product() {
+        // (1 LSB * 1 LSB) / 1 LSB loses everything through mul first
+        assert_eq!(div(mul(1, 1), 1), 0);
+        assert_eq!(mul_div(1, 1, 1), 1);
+        assert_eq!(mul_div(1000 * ONE, 1000 * ONE, 2000 * ONE), 500 * ONE);
+    }
+
+    #[test]
+    fn fma_keeps_the_full_product() {
+        // The out-of-range product is brought back by c
+        assert_eq!(fma(200 * ONE, 200 * ONE, -30_000 * ONE), 10_000 * ONE);
+    }
+
+    #[test]
+    fn div_rem_truncates_toward_zero() {
+        assert_eq!(div_rem(7 * ONE, 2 * ONE), DivRem { quot: 3, rem: ONE });
+        assert_eq!(div_rem(-7 * ONE, 2 * ONE), DivRem { quot: -3, rem: -ONE });
+        assert_eq!(div_rem(i32::MIN, -1).rem, 0);
+    }
+
+    #[test]
+    fn rem_euclid_is_never_negative() {
+        assert_eq!(rem_euclid(-7 * ONE, 2 * ONE), ONE);
+        assert_eq!(rem_euclid(-7 * ONE, -2 * ONE), ONE);
+        assert_eq!(rem_euclid(ONE, 0), 0);
+    }
+
+    #[test]
+    fn wrap_into_range() {
+        assert_eq!(wrap(5 * ONE, 0, 3 * ONE), 2 * ONE);
+        assert_eq!(wrap(-ONE, 0, 3 * ONE), 2 * ONE);
+        assert_eq!(wrap(i32::MAX, i32::MIN, i32::MAX), i32::MIN);
+        assert_eq!(wrap(ONE, 2 * ONE, 2 * ONE), 2 * ONE);
+    }
+
+    #[test]
+    fn roots() {
+        assert_eq!(sqrt(4 * ONE), 2 * ONE);
+        assert_eq!(sqrt(ONE / 4), ONE / 2);
+        assert_eq!(cbrt(27 * ONE), 3 * ONE);
+        assert_eq!(cbrt(-8 * ONE), -2 * ONE);
+        assert_eq!(rsqrt(4 * ONE), ONE / 2);
+        for x in (1..i32::MAX).step_by(1_234_567) {
+            // Integer root of the raw value, scaled up by 2^8
+            assert_eq!(sqrt(x), ((x as u64).isqrt() << 8) as i32);
+            let want = 65536.0 / (x as f64 / 65536.0).sqrt();
+            assert!((rsqrt(x) as f64 - want).abs() <= 1.5, "rsqrt({x})");
+        }
+
+        clear_flags();
+        assert_eq!(sqrt(-ONE), 0);
+        assert_eq!(get_flags(), StatusFlag::Domain as u32);
+    }
+
+    #[test]
+    fn lengths_do_not_overflow() {
+        assert_eq!(hypot(3 * ONE, 4 * ONE), 5 * ONE);
+        assert_eq!(length2(3 * ONE, 4 * ONE), 5 * ONE);
+        assert_eq!(hypot3(2 * ONE, 3 * ONE, 6 * ONE), 7 * ONE);
+        // Well past ~181.0, where mul-based squares wrap
+        assert_eq!(hypot(300 * ONE, 400 * ONE), 500 * ONE);
+        assert_eq!(distance(ONE, ONE, 4 * ONE, 5 * ONE), 5 * ONE);
+        let widest = ((((1i128 << 32) - 1).pow(2) * 2) >> 16) as i64;
+        assert_eq!(distance_sq_i64(i32::MIN, i32::MIN, i32::MAX, i32::MAX), widest);
+        assert_eq!(hypot(i32::MAX, i32::MAX), i32::MAX);
+    }
+}
@@ -0,0 +1,95 @@
+//! Morton (Z-order) codes
+//!
+//! Interleaves the bits of two 32-bit grid coordinates into one 64-bit
+//! key, x in the even bits and y in the odd ones. Sorting by the key
+//! keeps nearby tiles close together, and the save format and the sim
+//! use this one definition for chunk keys.
+
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+/// Spread the 32 bits of v into the even bits of a u64
+fn spread(v: u32) -> u64 {
+    let mut x = v as u64;
+    x = (x | (x << 16)) & 0x0000_FFFF_0000_FFFF;
+    x = (x | (x << 8)) & 0x00FF_00FF_00FF_00FF;
+    x = (x | (x << 4)) & 0x0F0F_0F0F_0F0F_0F0F;
+    x = (x | (x << 2)) & 0x3333_3333_3333_3333;
+    x = (x | (x << 1)) & 0x5555_5555_5555_5555;
+    x
+}
+
+/// Gather the even bits of a u64 back into 32 bits
+fn compact(code: u64) -> u32 {
+    let mut x = code & 0x5555_5555_5555_5555;
+    x = (x | (x >> 1)) & 0x3333_3333_3333_3333;
+    x = (x | (x >> 2)) & 0x0F0F_0F0F_0F0F_0F0F;
+    x = (x | (x >> 4)) & 0x00FF_00FF_00FF_00FF;
+    x = (x | (x >> 8)) & 0x0000_FFFF_0000_FFFF;
+    x = (x | (x >> 16)) & 0x0000_0000_FFFF_FFFF;
+    x as u32
+}
+
+/// Z-order key of (x, y): bit i of x lands on bit 2i, bit i of y on 2i + 1
+///
+/// Signed coordinates should be offset (or have their sign bit flipped)
+/// first so the order stays monotonic.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn morton_encode(x: u32, y: u32) -> u64 {
+    spread(x) | (spread(y) << 1)
+}
+
+/// Result of `morton_decode`
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MortonCoords {
+    pub x: u32,
+    pub y: u32,
+}
+
+/// Coordinates of a Z-order key (inverse of `morton_encode`)
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn morton_decode(code: u64) -> MortonCoords {
+    MortonCoords {
+        x: compact(code),
+        y: compact(code >> 1),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Bit-by-bit reference interleave
+    fn interleave(x: u32, y: u32) -> u64 {
+        (0..32).fold(0, |code, i| code | ((x as u64 >> i & 1) << (2 * i)) | ((y as u64 >> i & 1) << (2 * i + 1)))
+    }
+
+    #[test]
+    fn encode_interleaves_bits() {
+        assert_eq!(morton_encode(0, 0), 0);
+        assert_eq!(morton_encode(1, 0), 1);
+        assert_eq!(morton_encode(0, 1), 2);
+        assert_eq!(morton_encode(3, 5), 0b100111);
+        assert_eq!(morton_encode(u32::MAX, 0), 0x5555_5555_5555_5555);
+        assert_eq!(morton_encode(u32::MAX, u32::MAX), u64::MAX);
+        let mut s = 1u64;
+        for _ in 0..1000 {
+            s = s.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            let (x, y) = ((s >> 32) as u32, s as u32);
+            assert_eq!(morton_encode(x, y), interleave(x, y));
+            assert_eq!(morton_decode(morton_encode(x, y)), MortonCoords { x, y });
+        }
+    }
+
+    #[test]
+    fn keys_follow_z_order() {
+        let order: Vec<(u32, u32)> = (0..16).map(morton_decode).map(|m| (m.x, m.y)).collect();
+        assert_eq!(order[..8], [(0, 0), (1, 0), (0, 1), (1, 1), (2, 0), (3, 0), (2, 1), (3, 1)]);
+        // Each aligned run of 4 keys is one 2x2 block
+        for (k, block) in order.chunks(4).enumerate() {
+            let (bx, by) = (2 * (k as u32 & 1), k as u32 & 2);
+            assert!(block.iter().all(|&(x, y)| x / 2 == bx / 2 && y / 2 == by / 2));
+        }
+    }
+}
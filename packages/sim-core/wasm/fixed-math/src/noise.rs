@@ -0,0 +1,729 @@
+//! Gradient noise for Q16.16 coordinates
+//!
+//! `Noise` owns a 256-entry permutation table shuffled from a seed with
+//! `FixedRng`, so the same seed gives the same field on every client and
+//! a map can be regenerated from its seed alone. Lattice coordinates wrap
+//! every 256 units. Interpolation uses the quintic fade
+//! 6t⁵ - 15t⁴ + 10t³, and every product truncates like `mul` in a fixed
+//! order. Simplex noise runs internally in Q2.30 and rounds once.
+
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+use crate::rng::FixedRng;
+use crate::Q30_FRAC_BITS;
+
+/// 1.0 in Q16.16
+const ONE: i64 = 1 << 16;
+
+/// 1.0 in Q2.30
+const ONE_Q30: i64 = 1 << Q30_FRAC_BITS;
+
+/// 2D skew factor (√3 - 1) / 2 in Q2.30
+const F2_Q30: i64 = 393_016_785;
+/// 2D unskew factor (3 - √3) / 6 in Q2.30
+const G2_Q30: i64 = 226_908_346;
+
+/// Kernel radius² (0.5) shared by 2D and 3D simplex
+const SIMPLEX_R2_Q30: i64 = ONE_Q30 / 2;
+
+/// Most octaves `fbm` will sum
+const MAX_OCTAVES: u32 = 16;
+
+/// Output scales that bring simplex sums to [-1.0, 1.0]
+const SIMPLEX2_SCALE: i64 = 70;
+const SIMPLEX3_SCALE: i64 = 76;
+
+/// Cube-edge gradients used by simplex noise (2D takes x and y)
+const GRAD3: [(i64, i64, i64); 12] = [
+    (1, 1, 0),
+    (-1, 1, 0),
+    (1, -1, 0),
+    (-1, -1, 0),
+    (1, 0, 1),
+    (-1, 0, 1),
+    (1, 0, -1),
+    (-1, 0, -1),
+    (0, 1, 1),
+    (0, -1, 1),
+    (0, 1, -1),
+    (0, -1, -1),
+];
+
+/// Q2.30 multiply
+#[inline]
+fn m30(a: i64, b: i64) -> i64 {
+    (a * b) >> Q30_FRAC_BITS
+}
+
+/// Radial falloff (r² - d²)^4 * (g · d) of one simplex corner in Q2.30
+fn corner(gi: usize, x: i64, y: i64, z: i64) -> i64 {
+    let t = SIMPLEX_R2_Q30 - m30(x, x) - m30(y, y) - m30(z, z);
+    if t <= 0 {
+        return 0;
+    }
+    let (gx, gy, gz) = GRAD3[gi];
+    let t2 = m30(t, t);
+    m30(m30(t2, t2), gx * x + gy * y + gz * z)
+}
+
+/// Round a scaled Q2.30 simplex sum to Q16.16 and clamp it to ±1.0
+fn simplex_out(sum: i64, scale: i64) -> i32 {
+    let half = 1 << (Q30_FRAC_BITS - 17);
+    unit((sum * scale + half) >> (Q30_FRAC_BITS - 16))
+}
+
+/// Q16.16 multiply on i64 (floors like `mul`)
+#[inline]
+fn m(a: i64, b: i64) -> i64 {
+    (a * b) >> 16
+}
+
+/// Quintic fade 6t⁵ - 15t⁴ + 10t³ for t in [0, 1.0)
+fn fade(t: i64) -> i64 {
+    let t3 = m(m(t, t), t);
+    m(t3, m(t, m(t, 6 * ONE) - 15 * ONE) + 10 * ONE)
+}
+
+fn lerp(a: i64, b: i64, t: i64) -> i64 {
+    a + m(t, b - a)
+}
+
+/// Lattice cell (wrapped to 0..256) and fractional offset of a coordinate
+fn split(v: i32) -> (usize, i64) {
+    (((v >> 16) & 255) as usize, (v & 0xFFFF) as i64)
+}
+
+/// Clamp a nominal [-1.0, 1.0] result into range and narrow it
+fn unit(v: i64) -> i32 {
+    v.clamp(-ONE, ONE) as i32
+}
+
+fn grad1(hash: u8, x: i64) -> i64 {
+    if hash & 1 == 0 {
+        x
+    } else {
+        -x
+    }
+}
+
+/// Dot with one of the four diagonal gradients (±1, ±1)
+fn grad2(hash: u8, x: i64, y: i64) -> i64 {
+    let u = if hash & 1 == 0 { x } else { -x };
+    let v = if hash & 2 == 0 { y } else { -y };
+    u + v
+}
+
+/// Dot with one of the twelve cube-edge gradients (improved Perlin)
+fn grad3(hash: u8, x: i64, y: i64, z: i64) -> i64 {
+    let h = hash & 15;
+    let u = if h < 8 { x } else { y };
+    let v = if h < 4 {
+        y
+    } else if h == 12 || h == 14 {
+        x
+    } else {
+        z
+    };
+    (if h & 1 == 0 { u } else { -u }) + (if h & 2 == 0 { v } else { -v })
+}
+
+/// Distance function for `Noise::worley`
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DistanceMetric {
+    Euclidean = 0,
+    Manhattan = 1,
+    Chebyshev = 2,
+}
+
+/// Result of `Noise::worley`
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct WorleySample {
+    /// Distance to the nearest feature point (Q16.16)
+    pub f1: i32,
+    /// Distance to the second-nearest feature point (Q16.16)
+    pub f2: i32,
+    /// Stable 32-bit id of the nearest feature point's cell
+    pub id: u32,
+}
+
+/// Seeded noise generator
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone, Debug)]
+pub struct Noise {
+    /// Permutation of 0..256, repeated so lookups never wrap
+    perm: [u8; 512],
+}
+
+impl Noise {
+    fn hash(&self, i: usize) -> usize {
+        self.perm[i] as usize
+    }
+
+    /// Lattice value in [-1.0, 1.0] for value noise
+    fn lattice_value(&self, i: usize, j: usize) -> i64 {
+        self.hash(i + self.hash(j)) as i64 * 2 * ONE / 255 - ONE
+    }
+
+    /// Four hash bytes for a wrapped cell: feature offset and id
+    ///
+    /// Two independent chains (x then y, y then x) give 2^16 distinct
+    /// offsets; the feature point sits at (a.c, b.d) in 8.8 bits.
+    fn cell_bytes(&self, i: usize, j: usize) -> [u8; 4] {
+        let a = self.hash(i + self.hash(j));
+        let b = self.hash(j + self.hash(i));
+        let c = self.hash(a + b);
+        let d = self.hash(b + self.hash(a));
+        [a as u8, b as u8, c as u8, d as u8]
+    }
+
+    /// Simplex gradient index for a wrapped lattice corner
+    fn simplex_grad(&self, i: usize, j: usize, k: usize) -> usize {
+        self.hash(i + self.hash(j + self.hash(k))) % 12
+    }
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl Noise {
+    /// Generator whose permutation is a `FixedRng::shuffle` of 0..256
+    #[cfg_attr(feature = "wasm", wasm_bindgen(constructor))]
+    pub fn new(seed: u64) -> Noise {
+        let mut order: [i32; 256] = core::array::from_fn(|i| i as i32);
+        FixedRng::new(seed).shuffle(&mut order);
+        let mut perm = [0u8; 512];
+        for (i, &p) in order.iter().enumerate() {
+            perm[i] = p as u8;
+            perm[i + 256] = p as u8;
+        }
+        Noise { perm }
+    }
+
+    /// 1D Perlin noise in [-1.0, 1.0]
+    ///
+    /// Gradients ±1, with the result doubled so the extremes reach ±1.0.
+    /// 0 at every integer coordinate.
+    pub fn perlin1(&self, x: i32) -> i32 {
+        let (xi, xf) = split(x);
+        let a = grad1(self.perm[xi], xf);
+        let b = grad1(self.perm[xi + 1], xf - ONE);
+        unit(2 * lerp(a, b, fade(xf)))
+    }
+
+    /// 2D Perlin noise in [-1.0, 1.0]
+    ///
+    /// Diagonal gradients; 0 at every lattice point.
+    pub fn perlin2(&self, x: i32, y: i32) -> i32 {
+        let (xi, xf) = split(x);
+        let (yi, yf) = split(y);
+        let (u, v) = (fade(xf), fade(yf));
+        let a = self.hash(xi) + yi;
+        let b = self.hash(xi + 1) + yi;
+        let x0 = lerp(grad2(self.perm[a], xf, yf), grad2(self.perm[b], xf - ONE, yf), u);
+        let x1 = lerp(grad2(self.perm[a + 1], xf, yf - ONE), grad2(self.perm[b + 1], xf - ONE, yf - ONE), u);
+        unit(lerp(x0, x1, v))
+    }
+
+    /// 3D Perlin noise, nominally [-1.0, 1.0]
+    ///
+    /// Ken Perlin's improved noise (cube-edge gradients). The raw value
+    /// can slightly exceed ±1.0 near a few symmetric points and is
+    /// clamped there. 0 at every lattice point.
+    pub fn perlin3(&self, x: i32, y: i32, z: i32) -> i32 {
+        let (xi, xf) = split(x);
+        let (yi, yf) = split(y);
+        let (zi, zf) = split(z);
+        let (u, v, w) = (fade(xf), fade(yf), fade(zf));
+        let a = self.hash(xi) + yi;
+        let aa = self.hash(a) + zi;
+        let ab = self.hash(a + 1) + zi;
+        let b = self.hash(xi + 1) + yi;
+        let ba = self.hash(b) + zi;
+        let bb = self.hash(b + 1) + zi;
+        let (x1, y1, z1) = (xf - ONE, yf - ONE, zf - ONE);
+        let lower = lerp(
+            lerp(grad3(self.perm[aa], xf, yf, zf), grad3(self.perm[ba], x1, yf, zf), u),
+            lerp(grad3(self.perm[ab], xf, y1, zf), grad3(self.perm[bb], x1, y1, zf), u),
+            v,
+        );
+        let upper = lerp(
+            lerp(grad3(self.perm[aa + 1], xf, yf, z1), grad3(self.perm[ba + 1], x1, yf, z1), u),
+            lerp(grad3(self.perm[ab + 1], xf, y1, z1), grad3(self.perm[bb + 1], x1, y1, z1), u),
+            v,
+        );
+        unit(lerp(lower, upper, w))
+    }
+
+    /// 2D simplex noise in [-1.0, 1.0]
+    ///
+    /// Gustavson's formulation: skew into the triangular lattice, sum
+    /// three corner kernels (0.5 - d²)^4 (g · d) and scale by 70. Fewer
+    /// axis-aligned artifacts than `perlin2` at a similar cost. Computed
+    /// in Q2.30 and rounded once; sampled extremes are about ±0.998, and
+    /// the result is clamped to ±1.0 as a guard.
+    pub fn simplex2(&self, x: i32, y: i32) -> i32 {
+        let (x, y) = ((x as i64) << 14, (y as i64) << 14);
+        let s = (((x + y) as i128 * F2_Q30 as i128) >> Q30_FRAC_BITS) as i64;
+        let (i, j) = ((x + s) >> Q30_FRAC_BITS, (y + s) >> Q30_FRAC_BITS);
+        let t = (i + j) * G2_Q30;
+        let x0 = x - (i << Q30_FRAC_BITS) + t;
+        let y0 = y - (j << Q30_FRAC_BITS) + t;
+        let (i1, j1) = if x0 > y0 { (1, 0) } else { (0, 1) };
+        let (x1, y1) = (x0 - i1 * ONE_Q30 + G2_Q30, y0 - j1 * ONE_Q30 + G2_Q30);
+        let (x2, y2) = (x0 - ONE_Q30 + 2 * G2_Q30, y0 - ONE_Q30 + 2 * G2_Q30);
+
+        let (ii, jj) = ((i & 255) as usize, (j & 255) as usize);
+        let (i1, j1) = (i1 as usize, j1 as usize);
+        let sum = corner(self.simplex_grad(ii, jj, 0), x0, y0, 0)
+            + corner(self.simplex_grad(ii + i1, jj + j1, 0), x1, y1, 0)
+            + corner(self.simplex_grad(ii + 1, jj + 1, 0), x2, y2, 0);
+        simplex_out(sum, SIMPLEX2_SCALE)
+    }
+
+    /// 3D simplex noise in [-1.0, 1.0]
+    ///
+    /// Skew by exactly 1/3 and unskew by 1/6 (integer divisions in
+    /// Q2.30), four corner kernels (0.5 - d²)^4 (g · d) scaled by 76,
+    /// rounded once. Sampled extremes are about ±0.99; clamped to ±1.0
+    /// as a guard.
+    pub fn simplex3(&self, x: i32, y: i32, z: i32) -> i32 {
+        let (x, y, z) = ((x as i64) << 14, (y as i64) << 14, (z as i64) << 14);
+        let s = (x + y + z).div_euclid(3);
+        let (i, j, k) = ((x + s) >> Q30_FRAC_BITS, (y + s) >> Q30_FRAC_BITS, (z + s) >> Q30_FRAC_BITS);
+        let t = ((i + j + k) << Q30_FRAC_BITS).div_euclid(6);
+        let x0 = x - (i << Q30_FRAC_BITS) + t;
+        let y0 = y - (j << Q30_FRAC_BITS) + t;
+        let z0 = z - (k << Q30_FRAC_BITS) + t;
+
+        // Second and third corners of the simplex, by rank of x0, y0, z0
+        let ((i1, j1, k1), (i2, j2, k2)) = if x0 >= y0 {
+            if y0 >= z0 {
+                ((1, 0, 0), (1, 1, 0))
+            } else if x0 >= z0 {
+                ((1, 0, 0), (1, 0, 1))
+            } else {
+                ((0, 0, 1), (1, 0, 1))
+            }
+        } else if y0 < z0 {
+            ((0, 0, 1), (0, 1, 1))
+        } else if x0 < z0 {
+            ((0, 1, 0), (0, 1, 1))
+        } else {
+            ((0, 1, 0), (1, 1, 0))
+        };
+
+        let g1 = ONE_Q30.div_euclid(6);
+        let g2 = (2 * ONE_Q30).div_euclid(6);
+        let g3 = ONE_Q30 / 2;
+        let offset = |d: i64, a: i64, g: i64| d - a * ONE_Q30 + g;
+
+        let (ii, jj, kk) = ((i & 255) as usize, (j & 255) as usize, (k & 255) as usize);
+        let at = |a: i64| a as usize;
+        let sum = corner(self.simplex_grad(ii, jj, kk), x0, y0, z0)
+            + corner(
+                self.simplex_grad(ii + at(i1), jj + at(j1), kk + at(k1)),
+                offset(x0, i1, g1),
+                offset(y0, j1, g1),
+                offset(z0, k1, g1),
+            )
+            + corner(
+                self.simplex_grad(ii + at(i2), jj + at(j2), kk + at(k2)),
+                offset(x0, i2, g2),
+                offset(y0, j2, g2),
+                offset(z0, k2, g2),
+            )
+            + corner(
+                self.simplex_grad(ii + 1, jj + 1, kk + 1),
+                offset(x0, 1, g3),
+                offset(y0, 1, g3),
+                offset(z0, 1, g3),
+            );
+        simplex_out(sum, SIMPLEX3_SCALE)
+    }
+
+    /// 2D value noise in [-1.0, 1.0]
+    ///
+    /// Random lattice values (one table lookup pair per corner) blended
+    /// with the quintic fade. Cheaper than gradient noise but blockier.
+    pub fn value2(&self, x: i32, y: i32) -> i32 {
+        let (xi, xf) = split(x);
+        let (yi, yf) = split(y);
+        let (u, v) = (fade(xf), fade(yf));
+        let top = lerp(self.lattice_value(xi, yi), self.lattice_value(xi + 1, yi), u);
+        let bottom = lerp(self.lattice_value(xi, yi + 1), self.lattice_value(xi + 1, yi + 1), u);
+        unit(lerp(top, bottom, v))
+    }
+
+    /// Fractal Brownian motion: octaves of `perlin2`, in [-1.0, 1.0]
+    ///
+    /// Octave n samples at frequency lacunarity^n with amplitude gain^n
+    /// (both Q16.16, starting from 1.0), summed in octave order and
+    /// divided by the total |amplitude| so the result stays in range.
+    /// Scaled coordinates wrap to i32, which keeps the noise exact
+    /// because the lattice repeats every 256 units. Octaves are capped
+    /// at 16; 0 octaves returns 0.
+    pub fn fbm(&self, x: i32, y: i32, octaves: u32, lacunarity: i32, gain: i32) -> i32 {
+        let (mut freq, mut amp) = (ONE, ONE);
+        let (mut sum, mut total) = (0i64, 0i64);
+        for _ in 0..octaves.min(MAX_OCTAVES) {
+            let sx = ((x as i128 * freq as i128) >> 16) as i32;
+            let sy = ((y as i128 * freq as i128) >> 16) as i32;
+            sum += m(amp, self.perlin2(sx, sy) as i64);
+            total += amp.abs();
+            freq = m(freq, lacunarity as i64).clamp(-(1 << 40), 1 << 40);
+            amp = m(amp, gain as i64);
+        }
+        if total == 0 {
+            return 0;
+        }
+        unit((sum << 16) / total)
+    }
+
+    /// Worley (cellular) noise: F1, F2 and the nearest cell's id
+    ///
+    /// One feature point per lattice cell, placed from the permutation
+    /// table. The search covers every cell that can hold one of the two
+    /// nearest points (5×5 for Euclidean and Chebyshev, 7×7 for
+    /// Manhattan), so F2 is exact too. Euclidean distances are integer
+    /// square roots of exact squared distances; ties keep the cell found
+    /// first in row-major order.
+    pub fn worley(&self, x: i32, y: i32, metric: DistanceMetric) -> WorleySample {
+        let reach: i64 = if metric == DistanceMetric::Manhattan { 3 } else { 2 };
+        let (cx, cy) = ((x >> 16) as i64, (y >> 16) as i64);
+        let (mut f1, mut f2, mut id) = (i64::MAX, i64::MAX, 0u32);
+        for j in cy - reach..=cy + reach {
+            for i in cx - reach..=cx + reach {
+                let bytes = self.cell_bytes((i & 255) as usize, (j & 255) as usize);
+                let px = (i << 16) + ((bytes[0] as i64) << 8 | bytes[2] as i64);
+                let py = (j << 16) + ((bytes[1] as i64) << 8 | bytes[3] as i64);
+                let (dx, dy) = ((px - x as i64).abs(), (py - y as i64).abs());
+                let d = match metric {
+                    DistanceMetric::Euclidean => dx * dx + dy * dy,
+                    DistanceMetric::Manhattan => dx + dy,
+                    DistanceMetric::Chebyshev => dx.max(dy),
+                };
+                if d < f1 {
+                    f2 = f1;
+                    f1 = d;
+                    id = u32::from_le_bytes(bytes);
+                } else if d < f2 {
+                    f2 = d;
+                }
+            }
+        }
+        let finish = |d: i64| match metric {
+            DistanceMetric::Euclidean => (d as u64).isqrt() as i32,
+            _ => d as i32,
+        };
+        WorleySample {
+            f1: finish(f1),
+            f2: finish(f2),
+            id,
+        }
+    }
+}
+
+/// Sampling and fBm settings for `heightmap`
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct HeightmapParams {
+    /// World position of sample (0, 0) (Q16.16)
+    pub origin_x: i32,
+    pub origin_y: i32,
+    /// World distance between neighbouring samples (Q16.16)
+    pub step: i32,
+    /// fBm octave count (capped at 16)
+    pub octaves: u32,
+    /// fBm frequency multiplier per octave (Q16.16)
+    pub lacunarity: i32,
+    /// fBm amplitude multiplier per octave (Q16.16)
+    pub gain: i32,
+    /// Domain warp displacement in world units (Q16.16); 0 disables it
+    pub warp_strength: i32,
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl HeightmapParams {
+    #[cfg_attr(feature = "wasm", wasm_bindgen(constructor))]
+    pub fn new(
+        origin_x: i32,
+        origin_y: i32,
+        step: i32,
+        octaves: u32,
+        lacunarity: i32,
+        gain: i32,
+        warp_strength: i32,
+    ) -> HeightmapParams {
+        HeightmapParams { origin_x, origin_y, step, octaves, lacunarity, gain, warp_strength }
+    }
+}
+
+/// Offset of the second warp channel, (5.2, 1.3) in Q16.16
+const WARP_OFFSET: (i32, i32) = (340_787, 85_197);
+
+/// Fill a row-major width × height grid with domain-warped fBm
+///
+/// Sample (col, row) sits at origin + (col, row) * step, with wrapping
+/// i32 coordinates like `fbm`. With a non-zero warp strength the point
+/// is first displaced by warp_strength * (fbm(p), fbm(p + (5.2, 1.3))),
+/// then the height is fbm of the displaced point; values lie in
+/// [-1.0, 1.0]. The permutation is built from `seed` once per call.
+/// Writes min(width * height, out.len()) samples.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn heightmap(seed: u64, params: &HeightmapParams, width: u32, height: u32, out: &mut [i32]) {
+    let noise = Noise::new(seed);
+    let fbm = |x: i32, y: i32| noise.fbm(x, y, params.octaves, params.lacunarity, params.gain);
+    let count = (width as usize * height as usize).min(out.len());
+    for (index, sample) in out[..count].iter_mut().enumerate() {
+        let (col, row) = ((index % width as usize) as i32, (index / width as usize) as i32);
+        let x = params.origin_x.wrapping_add(col.wrapping_mul(params.step));
+        let y = params.origin_y.wrapping_add(row.wrapping_mul(params.step));
+        *sample = if params.warp_strength == 0 {
+            fbm(x, y)
+        } else {
+            let warp = |v: i32| m(params.warp_strength as i64, v as i64) as i32;
+            let wx = warp(fbm(x, y));
+            let wy = warp(fbm(x.wrapping_add(WARP_OFFSET.0), y.wrapping_add(WARP_OFFSET.1)));
+            fbm(x.wrapping_add(wx), y.wrapping_add(wy))
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ONE: i32 = 1 << 16;
+
+    /// Sample coordinates covering several cells, negatives and the wrap
+    fn coords() -> impl Iterator<Item = i32> {
+        (-40..40).map(|i| i * 12_345 + 777)
+    }
+
+    #[test]
+    fn permutation_is_seeded() {
+        let a = Noise::new(1);
+        let mut sorted = a.perm[..256].to_vec();
+        sorted.sort_unstable();
+        assert!(sorted.iter().enumerate().all(|(i, &p)| p as usize == i));
+        assert_eq!(a.perm[..256], a.perm[256..]);
+        assert_eq!(a.perm, Noise::new(1).perm);
+        assert_ne!(a.perm, Noise::new(2).perm);
+    }
+
+    #[test]
+    fn perlin_is_zero_on_the_lattice() {
+        let n = Noise::new(3);
+        for i in -5..5 {
+            let p = i * ONE;
+            assert_eq!(n.perlin1(p), 0);
+            assert_eq!(n.perlin2(p, 3 * ONE), 0);
+            assert_eq!(n.perlin3(ONE, p, -2 * ONE), 0);
+        }
+    }
+
+    #[test]
+    fn perlin_stays_in_range_and_varies() {
+        let n = Noise::new(3);
+        let mut distinct = Vec::new();
+        for x in coords() {
+            for y in coords().step_by(7) {
+                let v = n.perlin2(x, y);
+                assert!((-ONE..=ONE).contains(&v));
+                assert!((-ONE..=ONE).contains(&n.perlin3(x, y, x ^ y)));
+                distinct.push(v);
+            }
+            assert!((-ONE..=ONE).contains(&n.perlin1(x)));
+        }
+        distinct.sort_unstable();
+        distinct.dedup();
+        assert!(distinct.len() > 500);
+    }
+
+    #[test]
+    fn perlin_wraps_every_256_units() {
+        let n = Noise::new(9);
+        let period = 256 * ONE;
+        for x in coords() {
+            assert_eq!(n.perlin1(x), n.perlin1(x.wrapping_add(period)));
+            assert_eq!(n.perlin2(x, -x), n.perlin2(x - period, -x + period));
+            assert_eq!(n.perlin3(x, 5, -x), n.perlin3(x, 5 + period, -x));
+        }
+    }
+
+    #[test]
+    fn perlin_is_continuous() {
+        let n = Noise::new(4);
+        for x in coords() {
+            assert!((n.perlin2(x, 1000) - n.perlin2(x + 16, 1000)).abs() < 64);
+            assert!((n.perlin1(x) - n.perlin1(x + 16)).abs() < 64);
+        }
+    }
+
+    #[test]
+    fn simplex_range_and_spread() {
+        let n = Noise::new(5);
+        let (mut lo, mut hi) = (0, 0);
+        for x in coords() {
+            for y in coords().step_by(3) {
+                let v2 = n.simplex2(x * 3, y);
+                let v3 = n.simplex3(x, y * 2, x - y);
+                for v in [v2, v3] {
+                    assert!((-ONE..=ONE).contains(&v));
+                    lo = lo.min(v);
+                    hi = hi.max(v);
+                }
+            }
+        }
+        // Sums scaled to roughly fill [-1, 1]
+        assert!(lo < -ONE / 2 && hi > ONE / 2);
+    }
+
+    #[test]
+    fn simplex_is_zero_at_the_origin_and_continuous() {
+        let n = Noise::new(5);
+        // Only the corner at the origin is in reach, and its d is 0
+        assert_eq!(n.simplex2(0, 0), 0);
+        assert_eq!(n.simplex3(0, 0, 0), 0);
+        // The kernels' slope stays below 8, so a 16 LSB step moves < 128
+        for x in (-2_000_000..2_000_000).step_by(997) {
+            for y in [500, -70_001] {
+                assert!((n.simplex2(x, y) - n.simplex2(x + 16, y)).abs() < 128);
+                assert!((n.simplex3(x, y, -x) - n.simplex3(x + 16, y, -x)).abs() < 128);
+            }
+        }
+    }
+
+    #[test]
+    fn simplex_is_deterministic_per_seed() {
+        let (a, b) = (Noise::new(8), Noise::new(8));
+        let c = Noise::new(80);
+        let mut differs = false;
+        for x in coords() {
+            assert_eq!(a.simplex2(x, -x), b.simplex2(x, -x));
+            assert_eq!(a.simplex3(x, 7, -x), b.simplex3(x, 7, -x));
+            differs |= a.simplex2(x, -x) != c.simplex2(x, -x);
+        }
+        assert!(differs);
+    }
+
+    #[test]
+    fn value_noise_hits_lattice_values() {
+        let n = Noise::new(6);
+        for i in 0..20usize {
+            let v = n.value2(i as i32 * ONE, 3 * ONE);
+            assert_eq!(v as i64, n.lattice_value(i, 3));
+        }
+        for x in coords() {
+            assert!((-ONE..=ONE).contains(&n.value2(x, x / 3)));
+        }
+    }
+
+    #[test]
+    fn fbm_single_octave_is_perlin() {
+        let n = Noise::new(6);
+        for x in coords() {
+            assert_eq!(n.fbm(x, -x, 1, 2 * ONE, ONE / 2), n.perlin2(x, -x));
+        }
+        assert_eq!(n.fbm(12_345, 678, 0, 2 * ONE, ONE / 2), 0);
+        assert_eq!(n.fbm(12_345, 678, 3, 2 * ONE, 0), n.perlin2(12_345, 678));
+    }
+
+    #[test]
+    fn fbm_is_normalized_and_capped() {
+        let n = Noise::new(6);
+        for x in coords() {
+            let v = n.fbm(x, x * 2, 6, 2 * ONE, ONE / 2);
+            assert!((-ONE..=ONE).contains(&v));
+            assert!((-ONE..=ONE).contains(&n.fbm(x, 3, 4, 2 * ONE, -ONE)));
+            assert_eq!(n.fbm(x, 1, 40, 3 * ONE / 2, ONE / 2), n.fbm(x, 1, 16, 3 * ONE / 2, ONE / 2));
+        }
+    }
+
+    /// Feature point of a lattice cell, as `worley` places it
+    fn feature(n: &Noise, i: i64, j: i64) -> (i64, i64) {
+        let b = n.cell_bytes((i & 255) as usize, (j & 255) as usize);
+        ((i << 16) + ((b[0] as i64) << 8 | b[2] as i64), (j << 16) + ((b[1] as i64) << 8 | b[3] as i64))
+    }
+
+    #[test]
+    fn worley_matches_a_wide_search() {
+        let n = Noise::new(12);
+        for metric in [DistanceMetric::Euclidean, DistanceMetric::Manhattan, DistanceMetric::Chebyshev] {
+            for x in coords().step_by(3) {
+                let y = x.wrapping_mul(7) / 5;
+                let (cx, cy) = ((x >> 16) as i64, (y >> 16) as i64);
+                let mut d: Vec<i64> = Vec::new();
+                for j in cy - 5..=cy + 5 {
+                    for i in cx - 5..=cx + 5 {
+                        let (px, py) = feature(&n, i, j);
+                        let (dx, dy) = ((px - x as i64).abs(), (py - y as i64).abs());
+                        d.push(match metric {
+                            DistanceMetric::Euclidean => ((dx * dx + dy * dy) as u64).isqrt() as i64,
+                            DistanceMetric::Manhattan => dx + dy,
+                            DistanceMetric::Chebyshev => dx.max(dy),
+                        });
+                    }
+                }
+                d.sort_unstable();
+                let s = n.worley(x, y, metric);
+                assert_eq!((s.f1 as i64, s.f2 as i64), (d[0], d[1]), "{metric:?} {x} {y}");
+            }
+        }
+    }
+
+    #[test]
+    fn worley_is_zero_at_a_feature_point() {
+        let n = Noise::new(12);
+        let (px, py) = feature(&n, 3, -2);
+        let s = n.worley(px as i32, py as i32, DistanceMetric::Euclidean);
+        assert_eq!(s.f1, 0);
+        assert!(s.f2 > 0);
+        let b = n.cell_bytes(3, 254);
+        assert_eq!(s.id, u32::from_le_bytes(b));
+        // Nearby samples share the nearest cell's id
+        assert_eq!(n.worley(px as i32 + 5, py as i32 - 5, DistanceMetric::Chebyshev).id, s.id);
+    }
+
+    #[test]
+    fn heightmap_samples_fbm_on_the_grid() {
+        let params = HeightmapParams::new(-3 * ONE, 5 * ONE, ONE / 4, 4, 2 * ONE, ONE / 2, 0);
+        let mut out = [0; 12];
+        heightmap(77, &params, 4, 3, &mut out);
+        let n = Noise::new(77);
+        for (i, &h) in out.iter().enumerate() {
+            let (col, row) = ((i % 4) as i32, (i / 4) as i32);
+            assert_eq!(h, n.fbm(-3 * ONE + col * ONE / 4, 5 * ONE + row * ONE / 4, 4, 2 * ONE, ONE / 2));
+        }
+    }
+
+    #[test]
+    fn heightmap_warp_displaces_the_sample() {
+        let flat = HeightmapParams::new(0, 0, ONE / 3, 3, 2 * ONE, ONE / 2, 0);
+        let warped = HeightmapParams { warp_strength: 4 * ONE, ..flat };
+        let (mut a, mut b) = ([0; 16], [0; 16]);
+        heightmap(1, &flat, 4, 4, &mut a);
+        heightmap(1, &warped, 4, 4, &mut b);
+        assert_ne!(a, b);
+        assert!(b.iter().all(|v| (-ONE..=ONE).contains(v)));
+        let n = Noise::new(1);
+        let f = |x: i32, y: i32| n.fbm(x, y, 3, 2 * ONE, ONE / 2);
+        let (x, y) = (ONE / 3, 0);
+        let wx = m(4 * ONE as i64, f(x, y) as i64) as i32;
+        let wy = m(4 * ONE as i64, f(x + WARP_OFFSET.0, y + WARP_OFFSET.1) as i64) as i32;
+        assert_eq!(b[1], f(x + wx, y + wy));
+    }
+
+    #[test]
+    fn heightmap_writes_at_most_the_output_length() {
+        let params = HeightmapParams::new(ONE / 5, 0, ONE / 3, 2, 2 * ONE, ONE / 2, 0);
+        let mut out = [i32::MIN; 5];
+        heightmap(3, &params, 2, 2, &mut out);
+        assert!(out[..4].iter().all(|&v| v != i32::MIN && v != 0));
+        assert_eq!(out[4], i32::MIN);
+        let mut short = [i32::MIN; 3];
+        heightmap(3, &params, 2, 2, &mut short);
+        assert_eq!(short, out[..3]);
+    }
+}
@@ -0,0 +1,172 @@
+//! Arc-length parameterized paths
+//!
+//! A `Path` is a polyline of Q16.16 points with the cumulative length of
+//! every segment precomputed in 64-bit, so constant-speed movement is a
+//! lookup by distance instead of a float arc-length estimate. Bezier
+//! chains are flattened into a polyline at construction time.
+
+use alloc::vec::Vec;
+
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+use crate::curve::bezier3;
+use crate::saturate_i64;
+use crate::vec2::FixedVec2;
+
+/// 1.0 in Q16.16
+const ONE: i64 = 1 << 16;
+
+/// Polyline with precomputed cumulative arc length
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone, Debug)]
+pub struct Path {
+    /// Vertices as (x, y) pairs
+    points: Vec<[i32; 2]>,
+    /// Arc length from the first vertex to each vertex, Q16.16 in i64
+    cumulative: Vec<i64>,
+}
+
+impl Path {
+    fn from_vertices(points: Vec<[i32; 2]>) -> Path {
+        let mut cumulative = Vec::with_capacity(points.len());
+        let mut total = 0i64;
+        for (i, p) in points.iter().enumerate() {
+            if i > 0 {
+                let q = points[i - 1];
+                let dx = (p[0] as i64 - q[0] as i64).unsigned_abs() as u128;
+                let dy = (p[1] as i64 - q[1] as i64).unsigned_abs() as u128;
+                // sqrt of a Q32.32 square is Q16.16; dx² + dy² < 2^65
+                total += (dx * dx + dy * dy).isqrt() as i64;
+            }
+            cumulative.push(total);
+        }
+        Path { points, cumulative }
+    }
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl Path {
+    /// Build a path from packed `[x0, y0, x1, y1, ...]` vertices
+    ///
+    /// A trailing odd value is ignored.
+    pub fn from_polyline(points: &[i32]) -> Path {
+        Path::from_vertices(points.chunks_exact(2).map(|p| [p[0], p[1]]).collect())
+    }
+
+    /// Build a path from a chain of cubic Bezier segments
+    ///
+    /// `points` holds `[P0, C0a, C0b, P1, C1a, C1b, P2, ...]` as packed
+    /// x, y pairs (3n + 1 points for n segments; consecutive segments
+    /// share their end points). Each segment is flattened at
+    /// `samples_per_segment` (at least 1) uniform t steps; the anchor
+    /// points are hit exactly.
+    pub fn from_bezier3_chain(points: &[i32], samples_per_segment: u32) -> Path {
+        let samples = samples_per_segment.max(1) as i64;
+        let mut vertices = Vec::new();
+        if points.len() >= 2 {
+            vertices.push([points[0], points[1]]);
+        }
+
+        let mut start = 0;
+        while start + 8 <= points.len() {
+            let segment = &points[start..start + 8];
+            for k in 1..=samples {
+                let p = bezier3(segment, (k * ONE / samples) as i32);
+                vertices.push([p.x, p.y]);
+            }
+            start += 6;
+        }
+        Path::from_vertices(vertices)
+    }
+
+    /// Total arc length, Q16.16 (saturates at i32::MAX)
+    pub fn length(&self) -> i32 {
+        saturate_i64(self.cumulative.last().copied().unwrap_or(0))
+    }
+
+    /// Number of polyline vertices (after Bezier flattening)
+    pub fn vertex_count(&self) -> u32 {
+        self.points.len() as u32
+    }
+
+    /// Point at arc length `distance` from the start
+    ///
+    /// Distance is clamped to [0, length]. Within a segment the point is
+    /// interpolated as a + (b - a) * offset / segment_length in 128-bit,
+    /// floored once. An empty path returns the origin.
+    pub fn position_at_distance(&self, distance: i32) -> FixedVec2 {
+        let Some(&[x, y]) = self.points.first() else {
+            return FixedVec2::zero();
+        };
+        let total = *self.cumulative.last().unwrap();
+        let d = distance as i64;
+        if d <= 0 {
+            return FixedVec2::new(x, y);
+        }
+        if d >= total {
+            let [x, y] = self.points[self.points.len() - 1];
+            return FixedVec2::new(x, y);
+        }
+
+        // Last vertex at or before d; its segment has non-zero length
+        let i = self.cumulative.partition_point(|&c| c <= d) - 1;
+        let a = self.points[i];
+        let b = self.points[i + 1];
+        let offset = (d - self.cumulative[i]) as i128;
+        let seg_len = (self.cumulative[i + 1] - self.cumulative[i]) as i128;
+        let axis = |a: i32, b: i32| {
+            let delta = b as i128 - a as i128;
+            (a as i128 + (delta * offset).div_euclid(seg_len)) as i32
+        };
+        FixedVec2::new(axis(a[0], b[0]), axis(a[1], b[1]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ONE_FP: i32 = ONE as i32;
+
+    #[test]
+    fn polyline_length_and_lookup() {
+        // (0, 0) -> (3, 4) -> (3, 10): lengths 5 and 6
+        let path = Path::from_polyline(&[0, 0, 3 * ONE_FP, 4 * ONE_FP, 3 * ONE_FP, 10 * ONE_FP, 7]);
+        assert_eq!(path.vertex_count(), 3);
+        assert_eq!(path.length(), 11 * ONE_FP);
+        assert_eq!(path.position_at_distance(-ONE_FP), FixedVec2::new(0, 0));
+        assert_eq!(path.position_at_distance(5 * ONE_FP / 2), FixedVec2::new(3 * ONE_FP / 2, 2 * ONE_FP));
+        assert_eq!(path.position_at_distance(5 * ONE_FP), FixedVec2::new(3 * ONE_FP, 4 * ONE_FP));
+        assert_eq!(path.position_at_distance(8 * ONE_FP), FixedVec2::new(3 * ONE_FP, 7 * ONE_FP));
+        assert_eq!(path.position_at_distance(i32::MAX), FixedVec2::new(3 * ONE_FP, 10 * ONE_FP));
+    }
+
+    #[test]
+    fn repeated_vertices_are_skipped() {
+        let path = Path::from_polyline(&[0, 0, 0, 0, 2 * ONE_FP, 0]);
+        assert_eq!(path.position_at_distance(0), FixedVec2::new(0, 0));
+        assert_eq!(path.position_at_distance(ONE_FP), FixedVec2::new(ONE_FP, 0));
+    }
+
+    #[test]
+    fn empty_path_returns_the_origin() {
+        let path = Path::from_polyline(&[]);
+        assert_eq!(path.length(), 0);
+        assert_eq!(path.position_at_distance(ONE_FP), FixedVec2::zero());
+    }
+
+    #[test]
+    fn bezier_chain_hits_the_anchors() {
+        let chain = [
+            0, 0, ONE_FP, 2 * ONE_FP, 3 * ONE_FP, 2 * ONE_FP, 4 * ONE_FP, 0,
+            5 * ONE_FP, -2 * ONE_FP, 7 * ONE_FP, -2 * ONE_FP, 8 * ONE_FP, 0,
+        ];
+        let path = Path::from_bezier3_chain(&chain, 16);
+        assert_eq!(path.vertex_count(), 33);
+        assert_eq!(path.position_at_distance(0), FixedVec2::new(0, 0));
+        assert_eq!(path.position_at_distance(path.length()), FixedVec2::new(8 * ONE_FP, 0));
+        // Curved, so longer than the chord
+        assert!(path.length() > 8 * ONE_FP);
+    }
+}
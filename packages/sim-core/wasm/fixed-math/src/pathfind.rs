@@ -0,0 +1,424 @@
+//! A* pathfinding on weighted tile grids
+//!
+//! `PathGrid` keeps per-tile movement costs in wasm memory so a wave's
+//! worth of searches reuse one grid. The open set is a binary heap keyed
+//! on (f, h, tile index), a total order on integers, so which of several
+//! equally good paths comes out depends only on the grid and never on
+//! heap internals or JS engine details.
+
+use core::cmp::Reverse;
+use alloc::collections::BinaryHeap;
+use alloc::{vec, vec::Vec};
+
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+use crate::consts::{FRAC_1_SQRT2, SQRT2};
+use crate::raster::supercover;
+
+/// Orthogonal neighbour offsets, then diagonals
+const STEPS: [(i32, i32); 8] = [(1, 0), (0, -1), (-1, 0), (0, 1), (1, -1), (-1, -1), (-1, 1), (1, 1)];
+
+/// Cost of a diagonal step into a tile of the given cost
+fn diagonal(cost: i64) -> i64 {
+    (cost * SQRT2 as i64) >> 16
+}
+
+/// Grid of Q16.16 tile entry costs
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone, Debug)]
+pub struct PathGrid {
+    width: u32,
+    height: u32,
+    /// Cost of entering each tile, row-major; <= 0 is impassable
+    costs: Vec<i32>,
+    allow_diagonal: bool,
+    /// Smallest passable cost, for the admissible heuristic
+    min_cost: i64,
+}
+
+impl PathGrid {
+    fn index(&self, x: i32, y: i32) -> Option<usize> {
+        (x >= 0 && y >= 0 && (x as u32) < self.width && (y as u32) < self.height)
+            .then(|| y as usize * self.width as usize + x as usize)
+    }
+
+    fn passable(&self, x: i32, y: i32) -> bool {
+        self.index(x, y).is_some_and(|i| self.costs[i] > 0)
+    }
+
+    fn refresh_min_cost(&mut self) {
+        self.min_cost = self.costs.iter().filter(|&&c| c > 0).min().copied().unwrap_or(1) as i64;
+    }
+
+    /// Lower bound on the cost from (x, y) to the goal
+    ///
+    /// Manhattan (4-way) or octile (8-way) distance at the cheapest tile
+    /// cost, so it never overestimates.
+    fn heuristic(&self, x: i32, y: i32, gx: i32, gy: i32) -> i64 {
+        let (dx, dy) = ((x as i64 - gx as i64).abs(), (y as i64 - gy as i64).abs());
+        if self.allow_diagonal {
+            let (lo, hi) = (dx.min(dy), dx.max(dy));
+            (hi - lo) * self.min_cost + lo * diagonal(self.min_cost)
+        } else {
+            (dx + dy) * self.min_cost
+        }
+    }
+
+    /// Neighbour moves from tile `at`: (step index, neighbour, step cost)
+    ///
+    /// Cost is that of entering `enter` (the neighbour for a forward
+    /// search, `at` itself for a reverse one); diagonal moves past an
+    /// impassable corner are skipped.
+    fn moves(&self, at: usize, reverse: bool) -> impl Iterator<Item = (usize, usize, i64)> + '_ {
+        let w = self.width as usize;
+        let (cx, cy) = ((at % w) as i32, (at / w) as i32);
+        let count = if self.allow_diagonal { 8 } else { 4 };
+        STEPS[..count].iter().enumerate().filter_map(move |(k, &(dx, dy))| {
+            let next = self.index(cx + dx, cy + dy)?;
+            if self.costs[next] <= 0 || (k >= 4 && (!self.passable(cx + dx, cy) || !self.passable(cx, cy + dy))) {
+                return None;
+            }
+            let enter = self.costs[if reverse { at } else { next }] as i64;
+            Some((k, next, if k >= 4 { diagonal(enter) } else { enter }))
+        })
+    }
+
+    /// Cost from every tile to the goal (i64::MAX when unreachable)
+    fn distances_to(&self, goal_x: i32, goal_y: i32) -> Vec<i64> {
+        let mut dist = vec![i64::MAX; self.costs.len()];
+        if !self.passable(goal_x, goal_y) {
+            return dist;
+        }
+        let goal = goal_y as usize * self.width as usize + goal_x as usize;
+        let mut open = BinaryHeap::new();
+        dist[goal] = 0;
+        open.push(Reverse((0, goal)));
+        while let Some(Reverse((d, current))) = open.pop() {
+            if d > dist[current] {
+                continue;
+            }
+            for (_, prev, step) in self.moves(current, true) {
+                if d + step < dist[prev] {
+                    dist[prev] = d + step;
+                    open.push(Reverse((d + step, prev)));
+                }
+            }
+        }
+        dist
+    }
+
+    /// Whether a straight walk between two tiles stays on passable tiles
+    fn walkable(&self, ax: i32, ay: i32, bx: i32, by: i32) -> bool {
+        supercover(ax, ay, bx, by).chunks_exact(2).all(|t| self.passable(t[0], t[1]))
+    }
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl PathGrid {
+    /// Grid from row-major costs (missing entries are impassable)
+    ///
+    /// With `allow_diagonal`, diagonal steps cost √2 times the entered
+    /// tile and may not cut a corner past an impassable tile.
+    #[cfg_attr(feature = "wasm", wasm_bindgen(constructor))]
+    pub fn new(width: u32, height: u32, costs: &[i32], allow_diagonal: bool) -> PathGrid {
+        let tiles = width as usize * height as usize;
+        let mut grid_costs = vec![0; tiles];
+        let n = tiles.min(costs.len());
+        grid_costs[..n].copy_from_slice(&costs[..n]);
+        let mut grid = PathGrid { width, height, costs: grid_costs, allow_diagonal, min_cost: 1 };
+        grid.refresh_min_cost();
+        grid
+    }
+
+    /// Change one tile's cost; out-of-range tiles are ignored
+    pub fn set_cost(&mut self, x: i32, y: i32, cost: i32) {
+        if let Some(i) = self.index(x, y) {
+            self.costs[i] = cost;
+            self.refresh_min_cost();
+        }
+    }
+
+    pub fn cost(&self, x: i32, y: i32) -> i32 {
+        self.index(x, y).map_or(0, |i| self.costs[i])
+    }
+
+    /// Cheapest path from start to goal, packed `[x0, y0, ...]`
+    ///
+    /// Includes both endpoints; empty when either endpoint is impassable
+    /// or the goal is unreachable. A tile's cost is paid on entering it.
+    /// Ties on f go to the lower heuristic (closer to the goal), then to
+    /// the lower tile index; a tile's parent only changes on a strictly
+    /// cheaper route.
+    pub fn find_path(&self, start_x: i32, start_y: i32, goal_x: i32, goal_y: i32) -> Vec<i32> {
+        if !self.passable(start_x, start_y) || !self.passable(goal_x, goal_y) {
+            return Vec::new();
+        }
+        let w = self.width as usize;
+        let (start, goal) = (start_y as usize * w + start_x as usize, goal_y as usize * w + goal_x as usize);
+        let mut g = vec![i64::MAX; self.costs.len()];
+        let mut parent = vec![usize::MAX; self.costs.len()];
+        let mut closed = vec![false; self.costs.len()];
+        let mut open = BinaryHeap::new();
+        g[start] = 0;
+        let h = self.heuristic(start_x, start_y, goal_x, goal_y);
+        open.push(Reverse((h, h, start)));
+
+        while let Some(Reverse((_, _, current))) = open.pop() {
+            if closed[current] {
+                continue;
+            }
+            if current == goal {
+                break;
+            }
+            closed[current] = true;
+            for (_, next, step) in self.moves(current, false) {
+                if closed[next] {
+                    continue;
+                }
+                let tentative = g[current] + step;
+                if tentative < g[next] {
+                    g[next] = tentative;
+                    parent[next] = current;
+                    let h = self.heuristic((next % w) as i32, (next / w) as i32, goal_x, goal_y);
+                    open.push(Reverse((tentative + h, h, next)));
+                }
+            }
+        }
+
+        if g[goal] == i64::MAX {
+            return Vec::new();
+        }
+        let mut tiles = vec![goal];
+        while let Some(&last) = tiles.last() {
+            if last == start {
+                break;
+            }
+            tiles.push(parent[last]);
+        }
+        tiles.iter().rev().flat_map(|&t| [(t % w) as i32, (t / w) as i32]).collect()
+    }
+
+    /// Drop waypoints a straight walk can skip
+    ///
+    /// Greedy string pulling over a packed tile path: from each kept
+    /// waypoint, jump to the farthest later waypoint whose `supercover`
+    /// line is entirely passable. Costs are not re-weighed, so this suits
+    /// grids where passable tiles cost about the same.
+    pub fn smooth_path(&self, path: &[i32]) -> Vec<i32> {
+        let points: Vec<(i32, i32)> = path.chunks_exact(2).map(|p| (p[0], p[1])).collect();
+        if points.len() <= 2 {
+            return points.iter().flat_map(|&(x, y)| [x, y]).collect();
+        }
+        let mut out = vec![points[0].0, points[0].1];
+        let mut anchor = 0;
+        while anchor < points.len() - 1 {
+            let (ax, ay) = points[anchor];
+            let next = (anchor + 1..points.len())
+                .rev()
+                .find(|&j| self.walkable(ax, ay, points[j].0, points[j].1))
+                .unwrap_or(anchor + 1);
+            out.extend([points[next].0, points[next].1]);
+            anchor = next;
+        }
+        out
+    }
+
+    /// Cost from every tile to the goal (Dijkstra map), row-major
+    ///
+    /// The cost of a route is the sum of the tiles entered after leaving
+    /// a tile, goal included, exactly as `find_path` charges it. Values
+    /// saturate to i32::MAX, which also marks impassable and unreachable
+    /// tiles.
+    pub fn dijkstra_map(&self, goal_x: i32, goal_y: i32) -> Vec<i32> {
+        self.distances_to(goal_x, goal_y).iter().map(|&d| d.min(i32::MAX as i64) as i32).collect()
+    }
+
+    /// Per-tile unit direction toward the goal, packed `[dx0, dy0, ...]`
+    ///
+    /// One Dijkstra pass from the goal, then each tile points at the
+    /// neighbour that continues a cheapest route (the first such in step
+    /// order: +x, -y, -x, +y, then diagonals). Directions are Q16.16 unit
+    /// vectors, diagonals (±1/√2, ±1/√2). The goal, impassable and
+    /// unreachable tiles get (0, 0).
+    pub fn build_flow_field(&self, goal_x: i32, goal_y: i32) -> Vec<i32> {
+        let dist = self.distances_to(goal_x, goal_y);
+        let mut out = vec![0; 2 * self.costs.len()];
+        for (tile, &d) in dist.iter().enumerate() {
+            if d == 0 || d == i64::MAX {
+                continue;
+            }
+            let best = self
+                .moves(tile, false)
+                .filter(|&(_, next, step)| dist[next] != i64::MAX && dist[next] + step == d)
+                .map(|(k, _, _)| k)
+                .next();
+            if let Some(k) = best {
+                let (dx, dy) = STEPS[k];
+                let unit = if k >= 4 { FRAC_1_SQRT2 } else { 1 << 16 };
+                out[2 * tile] = dx * unit;
+                out[2 * tile + 1] = dy * unit;
+            }
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ONE: i32 = 1 << 16;
+
+    /// Grid from rows of whole costs ('#' impassable, digits = cost)
+    fn grid(rows: &[&str], diagonal: bool) -> PathGrid {
+        let costs: Vec<i32> = rows
+            .iter()
+            .flat_map(|r| r.chars().map(|c| c.to_digit(10).map_or(0, |d| d as i32 * ONE)))
+            .collect();
+        PathGrid::new(rows[0].len() as u32, rows.len() as u32, &costs, diagonal)
+    }
+
+    /// Cost find_path charges for a packed path
+    fn path_cost(g: &PathGrid, path: &[i32]) -> i64 {
+        path.chunks(2)
+            .collect::<Vec<_>>()
+            .windows(2)
+            .map(|w| {
+                let c = g.cost(w[1][0], w[1][1]) as i64;
+                if w[0][0] != w[1][0] && w[0][1] != w[1][1] { diagonal(c) } else { c }
+            })
+            .sum()
+    }
+
+    #[test]
+    fn path_goes_around_walls() {
+        let g = grid(&["11111", "1###1", "11111"], false);
+        let path = g.find_path(0, 1, 4, 1);
+        assert_eq!(path[..2], [0, 1]);
+        assert_eq!(path[path.len() - 2..], [4, 1]);
+        assert_eq!(path.len(), 2 * 7);
+        for t in path.chunks(2).collect::<Vec<_>>().windows(2) {
+            assert_eq!((t[0][0] - t[1][0]).abs() + (t[0][1] - t[1][1]).abs(), 1);
+            assert!(g.cost(t[1][0], t[1][1]) > 0);
+        }
+        assert_eq!(g.find_path(2, 0, 2, 0), [2, 0]);
+    }
+
+    #[test]
+    fn path_prefers_cheap_tiles() {
+        let g = grid(&["19991", "11111"], false);
+        let path = g.find_path(0, 0, 4, 0);
+        assert_eq!(path, [0, 0, 0, 1, 1, 1, 2, 1, 3, 1, 4, 1, 4, 0]);
+        assert_eq!(path_cost(&g, &path), 6 * ONE as i64);
+    }
+
+    #[test]
+    fn blocked_or_unreachable_gives_empty() {
+        let mut g = grid(&["11#11", "11#11"], true);
+        assert!(g.find_path(0, 0, 4, 1).is_empty());
+        assert!(g.find_path(2, 0, 0, 0).is_empty());
+        assert!(g.find_path(0, 0, 9, 9).is_empty());
+        g.set_cost(2, 1, ONE);
+        assert_eq!(g.find_path(0, 0, 4, 1).len(), 2 * 5);
+        g.set_cost(50, 1, ONE);
+        assert_eq!(g.cost(50, 1), 0);
+    }
+
+    #[test]
+    fn diagonals_never_cut_corners() {
+        let g = grid(&["1#", "11"], true);
+        assert_eq!(g.find_path(0, 0, 1, 1), [0, 0, 0, 1, 1, 1]);
+        let open = grid(&["11", "11"], true);
+        assert_eq!(open.find_path(0, 0, 1, 1), [0, 0, 1, 1]);
+    }
+
+    #[test]
+    fn ties_are_deterministic() {
+        let g = grid(&["1111", "1111", "1111"], false);
+        let path = g.find_path(0, 0, 3, 2);
+        // Every route costs 5; (f, h, index) picks the row-0 one
+        assert_eq!(path, [0, 0, 1, 0, 2, 0, 3, 0, 3, 1, 3, 2]);
+    }
+
+    #[test]
+    fn smoothing_keeps_walkable_shortcuts() {
+        let g = grid(&["11111", "1###1", "11111"], false);
+        let path = g.find_path(0, 0, 4, 2);
+        let smooth = g.smooth_path(&path);
+        assert_eq!(smooth, [0, 0, 4, 0, 4, 2]);
+        let open = grid(&["111", "111", "111"], false);
+        assert_eq!(open.smooth_path(&open.find_path(0, 0, 2, 2)), [0, 0, 2, 2]);
+        assert_eq!(open.smooth_path(&[1, 1, 2, 2, 7]), [1, 1, 2, 2]);
+    }
+
+    /// Random grid of costs 1..=4 with about a fifth impassable
+    fn random_grid(w: u32, h: u32, seed: u32, diagonal: bool) -> PathGrid {
+        let mut s = seed;
+        let costs: Vec<i32> = (0..w * h)
+            .map(|_| {
+                s = s.wrapping_mul(1664525).wrapping_add(1013904223);
+                let r = s >> 24;
+                if r < 50 { 0 } else { (1 + (r % 4) as i32) * ONE + (r as i32 * 37) }
+            })
+            .collect();
+        PathGrid::new(w, h, &costs, diagonal)
+    }
+
+    #[test]
+    fn a_star_is_optimal_against_dijkstra_map() {
+        for (seed, diagonal) in [(1, false), (2, true), (3, false), (4, true)] {
+            let mut g = random_grid(12, 9, seed, diagonal);
+            let (gx, gy) = (10, 7);
+            g.set_cost(gx, gy, ONE);
+            let map = g.dijkstra_map(gx, gy);
+            let mut reached = 0;
+            for sy in 0..9 {
+                for sx in 0..12 {
+                    let path = g.find_path(sx, sy, gx, gy);
+                    let d = map[(sy * 12 + sx) as usize];
+                    if path.is_empty() {
+                        assert_eq!(d, i32::MAX, "seed {seed} ({sx}, {sy})");
+                    } else {
+                        assert_eq!(path_cost(&g, &path), d as i64, "seed {seed} ({sx}, {sy})");
+                        reached += 1;
+                    }
+                }
+            }
+            assert!(reached > 30);
+        }
+    }
+
+    #[test]
+    fn dijkstra_map_marks_walls_and_the_goal() {
+        let g = grid(&["121", "#1#"], false);
+        assert_eq!(g.dijkstra_map(0, 0), [0, ONE, 3 * ONE, i32::MAX, 3 * ONE, i32::MAX]);
+        assert!(g.dijkstra_map(0, 1).iter().all(|&d| d == i32::MAX));
+    }
+
+    #[test]
+    fn flow_field_descends_the_dijkstra_map() {
+        let mut g = random_grid(10, 8, 5, true);
+        let (gx, gy) = (2, 3);
+        g.set_cost(gx, gy, ONE);
+        let map = g.dijkstra_map(gx, gy);
+        let flow = g.build_flow_field(gx, gy);
+        let mut moving = 0;
+        for i in 0..80usize {
+            let (dx, dy) = (flow[2 * i], flow[2 * i + 1]);
+            if map[i] == 0 || map[i] == i32::MAX {
+                assert_eq!((dx, dy), (0, 0));
+                continue;
+            }
+            let (sx, sy) = (dx.signum(), dy.signum());
+            let diag = sx != 0 && sy != 0;
+            assert_eq!(dx.abs(), if diag { FRAC_1_SQRT2 } else if sx != 0 { ONE } else { 0 });
+            let (x, y) = ((i % 10) as i32 + sx, (i / 10) as i32 + sy);
+            let c = g.cost(x, y) as i64;
+            let step = if diag { diagonal(c) } else { c };
+            assert_eq!(map[(y * 10 + x) as usize] as i64 + step, map[i] as i64);
+            moving += 1;
+        }
+        assert!(moving > 30);
+    }
+}
@@ -0,0 +1,311 @@
+//! Batch physics kernels
+//!
+//! Entity state lives in packed per-component arrays that are updated
+//! in place (one wasm call per tick instead of several per entity).
+//! Every step uses the crate's scalar ops, so a batch update is
+//! bit-identical to applying the same formula one entity at a time.
+
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+use crate::collide::unit_q32;
+use crate::damping::decay_factor_q30;
+use crate::vec2::FixedVec2;
+use crate::{mul, simd_prefix, truncate_i128, Q30_FRAC_BITS};
+
+/// Semi-implicit (symplectic) Euler step for `count` entities
+///
+/// Per entity: v += a * dt, then x += v * dt with the new velocity
+/// (`mul` products, wrapping adds like `FixedVec2::add`). The arrays
+/// are updated in place; `count` is clamped to the shortest array.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[allow(clippy::too_many_arguments)]
+pub fn integrate(
+    xs: &mut [i32],
+    ys: &mut [i32],
+    vxs: &mut [i32],
+    vys: &mut [i32],
+    axs: &[i32],
+    ays: &[i32],
+    dt: i32,
+    count: u32,
+) {
+    let n = [xs.len(), ys.len(), vxs.len(), vys.len(), axs.len(), ays.len()]
+        .into_iter()
+        .fold(count as usize, usize::min);
+    let start = simd_prefix!(crate::simd::integrate(xs, ys, vxs, vys, axs, ays, dt, n));
+    for i in start..n {
+        vxs[i] = vxs[i].wrapping_add(mul(axs[i], dt));
+        vys[i] = vys[i].wrapping_add(mul(ays[i], dt));
+        xs[i] = xs[i].wrapping_add(mul(vxs[i], dt));
+        ys[i] = ys[i].wrapping_add(mul(vys[i], dt));
+    }
+}
+
+/// Whether entity i is pinned (missing entries are free)
+fn is_pinned(pinned: &[u8], i: usize) -> bool {
+    pinned.get(i).is_some_and(|&p| p != 0)
+}
+
+/// Position-Verlet step for packed `[x0, y0, x1, y1, ...]` points
+///
+/// Per free point: x_next = 2x - x_prev + a dt², with the uniform
+/// acceleration (ax, ay) (e.g. gravity) and a dt² formed by `mul`; the
+/// old position becomes `previous`. Velocity is implicit in
+/// positions - previous, so constraint corrections feed back into the
+/// motion. Points with a nonzero `pinned` entry stay where they are
+/// (`pinned` may be shorter than the point count). The shorter of
+/// `positions` and `previous` sets the count.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn verlet_integrate(positions: &mut [i32], previous: &mut [i32], pinned: &[u8], ax: i32, ay: i32, dt: i32) {
+    let dt2 = mul(dt, dt);
+    let (step_x, step_y) = (mul(ax, dt2), mul(ay, dt2));
+    let n = positions.len().min(previous.len()) / 2;
+    for i in 0..n {
+        if is_pinned(pinned, i) {
+            previous[2 * i] = positions[2 * i];
+            previous[2 * i + 1] = positions[2 * i + 1];
+            continue;
+        }
+        for (k, step) in [(2 * i, step_x), (2 * i + 1, step_y)] {
+            let x = positions[k];
+            positions[k] = x.wrapping_mul(2).wrapping_sub(previous[k]).wrapping_add(step);
+            previous[k] = x;
+        }
+    }
+}
+
+/// Relax distance constraints between Verlet points in place
+///
+/// `links` holds packed `[a, b, rest_length]` triples (point indices and
+/// a Q16.16 length). Each iteration visits the links in array order
+/// (Gauss-Seidel), moving both endpoints along their offset so the
+/// distance becomes `rest_length`: the correction delta * (dist - rest)
+/// / dist is computed in 128-bit and split between free endpoints
+/// (truncated half to a, the remainder to b, so nothing is lost), or
+/// applied entirely to the free one if the other is pinned. An endpoint
+/// pushed out of the i32 range wraps and raises `StatusFlag::Overflow`.
+/// Links with out-of-range indices, coincident endpoints or two pinned
+/// endpoints are skipped.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn verlet_constrain(positions: &mut [i32], pinned: &[u8], links: &[i32], iterations: u32) {
+    let n = positions.len() / 2;
+    for _ in 0..iterations {
+        for link in links.chunks_exact(3) {
+            let (a, b, rest) = (link[0] as usize, link[1] as usize, link[2] as i128);
+            if a >= n || b >= n || a == b {
+                continue;
+            }
+            let (pin_a, pin_b) = (is_pinned(pinned, a), is_pinned(pinned, b));
+            if pin_a && pin_b {
+                continue;
+            }
+
+            let dx = positions[2 * b] as i128 - positions[2 * a] as i128;
+            let dy = positions[2 * b + 1] as i128 - positions[2 * a + 1] as i128;
+            let dist = ((dx * dx + dy * dy) as u128).isqrt() as i128;
+            if dist == 0 {
+                continue;
+            }
+            let cx = dx * (dist - rest) / dist;
+            let cy = dy * (dist - rest) / dist;
+            let (ax, ay) = match (pin_a, pin_b) {
+                (false, false) => (cx / 2, cy / 2),
+                (false, true) => (cx, cy),
+                _ => (0, 0),
+            };
+            let (bx, by) = (cx - ax, cy - ay);
+            positions[2 * a] = truncate_i128(positions[2 * a] as i128 + ax);
+            positions[2 * a + 1] = truncate_i128(positions[2 * a + 1] as i128 + ay);
+            positions[2 * b] = truncate_i128(positions[2 * b] as i128 - bx);
+            positions[2 * b + 1] = truncate_i128(positions[2 * b + 1] as i128 - by);
+        }
+    }
+}
+
+/// Velocity after linear drag over dt, in closed form
+///
+/// v * exp(-drag_coeff * dt) rather than the per-tick approximation
+/// v * (1 - drag_coeff * dt), so one catch-up step of 3 dt lands where
+/// three steps of dt would (up to the final truncation) instead of
+/// drifting with the tick size. Both components share one Q2.30 factor
+/// (the same one `decay` uses) and are truncated toward zero, so a
+/// dragged velocity always comes to rest at exactly 0. Non-positive
+/// drag_coeff * dt leaves the velocity unchanged.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn apply_drag(vel: &FixedVec2, drag_coeff: i32, dt: i32) -> FixedVec2 {
+    let factor = decay_factor_q30(drag_coeff, dt);
+    let scale = |v: i32| ((v as i64 * factor) / (1 << Q30_FRAC_BITS)) as i32;
+    FixedVec2 { x: scale(vel.x), y: scale(vel.y) }
+}
+
+/// Explosion knockback impulses for packed `[x0, y0, x1, y1, ...]` points
+///
+/// Writes one impulse per point into `out` (same layout): away from
+/// `center` with magnitude strength * (1 - distance / radius), linear
+/// falloff reaching 0 at the edge, and (0, 0) for points outside the
+/// radius. Distances are exact 128-bit squares compared against
+/// radius²; a point at the center itself is pushed along +x. The count
+/// is set by the shorter array. Returns the number of points inside the
+/// radius.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn knockback_impulses(center: &FixedVec2, radius: i32, strength: i32, positions: &[i32], out: &mut [i32]) -> u32 {
+    let n = positions.len().min(out.len()) / 2;
+    let r = radius as i128;
+    let mut hits = 0;
+    for i in 0..n {
+        let dx = positions[2 * i] as i128 - center.x as i128;
+        let dy = positions[2 * i + 1] as i128 - center.y as i128;
+        let d2 = dx * dx + dy * dy;
+        if r <= 0 || d2 > r * r {
+            out[2 * i] = 0;
+            out[2 * i + 1] = 0;
+            continue;
+        }
+        hits += 1;
+        let dist = (d2 as u128).isqrt() as i128;
+        let magnitude = strength as i128 * (r - dist) / r;
+        let (ux, uy) = unit_q32(dx, dy);
+        out[2 * i] = truncate_i128((ux as i128 * magnitude) >> 16);
+        out[2 * i + 1] = truncate_i128((uy as i128 * magnitude) >> 16);
+    }
+    hits
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ONE: i32 = 1 << 16;
+
+    #[test]
+    fn integrate_matches_per_entity_formula() {
+        let mut xs = [0, ONE, -3 * ONE, i32::MAX, 5];
+        let mut ys = [0, 2 * ONE, 7 * ONE, 0, -5];
+        let mut vxs = [ONE, -ONE / 3, 123_456, ONE, 0];
+        let mut vys = [0, ONE / 7, -654_321, 0, 0];
+        let axs = [0, ONE, -ONE / 5, 0, 9];
+        let ays = [-10 * ONE, 0, 3 * ONE, 0, 9];
+        let dt = ONE / 60;
+        let (x0, y0, vx0, vy0) = (xs, ys, vxs, vys);
+        integrate(&mut xs, &mut ys, &mut vxs, &mut vys, &axs, &ays, dt, 10);
+        for i in 0..5 {
+            let vx = vx0[i].wrapping_add(mul(axs[i], dt));
+            let vy = vy0[i].wrapping_add(mul(ays[i], dt));
+            assert_eq!((vxs[i], vys[i]), (vx, vy));
+            assert_eq!((xs[i], ys[i]), (x0[i].wrapping_add(mul(vx, dt)), y0[i].wrapping_add(mul(vy, dt))));
+        }
+    }
+
+    #[test]
+    fn integrate_stops_at_count() {
+        let mut xs = [0; 3];
+        let mut ys = [0; 3];
+        let mut vxs = [ONE; 3];
+        let mut vys = [0; 3];
+        integrate(&mut xs, &mut ys, &mut vxs, &mut vys, &[0; 3], &[0; 2], ONE, 3);
+        assert_eq!(xs, [ONE, ONE, 0]);
+        integrate(&mut xs, &mut ys, &mut vxs, &mut vys, &[0; 3], &[0; 3], ONE, 1);
+        assert_eq!(xs, [2 * ONE, ONE, 0]);
+    }
+
+    #[test]
+    fn verlet_carries_implicit_velocity() {
+        let mut pos = [ONE, 0, 5 * ONE, 5 * ONE];
+        let mut prev = [0, 0, 4 * ONE, 5 * ONE];
+        verlet_integrate(&mut pos, &mut prev, &[0, 1], 0, -2 * ONE, ONE / 2);
+        // a dt² = -2 * 0.25
+        assert_eq!(pos, [2 * ONE, -ONE / 2, 5 * ONE, 5 * ONE]);
+        assert_eq!(prev, [ONE, 0, 5 * ONE, 5 * ONE]);
+        verlet_integrate(&mut pos, &mut prev, &[], 0, 0, ONE);
+        assert_eq!(pos, [3 * ONE, -ONE, 5 * ONE, 5 * ONE]);
+    }
+
+    #[test]
+    fn constraints_restore_rest_length() {
+        let mut pos = [0, 0, 4 * ONE, 0];
+        verlet_constrain(&mut pos, &[], &[0, 1, 2 * ONE], 1);
+        assert_eq!(pos, [ONE, 0, 3 * ONE, 0]);
+        // Pinned endpoint: the free one takes the full correction
+        let mut pos = [0, 0, 0, 4 * ONE];
+        verlet_constrain(&mut pos, &[1], &[0, 1, 2 * ONE], 1);
+        assert_eq!(pos, [0, 0, 0, 2 * ONE]);
+        let mut pos = [0, 0, 0, 4 * ONE];
+        verlet_constrain(&mut pos, &[0, 1], &[0, 1, 2 * ONE], 1);
+        assert_eq!(pos, [0, 2 * ONE, 0, 4 * ONE]);
+    }
+
+    #[test]
+    fn constraints_skip_degenerate_links() {
+        let start = [ONE, ONE, ONE, ONE, 9 * ONE, 0];
+        let mut pos = start;
+        verlet_constrain(&mut pos, &[0, 0, 0], &[0, 1, ONE, 0, 0, ONE, 2, 7, ONE], 5);
+        assert_eq!(pos, start);
+        verlet_constrain(&mut pos, &[1, 0, 1], &[0, 2, ONE], 3);
+        assert_eq!(pos, start);
+    }
+
+    #[test]
+    fn chain_converges_with_iterations() {
+        let mut pos = [0, 0, 3 * ONE, 0, 6 * ONE, 0];
+        let links = [0, 1, ONE, 1, 2, ONE];
+        verlet_constrain(&mut pos, &[1], &links, 40);
+        let gap = |a: usize, b: usize| ((pos[2 * b] - pos[2 * a]) as f64 / ONE as f64).abs();
+        assert!((gap(0, 1) - 1.0).abs() < 1e-3 && (gap(1, 2) - 1.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn drag_matches_exponential_decay() {
+        let vel = FixedVec2::new(10 * ONE, -4 * ONE);
+        let d = apply_drag(&vel, ONE / 2, ONE);
+        assert_eq!(d, FixedVec2::new(crate::damping::decay(vel.x, ONE / 2, ONE), crate::damping::decay(vel.y, ONE / 2, ONE)));
+        let e = (-0.5f64).exp();
+        assert!((d.x as f64 - 10.0 * e * ONE as f64).abs() < 4.0);
+        assert!((d.y as f64 + 4.0 * e * ONE as f64).abs() < 4.0);
+    }
+
+    #[test]
+    fn drag_is_independent_of_tick_size() {
+        let vel = FixedVec2::new(7 * ONE, 3 * ONE);
+        let dt = ONE / 20;
+        let mut stepped = vel;
+        for _ in 0..3 {
+            stepped = apply_drag(&stepped, 2 * ONE, dt);
+        }
+        let once = apply_drag(&vel, 2 * ONE, 3 * dt);
+        assert!((once.x - stepped.x).abs() <= 2 && (once.y - stepped.y).abs() <= 2);
+    }
+
+    #[test]
+    fn drag_comes_to_rest_and_ignores_non_positive_input() {
+        let mut v = FixedVec2::new(-ONE, ONE);
+        for _ in 0..200 {
+            v = apply_drag(&v, 4 * ONE, ONE / 10);
+        }
+        assert_eq!(v, FixedVec2::zero());
+        let vel = FixedVec2::new(3, -3);
+        assert_eq!(apply_drag(&vel, -ONE, ONE), vel);
+        assert_eq!(apply_drag(&vel, ONE, 0), vel);
+    }
+
+    #[test]
+    fn knockback_falls_off_linearly() {
+        let center = FixedVec2::new(ONE, ONE);
+        let pos = [3 * ONE, ONE, ONE, -2 * ONE, 5 * ONE, ONE, 9 * ONE, ONE, ONE, ONE];
+        let mut out = [-1; 10];
+        assert_eq!(knockback_impulses(&center, 4 * ONE, 8 * ONE, &pos, &mut out), 4);
+        // Half the radius: half strength; three quarters: a quarter; the
+        // edge and outside: zero; the center itself: full strength along +x
+        assert_eq!(out, [4 * ONE, 0, 0, -2 * ONE, 0, 0, 0, 0, 8 * ONE, 0]);
+    }
+
+    #[test]
+    fn knockback_degenerate_inputs() {
+        let mut out = [7; 4];
+        assert_eq!(knockback_impulses(&FixedVec2::zero(), 0, ONE, &[0, 0, ONE, 0], &mut out), 0);
+        assert_eq!(out, [0; 4]);
+        let mut out = [7; 3];
+        assert_eq!(knockback_impulses(&FixedVec2::zero(), ONE, ONE, &[0, 0, 0, 0], &mut out), 1);
+        assert_eq!(out, [ONE, 0, 7]);
+    }
+}
@@ -0,0 +1,162 @@
+//! Poisson-disk sampling
+//!
+//! Bridson's algorithm over a Q16.16 rectangle, driven by a caller's
+//! `FixedRng`: obstacle and resource scatter keeps a minimum spacing and
+//! is reproduced exactly from the generator state. Candidates come from
+//! integer rejection sampling of the annulus, so no trig is involved.
+
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+use crate::aabb::Aabb;
+use crate::rng::FixedRng;
+
+/// 1/√2 in Q16.16, rounded down
+const FRAC_1_SQRT_2: i64 = 46_340;
+
+/// Points at least `radius` apart covering `bounds`, packed `[x0, y0, ...]`
+///
+/// Standard Bridson: start from one uniform point, then repeatedly pick a
+/// random active point and try up to `attempts` candidates uniformly
+/// placed in the annulus [radius, 2 radius] around it; a candidate is
+/// kept when it lies inside the (closed) bounds and no earlier point is
+/// strictly closer than radius. An active point that yields nothing is
+/// retired. Distances are exact squared comparisons on a background grid
+/// with cells of radius / √2, so each cell holds at most one point.
+///
+/// Points are returned in generation order, which depends only on the
+/// inputs and the generator state. A non-positive radius or an empty box
+/// returns no points and draws nothing.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn poisson_disk(bounds: &Aabb, radius: i32, attempts: u32, rng: &mut FixedRng) -> Vec<i32> {
+    if radius <= 0 || bounds.min_x > bounds.max_x || bounds.min_y > bounds.max_y {
+        return Vec::new();
+    }
+    let r = radius as i64;
+    let r2 = r as i128 * r as i128;
+    let cell = ((r * FRAC_1_SQRT_2) >> 16).max(1);
+    let cell_of = |x: i64, y: i64| ((x - bounds.min_x as i64) / cell, (y - bounds.min_y as i64) / cell);
+    let reach = (r + cell - 1) / cell;
+    // Uniform in the closed range [lo, hi] of i64 values that fit i32
+    let uniform = |rng: &mut FixedRng, lo: i64, hi: i64| lo + rng.below_u64((hi - lo + 1) as u64) as i64;
+
+    let mut points: Vec<(i64, i64)> = Vec::new();
+    let mut grid: BTreeMap<(i64, i64), usize> = BTreeMap::new();
+    let mut active: Vec<usize> = Vec::new();
+
+    let first = (
+        uniform(rng, bounds.min_x as i64, bounds.max_x as i64),
+        uniform(rng, bounds.min_y as i64, bounds.max_y as i64),
+    );
+    grid.insert(cell_of(first.0, first.1), 0);
+    points.push(first);
+    active.push(0);
+
+    while !active.is_empty() {
+        let slot = rng.below(active.len() as u32) as usize;
+        let (px, py) = points[active[slot]];
+        let mut placed = false;
+        for _ in 0..attempts {
+            let (dx, dy) = loop {
+                let dx = uniform(rng, -2 * r, 2 * r);
+                let dy = uniform(rng, -2 * r, 2 * r);
+                let d2 = dx as i128 * dx as i128 + dy as i128 * dy as i128;
+                if d2 >= r2 && d2 <= 4 * r2 {
+                    break (dx, dy);
+                }
+            };
+            let (x, y) = (px + dx, py + dy);
+            if x < bounds.min_x as i64 || x > bounds.max_x as i64 || y < bounds.min_y as i64 || y > bounds.max_y as i64 {
+                continue;
+            }
+            let (cx, cy) = cell_of(x, y);
+            let crowded = (cx - reach..=cx + reach).any(|gx| {
+                (cy - reach..=cy + reach).any(|gy| {
+                    grid.get(&(gx, gy)).is_some_and(|&i| {
+                        let (qx, qy) = points[i];
+                        let (ex, ey) = ((qx - x) as i128, (qy - y) as i128);
+                        ex * ex + ey * ey < r2
+                    })
+                })
+            });
+            if !crowded {
+                grid.insert((cx, cy), points.len());
+                active.push(points.len());
+                points.push((x, y));
+                placed = true;
+                break;
+            }
+        }
+        if !placed {
+            active.swap_remove(slot);
+        }
+    }
+
+    points.iter().flat_map(|&(x, y)| [x as i32, y as i32]).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ONE: i32 = 1 << 16;
+
+    fn pairs(points: &[i32]) -> Vec<(i64, i64)> {
+        points.chunks(2).map(|p| (p[0] as i64, p[1] as i64)).collect()
+    }
+
+    #[test]
+    fn points_keep_spacing_inside_bounds() {
+        let bounds = Aabb::new(-10 * ONE, 0, 10 * ONE, 15 * ONE);
+        let r = 2 * ONE;
+        let pts = pairs(&poisson_disk(&bounds, r, 30, &mut FixedRng::new(17)));
+        assert!(pts.len() > 20);
+        let r2 = r as i64 * r as i64;
+        for (i, &(x, y)) in pts.iter().enumerate() {
+            assert!((bounds.min_x as i64..=bounds.max_x as i64).contains(&x));
+            assert!((bounds.min_y as i64..=bounds.max_y as i64).contains(&y));
+            for &(qx, qy) in &pts[i + 1..] {
+                assert!((qx - x) * (qx - x) + (qy - y) * (qy - y) >= r2);
+            }
+        }
+    }
+
+    #[test]
+    fn sampling_covers_the_box() {
+        // Every probe lies within 2r of a sample (no large holes)
+        let bounds = Aabb::new(0, 0, 20 * ONE, 20 * ONE);
+        let r = 2 * ONE as i64;
+        let pts = pairs(&poisson_disk(&bounds, r as i32, 30, &mut FixedRng::new(3)));
+        for px in (0..=20).map(|i| i as i64 * ONE as i64) {
+            for py in (0..=20).map(|i| i as i64 * ONE as i64) {
+                assert!(pts.iter().any(|&(x, y)| (x - px) * (x - px) + (y - py) * (y - py) <= 4 * r * r));
+            }
+        }
+    }
+
+    #[test]
+    fn sampling_is_reproducible_from_the_generator() {
+        let bounds = Aabb::new(0, 0, 8 * ONE, 8 * ONE);
+        let mut a = FixedRng::new(40);
+        let mut b = a.clone();
+        let first = poisson_disk(&bounds, ONE, 20, &mut a);
+        assert_eq!(first, poisson_disk(&bounds, ONE, 20, &mut b));
+        assert_eq!(a, b);
+        assert_ne!(first, poisson_disk(&bounds, ONE, 20, &mut a));
+    }
+
+    #[test]
+    fn degenerate_inputs_draw_nothing() {
+        let mut rng = FixedRng::new(1);
+        let before = rng.clone();
+        assert!(poisson_disk(&Aabb::new(0, 0, ONE, ONE), 0, 30, &mut rng).is_empty());
+        assert!(poisson_disk(&Aabb::new(ONE, 0, 0, ONE), ONE, 30, &mut rng).is_empty());
+        assert_eq!(rng, before);
+        // A point-sized box holds exactly its one point
+        assert_eq!(poisson_disk(&Aabb::new(ONE, 2, ONE, 2), ONE, 5, &mut rng), [ONE, 2]);
+        assert_eq!(poisson_disk(&Aabb::new(0, 0, ONE, ONE), ONE, 0, &mut rng).len(), 2);
+    }
+}
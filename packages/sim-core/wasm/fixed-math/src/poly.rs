@@ -0,0 +1,286 @@
+//! Polynomials with Q16.16 coefficients
+//!
+//! Evaluation and real-root solving. Discriminants and products are formed in 128-bit so no intermediate
+//! can overflow; every root is divided and saturated to Q16.16 once.
+
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+use crate::saturate_i128;
+
+/// Real roots of a polynomial, ascending
+///
+/// Only the first `count` roots are meaningful; the rest are 0.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct QuadraticRoots {
+    /// Number of distinct real roots (0, 1 or 2)
+    pub count: u32,
+    /// Smaller root (Q16.16)
+    pub x0: i32,
+    /// Larger root (Q16.16)
+    pub x1: i32,
+}
+
+impl QuadraticRoots {
+    fn none() -> QuadraticRoots {
+        QuadraticRoots { count: 0, x0: 0, x1: 0 }
+    }
+
+    fn one(x: i32) -> QuadraticRoots {
+        QuadraticRoots { count: 1, x0: x, x1: 0 }
+    }
+}
+
+/// (n << 16) / d truncated toward zero and saturated to Q16.16
+fn div_q16(n: i128, d: i128) -> i32 {
+    saturate_i128((n << 16) / d)
+}
+
+/// Real roots of a x² + b x + c = 0 for Q16.16 coefficients
+///
+/// The discriminant b² - 4ac is exact in 128-bit (Q32.32) and its
+/// square root is floored to Q16.16. Roots use the cancellation-free
+/// form q = -(b + sign(b) sqrt(D)) / 2, x = q / a and x = c / q, each
+/// truncated toward zero; out-of-range roots saturate and raise
+/// `StatusFlag::Overflow`.
+///
+/// Degenerate inputs: a = 0 solves b x + c = 0 (one root, or none when
+/// b = 0 too); D < 0 gives no roots; D = 0 gives one root -b / 2a.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn solve_quadratic(a: i32, b: i32, c: i32) -> QuadraticRoots {
+    quadratic_roots(a as i128, b as i128, c as i128)
+}
+
+/// `solve_quadratic` on widened coefficients (the cubic solver passes
+/// 3a and 2b, which may not fit in i32)
+fn quadratic_roots(a: i128, b: i128, c: i128) -> QuadraticRoots {
+    if a == 0 {
+        if b == 0 {
+            return QuadraticRoots::none();
+        }
+        return QuadraticRoots::one(div_q16(-c, b));
+    }
+
+    let disc = b * b - 4 * a * c;
+    if disc < 0 {
+        return QuadraticRoots::none();
+    }
+    if disc == 0 {
+        return QuadraticRoots::one(div_q16(-b, 2 * a));
+    }
+
+    let s = (disc as u128).isqrt() as i128;
+    let q2 = if b < 0 { s - b } else { -b - s };
+    // q2 = 2q is non-zero: |b| + s > 0 since D > 0
+    let r0 = div_q16(q2, 2 * a);
+    let r1 = div_q16(2 * c, q2);
+    let (x0, x1) = if r0 <= r1 { (r0, r1) } else { (r1, r0) };
+    if x0 == x1 {
+        return QuadraticRoots::one(x0);
+    }
+    QuadraticRoots { count: 2, x0, x1 }
+}
+
+/// Evaluate c0 + c1 x + c2 x² + ... with Horner's scheme
+///
+/// `coeffs` are Q16.16 in ascending power order. Each step is
+/// acc = (acc * x) >> 16 + c, floored like `mul`, on a 128-bit
+/// accumulator clamped to the i64 range; the result saturates to i32
+/// (raising `StatusFlag::Overflow`). An empty slice evaluates to 0.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn poly_eval(coeffs: &[i32], x: i32) -> i32 {
+    let limit = i64::MAX as i128;
+    let acc = coeffs.iter().rev().fold(0i128, |acc, &c| {
+        (((acc * x as i128) >> 16) + c as i128).clamp(-limit - 1, limit)
+    });
+    saturate_i128(acc)
+}
+
+/// Real roots of a cubic, ascending
+///
+/// Only the first `count` roots are meaningful; the rest are 0.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CubicRoots {
+    /// Number of distinct real roots found (0 to 3)
+    pub count: u32,
+    /// Smallest root (Q16.16)
+    pub x0: i32,
+    /// Middle root (Q16.16)
+    pub x1: i32,
+    /// Largest root (Q16.16)
+    pub x2: i32,
+}
+
+/// Sign of a x³ + b x² + c x + d at a Q16.16 x, exact in Q64
+fn cubic_sign(a: i128, b: i128, c: i128, d: i128, x: i64) -> i32 {
+    const ONE: i128 = 1 << 16;
+    let x = x as i128;
+    let v = ((a * x + b * ONE) * x + c * ONE * ONE) * x + d * ONE * ONE * ONE;
+    v.signum() as i32
+}
+
+/// Real roots of a x³ + b x² + c x + d = 0 for Q16.16 coefficients
+///
+/// The derivative's roots split the i32 range into intervals on which
+/// the cubic is monotone; each interval with a sign change is bisected
+/// on the Q16.16 grid with the polynomial's sign evaluated exactly in
+/// 128-bit. Roots are therefore exact when representable and floored to
+/// the grid otherwise. Roots outside the Q16.16 range are not reported,
+/// and a tangent (double) root is found only when it lies exactly on the
+/// grid.
+///
+/// a = 0 falls back to `solve_quadratic`.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn solve_cubic(a: i32, b: i32, c: i32, d: i32) -> CubicRoots {
+    if a == 0 {
+        let q = solve_quadratic(b, c, d);
+        return CubicRoots { count: q.count, x0: q.x0, x1: q.x1, x2: 0 };
+    }
+
+    // p and -p share roots; keep a > 0 so the sign pattern is fixed
+    let s = if a < 0 { -1 } else { 1 };
+    let (a, b, c, d) = (s * a as i128, s * b as i128, s * c as i128, s * d as i128);
+
+    // Interval bounds: the i32 range split at the critical points
+    let crit = quadratic_roots(3 * a, 2 * b, c);
+    let mut bounds = [i32::MIN as i64, crit.x0 as i64, crit.x1 as i64, i32::MAX as i64];
+    let nb = crit.count as usize + 2;
+    bounds[nb - 1] = i32::MAX as i64;
+
+    // At most one root per monotone interval
+    let mut roots = [0i32; 3];
+    let mut count = 0;
+    for w in bounds[..nb].windows(2) {
+        let (mut lo, mut hi) = (w[0], w[1]);
+        let s_lo = cubic_sign(a, b, c, d, lo);
+        let s_hi = cubic_sign(a, b, c, d, hi);
+        let root = if s_lo == 0 {
+            lo
+        } else if s_hi == 0 {
+            hi
+        } else if s_lo != s_hi {
+            loop {
+                if hi - lo <= 1 {
+                    break lo;
+                }
+                let mid = lo + (hi - lo) / 2;
+                let s_mid = cubic_sign(a, b, c, d, mid);
+                if s_mid == 0 {
+                    break mid;
+                }
+                if s_mid == s_lo {
+                    lo = mid;
+                } else {
+                    hi = mid;
+                }
+            }
+        } else {
+            continue;
+        };
+        // A root on a shared interval bound shows up twice
+        if count == 0 || roots[count - 1] != root as i32 {
+            roots[count] = root as i32;
+            count += 1;
+        }
+    }
+
+    CubicRoots { count: count as u32, x0: roots[0], x1: roots[1], x2: roots[2] }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::flags::{clear_flags, get_flags, StatusFlag};
+
+    const ONE: i32 = 1 << 16;
+
+    fn roots2(r: QuadraticRoots) -> Vec<i32> {
+        [r.x0, r.x1][..r.count as usize].to_vec()
+    }
+
+    #[test]
+    fn quadratic_roots_ascending() {
+        // (x - 1)(x - 3)
+        assert_eq!(roots2(solve_quadratic(ONE, -4 * ONE, 3 * ONE)), [ONE, 3 * ONE]);
+        // -(x + 2)(x - 0.5)
+        assert_eq!(roots2(solve_quadratic(-ONE, -3 * ONE / 2, ONE)), [-2 * ONE, ONE / 2]);
+        // (x - 2)²
+        assert_eq!(roots2(solve_quadratic(ONE, -4 * ONE, 4 * ONE)), [2 * ONE]);
+        assert_eq!(roots2(solve_quadratic(ONE, 0, ONE)), []);
+    }
+
+    #[test]
+    fn quadratic_degenerate_cases() {
+        assert_eq!(roots2(solve_quadratic(0, 2 * ONE, -ONE)), [ONE / 2]);
+        assert_eq!(roots2(solve_quadratic(0, 0, ONE)), []);
+    }
+
+    #[test]
+    fn quadratic_avoids_cancellation() {
+        // x² - 1000x + 0.01: the small root is ~1e-5, lost by the textbook form
+        let r = solve_quadratic(ONE, -1000 * ONE, 655);
+        assert_eq!(r.count, 2);
+        assert_eq!(r.x0, 0);
+        assert!((r.x1 - 1000 * ONE).abs() <= 1);
+        let r = solve_quadratic(ONE, -100 * ONE, ONE);
+        assert!((r.x0 as f64 - 0.010001 * 65536.0).abs() <= 1.0);
+    }
+
+    #[test]
+    fn quadratic_roots_out_of_range_saturate() {
+        clear_flags();
+        // 2^-16 x² - x = 0 has roots 0 and 65536
+        let r = solve_quadratic(1, -ONE, 0);
+        assert_eq!(roots2(r), [0, i32::MAX]);
+        assert_eq!(get_flags(), StatusFlag::Overflow as u32);
+    }
+
+    fn roots3(r: CubicRoots) -> Vec<i32> {
+        [r.x0, r.x1, r.x2][..r.count as usize].to_vec()
+    }
+
+    #[test]
+    fn horner_evaluation() {
+        assert_eq!(poly_eval(&[], 5 * ONE), 0);
+        // 1 + 2x + 3x² at 2
+        assert_eq!(poly_eval(&[ONE, 2 * ONE, 3 * ONE], 2 * ONE), 17 * ONE);
+        assert_eq!(poly_eval(&[ONE, 2 * ONE, 3 * ONE], -ONE / 2), 3 * ONE / 4);
+
+        clear_flags();
+        assert_eq!(poly_eval(&[0, 0, ONE], 1000 * ONE), i32::MAX);
+        assert_eq!(get_flags(), StatusFlag::Overflow as u32);
+    }
+
+    #[test]
+    fn cubic_with_three_roots() {
+        // (x + 2)(x - 1)(x - 3) = x³ - 2x² - 5x + 6
+        let r = solve_cubic(ONE, -2 * ONE, -5 * ONE, 6 * ONE);
+        assert_eq!(roots3(r), [-2 * ONE, ONE, 3 * ONE]);
+        // Negated leading coefficient, same roots
+        let r = solve_cubic(-ONE, 2 * ONE, 5 * ONE, -6 * ONE);
+        assert_eq!(roots3(r), [-2 * ONE, ONE, 3 * ONE]);
+    }
+
+    #[test]
+    fn cubic_single_and_off_grid_roots() {
+        // x³ - 2: one real root, floored to the grid
+        let r = solve_cubic(ONE, 0, 0, -2 * ONE);
+        assert_eq!(r.count, 1);
+        assert_eq!(r.x0, (2f64.cbrt() * 65536.0).floor() as i32);
+        // x³ + x: only x = 0
+        assert_eq!(roots3(solve_cubic(ONE, 0, ONE, 0)), [0]);
+    }
+
+    #[test]
+    fn cubic_double_root_on_the_grid() {
+        // (x - 1)²(x + 2) = x³ - 3x + 2
+        assert_eq!(roots3(solve_cubic(ONE, 0, -3 * ONE, 2 * ONE)), [-2 * ONE, ONE]);
+    }
+
+    #[test]
+    fn cubic_falls_back_to_quadratic() {
+        assert_eq!(roots3(solve_cubic(0, ONE, -4 * ONE, 3 * ONE)), [ONE, 3 * ONE]);
+    }
+}
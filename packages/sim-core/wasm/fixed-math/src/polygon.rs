@@ -0,0 +1,381 @@
+//! Polygon utilities on packed Q16.16 vertex arrays
+//!
+//! Vertices are packed `[x0, y0, x1, y1, ...]`; the last vertex connects
+//! back to the first and a trailing odd value is ignored. Either winding
+//! order works; signed results are positive for counter-clockwise.
+
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+use crate::saturate_i128;
+use crate::vec2::FixedVec2;
+
+/// Edges (a, b) of the closed polygon as i128 coordinates
+fn edges(vertices: &[i32]) -> impl Iterator<Item = ((i128, i128), (i128, i128))> + '_ {
+    let n = vertices.len() / 2;
+    let at = move |i: usize| (vertices[2 * i] as i128, vertices[2 * i + 1] as i128);
+    (0..n).map(move |i| (at(i), at((i + 1) % n)))
+}
+
+/// Whether (x, y) lies inside the polygon or on its boundary
+///
+/// Nonzero winding rule, so self-overlapping regions count as inside.
+/// Every test is an exact 128-bit orientation product: a point exactly
+/// on an edge is always inside, whichever way the polygon is wound.
+/// Fewer than three vertices contain nothing but their own edges.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn point_in_polygon(vertices: &[i32], x: i32, y: i32) -> bool {
+    let (px, py) = (x as i128, y as i128);
+    let mut winding = 0i32;
+    for ((ax, ay), (bx, by)) in edges(vertices) {
+        let side = (bx - ax) * (py - ay) - (px - ax) * (by - ay);
+        if side == 0 && ax.min(bx) <= px && px <= ax.max(bx) && ay.min(by) <= py && py <= ay.max(by) {
+            return true;
+        }
+        if ay <= py {
+            if by > py && side > 0 {
+                winding += 1;
+            }
+        } else if by <= py && side < 0 {
+            winding -= 1;
+        }
+    }
+    winding != 0
+}
+
+/// Twice the signed area in Q32.32 (shoelace sum)
+fn double_area_q32(vertices: &[i32]) -> i128 {
+    edges(vertices).map(|((ax, ay), (bx, by))| ax * by - bx * ay).sum()
+}
+
+/// Signed area of the polygon (Q16.16), positive for counter-clockwise
+///
+/// Shoelace formula summed exactly in 128-bit, floored once; saturates
+/// (raising `StatusFlag::Overflow`) past the Q16.16 range.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn polygon_area(vertices: &[i32]) -> i32 {
+    saturate_i128(double_area_q32(vertices) >> 17)
+}
+
+/// Area centroid of the polygon
+///
+/// Σ (pᵢ + pᵢ₊₁) cross(pᵢ, pᵢ₊₁) / (6 A) with both sums exact in
+/// 128-bit and one division per axis, truncated toward zero. Degenerate
+/// polygons (zero area) fall back to the mean of the vertices; an empty
+/// array gives (0, 0).
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn polygon_centroid(vertices: &[i32]) -> FixedVec2 {
+    let n = (vertices.len() / 2) as i128;
+    if n == 0 {
+        return FixedVec2::zero();
+    }
+
+    let area2 = double_area_q32(vertices);
+    if area2 == 0 {
+        let (sx, sy) = edges(vertices).fold((0, 0), |(sx, sy), ((ax, ay), _)| (sx + ax, sy + ay));
+        return FixedVec2 { x: (sx / n) as i32, y: (sy / n) as i32 };
+    }
+
+    let (mut cx, mut cy) = (0i128, 0i128);
+    for ((ax, ay), (bx, by)) in edges(vertices) {
+        let cross = ax * by - bx * ay;
+        cx += (ax + bx) * cross;
+        cy += (ay + by) * cross;
+    }
+    FixedVec2 {
+        x: saturate_i128(cx / (3 * area2)),
+        y: saturate_i128(cy / (3 * area2)),
+    }
+}
+
+/// Orientation of o → a → b: positive for a counter-clockwise turn
+#[cfg(feature = "alloc")]
+fn turn(o: (i32, i32), a: (i32, i32), b: (i32, i32)) -> i128 {
+    let (ox, oy) = (o.0 as i128, o.1 as i128);
+    (a.0 as i128 - ox) * (b.1 as i128 - oy) - (a.1 as i128 - oy) * (b.0 as i128 - ox)
+}
+
+/// Append one monotone chain to `hull`, keeping only left turns
+#[cfg(feature = "alloc")]
+fn hull_chain<'a>(points: impl Iterator<Item = &'a (i32, i32)>, hull: &mut Vec<(i32, i32)>) {
+    let start = hull.len();
+    for &p in points {
+        while hull.len() >= start + 2 && turn(hull[hull.len() - 2], hull[hull.len() - 1], p) <= 0 {
+            hull.pop();
+        }
+        hull.push(p);
+    }
+    hull.pop();
+}
+
+/// Convex hull of packed points (Andrew's monotone chain)
+///
+/// Returns the hull as packed vertices in counter-clockwise order,
+/// starting from the smallest point by (x, then y). Duplicate points
+/// are merged and points on a hull edge (collinear) are dropped, so the
+/// output depends only on the set of input points, not their order.
+/// Fewer than three distinct points come back as-is (sorted, deduped);
+/// all-collinear input gives its two extreme points.
+#[cfg(feature = "alloc")]
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn convex_hull(points: &[i32]) -> Vec<i32> {
+    let mut pts: Vec<(i32, i32)> = points.chunks_exact(2).map(|p| (p[0], p[1])).collect();
+    pts.sort_unstable();
+    pts.dedup();
+    if pts.len() < 3 {
+        return pts.iter().flat_map(|&(x, y)| [x, y]).collect();
+    }
+
+    let mut hull: Vec<(i32, i32)> = Vec::with_capacity(2 * pts.len());
+    // Lower chain left to right, then upper chain right to left; each
+    // chain's last point starts the other one, so it is dropped
+    hull_chain(pts.iter(), &mut hull);
+    hull_chain(pts.iter().rev(), &mut hull);
+    hull.iter().flat_map(|&(x, y)| [x, y]).collect()
+}
+
+/// Whether p lies in triangle abc (counter-clockwise) or on its boundary
+#[cfg(feature = "alloc")]
+fn in_ccw_triangle(p: (i32, i32), a: (i32, i32), b: (i32, i32), c: (i32, i32)) -> bool {
+    turn(a, b, p) >= 0 && turn(b, c, p) >= 0 && turn(c, a, p) >= 0
+}
+
+/// Triangulate a simple polygon by ear clipping
+///
+/// Returns vertex indices, three per triangle, with each triangle in the
+/// polygon's own winding. Each step clips the ear at the lowest remaining
+/// index: a strictly convex vertex whose triangle with its two remaining
+/// neighbours contains no other remaining vertex (touching counts, so
+/// diagonals never pass through a vertex). All tests are exact 128-bit
+/// orientation products, so the output order is fully determined by the
+/// input. No zero-area triangle is emitted: when only collinear vertices
+/// are left to clip they are dropped, and if no ear can be found at all
+/// (the polygon is not simple) the triangles so far are returned. Fewer
+/// than three vertices give an empty list.
+#[cfg(feature = "alloc")]
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn triangulate(vertices: &[i32]) -> Vec<u32> {
+    let pts: Vec<(i32, i32)> = vertices.chunks_exact(2).map(|p| (p[0], p[1])).collect();
+    let mut remaining: Vec<usize> = (0..pts.len()).collect();
+    let mut out = Vec::with_capacity(3 * pts.len().saturating_sub(2));
+    if pts.len() < 3 {
+        return out;
+    }
+    // Work in counter-clockwise order, emit in the input winding
+    let ccw = double_area_q32(vertices) >= 0;
+    let at = |i: usize| pts[i];
+
+    while remaining.len() >= 3 {
+        let n = remaining.len();
+        let neighbours = |k: usize| {
+            let (prev, next) = (remaining[(k + n - 1) % n], remaining[(k + 1) % n]);
+            if ccw { (prev, next) } else { (next, prev) }
+        };
+
+        let ear = (0..n).find(|&k| {
+            let (a, b) = neighbours(k);
+            let (pa, pb, pc) = (at(a), at(remaining[k]), at(b));
+            if turn(pa, pb, pc) <= 0 {
+                return false;
+            }
+            remaining.iter().all(|&j| {
+                let p = at(j);
+                j == a || j == b || j == remaining[k] || p == pa || p == pb || p == pc || !in_ccw_triangle(p, pa, pb, pc)
+            })
+        });
+
+        match ear {
+            Some(k) => {
+                let (a, b) = neighbours(k);
+                let (a, b) = if ccw { (a, b) } else { (b, a) };
+                out.extend([a as u32, remaining[k] as u32, b as u32]);
+                remaining.remove(k);
+            }
+            None => {
+                // Drop the first collinear vertex; none means not simple
+                let Some(k) = (0..n).find(|&k| {
+                    let (a, b) = neighbours(k);
+                    turn(at(a), at(remaining[k]), at(b)) == 0
+                }) else {
+                    break;
+                };
+                remaining.remove(k);
+            }
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ONE: i32 = 1 << 16;
+
+    /// Packed whole-number vertices
+    fn poly(coords: &[i32]) -> Vec<i32> {
+        coords.iter().map(|c| c * ONE).collect()
+    }
+
+    #[test]
+    fn point_in_polygon_boundary_and_winding() {
+        let square = poly(&[0, 0, 4, 0, 4, 4, 0, 4]);
+        let reversed = poly(&[0, 4, 4, 4, 4, 0, 0, 0]);
+        for sq in [&square, &reversed] {
+            assert!(point_in_polygon(sq, 2 * ONE, 2 * ONE));
+            assert!(point_in_polygon(sq, 4 * ONE, ONE));
+            assert!(point_in_polygon(sq, 0, 0));
+            assert!(!point_in_polygon(sq, 5 * ONE, 2 * ONE));
+            assert!(!point_in_polygon(sq, 4 * ONE + 1, 4 * ONE));
+        }
+        // Concave L shape: the notch is outside
+        let l = poly(&[0, 0, 4, 0, 4, 2, 2, 2, 2, 4, 0, 4]);
+        assert!(!point_in_polygon(&l, 3 * ONE, 3 * ONE));
+        assert!(point_in_polygon(&l, ONE, 3 * ONE));
+        assert!(!point_in_polygon(&[0, 0, ONE, ONE], ONE / 2, 0));
+    }
+
+    #[test]
+    fn area_is_signed_by_winding() {
+        assert_eq!(polygon_area(&poly(&[0, 0, 4, 0, 4, 3])), 6 * ONE);
+        assert_eq!(polygon_area(&poly(&[0, 0, 4, 3, 4, 0])), -6 * ONE);
+        assert_eq!(polygon_area(&poly(&[0, 0, 4, 0, 4, 2, 2, 2, 2, 4, 0, 4])), 12 * ONE);
+        assert_eq!(polygon_area(&[]), 0);
+    }
+
+    #[test]
+    fn centroid_of_area() {
+        assert_eq!(polygon_centroid(&poly(&[0, 0, 4, 0, 4, 4, 0, 4])), FixedVec2::new(2 * ONE, 2 * ONE));
+        assert_eq!(polygon_centroid(&poly(&[0, 0, 6, 0, 0, 3])), FixedVec2::new(2 * ONE, ONE));
+        // Degenerate: vertex mean
+        assert_eq!(polygon_centroid(&poly(&[0, 0, 2, 2, 4, 4])), FixedVec2::new(2 * ONE, 2 * ONE));
+        assert_eq!(polygon_centroid(&[]), FixedVec2::zero());
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn convex_hull_is_ccw_from_lowest_point() {
+        let pts = poly(&[2, 2, 4, 4, 0, 0, 4, 0, 0, 4, 1, 3, 2, 0]);
+        let hull = poly(&[0, 0, 4, 0, 4, 4, 0, 4]);
+        assert_eq!(convex_hull(&pts), hull);
+        // Input order does not matter
+        let mut shuffled: Vec<i32> = pts.chunks(2).rev().flatten().copied().collect();
+        assert_eq!(convex_hull(&shuffled), hull);
+        shuffled.extend_from_slice(&poly(&[4, 4, 0, 0]));
+        assert_eq!(convex_hull(&shuffled), hull);
+        assert!(polygon_area(&convex_hull(&pts)) > 0);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn convex_hull_degenerate_inputs() {
+        assert_eq!(convex_hull(&[]), Vec::<i32>::new());
+        assert_eq!(convex_hull(&poly(&[3, 1, 3, 1])), poly(&[3, 1]));
+        assert_eq!(convex_hull(&poly(&[3, 1, 0, 2])), poly(&[0, 2, 3, 1]));
+        assert_eq!(convex_hull(&poly(&[1, 1, 3, 3, 0, 0, 2, 2])), poly(&[0, 0, 3, 3]));
+    }
+
+    /// Check a triangulation: every triangle non-degenerate, in the input
+    /// winding, with its centroid inside, and the areas summing exactly
+    #[cfg(feature = "alloc")]
+    fn check_triangulation(vertices: &[i32], tris: &[u32]) {
+        let pt = |i: u32| (vertices[2 * i as usize], vertices[2 * i as usize + 1]);
+        let total = double_area_q32(vertices);
+        let mut sum = 0i128;
+        for t in tris.chunks_exact(3) {
+            let (a, b, c) = (pt(t[0]), pt(t[1]), pt(t[2]));
+            let d = turn(a, b, c);
+            assert!(d != 0 && (d > 0) == (total > 0), "triangle {t:?}");
+            let cx = ((a.0 as i64 + b.0 as i64 + c.0 as i64) / 3) as i32;
+            let cy = ((a.1 as i64 + b.1 as i64 + c.1 as i64) / 3) as i32;
+            assert!(point_in_polygon(vertices, cx, cy), "triangle {t:?}");
+            sum += d;
+        }
+        assert_eq!(tris.len() % 3, 0);
+        assert_eq!(sum, total);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn triangulate_convex_polygons() {
+        let square = poly(&[0, 0, 4, 0, 4, 4, 0, 4]);
+        let tris = triangulate(&square);
+        // Ears are clipped at the lowest remaining index
+        assert_eq!(tris, [3, 0, 1, 3, 1, 2]);
+        check_triangulation(&square, &tris);
+
+        let hexagon = poly(&[2, 0, 4, 1, 4, 3, 2, 4, 0, 3, 0, 1]);
+        let tris = triangulate(&hexagon);
+        assert_eq!(tris.len(), 3 * 4);
+        check_triangulation(&hexagon, &tris);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn triangulate_keeps_the_input_winding() {
+        let cw = poly(&[0, 0, 0, 4, 4, 4, 4, 0]);
+        let tris = triangulate(&cw);
+        assert_eq!(tris.len(), 6);
+        check_triangulation(&cw, &tris);
+        for t in tris.chunks_exact(3) {
+            let pt = |i: u32| (cw[2 * i as usize], cw[2 * i as usize + 1]);
+            assert!(turn(pt(t[0]), pt(t[1]), pt(t[2])) < 0);
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn triangulate_concave_polygons() {
+        // L-shape and a comb with deep notches
+        let l_shape = poly(&[0, 0, 4, 0, 4, 2, 2, 2, 2, 4, 0, 4]);
+        let comb = poly(&[0, 0, 7, 0, 7, 3, 6, 3, 6, 1, 5, 1, 5, 3, 4, 3, 4, 1, 3, 1, 3, 3, 2, 3, 2, 1, 1, 1, 1, 3, 0, 3]);
+        for shape in [&l_shape, &comb] {
+            let tris = triangulate(shape);
+            assert_eq!(tris.len(), 3 * (shape.len() / 2 - 2));
+            check_triangulation(shape, &tris);
+        }
+
+        // Star polygons with alternating radii, in both windings
+        for points in [5, 8, 13] {
+            let mut star = Vec::new();
+            for i in 0..2 * points {
+                let a = i as f64 * core::f64::consts::PI / points as f64;
+                let r = if i % 2 == 0 { 10.0 } else { 3.0 + i as f64 / 10.0 };
+                star.push((r * a.cos() * ONE as f64) as i32);
+                star.push((r * a.sin() * ONE as f64) as i32);
+            }
+            let tris = triangulate(&star);
+            assert_eq!(tris.len(), 3 * (2 * points - 2));
+            check_triangulation(&star, &tris);
+
+            let reversed: Vec<i32> = star.chunks_exact(2).rev().flatten().copied().collect();
+            check_triangulation(&reversed, &triangulate(&reversed));
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn triangulate_drops_collinear_vertices() {
+        // Midpoints on two edges
+        let square = poly(&[0, 0, 2, 0, 4, 0, 4, 4, 2, 4, 0, 4]);
+        let tris = triangulate(&square);
+        assert_eq!(tris, [5, 0, 1, 5, 1, 2, 2, 3, 4, 5, 2, 4]);
+        check_triangulation(&square, &tris);
+
+        assert!(triangulate(&poly(&[0, 0, 1, 1, 2, 2, 3, 3])).is_empty());
+        assert!(triangulate(&poly(&[0, 0, 1, 1])).is_empty());
+        assert!(triangulate(&[]).is_empty());
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn triangulate_stops_on_non_simple_input() {
+        // A bowtie: one ear is clipped, then no ear or collinear vertex
+        // is left and the partial result comes back
+        let bowtie = poly(&[0, 0, 4, 4, 4, 0, 0, 4]);
+        assert_eq!(triangulate(&bowtie), [3, 0, 1]);
+    }
+}
@@ -0,0 +1,219 @@
+//! Pseudo-random distribution (bad luck protection)
+//!
+//! `CritTracker` rolls with a chance that grows linearly with every
+//! miss, C, 2C, 3C, ..., and resets after a hit, with C chosen so the
+//! long-run hit rate equals the nominal probability. Streaks of misses
+//! and hits both become rarer than with independent rolls. All chances
+//! are 32-bit fractions, so even very small nominal chances keep a
+//! usable C.
+
+#[cfg(feature = "alloc")]
+use alloc::{vec, vec::Vec};
+
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+use crate::flags::{self, StatusFlag};
+use crate::rng::FixedRng;
+
+/// 1.0 as a 32-bit fraction
+const CERTAIN: u64 = 1 << 32;
+
+/// Mean attempts per hit for increment c, in 32-bit fractions
+///
+/// E[N] = sum over n >= 0 of P(no hit in the first n attempts). The
+/// survival product truncates each step, so the sum is finite: it ends
+/// once the chance reaches 1.0 or the survival rounds to 0.
+fn mean_attempts_q32(c: u64) -> u128 {
+    let mut survival = CERTAIN as u128;
+    let mut sum = 0u128;
+    let mut chance = 0u64;
+    while survival > 0 {
+        sum += survival;
+        chance = (chance + c).min(CERTAIN);
+        survival = (survival * (CERTAIN - chance) as u128) >> 32;
+    }
+    sum
+}
+
+/// Increment C (32-bit fraction) whose long-run hit rate is p (Q16.16)
+///
+/// Bisection on C in [0, p]: the mean attempt count falls as C grows,
+/// and the target is exactly 1 / p.
+fn constant_for(p: i32) -> u64 {
+    if p <= 0 {
+        return 0;
+    }
+    if p >= 1 << 16 {
+        return CERTAIN;
+    }
+    let (mut lo, mut hi) = (0u64, (p as u64) << 16);
+    while hi - lo > 1 {
+        let mid = lo + (hi - lo) / 2;
+        if mean_attempts_q32(mid) * p as u128 > 1u128 << 48 {
+            lo = mid;
+        } else {
+            hi = mid;
+        }
+    }
+    hi
+}
+
+/// Pity-counter crit roller
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CritTracker {
+    /// Chance added per attempt, as a 32-bit fraction (1.0 = 2^32)
+    constant: u64,
+    /// Misses since the last hit
+    misses: u32,
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl CritTracker {
+    /// Tracker whose long-run hit rate is p (Q16.16, clamped to [0, 1.0])
+    ///
+    /// C is solved by bisection at construction; the cost grows roughly
+    /// with 1 / sqrt(C), so build trackers once and keep them.
+    #[cfg_attr(feature = "wasm", wasm_bindgen(constructor))]
+    pub fn new(p: i32) -> CritTracker {
+        CritTracker {
+            constant: constant_for(p),
+            misses: 0,
+        }
+    }
+
+    /// Roll once: true on a hit
+    ///
+    /// The chance is (misses + 1) * C, capped at 1.0, compared against
+    /// one `next_u32` draw. A hit resets the counter. Always exactly one
+    /// draw.
+    pub fn roll(&mut self, rng: &mut FixedRng) -> bool {
+        let chance = self.next_chance_q32();
+        let hit = (rng.next_u32() as u64) < chance;
+        self.misses = if hit { 0 } else { self.misses.saturating_add(1) };
+        hit
+    }
+
+    fn next_chance_q32(&self) -> u64 {
+        (self.constant.saturating_mul(self.misses as u64 + 1)).min(CERTAIN)
+    }
+
+    /// Chance of the next roll in Q16.16 (rounded down)
+    pub fn current_chance(&self) -> i32 {
+        (self.next_chance_q32() >> 16) as i32
+    }
+
+    /// Misses since the last hit
+    pub fn misses(&self) -> u32 {
+        self.misses
+    }
+
+    /// Forget the current streak
+    pub fn reset(&mut self) {
+        self.misses = 0;
+    }
+
+    /// Tracker state as `[constant, misses]`
+    #[cfg(feature = "alloc")]
+    pub fn get_state(&self) -> Vec<u64> {
+        vec![self.constant, self.misses as u64]
+    }
+
+    /// Restore a state captured by `get_state`
+    ///
+    /// Anything other than two words, a constant above 1.0 or a counter
+    /// beyond u32 leaves the tracker unchanged and raises
+    /// `StatusFlag::Domain`.
+    pub fn set_state(&mut self, words: &[u64]) {
+        match *words {
+            [constant, misses] if constant <= CERTAIN && misses <= u32::MAX as u64 => {
+                self.constant = constant;
+                self.misses = misses as u32;
+            }
+            _ => flags::raise(StatusFlag::Domain),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::flags::{clear_flags, get_flags};
+
+    const ONE: i32 = 1 << 16;
+
+    fn c_of(p: i32) -> f64 {
+        CritTracker::new(p).constant as f64 / CERTAIN as f64
+    }
+
+    #[test]
+    fn constants_match_the_known_prd_table() {
+        assert!((c_of(ONE / 4) - 0.084744).abs() < 1e-5);
+        assert!((c_of(ONE / 2) - 0.302103).abs() < 1e-5);
+        assert!((c_of(ONE / 10) - 0.014746).abs() < 1e-5);
+        assert_eq!(c_of(0), 0.0);
+        assert_eq!(c_of(-ONE), 0.0);
+        assert_eq!(c_of(ONE), 1.0);
+    }
+
+    #[test]
+    fn long_run_rate_equals_nominal() {
+        let mut rng = FixedRng::new(6);
+        let mut crit = CritTracker::new(ONE / 4);
+        let hits = (0..40_000).filter(|_| crit.roll(&mut rng)).count();
+        assert!((9600..10_400).contains(&hits));
+        let mut never = CritTracker::new(0);
+        let mut always = CritTracker::new(ONE);
+        assert!((0..100).all(|_| !never.roll(&mut rng) && always.roll(&mut rng)));
+    }
+
+    #[test]
+    fn chance_grows_with_misses_and_resets() {
+        let mut crit = CritTracker::new(ONE / 4);
+        let c = crit.current_chance();
+        crit.set_state(&[crit.constant, 3]);
+        assert!((crit.current_chance() - 4 * c).abs() <= 4);
+        crit.set_state(&[crit.constant, 100]);
+        assert_eq!(crit.current_chance(), ONE);
+        crit.reset();
+        assert_eq!((crit.misses(), crit.current_chance()), (0, c));
+    }
+
+    #[test]
+    fn roll_draws_once_and_tracks_misses() {
+        let mut rng = FixedRng::new(6);
+        let mut twin = rng.clone();
+        let mut crit = CritTracker::new(ONE / 2);
+        for _ in 0..20 {
+            let before = crit.misses();
+            let threshold = crit.next_chance_q32();
+            let hit = crit.roll(&mut rng);
+            assert_eq!(hit, (twin.next_u32() as u64) < threshold);
+            assert_eq!(crit.misses(), if hit { 0 } else { before + 1 });
+        }
+        assert_eq!(rng, twin);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn state_round_trips() {
+        let mut crit = CritTracker::new(ONE / 3);
+        crit.set_state(&[crit.constant, 2]);
+        let mut restored = CritTracker::new(0);
+        restored.set_state(&crit.get_state());
+        assert_eq!(restored, crit);
+    }
+
+    #[test]
+    fn set_state_rejects_malformed_input() {
+        let mut crit = CritTracker::new(ONE / 3);
+        let before = crit.clone();
+        for words in [&[][..], &[1], &[1, 2, 3], &[CERTAIN + 1, 0], &[1, 1 << 32]] {
+            clear_flags();
+            crit.set_state(words);
+            assert_eq!(crit, before);
+            assert_eq!(get_flags(), StatusFlag::Domain as u32);
+        }
+    }
+}
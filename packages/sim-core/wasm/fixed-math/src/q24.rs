@@ -0,0 +1,126 @@
+//! Q8.24 arithmetic for small, fine-grained quantities
+//!
+//! Per-tick regen rates and tiny probabilities never leave ±128 but lose
+//! most of their significant bits in Q16.16 (a 0.001% chance is 0.65 LSB
+//! there). Q8.24 trades 8 integer bits for 8 more fractional ones. The
+//! ops mirror the Q16.16 `mul`/`div` formulas with a 24-bit shift and the
+//! same truncation rules. Widening from Q16.16 is exact for inputs inside
+//! the Q8.24 range; narrowing back takes an explicit `Rounding`.
+
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+use crate::round::{shift_round, Rounding};
+use crate::{div_by_zero, saturate_i64, truncate_i64};
+
+/// 1.0 in Q8.24
+pub const Q24_ONE: i32 = 1 << 24;
+
+/// Widen a Q16.16 value to Q8.24
+///
+/// Exact for |fp| < 128.0; values outside the Q8.24 range saturate and
+/// raise `StatusFlag::Overflow`.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn q24_from_q16(fp: i32) -> i32 {
+    saturate_i64((fp as i64) << 8)
+}
+
+/// Narrow a Q8.24 value to Q16.16, dropping 8 fractional bits
+///
+/// Every Q8.24 value fits in Q16.16, so only the rounding of the dropped
+/// bits is lost, and `q24_to_q16(q24_from_q16(fp), _)` returns fp for
+/// any mode.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn q24_to_q16(v: i32, mode: Rounding) -> i32 {
+    shift_round(v as i64, 8, mode) as i32
+}
+
+/// Multiply two Q8.24 numbers
+///
+/// Formula: (a * b) >> 24, floored like `mul`
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn q24_mul(a: i32, b: i32) -> i32 {
+    truncate_i64((a as i64 * b as i64) >> 24)
+}
+
+/// Divide two Q8.24 numbers
+///
+/// Formula: (a << 24) / b, truncated toward zero like `div`
+/// Division by zero follows the same policy as `div`.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn q24_div(a: i32, b: i32) -> i32 {
+    if b == 0 {
+        return div_by_zero(a < 0);
+    }
+    truncate_i64(((a as i64) << 24) / b as i64)
+}
+
+/// Scale a Q16.16 value by a Q8.24 factor, giving Q16.16
+///
+/// Formula: (fp * v) >> 24
+/// Applies a fine rate to a Q16.16 quantity with a single truncation,
+/// without narrowing the rate first.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn mul_q16_q24(fp: i32, v: i32) -> i32 {
+    truncate_i64((fp as i64 * v as i64) >> 24)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::flags::{clear_flags, get_flags, StatusFlag};
+
+    const ONE: i32 = 1 << 16;
+
+    const MODES: [Rounding; 5] = [Rounding::Floor, Rounding::Ceil, Rounding::Trunc, Rounding::HalfUp, Rounding::HalfEven];
+
+    #[test]
+    fn widening_is_exact_inside_the_range() {
+        assert_eq!(q24_from_q16(ONE), Q24_ONE);
+        assert_eq!(q24_from_q16(-ONE / 8), -Q24_ONE / 8);
+        for fp in (-128 * ONE..128 * ONE).step_by(7919) {
+            for mode in MODES {
+                assert_eq!(q24_to_q16(q24_from_q16(fp), mode), fp);
+            }
+        }
+        clear_flags();
+        assert_eq!(q24_from_q16(128 * ONE), i32::MAX);
+        assert_eq!(q24_from_q16(-129 * ONE), i32::MIN);
+        assert_eq!(get_flags(), StatusFlag::Overflow as u32);
+    }
+
+    #[test]
+    fn narrowing_rounds_by_mode() {
+        // 2.5 and -2.5 Q16.16 LSBs
+        let (pos, neg) = (2 * 256 + 128, -(2 * 256 + 128));
+        let want = [(2, -3), (3, -2), (2, -2), (3, -2), (2, -2)];
+        for (mode, (p, n)) in MODES.into_iter().zip(want) {
+            assert_eq!(q24_to_q16(pos, mode), p, "{mode:?}");
+            assert_eq!(q24_to_q16(neg, mode), n, "{mode:?}");
+        }
+        assert_eq!(q24_to_q16(i32::MAX, Rounding::Floor), i32::MAX >> 8);
+    }
+
+    #[test]
+    fn mul_and_div() {
+        assert_eq!(q24_mul(3 * Q24_ONE, Q24_ONE / 4), 3 * Q24_ONE / 4);
+        // 0.001% survives Q8.24 (167 LSB) where Q16.16 keeps 0.65 LSB
+        let chance = q24_mul(Q24_ONE / 1000, Q24_ONE / 100);
+        assert_eq!(chance, 167);
+        assert_eq!(q24_mul(-1, Q24_ONE / 2), -1);
+        assert_eq!(q24_div(-Q24_ONE, 3 * Q24_ONE), -Q24_ONE / 3);
+
+        clear_flags();
+        assert_eq!(q24_div(Q24_ONE, 0), i32::MAX);
+        assert_eq!(get_flags(), StatusFlag::DivByZero as u32);
+    }
+
+    #[test]
+    fn mul_q16_q24_truncates_once() {
+        assert_eq!(mul_q16_q24(10 * ONE, Q24_ONE / 4), 10 * ONE / 4);
+        // 256.0 at the smallest Q8.24 rate is one LSB; narrowing the rate
+        // to Q16.16 first would give 0
+        assert_eq!(mul_q16_q24(1 << 24, 1), 1);
+        assert_eq!(q24_to_q16(1, Rounding::Floor), 0);
+    }
+}
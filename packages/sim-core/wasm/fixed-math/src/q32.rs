@@ -0,0 +1,221 @@
+//! Q32.32 arithmetic on i64 (BigInt in JS)
+//!
+//! Economy totals outgrow Q16.16's ±32768 range late in a run, so they
+//! live in Q32.32 instead: 32 integer bits (±2^31) with 32 fractional
+//! bits. Values cross the boundary as BigInt. The formulas mirror the
+//! Q16.16 ops with a 128-bit intermediate, but results saturate at the
+//! i64 range (raising `StatusFlag::Overflow`) rather than wrapping, since
+//! a resource total that flips sign is never the right answer.
+
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+use crate::flags::{self, StatusFlag};
+use crate::{div_by_zero, saturate_i64};
+
+/// 1.0 in Q32.32
+pub const Q32_ONE: i64 = 1 << 32;
+
+/// Clamp a 128-bit intermediate to i64, raising Overflow when it clamps
+fn saturate_q32(v: i128) -> i64 {
+    match i64::try_from(v) {
+        Ok(r) => r,
+        Err(_) => {
+            flags::raise(StatusFlag::Overflow);
+            if v < 0 { i64::MIN } else { i64::MAX }
+        }
+    }
+}
+
+/// Division-by-zero result widened to the i64 range
+///
+/// Goes through `div_by_zero`, so the flag and the policy (including Trap)
+/// are shared with the Q16.16 ops.
+fn div_by_zero_q32(numerator_negative: bool) -> i64 {
+    match div_by_zero(numerator_negative) {
+        0 => 0,
+        _ if numerator_negative => i64::MIN,
+        _ => i64::MAX,
+    }
+}
+
+/// Widen a Q16.16 value to Q32.32 (exact)
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn q32_from_q16(fp: i32) -> i64 {
+    (fp as i64) << 16
+}
+
+/// Narrow a Q32.32 value to Q16.16
+///
+/// The dropped 16 fractional bits are floored; values outside the Q16.16
+/// range saturate and raise `StatusFlag::Overflow`.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn q32_to_q16(v: i64) -> i32 {
+    saturate_i64(v >> 16)
+}
+
+/// Integer to Q32.32 (exact)
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn q32_from_int(n: i32) -> i64 {
+    (n as i64) << 32
+}
+
+/// Integer part of a Q32.32 value (floor)
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn q32_to_int(v: i64) -> i32 {
+    (v >> 32) as i32
+}
+
+/// Saturating addition of two Q32.32 numbers
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn q32_add(a: i64, b: i64) -> i64 {
+    saturate_q32(a as i128 + b as i128)
+}
+
+/// Saturating subtraction of two Q32.32 numbers (a - b)
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn q32_sub(a: i64, b: i64) -> i64 {
+    saturate_q32(a as i128 - b as i128)
+}
+
+/// Multiply two Q32.32 numbers
+///
+/// Formula: clamp((a * b) >> 32)
+/// The product is formed exactly in 128-bit and floored by the shift,
+/// like `mul`.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn q32_mul(a: i64, b: i64) -> i64 {
+    saturate_q32((a as i128 * b as i128) >> 32)
+}
+
+/// Multiply a Q32.32 number by a Q16.16 factor, giving Q32.32
+///
+/// Formula: clamp((a * fp) >> 16)
+/// Applies a rate or multiplier straight to a total without widening the
+/// factor first; bit-identical to `q32_mul(a, q32_from_q16(fp))`.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn q32_mul_q16(a: i64, fp: i32) -> i64 {
+    saturate_q32((a as i128 * fp as i128) >> 16)
+}
+
+/// Divide two Q32.32 numbers
+///
+/// Formula: clamp((a << 32) / b)
+/// The quotient truncates toward zero like `div`. Division by zero follows
+/// the `DivByZeroPolicy`, saturating to the i64 range by default.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn q32_div(a: i64, b: i64) -> i64 {
+    if b == 0 {
+        return div_by_zero_q32(a < 0);
+    }
+    saturate_q32(((a as i128) << 32) / b as i128)
+}
+
+/// Square root of a Q32.32 number
+///
+/// Formula: floor(sqrt(v << 32)), the exact integer square root of the
+/// 96-bit scaled value, so the result is the largest Q32.32 r with
+/// r * r <= v. Negative inputs return 0 and raise `StatusFlag::Domain`.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn q32_sqrt(v: i64) -> i64 {
+    if v <= 0 {
+        if v < 0 {
+            flags::raise(StatusFlag::Domain);
+        }
+        return 0;
+    }
+
+    let n = (v as u128) << 32;
+
+    // Start at a power of two >= sqrt(n); Newton then decreases monotonically
+    let bits = 128 - n.leading_zeros();
+    let mut x = 1u128 << bits.div_ceil(2);
+    loop {
+        let y = (x + n / x) >> 1;
+        if y >= x {
+            break;
+        }
+        x = y;
+    }
+    x as i64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::flags::{clear_flags, get_flags};
+
+    const ONE: i32 = 1 << 16;
+
+    #[test]
+    fn conversions_round_trip() {
+        assert_eq!(q32_from_q16(ONE + ONE / 2), Q32_ONE + Q32_ONE / 2);
+        assert_eq!(q32_to_q16(q32_from_q16(-12345)), -12345);
+        assert_eq!(q32_from_int(-3), -3 * Q32_ONE);
+        // Both narrowings floor
+        assert_eq!(q32_to_q16(-1), -1);
+        assert_eq!(q32_to_int(-1), -1);
+        assert_eq!(q32_to_int(3 * Q32_ONE - 1), 2);
+
+        clear_flags();
+        assert_eq!(q32_to_q16(40_000 * Q32_ONE), i32::MAX);
+        assert_eq!(get_flags(), StatusFlag::Overflow as u32);
+    }
+
+    #[test]
+    fn add_sub_saturate() {
+        assert_eq!(q32_add(Q32_ONE, Q32_ONE / 2), 3 * Q32_ONE / 2);
+        assert_eq!(q32_sub(Q32_ONE, 3 * Q32_ONE), -2 * Q32_ONE);
+        clear_flags();
+        assert_eq!(q32_add(i64::MAX, 1), i64::MAX);
+        assert_eq!(q32_sub(i64::MIN, 1), i64::MIN);
+        assert_eq!(get_flags(), StatusFlag::Overflow as u32);
+    }
+
+    #[test]
+    fn mul_floors_and_matches_widened_q16() {
+        assert_eq!(q32_mul(3 * Q32_ONE, -Q32_ONE / 4), -3 * Q32_ONE / 4);
+        // Half an LSB floors toward -∞
+        assert_eq!(q32_mul(1, Q32_ONE / 2), 0);
+        assert_eq!(q32_mul(-1, Q32_ONE / 2), -1);
+
+        let mut seed = 5u32;
+        for _ in 0..1000 {
+            seed = seed.wrapping_mul(1664525).wrapping_add(1013904223);
+            let a = ((seed as i64) << 20) ^ seed as i64;
+            seed = seed.wrapping_mul(1664525).wrapping_add(1013904223);
+            let fp = seed as i32;
+            assert_eq!(q32_mul_q16(a, fp), q32_mul(a, q32_from_q16(fp)));
+        }
+
+        clear_flags();
+        assert_eq!(q32_mul(i64::MAX, 2 * Q32_ONE), i64::MAX);
+        assert_eq!(get_flags(), StatusFlag::Overflow as u32);
+    }
+
+    #[test]
+    fn div_truncates_and_handles_zero() {
+        assert_eq!(q32_div(3 * Q32_ONE, 2 * Q32_ONE), 3 * Q32_ONE / 2);
+        // Truncation toward zero: -1/3 LSB is 0, not -1
+        assert_eq!(q32_div(-1, 3 * Q32_ONE), 0);
+        clear_flags();
+        assert_eq!(q32_div(Q32_ONE, 0), i64::MAX);
+        assert_eq!(q32_div(-Q32_ONE, 0), i64::MIN);
+        assert_eq!(get_flags(), StatusFlag::DivByZero as u32);
+    }
+
+    #[test]
+    fn sqrt_is_the_floor_root() {
+        assert_eq!(q32_sqrt(4 * Q32_ONE), 2 * Q32_ONE);
+        assert_eq!(q32_sqrt(Q32_ONE / 4), Q32_ONE / 2);
+        assert_eq!(q32_sqrt(0), 0);
+        for v in [1, 2, 3 * Q32_ONE + 7, i64::MAX] {
+            let r = q32_sqrt(v) as i128;
+            let n = (v as i128) << 32;
+            assert!(r * r <= n && (r + 1) * (r + 1) > n, "sqrt({v})");
+        }
+        clear_flags();
+        assert_eq!(q32_sqrt(-1), 0);
+        assert_eq!(get_flags(), StatusFlag::Domain as u32);
+    }
+}
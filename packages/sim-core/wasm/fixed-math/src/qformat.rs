@@ -0,0 +1,204 @@
+//! Fixed-point ops with a runtime Q format
+//!
+//! Each function takes the number of fractional bits as a parameter, so
+//! modders and tools can work in Q24.8, Q1.31 and so on from the same
+//! build. Values are i32 with `frac_bits` in 0..=31; any other count
+//! raises `StatusFlag::Domain` and returns 0. The formulas match the
+//! Q16.16 ops exactly (`mul_q(a, b, 16)` is `mul(a, b)`), and results go
+//! through the same truncation helpers, so overflow and division by zero
+//! behave the same in every format.
+
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+use crate::flags::{self, StatusFlag};
+use crate::round::{shift_round, Rounding};
+use crate::{div_by_zero, saturate_i64, truncate_i64};
+
+/// Largest supported fractional bit count
+const MAX_FRAC_BITS: u32 = 31;
+
+/// Whether frac_bits is usable, raising Domain when it is not
+fn check_bits(frac_bits: u32) -> bool {
+    if frac_bits > MAX_FRAC_BITS {
+        flags::raise(StatusFlag::Domain);
+        return false;
+    }
+    true
+}
+
+/// Multiply two fixed-point numbers with `frac_bits` fractional bits
+///
+/// Formula: (a * b) >> frac_bits, floored like `mul`
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn mul_q(a: i32, b: i32, frac_bits: u32) -> i32 {
+    if !check_bits(frac_bits) {
+        return 0;
+    }
+    truncate_i64((a as i64 * b as i64) >> frac_bits)
+}
+
+/// Divide two fixed-point numbers with `frac_bits` fractional bits
+///
+/// Formula: (a << frac_bits) / b, truncated toward zero like `div`
+/// Division by zero follows the same policy as `div`.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn div_q(a: i32, b: i32, frac_bits: u32) -> i32 {
+    if !check_bits(frac_bits) {
+        return 0;
+    }
+    if b == 0 {
+        return div_by_zero(a < 0);
+    }
+    truncate_i64(((a as i64) << frac_bits) / b as i64)
+}
+
+/// Square root of a fixed-point number with `frac_bits` fractional bits
+///
+/// Formula: floor(sqrt(v << frac_bits)), the exact integer square root.
+/// This is more precise than `sqrt`, whose Q16.16 result only carries 8
+/// fractional bits, so `sqrt_q(v, 16)` is not bit-identical to it.
+/// Negative inputs return 0 and raise `StatusFlag::Domain`.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn sqrt_q(v: i32, frac_bits: u32) -> i32 {
+    if !check_bits(frac_bits) {
+        return 0;
+    }
+    if v <= 0 {
+        if v < 0 {
+            flags::raise(StatusFlag::Domain);
+        }
+        return 0;
+    }
+
+    let n = (v as u64) << frac_bits;
+
+    // Start at a power of two >= sqrt(n); Newton then decreases monotonically
+    let bits = 64 - n.leading_zeros();
+    let mut x = 1u64 << bits.div_ceil(2);
+    loop {
+        let y = (x + n / x) >> 1;
+        if y >= x {
+            break;
+        }
+        x = y;
+    }
+    x as i32
+}
+
+/// Convert a value between two Q formats
+///
+/// Gaining fractional bits is exact while the value fits, and saturates
+/// with `StatusFlag::Overflow` when it does not; losing bits rounds as
+/// `mode` says and always fits.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn convert_q(v: i32, from_bits: u32, to_bits: u32, mode: Rounding) -> i32 {
+    if !check_bits(from_bits) || !check_bits(to_bits) {
+        return 0;
+    }
+    if to_bits >= from_bits {
+        saturate_i64((v as i64) << (to_bits - from_bits))
+    } else {
+        shift_round(v as i64, from_bits - to_bits, mode) as i32
+    }
+}
+
+/// Convert a plain integer to a format with `frac_bits` fractional bits
+///
+/// Formula: n << frac_bits, saturating with `StatusFlag::Overflow`
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn from_int_q(n: i32, frac_bits: u32) -> i32 {
+    if !check_bits(frac_bits) {
+        return 0;
+    }
+    saturate_i64((n as i64) << frac_bits)
+}
+
+/// Integer part of a value with `frac_bits` fractional bits (floor)
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn to_int_q(v: i32, frac_bits: u32) -> i32 {
+    if !check_bits(frac_bits) {
+        return 0;
+    }
+    v >> frac_bits
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::flags::{clear_flags, get_flags};
+
+    #[test]
+    fn q16_matches_the_fixed_ops() {
+        let mut seed = 11u32;
+        for _ in 0..1000 {
+            seed = seed.wrapping_mul(1664525).wrapping_add(1013904223);
+            let a = seed as i32 >> 8;
+            seed = seed.wrapping_mul(1664525).wrapping_add(1013904223);
+            let b = seed as i32 >> 12;
+            assert_eq!(mul_q(a, b, 16), crate::mul(a, b));
+            assert_eq!(div_q(a, b, 16), crate::div(a, b));
+        }
+    }
+
+    #[test]
+    fn other_formats() {
+        // Q24.8: 1.5 * 2.25 = 3.375
+        assert_eq!(mul_q(384, 576, 8), 864);
+        // Q1.31: 0.5 * 0.5 = 0.25 and 0.25 / 0.5 = 0.5
+        assert_eq!(mul_q(1 << 30, 1 << 30, 31), 1 << 29);
+        assert_eq!(div_q(1 << 29, 1 << 30, 31), 1 << 30);
+        // Q0: plain integer arithmetic, division truncates toward zero
+        assert_eq!(mul_q(-7, 6, 0), -42);
+        assert_eq!(div_q(-7, 2, 0), -3);
+        assert_eq!(from_int_q(-3, 8), -768);
+        assert_eq!(to_int_q(-1, 8), -1);
+    }
+
+    #[test]
+    fn sqrt_is_the_floor_root() {
+        // Q24.8: sqrt(2.25) = 1.5
+        assert_eq!(sqrt_q(576, 8), 384);
+        for bits in [0, 8, 16, 24, 31] {
+            for v in [1, 2, 1000, i32::MAX] {
+                let r = sqrt_q(v, bits) as u64;
+                let n = (v as u64) << bits;
+                assert!(r * r <= n && (r + 1) * (r + 1) > n, "sqrt_q({v}, {bits})");
+            }
+        }
+        // Finer than sqrt, which only keeps 8 fractional bits at Q16.16
+        assert_eq!(sqrt_q(2 << 16, 16), 92681);
+        clear_flags();
+        assert_eq!(sqrt_q(-1, 16), 0);
+        assert_eq!(get_flags(), StatusFlag::Domain as u32);
+    }
+
+    #[test]
+    fn convert_between_formats() {
+        // Q16.16 1.5 to Q24.8 and back
+        assert_eq!(convert_q(3 << 15, 16, 8, Rounding::Floor), 384);
+        assert_eq!(convert_q(384, 8, 16, Rounding::Floor), 3 << 15);
+        // Dropping bits rounds by mode: 2.5 LSB of Q24.8
+        let v = (5 << 8) / 2;
+        assert_eq!(convert_q(v, 16, 8, Rounding::HalfEven), 2);
+        assert_eq!(convert_q(v, 16, 8, Rounding::HalfUp), 3);
+        assert_eq!(convert_q(-v, 16, 8, Rounding::Trunc), -2);
+
+        clear_flags();
+        assert_eq!(convert_q(1 << 24, 16, 24, Rounding::Floor), i32::MAX);
+        assert_eq!(from_int_q(-1 << 10, 24), i32::MIN);
+        assert_eq!(get_flags(), StatusFlag::Overflow as u32);
+    }
+
+    #[test]
+    fn bad_bit_counts_raise_domain() {
+        clear_flags();
+        assert_eq!(mul_q(1, 1, 32), 0);
+        assert_eq!(div_q(1, 1, 40), 0);
+        assert_eq!(sqrt_q(4, 32), 0);
+        assert_eq!(convert_q(1, 16, 32, Rounding::Floor), 0);
+        assert_eq!(from_int_q(1, 99), 0);
+        assert_eq!(to_int_q(1, 32), 0);
+        assert_eq!(get_flags(), StatusFlag::Domain as u32);
+    }
+}
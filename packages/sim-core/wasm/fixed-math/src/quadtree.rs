@@ -0,0 +1,365 @@
+//! Quadtree broadphase
+//!
+//! `Quadtree` is the adaptive alternative to `SpatialHash` for very
+//! uneven distributions: leaves split when they exceed their capacity
+//! and merge back when they empty out. Node layout depends only on the
+//! set of stored positions (splits happen at fixed integer midpoints,
+//! never at data-dependent positions), and query results are sorted by
+//! id, so the structure is deterministic across clients.
+
+use alloc::collections::BTreeMap;
+use alloc::{vec, vec::Vec};
+
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+/// One node; children are four consecutive arena slots
+#[derive(Clone, Debug)]
+struct Node {
+    min_x: i32,
+    min_y: i32,
+    max_x: i32,
+    max_y: i32,
+    depth: u32,
+    /// Arena index of the first child (quadrants -x-y, +x-y, -x+y, +x+y)
+    children: Option<usize>,
+    items: Vec<u32>,
+}
+
+impl Node {
+    fn new(min_x: i32, min_y: i32, max_x: i32, max_y: i32, depth: u32) -> Node {
+        Node { min_x, min_y, max_x, max_y, depth, children: None, items: Vec::new() }
+    }
+
+    /// Split point: children take [min, mid] and [mid + 1, max]
+    fn mid(&self) -> (i32, i32) {
+        (
+            ((self.min_x as i64 + self.max_x as i64) >> 1) as i32,
+            ((self.min_y as i64 + self.max_y as i64) >> 1) as i32,
+        )
+    }
+
+    /// Child offset (0..4) of the quadrant holding (x, y)
+    fn quadrant(&self, x: i32, y: i32) -> usize {
+        let (mx, my) = self.mid();
+        (x > mx) as usize + 2 * (y > my) as usize
+    }
+
+    fn overlaps(&self, min_x: i32, min_y: i32, max_x: i32, max_y: i32) -> bool {
+        self.min_x <= max_x && min_x <= self.max_x && self.min_y <= max_y && min_y <= self.max_y
+    }
+}
+
+/// Point quadtree over a fixed Q16.16 region
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone, Debug)]
+pub struct Quadtree {
+    nodes: Vec<Node>,
+    /// Released child blocks available for reuse
+    free: Vec<usize>,
+    positions: BTreeMap<u32, (i32, i32)>,
+    capacity: usize,
+    max_depth: u32,
+}
+
+impl Quadtree {
+    /// Leaf that holds (or would hold) (x, y), with the path of its ancestors
+    fn leaf_path(&self, x: i32, y: i32) -> Vec<usize> {
+        let mut path = vec![0];
+        let mut n = 0;
+        while let Some(first) = self.nodes[n].children {
+            n = first + self.nodes[n].quadrant(x, y);
+            path.push(n);
+        }
+        path
+    }
+
+    fn split(&mut self, n: usize) {
+        let node = &self.nodes[n];
+        let (mx, my) = node.mid();
+        let d = node.depth + 1;
+        let kids = [
+            Node::new(node.min_x, node.min_y, mx, my, d),
+            Node::new(mx.saturating_add(1), node.min_y, node.max_x, my, d),
+            Node::new(node.min_x, my.saturating_add(1), mx, node.max_y, d),
+            Node::new(mx.saturating_add(1), my.saturating_add(1), node.max_x, node.max_y, d),
+        ];
+        let first = match self.free.pop() {
+            Some(first) => {
+                for (i, kid) in kids.into_iter().enumerate() {
+                    self.nodes[first + i] = kid;
+                }
+                first
+            }
+            None => {
+                let first = self.nodes.len();
+                self.nodes.extend(kids);
+                first
+            }
+        };
+
+        let items = core::mem::take(&mut self.nodes[n].items);
+        self.nodes[n].children = Some(first);
+        for id in items {
+            let (x, y) = self.positions[&id];
+            let c = first + self.nodes[n].quadrant(x, y);
+            self.nodes[c].items.push(id);
+        }
+        for c in first..first + 4 {
+            self.split_if_full(c);
+        }
+    }
+
+    /// Split a leaf holding more than `capacity` items (recursively), so
+    /// a node is split exactly when its subtree is over capacity
+    fn split_if_full(&mut self, n: usize) {
+        let node = &self.nodes[n];
+        if node.items.len() > self.capacity && node.depth < self.max_depth {
+            self.split(n);
+        }
+    }
+
+    /// Collapse `n` back into a leaf if its children are leaves holding
+    /// at most `capacity` items in total
+    fn try_merge(&mut self, n: usize) {
+        let Some(first) = self.nodes[n].children else {
+            return;
+        };
+        let kids = first..first + 4;
+        if kids.clone().any(|c| self.nodes[c].children.is_some()) {
+            return;
+        }
+        let total: usize = kids.clone().map(|c| self.nodes[c].items.len()).sum();
+        if total > self.capacity {
+            return;
+        }
+        let mut items = Vec::with_capacity(total);
+        for c in kids {
+            items.append(&mut self.nodes[c].items);
+        }
+        self.nodes[n].items = items;
+        self.nodes[n].children = None;
+        self.free.push(first);
+    }
+
+    fn query(&self, min_x: i32, min_y: i32, max_x: i32, max_y: i32, keep: impl Fn(i32, i32) -> bool) -> Vec<u32> {
+        let mut out = Vec::new();
+        let mut stack = vec![0];
+        while let Some(n) = stack.pop() {
+            let node = &self.nodes[n];
+            if !node.overlaps(min_x, min_y, max_x, max_y) {
+                continue;
+            }
+            match node.children {
+                Some(first) => stack.extend(first..first + 4),
+                None => out.extend(node.items.iter().copied().filter(|id| {
+                    let (x, y) = self.positions[id];
+                    keep(x, y)
+                })),
+            }
+        }
+        out.sort_unstable();
+        out
+    }
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl Quadtree {
+    /// Create an empty tree covering the closed box [min, max]
+    ///
+    /// Leaves split once they hold more than `capacity` (at least 1)
+    /// entities, down to `max_depth` levels below the root.
+    #[cfg_attr(feature = "wasm", wasm_bindgen(constructor))]
+    pub fn new(min_x: i32, min_y: i32, max_x: i32, max_y: i32, capacity: u32, max_depth: u32) -> Quadtree {
+        Quadtree {
+            nodes: vec![Node::new(min_x, min_y, max_x, max_y, 0)],
+            free: Vec::new(),
+            positions: BTreeMap::new(),
+            capacity: capacity.max(1) as usize,
+            max_depth,
+        }
+    }
+
+    /// Add an entity at (x, y); an existing id is moved instead
+    ///
+    /// Returns false (and stores nothing) if the point lies outside the
+    /// tree's region.
+    pub fn insert(&mut self, id: u32, x: i32, y: i32) -> bool {
+        let root = &self.nodes[0];
+        if x < root.min_x || x > root.max_x || y < root.min_y || y > root.max_y {
+            return false;
+        }
+        self.remove(id);
+
+        self.positions.insert(id, (x, y));
+        let leaf = *self.leaf_path(x, y).last().unwrap();
+        self.nodes[leaf].items.push(id);
+        self.split_if_full(leaf);
+        true
+    }
+
+    /// Move an entity to (x, y); same as `insert`
+    #[cfg_attr(feature = "wasm", wasm_bindgen(js_name = "move"))]
+    pub fn move_to(&mut self, id: u32, x: i32, y: i32) -> bool {
+        self.insert(id, x, y)
+    }
+
+    /// Remove an entity; returns whether it was present
+    pub fn remove(&mut self, id: u32) -> bool {
+        let Some((x, y)) = self.positions.remove(&id) else {
+            return false;
+        };
+        let path = self.leaf_path(x, y);
+        let items = &mut self.nodes[*path.last().unwrap()].items;
+        if let Some(i) = items.iter().position(|&e| e == id) {
+            items.swap_remove(i);
+        }
+        for &n in path.iter().rev().skip(1) {
+            self.try_merge(n);
+        }
+        true
+    }
+
+    /// Number of stored entities
+    pub fn len(&self) -> u32 {
+        self.positions.len() as u32
+    }
+
+    /// Whether the tree holds no entities
+    pub fn is_empty(&self) -> bool {
+        self.positions.is_empty()
+    }
+
+    /// Number of leaves, for tuning capacity and depth
+    pub fn leaf_count(&self) -> u32 {
+        let mut count = 0;
+        let mut stack = vec![0];
+        while let Some(n) = stack.pop() {
+            match self.nodes[n].children {
+                Some(first) => stack.extend(first..first + 4),
+                None => count += 1,
+            }
+        }
+        count
+    }
+
+    /// Ids of entities inside the closed box [min, max], ascending
+    pub fn query_aabb(&self, min_x: i32, min_y: i32, max_x: i32, max_y: i32) -> Vec<u32> {
+        self.query(min_x, min_y, max_x, max_y, |x, y| {
+            min_x <= x && x <= max_x && min_y <= y && y <= max_y
+        })
+    }
+
+    /// Ids of entities within distance r of (x, y), ascending
+    ///
+    /// Same exact squared-distance test as `SpatialHash::query_radius`.
+    pub fn query_radius(&self, x: i32, y: i32, r: i32) -> Vec<u32> {
+        if r < 0 {
+            return Vec::new();
+        }
+        let r2 = r as i128 * r as i128;
+        self.query(
+            x.saturating_sub(r),
+            y.saturating_sub(r),
+            x.saturating_add(r),
+            y.saturating_add(r),
+            |px, py| {
+                let dx = px as i128 - x as i128;
+                let dy = py as i128 - y as i128;
+                dx * dx + dy * dy <= r2
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ONE: i32 = 1 << 16;
+
+    fn tree() -> Quadtree {
+        Quadtree::new(-64 * ONE, -64 * ONE, 64 * ONE, 64 * ONE, 2, 8)
+    }
+
+    #[test]
+    fn queries_match_brute_force() {
+        let mut s = 99u32;
+        let mut next = || {
+            s = s.wrapping_mul(1664525).wrapping_add(1013904223);
+            ((s >> 8) % (120 * ONE as u32)) as i32 - 60 * ONE
+        };
+        let pts: Vec<(u32, i32, i32)> = (0..200).map(|id| (id, next(), next())).collect();
+        let mut qt = tree();
+        for &(id, x, y) in &pts {
+            assert!(qt.insert(id, x, y));
+        }
+        assert_eq!(qt.len(), 200);
+        assert!(qt.leaf_count() > 1);
+        let (cx, cy, r) = (5 * ONE, -3 * ONE, 17 * ONE);
+        let r2 = r as i128 * r as i128;
+        let near: Vec<u32> = pts
+            .iter()
+            .filter(|&&(_, x, y)| {
+                let (dx, dy) = (x as i128 - cx as i128, y as i128 - cy as i128);
+                dx * dx + dy * dy <= r2
+            })
+            .map(|p| p.0)
+            .collect();
+        assert_eq!(qt.query_radius(cx, cy, r), near);
+        let boxed: Vec<u32> = pts.iter().filter(|p| p.1 <= 0 && p.2 >= 10 * ONE).map(|p| p.0).collect();
+        assert_eq!(qt.query_aabb(i32::MIN, 10 * ONE, 0, i32::MAX), boxed);
+        assert!(qt.query_radius(0, 0, -1).is_empty());
+    }
+
+    #[test]
+    fn rejects_points_outside_region() {
+        let mut qt = tree();
+        assert!(!qt.insert(1, 64 * ONE + 1, 0));
+        assert!(qt.insert(1, 64 * ONE, -64 * ONE));
+        assert!(!qt.move_to(1, 0, -65 * ONE));
+        // A failed move leaves the entity where it was
+        assert_eq!(qt.query_aabb(64 * ONE, -64 * ONE, 64 * ONE, -64 * ONE), [1]);
+    }
+
+    #[test]
+    fn splits_and_merges_back() {
+        let mut qt = tree();
+        for id in 0..10 {
+            qt.insert(id, id as i32 * 10 * ONE - 50 * ONE, id as i32 * 7 * ONE - 30 * ONE);
+        }
+        assert!(qt.leaf_count() > 1);
+        for id in 0..10 {
+            assert!(qt.remove(id));
+        }
+        assert!(!qt.remove(0));
+        assert!(qt.is_empty());
+        assert_eq!(qt.leaf_count(), 1);
+    }
+
+    #[test]
+    fn layout_depends_only_on_positions() {
+        let pts: Vec<(u32, i32, i32)> = (0..30).map(|i| (i, (i as i32 * 37 % 100 - 50) * ONE, (i as i32 * 53 % 100 - 50) * ONE)).collect();
+        let mut a = tree();
+        let mut b = tree();
+        for &(id, x, y) in &pts {
+            a.insert(id, x, y);
+        }
+        for &(id, x, y) in pts.iter().rev() {
+            b.insert(id, 0, 0);
+            b.move_to(id, x, y);
+        }
+        assert_eq!(a.leaf_count(), b.leaf_count());
+        assert_eq!(a.query_radius(0, 0, 30 * ONE), b.query_radius(0, 0, 30 * ONE));
+    }
+
+    #[test]
+    fn coincident_points_stop_at_max_depth() {
+        let mut qt = Quadtree::new(0, 0, ONE, ONE, 1, 3);
+        for id in 0..5 {
+            qt.insert(id, ONE / 3, ONE / 3);
+        }
+        assert_eq!(qt.leaf_count(), 10);
+        assert_eq!(qt.query_radius(ONE / 3, ONE / 3, 0), [0, 1, 2, 3, 4]);
+    }
+}
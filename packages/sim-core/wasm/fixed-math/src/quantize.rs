@@ -0,0 +1,202 @@
+//! Snapping external floats onto the fixed-point grid
+//!
+//! Pointer coordinates and config values arrive as JS numbers; these
+//! functions are the one place they cross into Q16.16, with a single
+//! rounding rule, so equal inputs always become equal fixed values on
+//! every client. Only IEEE-754 multiply, divide and floor are used,
+//! which are exactly specified.
+
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+use crate::flags::{self, StatusFlag};
+use crate::round::Rounding;
+use crate::{saturate_i128, saturate_i64};
+
+/// 2^16 as f64
+const SCALE: f64 = 65_536.0;
+
+/// Step count beyond which every result saturates (2^40)
+const MAX_STEPS: f64 = 1_099_511_627_776.0;
+
+/// Nearest multiple of step (Q16.16) to a float, ties toward +∞
+///
+/// Computes q = value * 65536 / step in f64, rounds q to the nearest
+/// integer (exact halves go up, like `round_fp`) and returns q * step,
+/// saturated to i32 with `StatusFlag::Overflow`. A non-positive step
+/// means 1 LSB, i.e. plain rounding to the nearest Q16.16 value. NaN
+/// returns 0 and raises `StatusFlag::Domain`.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn quantize(value: f64, step: i32) -> i32 {
+    if value.is_nan() {
+        flags::raise(StatusFlag::Domain);
+        return 0;
+    }
+    let step = step.max(1);
+    let q = value * SCALE / step as f64;
+    let floor = q.floor();
+    let k = if q - floor >= 0.5 { floor + 1.0 } else { floor };
+    let k = k.clamp(-MAX_STEPS, MAX_STEPS) as i128;
+    saturate_i128(k * step as i128)
+}
+
+/// `quantize` applied to every element of a Float64Array
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn quantize_batch(values: &[f64], step: i32) -> Vec<i32> {
+    values.iter().map(|&v| quantize(v, step)).collect()
+}
+
+/// Round a float to the nearest Q16.16 value in the given direction
+///
+/// Scales by 2^16 (exact in f64) and rounds the scaled value to an
+/// integer as `mode` says, using only floor/ceil/trunc and the exact
+/// fraction q - floor(q), so the result is the same on every platform.
+/// Results outside the i32 range (including ±∞) saturate with
+/// `StatusFlag::Overflow`; NaN returns 0 and raises `StatusFlag::Domain`.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn from_f64(x: f64, mode: Rounding) -> i32 {
+    if x.is_nan() {
+        flags::raise(StatusFlag::Domain);
+        return 0;
+    }
+    let q = x * SCALE;
+    let floor = q.floor();
+    let fraction = q - floor;
+    let k = match mode {
+        Rounding::Floor => floor,
+        Rounding::Ceil => q.ceil(),
+        Rounding::Trunc => q.trunc(),
+        Rounding::HalfUp => if fraction >= 0.5 { floor + 1.0 } else { floor },
+        Rounding::HalfEven => {
+            let odd = floor % 2.0 != 0.0;
+            if fraction > 0.5 || (fraction == 0.5 && odd) { floor + 1.0 } else { floor }
+        }
+    };
+    saturate_i64(k.clamp(-MAX_STEPS, MAX_STEPS) as i64)
+}
+
+/// Exact float value of a Q16.16 number
+///
+/// Every Q16.16 value is representable in f64, so this never rounds and
+/// `from_f64(to_f64(fp), _)` returns fp for any mode.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn to_f64(fp: i32) -> f64 {
+    fp as f64 / SCALE
+}
+
+/// `from_f64` applied to every element of a Float64Array
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn from_f64_batch(values: &[f64], mode: Rounding) -> Vec<i32> {
+    values.iter().map(|&x| from_f64(x, mode)).collect()
+}
+
+/// `to_f64` applied to every element of an Int32Array
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn to_f64_batch(values: &[i32]) -> Vec<f64> {
+    values.iter().map(|&fp| to_f64(fp)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::flags::{clear_flags, get_flags};
+
+    const ONE: i32 = 1 << 16;
+
+    #[test]
+    fn quantize_snaps_to_step_multiples() {
+        assert_eq!(quantize(1.3, ONE / 2), ONE + ONE / 2);
+        assert_eq!(quantize(1.2, ONE / 2), ONE);
+        // Exact halves go toward +∞
+        assert_eq!(quantize(1.25, ONE / 2), ONE + ONE / 2);
+        assert_eq!(quantize(-1.25, ONE / 2), -ONE);
+        assert_eq!(quantize(-7.0, 3 * ONE), -6 * ONE);
+        assert_eq!(quantize(0.5 / 65536.0, 0), 1);
+        assert_eq!(quantize(-0.5 / 65536.0, -5), 0);
+    }
+
+    #[test]
+    fn quantize_saturates_and_rejects_nan() {
+        clear_flags();
+        assert_eq!(quantize(1e12, ONE), i32::MAX);
+        assert_eq!(get_flags(), StatusFlag::Overflow as u32);
+        clear_flags();
+        assert_eq!(quantize(f64::NEG_INFINITY, 1), i32::MIN);
+        assert_eq!(get_flags(), StatusFlag::Overflow as u32);
+        clear_flags();
+        assert_eq!(quantize(f64::NAN, ONE), 0);
+        assert_eq!(get_flags(), StatusFlag::Domain as u32);
+    }
+
+    #[test]
+    fn quantize_batch_matches_scalar() {
+        let values = [0.1, -2.75, 1e9, 3.5];
+        let expected: Vec<i32> = values.iter().map(|&v| quantize(v, ONE / 4)).collect();
+        assert_eq!(quantize_batch(&values, ONE / 4), expected);
+    }
+
+    const MODES: [Rounding; 5] = [Rounding::Floor, Rounding::Ceil, Rounding::Trunc, Rounding::HalfUp, Rounding::HalfEven];
+
+    #[test]
+    fn from_f64_rounds_by_mode() {
+        let lsb = 1.0 / 65536.0;
+        let cases = [
+            // x in LSBs, floor, ceil, trunc, half-up, half-even
+            (2.5, 2, 3, 2, 3, 2),
+            (3.5, 3, 4, 3, 4, 4),
+            (-2.5, -3, -2, -2, -2, -2),
+            (-3.5, -4, -3, -3, -3, -4),
+            (2.25, 2, 3, 2, 2, 2),
+            (-2.75, -3, -2, -2, -3, -3),
+        ];
+        for (x, a, b, c, d, e) in cases {
+            for (mode, want) in MODES.into_iter().zip([a, b, c, d, e]) {
+                assert_eq!(from_f64(x * lsb, mode), want, "{x} {mode:?}");
+            }
+        }
+        assert_eq!(from_f64(1.5, Rounding::Floor), ONE + ONE / 2);
+    }
+
+    #[test]
+    fn to_f64_round_trips_for_every_mode() {
+        for fp in [i32::MIN, -ONE - 1, -1, 0, 1, ONE / 3, i32::MAX] {
+            for mode in MODES {
+                assert_eq!(from_f64(to_f64(fp), mode), fp, "{fp} {mode:?}");
+            }
+        }
+        for fp in (i32::MIN..i32::MAX).step_by(104_729) {
+            assert_eq!(from_f64(to_f64(fp), Rounding::HalfEven), fp);
+        }
+        assert_eq!(to_f64(-ONE / 4), -0.25);
+    }
+
+    #[test]
+    fn from_f64_saturates_and_rejects_nan() {
+        for mode in MODES {
+            clear_flags();
+            assert_eq!(from_f64(f64::INFINITY, mode), i32::MAX);
+            assert_eq!(from_f64(f64::NEG_INFINITY, mode), i32::MIN);
+            assert_eq!(from_f64(32768.0, mode), i32::MAX);
+            assert_eq!(get_flags(), StatusFlag::Overflow as u32);
+            clear_flags();
+            assert_eq!(from_f64(f64::NAN, mode), 0);
+            assert_eq!(get_flags(), StatusFlag::Domain as u32);
+        }
+        // The last representable value does not overflow
+        clear_flags();
+        assert_eq!(from_f64(-32768.0, Rounding::Floor), i32::MIN);
+        assert_eq!(get_flags(), 0);
+    }
+
+    #[test]
+    fn f64_batches_match_scalar() {
+        let values = [0.1, -2.75, 1e9, f64::NAN, 3.5 / 65536.0];
+        for mode in MODES {
+            let expected: Vec<i32> = values.iter().map(|&x| from_f64(x, mode)).collect();
+            assert_eq!(from_f64_batch(&values, mode), expected);
+        }
+        let fps = [i32::MIN, -3, 0, ONE, i32::MAX];
+        let expected: Vec<f64> = fps.iter().map(|&fp| to_f64(fp)).collect();
+        assert_eq!(to_f64_batch(&fps), expected);
+    }
+}
@@ -0,0 +1,182 @@
+//! Tile line rasterization and line of sight
+//!
+//! Integer tile coordinates (not Q16.16). Both traversals start at the
+//! first endpoint and return packed `[x0, y0, x1, y1, ...]` tiles in walk
+//! order; swapping the endpoints can pick different tiles where the line
+//! is ambiguous, so callers that need symmetry should order endpoints
+//! consistently (for example by entity id).
+
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+/// Bresenham line from (x0, y0) to (x1, y1), both endpoints included
+///
+/// One tile per step along the major axis; error terms are 64-bit, so
+/// any i32 endpoints work. At an exact half-tile tie the minor axis
+/// steps early (the error is compared with <=), matching the classic
+/// integer formulation.
+#[cfg(feature = "alloc")]
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn bresenham(x0: i32, y0: i32, x1: i32, y1: i32) -> Vec<i32> {
+    let (dx, dy) = ((x1 as i64 - x0 as i64).abs(), -(y1 as i64 - y0 as i64).abs());
+    let (sx, sy) = (if x0 < x1 { 1 } else { -1 }, if y0 < y1 { 1 } else { -1 });
+    let (mut x, mut y) = (x0 as i64, y0 as i64);
+    let mut err = dx + dy;
+    let mut out = Vec::with_capacity(2 * (dx.max(-dy) as usize + 1));
+    loop {
+        out.extend([x as i32, y as i32]);
+        if x == x1 as i64 && y == y1 as i64 {
+            return out;
+        }
+        let e2 = 2 * err;
+        if e2 >= dy {
+            err += dy;
+            x += sx;
+        }
+        if e2 <= dx {
+            err += dx;
+            y += sy;
+        }
+    }
+}
+
+/// Visit the tiles `supercover` returns, in order, until visit says stop
+///
+/// Returns false if visit stopped the walk early.
+fn supercover_walk(x0: i32, y0: i32, x1: i32, y1: i32, mut visit: impl FnMut(i32, i32) -> bool) -> bool {
+    let (nx, ny) = ((x1 as i64 - x0 as i64).abs(), (y1 as i64 - y0 as i64).abs());
+    let (sx, sy) = (if x0 < x1 { 1 } else { -1 }, if y0 < y1 { 1 } else { -1 });
+    let (mut x, mut y) = (x0 as i64, y0 as i64);
+    if !visit(x0, y0) {
+        return false;
+    }
+    let (mut ix, mut iy) = (0i64, 0i64);
+    while ix < nx || iy < ny {
+        // Compare (0.5 + ix) / nx with (0.5 + iy) / ny, the next crossings
+        let order = ((1 + 2 * ix) as i128 * ny as i128).cmp(&((1 + 2 * iy) as i128 * nx as i128));
+        match order {
+            core::cmp::Ordering::Equal => {
+                if !visit((x + sx) as i32, y as i32) || !visit(x as i32, (y + sy) as i32) {
+                    return false;
+                }
+                x += sx;
+                y += sy;
+                ix += 1;
+                iy += 1;
+            }
+            core::cmp::Ordering::Less => {
+                x += sx;
+                ix += 1;
+            }
+            core::cmp::Ordering::Greater => {
+                y += sy;
+                iy += 1;
+            }
+        }
+        if !visit(x as i32, y as i32) {
+            return false;
+        }
+    }
+    true
+}
+
+/// Every tile touched by the segment between two tile centers
+///
+/// Walks tile boundaries in the order the segment crosses them, with the
+/// crossing order decided by exact integer comparison. Where the segment
+/// passes exactly through a tile corner, both tiles beside the corner
+/// are included (x side first) before the diagonal one, so nothing can
+/// be seen through a diagonal gap between two walls.
+#[cfg(feature = "alloc")]
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn supercover(x0: i32, y0: i32, x1: i32, y1: i32) -> Vec<i32> {
+    let mut out = Vec::new();
+    supercover_walk(x0, y0, x1, y1, |x, y| {
+        out.extend([x, y]);
+        true
+    });
+    out
+}
+
+/// Whether tile b is visible from tile a across a blocking grid
+///
+/// `grid` is row-major `width` × `height`, non-zero meaning opaque.
+/// Every tile `supercover` visits between the endpoints must be open;
+/// the endpoints themselves never block (a tower sees out of its own
+/// tile and onto the enemy's). Tiles outside the grid are opaque.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn los_clear(grid: &[u8], width: u32, height: u32, ax: i32, ay: i32, bx: i32, by: i32) -> bool {
+    supercover_walk(ax, ay, bx, by, |x, y| {
+        if (x, y) == (ax, ay) || (x, y) == (bx, by) {
+            return true;
+        }
+        if x < 0 || y < 0 || x as u32 >= width || y as u32 >= height {
+            return false;
+        }
+        grid.get(y as usize * width as usize + x as usize).is_some_and(|&c| c == 0)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "alloc")]
+    fn tiles(packed: &[i32]) -> Vec<(i32, i32)> {
+        packed.chunks(2).map(|t| (t[0], t[1])).collect()
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn bresenham_steps_once_per_major_axis_tile() {
+        // y = x / 2 ties at odd x and steps y early there
+        assert_eq!(tiles(&bresenham(0, 0, 4, 2)), [(0, 0), (1, 1), (2, 1), (3, 2), (4, 2)]);
+        assert_eq!(tiles(&bresenham(0, 0, 5, 1)), [(0, 0), (1, 0), (2, 0), (3, 1), (4, 1), (5, 1)]);
+        assert_eq!(tiles(&bresenham(0, 0, 0, -3)), [(0, 0), (0, -1), (0, -2), (0, -3)]);
+        assert_eq!(tiles(&bresenham(2, 2, -1, -1)), [(2, 2), (1, 1), (0, 0), (-1, -1)]);
+        assert_eq!(bresenham(5, 5, 5, 5), [5, 5]);
+        let line = bresenham(-7, 3, 12, -20);
+        assert_eq!(line.len(), 2 * 24);
+        assert_eq!(line[line.len() - 2..], [12, -20]);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn supercover_includes_both_corner_tiles() {
+        assert_eq!(tiles(&supercover(0, 0, 2, 2)), [(0, 0), (1, 0), (0, 1), (1, 1), (2, 1), (1, 2), (2, 2)]);
+        assert_eq!(tiles(&supercover(0, 0, 4, 1)), [(0, 0), (1, 0), (2, 0), (2, 1), (3, 1), (4, 1)]);
+        // (1.5, 0.5) is a corner crossing
+        assert_eq!(tiles(&supercover(0, 0, 3, 1)), [(0, 0), (1, 0), (2, 0), (1, 1), (2, 1), (3, 1)]);
+        assert_eq!(tiles(&supercover(0, 0, -3, 0)), [(0, 0), (-1, 0), (-2, 0), (-3, 0)]);
+        // 4-connected walk: each tile shares an edge with the previous
+        let walk = tiles(&supercover(-5, 7, 9, -2));
+        for w in walk.windows(2) {
+            assert_eq!((w[0].0 - w[1].0).abs() + (w[0].1 - w[1].1).abs(), 1);
+        }
+        assert_eq!(walk.len(), 14 + 9 + 1);
+    }
+
+    #[test]
+    fn los_blocks_on_walls_and_diagonal_gaps() {
+        #[rustfmt::skip]
+        let grid = [
+            0, 0, 0, 0,
+            0, 1, 0, 0,
+            0, 0, 1, 0,
+            0, 0, 0, 0,
+        ];
+        assert!(los_clear(&grid, 4, 4, 0, 0, 3, 0));
+        assert!(!los_clear(&grid, 4, 4, 0, 1, 3, 1));
+        assert!(!los_clear(&grid, 4, 4, 0, 0, 3, 3));
+        // The endpoints may be opaque themselves
+        assert!(los_clear(&grid, 4, 4, 1, 1, 1, 0));
+        assert!(los_clear(&grid, 4, 4, 1, 1, 2, 2));
+        // Corner-touching walls: the supercover sees both sides
+        let gap = [0, 1, 1, 0];
+        assert!(!los_clear(&gap, 2, 2, 0, 0, 1, 1));
+        assert!(!los_clear(&grid, 4, 4, 0, 0, 5, 0));
+    }
+}
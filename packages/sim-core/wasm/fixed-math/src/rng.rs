@@ -0,0 +1,522 @@
+//! Deterministic random numbers
+//!
+//! `FixedRng` is PCG32 (XSH-RR output on a 64-bit LCG): small, fast in
+//! wasm and fully specified by two u64 words, so a save game or replay
+//! can capture the generator and resume it bit-for-bit on any client.
+//!
+//! Seeds go through SplitMix64 before reaching PCG, so nearby seeds
+//! (0, 1, 2, ...) still give unrelated sequences, and `fork_stream` hands
+//! each subsystem its own generator so extra draws in one never shift
+//! the rolls of another.
+
+#[cfg(feature = "alloc")]
+use alloc::{collections::BTreeMap, vec, vec::Vec};
+
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+use crate::exp::ln_q30;
+use crate::flags::{self, StatusFlag};
+use crate::{saturate_i128, Q30_FRAC_BITS};
+
+/// LCG multiplier from the PCG reference implementation
+const MULTIPLIER: u64 = 6_364_136_223_846_793_005;
+
+/// 1.0 in Q16.16
+const ONE: i32 = 1 << 16;
+
+/// Uniform draws summed by `normal_fp`
+const NORMAL_TERMS: i32 = 12;
+
+/// SplitMix64 increment (2^64 / golden ratio)
+const GOLDEN_GAMMA: u64 = 0x9E37_79B9_7F4A_7C15;
+
+/// SplitMix64 finalizer
+fn mix64(mut z: u64) -> u64 {
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+/// Advance a SplitMix64 state and return its next output
+fn splitmix64(state: &mut u64) -> u64 {
+    *state = state.wrapping_add(GOLDEN_GAMMA);
+    mix64(*state)
+}
+
+/// PCG32 generator with serializable state
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FixedRng {
+    state: u64,
+    /// LCG increment; always odd
+    inc: u64,
+}
+
+impl FixedRng {
+    /// Reference `pcg32_srandom_r` seeding for a given stream
+    fn seeded(seed: u64, stream: u64) -> FixedRng {
+        let mut rng = FixedRng { state: 0, inc: (stream << 1) | 1 };
+        rng.step();
+        rng.state = rng.state.wrapping_add(seed);
+        rng.step();
+        rng
+    }
+
+    /// PCG state and stream both drawn from SplitMix64 over `seed`
+    fn from_splitmix(mut seed: u64) -> FixedRng {
+        let state = splitmix64(&mut seed);
+        let stream = splitmix64(&mut seed);
+        FixedRng::seeded(state, stream)
+    }
+
+    fn step(&mut self) {
+        self.state = self.state.wrapping_mul(MULTIPLIER).wrapping_add(self.inc);
+    }
+
+    /// Uniform value in [0, span) for span >= 1, by rejection
+    ///
+    /// Outputs below 2^32 mod span are redrawn so every residue has the
+    /// same number of preimages; at most half the outputs are ever
+    /// rejected, so the expected draw count is below two.
+    pub(crate) fn below(&mut self, span: u32) -> u32 {
+        let threshold = span.wrapping_neg() % span;
+        loop {
+            let r = self.next_u32();
+            if r >= threshold {
+                return r % span;
+            }
+        }
+    }
+
+    /// Uniform value in [0, span) for span >= 1, from 64-bit draws
+    ///
+    /// Same rejection scheme as `below` on two outputs (high word
+    /// first).
+    #[cfg(feature = "alloc")]
+    pub(crate) fn below_u64(&mut self, span: u64) -> u64 {
+        let threshold = span.wrapping_neg() % span;
+        loop {
+            let r = ((self.next_u32() as u64) << 32) | self.next_u32() as u64;
+            if r >= threshold {
+                return r % span;
+            }
+        }
+    }
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl FixedRng {
+    /// Create a generator from a 64-bit seed
+    ///
+    /// The seed is expanded with SplitMix64 into the PCG state and
+    /// stream, so equal seeds always produce equal sequences and
+    /// neighbouring seeds produce unrelated ones.
+    #[cfg_attr(feature = "wasm", wasm_bindgen(constructor))]
+    pub fn new(seed: u64) -> FixedRng {
+        FixedRng::from_splitmix(seed)
+    }
+
+    /// Independent generator for the subsystem identified by `tag`
+    ///
+    /// The child is derived from this generator's current state and the
+    /// tag, without advancing it: the same parent state and tag always
+    /// give the same child, different tags give unrelated streams. Fork
+    /// every subsystem from the root right after seeding, so their
+    /// sequences depend only on the seed and not on each other.
+    pub fn fork_stream(&self, tag: u32) -> FixedRng {
+        FixedRng::from_splitmix(self.state ^ mix64(self.inc ^ ((tag as u64) << 32)))
+    }
+
+    /// Next uniformly distributed 32-bit value
+    pub fn next_u32(&mut self) -> u32 {
+        let old = self.state;
+        self.step();
+        let xorshifted = (((old >> 18) ^ old) >> 27) as u32;
+        xorshifted.rotate_right((old >> 59) as u32)
+    }
+
+    /// Next Q16.16 value uniformly distributed in [0, 1.0)
+    ///
+    /// Uses the top 16 bits of `next_u32`, so every representable step
+    /// below 1.0 is equally likely.
+    pub fn next_fp01(&mut self) -> i32 {
+        (self.next_u32() >> 16) as i32
+    }
+
+    /// Generator state as `[state, increment]`
+    #[cfg(feature = "alloc")]
+    pub fn get_state(&self) -> Vec<u64> {
+        vec![self.state, self.inc]
+    }
+
+    /// Restore a state captured by `get_state`
+    ///
+    /// Anything other than two words leaves the generator unchanged and
+    /// raises `StatusFlag::Domain`. The increment is forced odd, as PCG
+    /// requires.
+    pub fn set_state(&mut self, words: &[u64]) {
+        match *words {
+            [state, inc] => {
+                self.state = state;
+                self.inc = inc | 1;
+            }
+            _ => flags::raise(StatusFlag::Domain),
+        }
+    }
+
+    /// Uniform integer in [lo, hi)
+    ///
+    /// Unbiased (rejection sampling, see `below`) for any span up to the
+    /// full i32 range. An empty range (hi <= lo) returns lo without
+    /// drawing.
+    pub fn range_i32(&mut self, lo: i32, hi: i32) -> i32 {
+        if hi <= lo {
+            return lo;
+        }
+        let span = (hi as i64 - lo as i64) as u32;
+        (lo as i64 + self.below(span) as i64) as i32
+    }
+
+    /// Uniform Q16.16 value in [lo, hi), at full 1-LSB resolution
+    ///
+    /// Same draw as `range_i32` on the raw values.
+    pub fn range_fp(&mut self, lo: i32, hi: i32) -> i32 {
+        self.range_i32(lo, hi)
+    }
+
+    /// True with probability p (Q16.16)
+    ///
+    /// Compares one `next_fp01` draw against p, so the probability is
+    /// exactly p / 65536. p <= 0 is never and p >= 1.0 is always true;
+    /// either way exactly one value is drawn, so the stream advances the
+    /// same regardless of p.
+    pub fn chance(&mut self, p: i32) -> bool {
+        self.next_fp01() < p
+    }
+
+    /// Shuffle an array in place (Fisher-Yates)
+    ///
+    /// Walks from the last element down, swapping each with a uniformly
+    /// chosen element at or below it, so all n! orders are equally
+    /// likely. Draws exactly one bounded value per element after the
+    /// first.
+    pub fn shuffle(&mut self, values: &mut [i32]) {
+        for i in (1..values.len()).rev() {
+            let j = self.below(i as u32 + 1) as usize;
+            values.swap(i, j);
+        }
+    }
+
+    /// k distinct indices from 0..n, in the order they were drawn
+    ///
+    /// A partial Fisher-Yates over the virtual array 0..n, with only the
+    /// displaced slots stored, so the cost is O(k log k) however large n
+    /// is. Every ordered k-subset is equally likely. k is clamped to n.
+    #[cfg(feature = "alloc")]
+    pub fn sample_k(&mut self, n: u32, k: u32) -> Vec<u32> {
+        let k = k.min(n);
+        let mut displaced: BTreeMap<u32, u32> = BTreeMap::new();
+        let mut out = Vec::with_capacity(k as usize);
+        for i in 0..k {
+            let j = i + self.below(n - i);
+            let picked = *displaced.get(&j).unwrap_or(&j);
+            let current = *displaced.get(&i).unwrap_or(&i);
+            displaced.insert(j, current);
+            out.push(picked);
+        }
+        out
+    }
+
+    /// Approximately normal Q16.16 value with the given mean and stddev
+    ///
+    /// Sums 12 `next_fp01` draws and subtracts 6.0 (Irwin-Hall), which
+    /// has mean 0 and variance 1 with tails cut at ±6 standard
+    /// deviations, then scales: mean + stddev * z, formed in 128-bit and
+    /// saturated. Always draws exactly 12 values.
+    pub fn normal_fp(&mut self, mean: i32, stddev: i32) -> i32 {
+        let z: i32 = (0..NORMAL_TERMS).map(|_| self.next_fp01()).sum::<i32>() - NORMAL_TERMS / 2 * ONE;
+        saturate_i128(mean as i128 + ((stddev as i128 * z as i128) >> 16))
+    }
+
+    /// Exponentially distributed Q16.16 value with the given rate
+    ///
+    /// Inverse transform -ln(u) / rate with u = 1.0 - `next_fp01` in
+    /// (0, 1.0], so the mean is 1 / rate and the tail is cut at
+    /// ln(65536) / rate (about 11.09 / rate). One draw; the logarithm is
+    /// the crate's Q2.30 `ln`, and the division rounds toward zero.
+    /// rate <= 0 is a division by zero under the active
+    /// `DivByZeroPolicy` and draws nothing.
+    pub fn exponential_fp(&mut self, rate: i32) -> i32 {
+        if rate <= 0 {
+            return crate::div_by_zero(false);
+        }
+        let u = ONE - self.next_fp01();
+        let neg_ln = -ln_q30(u) as i128;
+        saturate_i128((neg_ln << (32 - Q30_FRAC_BITS)) / rate as i128)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::flags::{clear_flags, get_flags};
+
+    #[test]
+    fn pcg32_reference_sequence() {
+        // pcg32-demo: pcg32_srandom_r(&rng, 42, 54)
+        let mut rng = FixedRng::seeded(42, 54);
+        let out: Vec<u32> = (0..6).map(|_| rng.next_u32()).collect();
+        assert_eq!(out, [0xa15c02b7, 0x7b47f409, 0xba1d3330, 0x83d2f293, 0xbfa4784b, 0xcbed606e]);
+    }
+
+    #[test]
+    fn next_fp01_is_the_top_half() {
+        let mut a = FixedRng::new(7);
+        let mut b = a.clone();
+        for _ in 0..100 {
+            let v = a.next_fp01();
+            assert!((0..ONE).contains(&v));
+            assert_eq!(v, (b.next_u32() >> 16) as i32);
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn state_round_trips() {
+        let mut rng = FixedRng::new(99);
+        rng.next_u32();
+        let saved = rng.get_state();
+        let expected: Vec<u32> = (0..4).map(|_| rng.next_u32()).collect();
+        let mut restored = FixedRng::new(0);
+        restored.set_state(&saved);
+        assert_eq!(restored, FixedRng { state: saved[0], inc: saved[1] });
+        assert_eq!((0..4).map(|_| restored.next_u32()).collect::<Vec<_>>(), expected);
+    }
+
+    #[test]
+    fn set_state_rejects_bad_lengths_and_forces_odd_increment() {
+        let mut rng = FixedRng::new(1);
+        let before = rng.clone();
+        for words in [&[][..], &[1], &[1, 2, 3]] {
+            clear_flags();
+            rng.set_state(words);
+            assert_eq!(rng, before);
+            assert_eq!(get_flags(), StatusFlag::Domain as u32);
+        }
+        clear_flags();
+        rng.set_state(&[5, 8]);
+        assert_eq!(rng, FixedRng { state: 5, inc: 9 });
+        assert_eq!(get_flags(), 0);
+    }
+
+    #[test]
+    fn splitmix64_reference_outputs() {
+        let mut s = 0;
+        assert_eq!(splitmix64(&mut s), 0xe220a8397b1dcdaf);
+        assert_eq!(splitmix64(&mut s), 0x6e789e6aa1b965f4);
+        assert_eq!(splitmix64(&mut s), 0x06c45d188009454f);
+    }
+
+    #[test]
+    fn neighbouring_seeds_are_unrelated() {
+        let firsts: Vec<u32> = (0..8).map(|seed| FixedRng::new(seed).next_u32()).collect();
+        for (i, a) in firsts.iter().enumerate() {
+            for b in &firsts[i + 1..] {
+                assert!((a ^ b).count_ones() > 4);
+            }
+        }
+        assert_eq!(FixedRng::new(3), FixedRng::new(3));
+    }
+
+    #[test]
+    fn fork_stream_is_pure_and_tag_dependent() {
+        let root = FixedRng::new(2024);
+        let before = root.clone();
+        let mut a = root.fork_stream(1);
+        let mut again = root.fork_stream(1);
+        let mut b = root.fork_stream(2);
+        assert_eq!(root, before);
+        let xs: Vec<u32> = (0..4).map(|_| a.next_u32()).collect();
+        assert_eq!(xs, (0..4).map(|_| again.next_u32()).collect::<Vec<_>>());
+        assert_ne!(xs, (0..4).map(|_| b.next_u32()).collect::<Vec<_>>());
+        // Children follow the parent state they were forked from
+        assert_eq!(root.fork_stream(2), FixedRng::new(2024).fork_stream(2));
+        let mut advanced = root.clone();
+        advanced.next_u32();
+        assert_ne!(advanced.fork_stream(2), root.fork_stream(2));
+    }
+
+    #[test]
+    fn range_i32_stays_in_bounds_and_covers_the_span() {
+        let mut rng = FixedRng::new(5);
+        let mut seen = [0u32; 7];
+        for _ in 0..7000 {
+            let v = rng.range_i32(-3, 4);
+            assert!((-3..4).contains(&v));
+            seen[(v + 3) as usize] += 1;
+        }
+        assert!(seen.iter().all(|&c| (800..1200).contains(&c)));
+        for _ in 0..100 {
+            rng.range_i32(i32::MIN, i32::MAX);
+            assert!(rng.range_fp(-ONE, ONE) < ONE);
+        }
+    }
+
+    #[test]
+    fn empty_range_draws_nothing() {
+        let mut rng = FixedRng::new(5);
+        let before = rng.clone();
+        assert_eq!(rng.range_i32(10, 10), 10);
+        assert_eq!(rng.range_fp(3 * ONE, -ONE), 3 * ONE);
+        assert_eq!(rng, before);
+    }
+
+    #[test]
+    fn below_rejects_the_biased_tail() {
+        // span 3 << 30: outputs below 2^32 mod span (2^30) are redrawn
+        let mut rng = FixedRng::new(11);
+        let mut reference = rng.clone();
+        for _ in 0..200 {
+            let v = rng.below(3 << 30);
+            let r = loop {
+                let r = reference.next_u32();
+                if r >= 1 << 30 {
+                    break r % (3 << 30);
+                }
+            };
+            assert_eq!(v, r);
+        }
+    }
+
+    #[test]
+    fn chance_draws_exactly_once() {
+        let mut rng = FixedRng::new(8);
+        let mut twin = rng.clone();
+        assert!(!rng.chance(0));
+        assert!(!rng.chance(-ONE));
+        assert!(rng.chance(ONE));
+        assert!(rng.chance(2 * ONE));
+        for _ in 0..4 {
+            twin.next_u32();
+        }
+        assert_eq!(rng, twin);
+        let hits = (0..10_000).filter(|_| rng.chance(ONE / 4)).count();
+        assert!((2300..2700).contains(&hits));
+    }
+
+    #[test]
+    fn shuffle_is_a_deterministic_permutation() {
+        let mut a = [1, 2, 3, 4, 5, 6, 7, 8, 9, 10];
+        let mut b = a;
+        FixedRng::new(3).shuffle(&mut a);
+        FixedRng::new(3).shuffle(&mut b);
+        assert_eq!(a, b);
+        let mut sorted = a;
+        sorted.sort_unstable();
+        assert_eq!(sorted, [1, 2, 3, 4, 5, 6, 7, 8, 9, 10]);
+        // One bounded draw per element after the first
+        let mut rng = FixedRng::new(3);
+        let mut twin = rng.clone();
+        let mut one = [42];
+        rng.shuffle(&mut one);
+        rng.shuffle(&mut []);
+        assert_eq!(one, [42]);
+        assert_eq!(rng, twin);
+        rng.shuffle(&mut [1, 2]);
+        twin.below(2);
+        assert_eq!(rng, twin);
+    }
+
+    #[test]
+    fn shuffle_orders_are_uniform() {
+        let mut rng = FixedRng::new(77);
+        let mut counts = [0u32; 6];
+        for _ in 0..6000 {
+            let mut v = [0, 1, 2];
+            rng.shuffle(&mut v);
+            let idx = match v {
+                [0, 1, 2] => 0,
+                [0, 2, 1] => 1,
+                [1, 0, 2] => 2,
+                [1, 2, 0] => 3,
+                [2, 0, 1] => 4,
+                _ => 5,
+            };
+            counts[idx] += 1;
+        }
+        assert!(counts.iter().all(|&c| (850..1150).contains(&c)));
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn sample_k_draws_distinct_indices() {
+        let mut rng = FixedRng::new(1);
+        let picks = rng.sample_k(1_000_000_000, 50);
+        let mut unique = picks.clone();
+        unique.sort_unstable();
+        unique.dedup();
+        assert_eq!(unique.len(), 50);
+        assert!(picks.iter().all(|&i| i < 1_000_000_000));
+        let mut all = rng.sample_k(8, 20);
+        all.sort_unstable();
+        assert_eq!(all, [0, 1, 2, 3, 4, 5, 6, 7]);
+        assert!(rng.sample_k(0, 3).is_empty());
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn sample_k_matches_full_shuffle_prefix() {
+        // A partial Fisher-Yates from the front equals the eager one
+        let mut rng = FixedRng::new(9);
+        let mut twin = rng.clone();
+        let picks = rng.sample_k(20, 6);
+        let mut v: Vec<u32> = (0..20).collect();
+        for i in 0..6 {
+            let j = i + twin.below(20 - i as u32) as usize;
+            v.swap(i, j);
+        }
+        assert_eq!(picks, v[..6]);
+    }
+
+    #[test]
+    fn normal_fp_moments_and_draw_count() {
+        let mut rng = FixedRng::new(21);
+        let mut twin = rng.clone();
+        rng.normal_fp(0, ONE);
+        for _ in 0..12 {
+            twin.next_u32();
+        }
+        assert_eq!(rng, twin);
+
+        let samples: Vec<f64> = (0..20_000).map(|_| rng.normal_fp(10 * ONE, 2 * ONE) as f64 / ONE as f64).collect();
+        let mean = samples.iter().sum::<f64>() / samples.len() as f64;
+        let var = samples.iter().map(|s| (s - mean) * (s - mean)).sum::<f64>() / samples.len() as f64;
+        assert!((mean - 10.0).abs() < 0.05);
+        assert!((var - 4.0).abs() < 0.15);
+        assert!(samples.iter().all(|s| (-2.0..=22.0).contains(s)));
+        assert_eq!(rng.normal_fp(-3 * ONE, 0), -3 * ONE);
+    }
+
+    #[test]
+    fn exponential_fp_mean_and_tail() {
+        let mut rng = FixedRng::new(13);
+        let n = 20_000;
+        let samples: Vec<i32> = (0..n).map(|_| rng.exponential_fp(2 * ONE)).collect();
+        let mean = samples.iter().map(|&s| s as f64).sum::<f64>() / n as f64 / ONE as f64;
+        assert!((mean - 0.5).abs() < 0.02);
+        let tail = (65536f64.ln() / 2.0 * ONE as f64) as i32;
+        assert!(samples.iter().all(|&s| (0..=tail + 2).contains(&s)));
+    }
+
+    #[test]
+    fn exponential_fp_rejects_non_positive_rate() {
+        let mut rng = FixedRng::new(13);
+        let before = rng.clone();
+        clear_flags();
+        assert_eq!(rng.exponential_fp(0), i32::MAX);
+        assert_eq!(rng.exponential_fp(-ONE), i32::MAX);
+        assert_eq!(get_flags(), StatusFlag::DivByZero as u32);
+        assert_eq!(rng, before);
+    }
+}
@@ -0,0 +1,185 @@
+//! Rounding and integer conversion for Q16.16
+//!
+//! Bit-for-bit identical to `FP.floor`/`FP.ceil`/`FP.round`/`FP.toInt`/
+//! `FP.fromInt` on the TypeScript side, including the i32 wrap of the
+//! `| 0` truncation at the extreme ends of the range.
+
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+/// Mask of the 16 fractional bits
+const FRAC_MASK: i32 = 0xFFFF;
+
+/// 0.5 in Q16.16
+const HALF: i32 = 1 << 15;
+
+/// Round toward -∞ to a whole number (result stays Q16.16)
+///
+/// Formula: fp & !0xFFFF
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn floor_fp(fp: i32) -> i32 {
+    fp & !FRAC_MASK
+}
+
+/// Round toward +∞ to a whole number (result stays Q16.16)
+///
+/// Formula: (fp + 0xFFFF) & !0xFFFF
+/// Wraps like the TS `| 0` for values above 32767.0.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn ceil_fp(fp: i32) -> i32 {
+    fp.wrapping_add(FRAC_MASK) & !FRAC_MASK
+}
+
+/// Round to the nearest whole number, ties toward +∞ (result stays Q16.16)
+///
+/// Formula: (fp + 0.5) & !0xFFFF
+/// So 2.5 -> 3.0 and -2.5 -> -2.0, matching `FP.round`.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn round_fp(fp: i32) -> i32 {
+    fp.wrapping_add(HALF) & !FRAC_MASK
+}
+
+/// Round toward zero to a whole number (result stays Q16.16)
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn trunc_fp(fp: i32) -> i32 {
+    if fp < 0 {
+        ceil_fp(fp)
+    } else {
+        floor_fp(fp)
+    }
+}
+
+/// Fractional part, always in [0, 1.0)
+///
+/// Formula: fp - floor_fp(fp), i.e. the low 16 bits. For negative values
+/// this is the distance above the floor: frac(-1.25) = 0.75.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn frac(fp: i32) -> i32 {
+    fp & FRAC_MASK
+}
+
+/// Convert Q16.16 to a plain integer, rounding toward -∞
+///
+/// Formula: fp >> 16
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn to_int(fp: i32) -> i32 {
+    fp >> 16
+}
+
+/// Convert a plain integer to Q16.16
+///
+/// Formula: n << 16 (wraps outside [-32768, 32767], like `FP.fromInt`)
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn from_int(n: i32) -> i32 {
+    n.wrapping_shl(16)
+}
+
+/// Rounding direction for format conversions that drop fractional bits
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Rounding {
+    /// Toward -∞ (a plain arithmetic shift)
+    Floor = 0,
+    /// Toward +∞
+    Ceil = 1,
+    /// Toward zero
+    Trunc = 2,
+    /// To nearest, ties toward +∞ (like `round_fp`)
+    HalfUp = 3,
+    /// To nearest, ties to the even neighbour (like `mul_round`)
+    HalfEven = 4,
+}
+
+/// Drop the low `shift` bits of v, rounding as `mode` says
+///
+/// shift must be in 1..=62; callers pass values far enough inside the
+/// i64 range that adding the rounding bias cannot overflow.
+pub(crate) fn shift_round(v: i64, shift: u32, mode: Rounding) -> i64 {
+    let floor = v >> shift;
+    let rem = v & ((1 << shift) - 1);
+    let half = 1 << (shift - 1);
+    let up = match mode {
+        Rounding::Floor => false,
+        Rounding::Ceil => rem != 0,
+        Rounding::Trunc => rem != 0 && v < 0,
+        Rounding::HalfUp => rem >= half,
+        Rounding::HalfEven => rem > half || (rem == half && floor & 1 == 1),
+    };
+    floor + up as i64
+}
+
+/// num / den rounded as `mode` says (den must be positive)
+pub(crate) fn div_round(num: i128, den: i128, mode: Rounding) -> i128 {
+    let floor = num.div_euclid(den);
+    let rem = num.rem_euclid(den);
+    let up = match mode {
+        Rounding::Floor => false,
+        Rounding::Ceil => rem != 0,
+        Rounding::Trunc => rem != 0 && num < 0,
+        Rounding::HalfUp => 2 * rem >= den,
+        Rounding::HalfEven => 2 * rem > den || (2 * rem == den && floor & 1 == 1),
+    };
+    floor + up as i128
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ONE: i32 = 1 << 16;
+
+    /// x.25, x.5 and x.75 in Q16.16
+    fn q(whole: i32, quarters: i32) -> i32 {
+        whole * ONE + quarters * (ONE / 4)
+    }
+
+    #[test]
+    fn whole_number_rounding() {
+        assert_eq!(floor_fp(q(-2, 1)), -2 * ONE);
+        assert_eq!(floor_fp(q(-1, -1)), -2 * ONE);
+        assert_eq!(ceil_fp(q(1, 1)), 2 * ONE);
+        assert_eq!(ceil_fp(q(-1, -1)), -ONE);
+        assert_eq!(round_fp(q(2, 2)), 3 * ONE);
+        assert_eq!(round_fp(q(-2, -2)), -2 * ONE);
+        assert_eq!(trunc_fp(q(-1, -3)), -ONE);
+        assert_eq!(trunc_fp(q(1, 3)), ONE);
+    }
+
+    #[test]
+    fn ceil_wraps_like_typescript() {
+        assert_eq!(ceil_fp(i32::MAX), i32::MIN);
+        assert_eq!(round_fp(i32::MAX), i32::MIN);
+    }
+
+    #[test]
+    fn frac_and_int_conversion() {
+        assert_eq!(frac(q(-1, -1)), q(0, 3));
+        assert_eq!(frac(q(3, 1)), q(0, 1));
+        assert_eq!(to_int(q(-1, -1)), -2);
+        assert_eq!(from_int(-3), -3 * ONE);
+        assert_eq!(from_int(32768), i32::MIN);
+        for fp in (i32::MIN..i32::MAX).step_by(65_537) {
+            assert_eq!(floor_fp(fp) + frac(fp), fp);
+        }
+    }
+
+    #[test]
+    fn shift_round_modes() {
+        let cases = [
+            // v, floor, ceil, trunc, half-up, half-even
+            (10, 2, 3, 2, 3, 2),
+            (14, 3, 4, 3, 4, 4),
+            (-10, -3, -2, -2, -2, -2),
+            (-14, -4, -3, -3, -3, -4),
+            (13, 3, 4, 3, 3, 3),
+        ];
+        let modes = [Rounding::Floor, Rounding::Ceil, Rounding::Trunc, Rounding::HalfUp, Rounding::HalfEven];
+        for (v, a, b, c, d, e) in cases {
+            for (mode, want) in modes.into_iter().zip([a, b, c, d, e]) {
+                // v / 4
+                assert_eq!(shift_round(v, 2, mode), want, "{v} {mode:?}");
+                assert_eq!(div_round(v as i128, 4, mode), want as i128, "{v} {mode:?}");
+            }
+        }
+    }
+}
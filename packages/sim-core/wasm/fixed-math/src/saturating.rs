@@ -0,0 +1,77 @@
+//! Saturating arithmetic for Q16.16
+//!
+//! Same formulas as the plain ops, with the 64-bit intermediate clamped
+//! to [i32::MIN, i32::MAX] instead of wrapping, for stats that should pin
+//! at a cap rather than flip sign under extreme buffs. Clamping raises
+//! `StatusFlag::Overflow`.
+
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+use crate::{div_by_zero, saturate_i64};
+
+/// Saturating addition of two fixed-point numbers
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn saturating_add(a: i32, b: i32) -> i32 {
+    saturate_i64(a as i64 + b as i64)
+}
+
+/// Saturating subtraction of two fixed-point numbers (a - b)
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn saturating_sub(a: i32, b: i32) -> i32 {
+    saturate_i64(a as i64 - b as i64)
+}
+
+/// Saturating multiply of two fixed-point numbers (Q16.16)
+///
+/// Formula: clamp((a * b) >> 16), same rounding as `mul`
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn saturating_mul(a: i32, b: i32) -> i32 {
+    saturate_i64((a as i64 * b as i64) >> 16)
+}
+
+/// Saturating divide of two fixed-point numbers (Q16.16)
+///
+/// Formula: clamp((a << 16) / b), same rounding as `div`
+/// Division by zero follows the same policy as `div`.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn saturating_div(a: i32, b: i32) -> i32 {
+    if b == 0 {
+        return div_by_zero(a < 0);
+    }
+    saturate_i64(((a as i64) << 16) / b as i64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::flags::{clear_flags, get_flags, StatusFlag};
+
+    const ONE: i32 = 1 << 16;
+
+    #[test]
+    fn clamps_to_the_i32_range() {
+        clear_flags();
+        assert_eq!(saturating_add(i32::MAX, ONE), i32::MAX);
+        assert_eq!(saturating_sub(i32::MIN, ONE), i32::MIN);
+        assert_eq!(saturating_mul(i32::MAX, -2 * ONE), i32::MIN);
+        assert_eq!(saturating_div(i32::MAX, ONE / 2), i32::MAX);
+        assert_eq!(get_flags(), StatusFlag::Overflow as u32);
+    }
+
+    #[test]
+    fn in_range_results_match_plain_ops() {
+        clear_flags();
+        assert_eq!(saturating_add(ONE, ONE), 2 * ONE);
+        assert_eq!(saturating_mul(3 * ONE, ONE / 2), crate::mul(3 * ONE, ONE / 2));
+        assert_eq!(saturating_div(-ONE, 3 * ONE), crate::div(-ONE, 3 * ONE));
+        assert_eq!(get_flags(), 0);
+    }
+
+    #[test]
+    fn divide_by_zero_follows_the_policy() {
+        clear_flags();
+        assert_eq!(saturating_div(-ONE, 0), i32::MIN);
+        assert_eq!(get_flags(), StatusFlag::DivByZero as u32);
+    }
+}
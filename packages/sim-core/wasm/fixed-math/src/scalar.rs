@@ -0,0 +1,117 @@
+//! Sign and comparison helpers for Q16.16
+//!
+//! Trivial individually, but keeping them here means every comparison
+//! and clamp in the sim and the replay verifier goes through one
+//! implementation with defined edge cases.
+
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+/// 1.0 in Q16.16
+const ONE: i32 = 1 << 16;
+
+/// Absolute value of a fixed-point number
+///
+/// abs(i32::MIN) saturates to i32::MAX (the true value 32768.0 is not
+/// representable) instead of wrapping back to i32::MIN.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn abs(fp: i32) -> i32 {
+    fp.saturating_abs()
+}
+
+/// Sign of a fixed-point number as Q16.16
+///
+/// Returns -1.0, 0 or 1.0 (-65536, 0, 65536) so the result can be
+/// multiplied straight back into a formula with `mul`.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn signum(fp: i32) -> i32 {
+    fp.signum() * ONE
+}
+
+/// Magnitude of `magnitude` with the sign of `sign`
+///
+/// Zero counts as positive, so copysign(x, 0) = abs(x). Uses `abs`, so
+/// a magnitude of i32::MIN gives i32::MAX or -i32::MAX.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn copysign(magnitude: i32, sign: i32) -> i32 {
+    let m = abs(magnitude);
+    if sign < 0 {
+        -m
+    } else {
+        m
+    }
+}
+
+/// Smaller of two fixed-point numbers
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn min_fp(a: i32, b: i32) -> i32 {
+    if a < b {
+        a
+    } else {
+        b
+    }
+}
+
+/// Larger of two fixed-point numbers
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn max_fp(a: i32, b: i32) -> i32 {
+    if a > b {
+        a
+    } else {
+        b
+    }
+}
+
+/// Clamp a fixed-point number into [lo, hi]
+///
+/// Same comparison order as `FP.clamp`: the lower bound is checked
+/// first, so an inverted range (lo > hi) returns lo instead of panicking.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn clamp(x: i32, lo: i32, hi: i32) -> i32 {
+    if x < lo {
+        return lo;
+    }
+    if x > hi {
+        return hi;
+    }
+    x
+}
+
+/// Clamp a fixed-point number into [0, 1.0]
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn clamp01(x: i32) -> i32 {
+    clamp(x, 0, ONE)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn abs_saturates_at_min() {
+        assert_eq!(abs(-3 * ONE), 3 * ONE);
+        assert_eq!(abs(i32::MIN), i32::MAX);
+    }
+
+    #[test]
+    fn signum_and_copysign() {
+        assert_eq!(signum(-5), -ONE);
+        assert_eq!(signum(0), 0);
+        assert_eq!(signum(7 * ONE), ONE);
+        assert_eq!(copysign(2 * ONE, -1), -2 * ONE);
+        assert_eq!(copysign(-2 * ONE, 0), 2 * ONE);
+        assert_eq!(copysign(i32::MIN, -ONE), -i32::MAX);
+    }
+
+    #[test]
+    fn min_max_clamp() {
+        assert_eq!(min_fp(-ONE, ONE), -ONE);
+        assert_eq!(max_fp(-ONE, ONE), ONE);
+        assert_eq!(clamp(5 * ONE, 0, 2 * ONE), 2 * ONE);
+        assert_eq!(clamp(-ONE, 0, 2 * ONE), 0);
+        // Inverted range: the lower bound wins
+        assert_eq!(clamp(ONE, 3 * ONE, 0), 3 * ONE);
+        assert_eq!(clamp01(ONE / 2), ONE / 2);
+        assert_eq!(clamp01(2 * ONE), ONE);
+    }
+}
@@ -0,0 +1,281 @@
+//! WebAssembly SIMD128 paths for the batch kernels
+//!
+//! Compiled only with the `simd` feature on wasm32 built with
+//! `-C target-feature=+simd128`. Each kernel runs four lanes at a time
+//! over the longest prefix that is a multiple of four and returns how
+//! many elements it handled; the caller finishes the tail with its
+//! scalar loop. Lanes compute the same integer formulas as the scalar
+//! code, so the results are bit-identical:
+//!
+//! - i32 lane adds wrap exactly like `wrapping_add`
+//! - products are exact 64-bit lanes shifted arithmetically, like `mul`,
+//!   and `StatusFlag::Overflow` is raised when any lane does not fit
+//!   (the flag is sticky, so once per block is the same as once per
+//!   element)
+//! - radius tests reject lanes outside the r box first, after which the
+//!   squared distance fits in an i64 lane without wrapping
+
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+use core::arch::wasm32::*;
+
+use crate::flags::{self, StatusFlag};
+
+/// i32 lanes per v128
+const LANES: usize = 4;
+
+/// Four i32 values starting at i (bounds-checked)
+fn load(s: &[i32], i: usize) -> v128 {
+    let chunk = &s[i..i + LANES];
+    // SAFETY: chunk holds 16 readable bytes; v128_load has no alignment requirement
+    unsafe { v128_load(chunk.as_ptr() as *const v128) }
+}
+
+/// Store four i32 values starting at i (bounds-checked)
+fn store(s: &mut [i32], i: usize, v: v128) {
+    let chunk = &mut s[i..i + LANES];
+    // SAFETY: chunk holds 16 writable bytes; v128_store has no alignment requirement
+    unsafe { v128_store(chunk.as_mut_ptr() as *mut v128, v) }
+}
+
+/// Largest multiple of four not above n
+fn blocks(n: usize) -> usize {
+    n - n % LANES
+}
+
+/// Lane-wise `mul`: ((a * b) >> 16) truncated to i32
+fn mul4(a: v128, b: v128) -> v128 {
+    let lo = i64x2_shr(i64x2_extmul_low_i32x4(a, b), 16);
+    let hi = i64x2_shr(i64x2_extmul_high_i32x4(a, b), 16);
+    let r = i32x4_shuffle::<0, 2, 4, 6>(lo, hi);
+    let wrapped = v128_or(
+        i64x2_ne(i64x2_extend_low_i32x4(r), lo),
+        i64x2_ne(i64x2_extend_high_i32x4(r), hi),
+    );
+    if v128_any_true(wrapped) {
+        flags::raise(StatusFlag::Overflow);
+    }
+    r
+}
+
+pub(crate) fn add_arrays(dst: &mut [i32], src: &[i32]) -> usize {
+    let n = blocks(dst.len().min(src.len()));
+    for i in (0..n).step_by(LANES) {
+        store(dst, i, i32x4_add(load(dst, i), load(src, i)));
+    }
+    n
+}
+
+pub(crate) fn mul_arrays(dst: &mut [i32], src: &[i32]) -> usize {
+    let n = blocks(dst.len().min(src.len()));
+    for i in (0..n).step_by(LANES) {
+        store(dst, i, mul4(load(dst, i), load(src, i)));
+    }
+    n
+}
+
+pub(crate) fn scale_array(values: &mut [i32], factor: i32) -> usize {
+    let n = blocks(values.len());
+    let f = i32x4_splat(factor);
+    for i in (0..n).step_by(LANES) {
+        store(values, i, mul4(load(values, i), f));
+    }
+    n
+}
+
+pub(crate) fn min_array(dst: &mut [i32], src: &[i32]) -> usize {
+    let n = blocks(dst.len().min(src.len()));
+    for i in (0..n).step_by(LANES) {
+        store(dst, i, i32x4_min(load(dst, i), load(src, i)));
+    }
+    n
+}
+
+/// Lane-wise `clamp`, keeping its lower-bound-first order
+pub(crate) fn clamp_array(values: &mut [i32], lo: i32, hi: i32) -> usize {
+    let n = blocks(values.len());
+    let (lo, hi) = (i32x4_splat(lo), i32x4_splat(hi));
+    for i in (0..n).step_by(LANES) {
+        let x = load(values, i);
+        let upper = v128_bitselect(hi, x, i32x4_gt(x, hi));
+        store(values, i, v128_bitselect(lo, upper, i32x4_lt(x, lo)));
+    }
+    n
+}
+
+/// Four lanes of `physics::integrate` for the first `n` entities
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn integrate(
+    xs: &mut [i32],
+    ys: &mut [i32],
+    vxs: &mut [i32],
+    vys: &mut [i32],
+    axs: &[i32],
+    ays: &[i32],
+    dt: i32,
+    n: usize,
+) -> usize {
+    let n = blocks(n);
+    let dt = i32x4_splat(dt);
+    for i in (0..n).step_by(LANES) {
+        let vx = i32x4_add(load(vxs, i), mul4(load(axs, i), dt));
+        let vy = i32x4_add(load(vys, i), mul4(load(ays, i), dt));
+        store(vxs, i, vx);
+        store(vys, i, vy);
+        store(xs, i, i32x4_add(load(xs, i), mul4(vx, dt)));
+        store(ys, i, i32x4_add(load(ys, i), mul4(vy, dt)));
+    }
+    n
+}
+
+/// Bits 0..2 of the result: which of two i64 lanes lie within r
+#[cfg(feature = "alloc")]
+fn within2(x: v128, y: v128, cx: v128, cy: v128, r: v128, r2: v128) -> u8 {
+    let dx = i64x2_sub(x, cx);
+    let dy = i64x2_sub(y, cy);
+    let in_box = v128_and(i64x2_le(i64x2_abs(dx), r), i64x2_le(i64x2_abs(dy), r));
+    let d2 = i64x2_add(i64x2_mul(dx, dx), i64x2_mul(dy, dy));
+    i64x2_bitmask(v128_and(in_box, i64x2_le(d2, r2)))
+}
+
+/// Push the indices within r of (cx, cy) for the leading blocks of four
+///
+/// r must be non-negative (the caller handles r < 0).
+#[cfg(feature = "alloc")]
+pub(crate) fn indices_within_radius(xs: &[i32], ys: &[i32], cx: i32, cy: i32, r: i32, out: &mut Vec<u32>) -> usize {
+    let n = blocks(xs.len().min(ys.len()));
+    let (cx, cy) = (i64x2_splat(cx as i64), i64x2_splat(cy as i64));
+    let r2 = i64x2_splat(r as i64 * r as i64);
+    let r = i64x2_splat(r as i64);
+    for i in (0..n).step_by(LANES) {
+        let (x, y) = (load(xs, i), load(ys, i));
+        let low = within2(i64x2_extend_low_i32x4(x), i64x2_extend_low_i32x4(y), cx, cy, r, r2);
+        let high = within2(i64x2_extend_high_i32x4(x), i64x2_extend_high_i32x4(y), cx, cy, r, r2);
+        let mask = low | high << 2;
+        for lane in 0..LANES {
+            if mask & (1 << lane) != 0 {
+                out.push((i + lane) as u32);
+            }
+        }
+    }
+    n
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::flags::{clear_flags, get_flags};
+    use crate::mul;
+    use crate::scalar::clamp;
+
+    /// Pseudo-random values covering the whole i32 range, ends included
+    fn values(n: usize, seed: u32) -> Vec<i32> {
+        let mut seed = seed;
+        let mut out = vec![i32::MIN, i32::MAX, 0, -1];
+        while out.len() < n {
+            seed = seed.wrapping_mul(1664525).wrapping_add(1013904223);
+            out.push(seed as i32 >> (seed % 17));
+        }
+        out.truncate(n);
+        out
+    }
+
+    /// Flags raised by f alone
+    fn flags_after(f: impl FnOnce()) -> u32 {
+        clear_flags();
+        f();
+        get_flags()
+    }
+
+    /// Run a lane kernel over n values and check that it handled the whole
+    /// blocks, matched the scalar op (and its flags) on them and left the
+    /// tail alone
+    fn check(n: usize, lanes: impl Fn(&mut [i32]) -> usize, scalar: impl Fn(usize, i32) -> i32) {
+        let input = values(n, n as u32);
+        let mut got = input.clone();
+        let mut done = 0;
+        let lane_flags = flags_after(|| done = lanes(&mut got));
+        assert_eq!(done, n - n % LANES);
+
+        let mut want = input.clone();
+        let scalar_flags = flags_after(|| {
+            for (i, w) in want.iter_mut().enumerate().take(done) {
+                *w = scalar(i, *w);
+            }
+        });
+        assert_eq!(got, want, "n = {n}");
+        assert_eq!(lane_flags, scalar_flags, "n = {n}");
+    }
+
+    #[test]
+    fn elementwise_lanes_match_scalar() {
+        for n in 0..=13 {
+            let other = values(n, 900 + n as u32);
+            check(n, |d| add_arrays(d, &other), |i, x| x.wrapping_add(other[i]));
+            check(n, |d| mul_arrays(d, &other), |i, x| mul(x, other[i]));
+            check(n, |d| scale_array(d, -3 << 15), |_, x| mul(x, -3 << 15));
+            check(n, |d| min_array(d, &other), |i, x| x.min(other[i]));
+            check(n, |d| clamp_array(d, -1 << 20, 1 << 24), |_, x| clamp(x, -1 << 20, 1 << 24));
+            // Inverted bounds keep the lower-bound-first order
+            check(n, |d| clamp_array(d, 3, 1), |_, x| clamp(x, 3, 1));
+        }
+    }
+
+    #[test]
+    fn mul_overflow_in_any_lane_raises() {
+        for lane in 0..LANES {
+            let mut d = [0; 4];
+            let mut s = [0; 4];
+            d[lane] = i32::MAX;
+            s[lane] = 4 << 16;
+            assert_eq!(flags_after(|| {
+                mul_arrays(&mut d, &s);
+            }), StatusFlag::Overflow as u32);
+            assert_eq!(d[lane], mul(i32::MAX, 4 << 16));
+        }
+    }
+
+    #[test]
+    fn integrate_lanes_match_scalar() {
+        let n = 11;
+        let (xs, ys, vxs, vys) = (values(n, 1), values(n, 2), values(n, 3), values(n, 4));
+        let (axs, ays) = (values(n, 5), values(n, 6));
+        let dt = (1 << 16) / 60;
+
+        let (mut x, mut y, mut vx, mut vy) = (xs.clone(), ys.clone(), vxs.clone(), vys.clone());
+        let done = integrate(&mut x, &mut y, &mut vx, &mut vy, &axs, &ays, dt, n);
+        assert_eq!(done, 8);
+        for i in 0..n {
+            let (mut ex, mut ey, mut evx, mut evy) = (xs[i], ys[i], vxs[i], vys[i]);
+            if i < done {
+                evx = evx.wrapping_add(mul(axs[i], dt));
+                evy = evy.wrapping_add(mul(ays[i], dt));
+                ex = ex.wrapping_add(mul(evx, dt));
+                ey = ey.wrapping_add(mul(evy, dt));
+            }
+            assert_eq!((x[i], y[i], vx[i], vy[i]), (ex, ey, evx, evy), "entity {i}");
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn radius_lanes_match_brute_force() {
+        let n = 14;
+        let (xs, ys) = (values(n, 7), values(n, 8));
+        for (cx, cy, r) in [(0, 0, 1 << 20), (i32::MIN, i32::MAX, i32::MAX), (5, -5, 0), (0, 0, 1 << 30)] {
+            let mut out = Vec::new();
+            let done = indices_within_radius(&xs, &ys, cx, cy, r, &mut out);
+            assert_eq!(done, 12);
+            let want: Vec<u32> = (0..done)
+                .filter(|&i| {
+                    let dx = xs[i] as i128 - cx as i128;
+                    let dy = ys[i] as i128 - cy as i128;
+                    dx * dx + dy * dy <= r as i128 * r as i128
+                })
+                .map(|i| i as u32)
+                .collect();
+            assert_eq!(out, want, "({cx}, {cy}) r {r}");
+        }
+    }
+}
@@ -0,0 +1,155 @@
+//! Sorting and searching over i32 arrays
+//!
+//! Engine `Array.prototype.sort` implementations have differed in
+//! stability and comparator handling; these run in wasm with fully
+//! specified tie behaviour, so target priority and render order agree
+//! on every client. Prefix sums live here too, as the partner of the
+//! bound searches for cumulative tables.
+
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+use crate::saturate_i64;
+
+/// Indices that order `keys` ascending, stable
+///
+/// Equal keys keep their original relative order (lower index first).
+/// The keys themselves are not modified.
+#[cfg(feature = "alloc")]
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn sort_indices(keys: &[i32]) -> Vec<u32> {
+    let mut order: Vec<u32> = (0..keys.len() as u32).collect();
+    order.sort_by_key(|&i| keys[i as usize]);
+    order
+}
+
+/// Indices that order `keys` descending, stable
+///
+/// Equal keys still keep the lower index first, so this is not the
+/// reverse of `sort_indices`.
+#[cfg(feature = "alloc")]
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn sort_indices_desc(keys: &[i32]) -> Vec<u32> {
+    let mut order: Vec<u32> = (0..keys.len() as u32).collect();
+    order.sort_by_key(|&i| core::cmp::Reverse(keys[i as usize]));
+    order
+}
+
+/// First index whose value is >= target in an ascending array
+///
+/// Returns the length when every value is smaller. The array must be
+/// sorted ascending; otherwise the result is some valid partition point
+/// but not meaningful.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn lower_bound(sorted: &[i32], target: i32) -> u32 {
+    sorted.partition_point(|&v| v < target) as u32
+}
+
+/// First index whose value is > target in an ascending array
+///
+/// upper_bound - lower_bound is the number of elements equal to target.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn upper_bound(sorted: &[i32], target: i32) -> u32 {
+    sorted.partition_point(|&v| v <= target) as u32
+}
+
+/// Replace each element with the sum of itself and everything before it
+///
+/// The running total is kept in 64-bit, so a large early element does
+/// not corrupt later sums; each stored value saturates to i32 (raising
+/// `StatusFlag::Overflow`) independently.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn prefix_sum(values: &mut [i32]) {
+    let mut total = 0i64;
+    for v in values.iter_mut() {
+        total += *v as i64;
+        *v = saturate_i64(total);
+    }
+}
+
+/// Inclusive prefix sums of an i32 array as exact 64-bit totals
+#[cfg(feature = "alloc")]
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn prefix_sum_i64(values: &[i32]) -> Vec<i64> {
+    values
+        .iter()
+        .scan(0i64, |total, &v| {
+            *total += v as i64;
+            Some(*total)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::flags::{self, StatusFlag};
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn sort_indices_is_stable() {
+        let keys = [3, 1, 3, -2, 1, 3];
+        assert_eq!(sort_indices(&keys), [3, 1, 4, 0, 2, 5]);
+        // Ties keep the lower index first in both directions
+        assert_eq!(sort_indices_desc(&keys), [0, 2, 5, 1, 4, 3]);
+        assert!(sort_indices(&[]).is_empty());
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn sort_indices_orders_keys() {
+        let mut seed = 7u32;
+        let keys: Vec<i32> = (0..200)
+            .map(|_| {
+                seed = seed.wrapping_mul(1664525).wrapping_add(1013904223);
+                (seed >> 24) as i32 - 128
+            })
+            .collect();
+        let asc = sort_indices(&keys);
+        for w in asc.windows(2) {
+            let (a, b) = (w[0] as usize, w[1] as usize);
+            assert!(keys[a] < keys[b] || (keys[a] == keys[b] && a < b));
+        }
+        let desc = sort_indices_desc(&keys);
+        for w in desc.windows(2) {
+            let (a, b) = (w[0] as usize, w[1] as usize);
+            assert!(keys[a] > keys[b] || (keys[a] == keys[b] && a < b));
+        }
+    }
+
+    #[test]
+    fn bounds_bracket_equal_runs() {
+        let sorted = [-5, 0, 0, 0, 3, 9];
+        assert_eq!(lower_bound(&sorted, 0), 1);
+        assert_eq!(upper_bound(&sorted, 0), 4);
+        assert_eq!(lower_bound(&sorted, 1), 4);
+        assert_eq!(upper_bound(&sorted, 1), 4);
+        assert_eq!(lower_bound(&sorted, -9), 0);
+        assert_eq!(upper_bound(&sorted, 9), 6);
+        assert_eq!(lower_bound(&[], 3), 0);
+    }
+
+    #[test]
+    fn prefix_sum_saturates_each_element() {
+        let mut values = [1, 2, 3, -4];
+        prefix_sum(&mut values);
+        assert_eq!(values, [1, 3, 6, 2]);
+
+        // The 64-bit total recovers after an element saturates
+        let mut values = [i32::MAX, 10, i32::MIN];
+        flags::clear_flags();
+        prefix_sum(&mut values);
+        assert_eq!(values, [i32::MAX, i32::MAX, 9]);
+        assert_eq!(flags::get_flags(), StatusFlag::Overflow as u32);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn prefix_sum_i64_is_exact() {
+        assert_eq!(prefix_sum_i64(&[i32::MAX, 10, i32::MIN]), [i32::MAX as i64, i32::MAX as i64 + 10, 9]);
+        assert!(prefix_sum_i64(&[]).is_empty());
+    }
+}
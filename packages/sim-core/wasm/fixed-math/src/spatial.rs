@@ -0,0 +1,283 @@
+//! Spatial hash broadphase
+//!
+//! `SpatialHash` buckets point entities into square cells so range
+//! queries only touch nearby entities. It lives in wasm memory and its
+//! query results are always sorted by id, so iteration order is part of
+//! the deterministic sim contract rather than an accident of JS `Map`
+//! insertion order.
+
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+use crate::simd_prefix;
+
+/// A stored entity: position and the cell it is bucketed in
+#[derive(Clone, Copy, Debug)]
+struct Entry {
+    x: i32,
+    y: i32,
+    cell: (i32, i32),
+}
+
+/// Uniform-grid spatial hash over Q16.16 points
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone, Debug)]
+pub struct SpatialHash {
+    /// Cell edge length (Q16.16, at least 1 LSB)
+    cell_size: i32,
+    cells: BTreeMap<(i32, i32), Vec<u32>>,
+    entries: BTreeMap<u32, Entry>,
+}
+
+impl SpatialHash {
+    fn cell_of(&self, x: i32, y: i32) -> (i32, i32) {
+        (x.div_euclid(self.cell_size), y.div_euclid(self.cell_size))
+    }
+
+    fn unlink(&mut self, id: u32, cell: (i32, i32)) {
+        if let Some(bucket) = self.cells.get_mut(&cell) {
+            if let Some(i) = bucket.iter().position(|&e| e == id) {
+                bucket.swap_remove(i);
+            }
+            if bucket.is_empty() {
+                self.cells.remove(&cell);
+            }
+        }
+    }
+
+    /// Ids whose position passes `keep`, from every cell in the range
+    ///
+    /// Falls back to a scan of all entries when the range covers more
+    /// cells than there are entities. Sorted by id either way.
+    fn collect(&self, lo: (i32, i32), hi: (i32, i32), keep: impl Fn(&Entry) -> bool) -> Vec<u32> {
+        let span = (hi.0 as i128 - lo.0 as i128 + 1) * (hi.1 as i128 - lo.1 as i128 + 1);
+        let mut out: Vec<u32> = if span > self.entries.len() as i128 {
+            self.entries.iter().filter(|(_, e)| keep(e)).map(|(&id, _)| id).collect()
+        } else {
+            let mut ids = Vec::new();
+            for cx in lo.0..=hi.0 {
+                for cy in lo.1..=hi.1 {
+                    if let Some(bucket) = self.cells.get(&(cx, cy)) {
+                        ids.extend(bucket.iter().copied().filter(|id| keep(&self.entries[id])));
+                    }
+                }
+            }
+            ids
+        };
+        out.sort_unstable();
+        out
+    }
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl SpatialHash {
+    /// Create an empty hash with the given cell size (Q16.16)
+    ///
+    /// A good cell size is about the most common query radius.
+    /// Non-positive sizes are raised to 1 LSB.
+    #[cfg_attr(feature = "wasm", wasm_bindgen(constructor))]
+    pub fn new(cell_size: i32) -> SpatialHash {
+        SpatialHash {
+            cell_size: cell_size.max(1),
+            cells: BTreeMap::new(),
+            entries: BTreeMap::new(),
+        }
+    }
+
+    /// Add an entity at (x, y); an existing id is moved instead
+    pub fn insert(&mut self, id: u32, x: i32, y: i32) {
+        self.move_to(id, x, y);
+    }
+
+    /// Move an entity to (x, y), inserting it if it is not present
+    #[cfg_attr(feature = "wasm", wasm_bindgen(js_name = "move"))]
+    pub fn move_to(&mut self, id: u32, x: i32, y: i32) {
+        let cell = self.cell_of(x, y);
+        if let Some(old) = self.entries.get(&id).copied() {
+            if old.cell == cell {
+                self.entries.insert(id, Entry { x, y, cell });
+                return;
+            }
+            self.unlink(id, old.cell);
+        }
+        self.cells.entry(cell).or_default().push(id);
+        self.entries.insert(id, Entry { x, y, cell });
+    }
+
+    /// Remove an entity; returns whether it was present
+    pub fn remove(&mut self, id: u32) -> bool {
+        match self.entries.remove(&id) {
+            Some(e) => {
+                self.unlink(id, e.cell);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Remove every entity (the cell size is kept)
+    pub fn clear(&mut self) {
+        self.cells.clear();
+        self.entries.clear();
+    }
+
+    /// Number of stored entities
+    pub fn len(&self) -> u32 {
+        self.entries.len() as u32
+    }
+
+    /// Whether the hash holds no entities
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Ids of entities inside the closed box [min, max], ascending
+    pub fn query_aabb(&self, min_x: i32, min_y: i32, max_x: i32, max_y: i32) -> Vec<u32> {
+        if min_x > max_x || min_y > max_y {
+            return Vec::new();
+        }
+        let inside = |e: &Entry| min_x <= e.x && e.x <= max_x && min_y <= e.y && e.y <= max_y;
+        self.collect(self.cell_of(min_x, min_y), self.cell_of(max_x, max_y), inside)
+    }
+
+    /// Ids of entities within distance r of (x, y), ascending
+    ///
+    /// The distance test is an exact 128-bit comparison of squared
+    /// distances (boundary included), so it agrees with `distance`.
+    pub fn query_radius(&self, x: i32, y: i32, r: i32) -> Vec<u32> {
+        if r < 0 {
+            return Vec::new();
+        }
+        let r2 = r as i128 * r as i128;
+        let near = |e: &Entry| {
+            let dx = e.x as i128 - x as i128;
+            let dy = e.y as i128 - y as i128;
+            dx * dx + dy * dy <= r2
+        };
+        let lo = self.cell_of(x.saturating_sub(r), y.saturating_sub(r));
+        let hi = self.cell_of(x.saturating_add(r), y.saturating_add(r));
+        self.collect(lo, hi, near)
+    }
+}
+
+/// Indices i with (xs[i], ys[i]) within distance r of (cx, cy)
+///
+/// One call for a whole blast: scans the packed coordinate arrays with
+/// the same exact squared-distance test as `SpatialHash::query_radius`
+/// (boundary included) and returns ascending indices. Extra elements of
+/// the longer array are ignored; a negative r matches nothing.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn indices_within_radius(xs: &[i32], ys: &[i32], cx: i32, cy: i32, r: i32) -> Vec<u32> {
+    if r < 0 {
+        return Vec::new();
+    }
+    let mut out = Vec::new();
+    let start = simd_prefix!(crate::simd::indices_within_radius(xs, ys, cx, cy, r, &mut out));
+    let r2 = r as i128 * r as i128;
+    for (i, (&x, &y)) in xs.iter().zip(ys).enumerate().skip(start) {
+        let dx = x as i128 - cx as i128;
+        let dy = y as i128 - cy as i128;
+        if dx * dx + dy * dy <= r2 {
+            out.push(i as u32);
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ONE: i32 = 1 << 16;
+
+    /// Scattered points in [-50, 50) with a small LCG
+    fn points(n: u32) -> Vec<(u32, i32, i32)> {
+        let mut s = 12345u32;
+        let mut next = || {
+            s = s.wrapping_mul(1664525).wrapping_add(1013904223);
+            ((s >> 8) % (100 * ONE as u32)) as i32 - 50 * ONE
+        };
+        (0..n).map(|id| (id * 3, next(), next())).collect()
+    }
+
+    fn brute(pts: &[(u32, i32, i32)], x: i32, y: i32, r: i32) -> Vec<u32> {
+        let r2 = r as i128 * r as i128;
+        let mut ids: Vec<u32> = pts
+            .iter()
+            .filter(|&&(_, px, py)| {
+                let (dx, dy) = (px as i128 - x as i128, py as i128 - y as i128);
+                dx * dx + dy * dy <= r2
+            })
+            .map(|&(id, _, _)| id)
+            .collect();
+        ids.sort_unstable();
+        ids
+    }
+
+    #[test]
+    fn queries_match_brute_force() {
+        let pts = points(300);
+        let mut hash = SpatialHash::new(4 * ONE);
+        for &(id, x, y) in pts.iter().rev() {
+            hash.insert(id, x, y);
+        }
+        assert_eq!(hash.len(), 300);
+        for &(x, y, r) in &[(0, 0, 10 * ONE), (-30 * ONE, 20 * ONE, 3 * ONE), (5 * ONE, -5 * ONE, 0), (0, 0, 200 * ONE)] {
+            assert_eq!(hash.query_radius(x, y, r), brute(&pts, x, y, r));
+        }
+        let mut boxed: Vec<u32> = pts
+            .iter()
+            .filter(|&&(_, x, y)| (-10 * ONE..=ONE).contains(&x) && (0..=25 * ONE).contains(&y))
+            .map(|&(id, _, _)| id)
+            .collect();
+        boxed.sort_unstable();
+        assert_eq!(hash.query_aabb(-10 * ONE, 0, ONE, 25 * ONE), boxed);
+        assert!(hash.query_radius(0, 0, -1).is_empty());
+        assert!(hash.query_aabb(ONE, 0, 0, ONE).is_empty());
+    }
+
+    #[test]
+    fn radius_boundary_is_included() {
+        let mut hash = SpatialHash::new(ONE);
+        hash.insert(1, 3 * ONE, 4 * ONE);
+        assert_eq!(hash.query_radius(0, 0, 5 * ONE), [1]);
+        assert!(hash.query_radius(0, 0, 5 * ONE - 1).is_empty());
+    }
+
+    #[test]
+    fn move_and_remove_update_cells() {
+        let mut hash = SpatialHash::new(2 * ONE);
+        hash.insert(7, -ONE, -ONE);
+        hash.insert(2, ONE, ONE);
+        hash.move_to(7, 20 * ONE, 20 * ONE);
+        assert_eq!(hash.query_radius(0, 0, 3 * ONE), [2]);
+        assert_eq!(hash.query_radius(20 * ONE, 20 * ONE, ONE), [7]);
+        // Re-inserting an id moves it
+        hash.insert(2, 21 * ONE, 20 * ONE);
+        assert_eq!(hash.len(), 2);
+        assert_eq!(hash.query_radius(20 * ONE, 20 * ONE, 2 * ONE), [2, 7]);
+        assert!(hash.remove(7));
+        assert!(!hash.remove(7));
+        assert_eq!(hash.query_aabb(0, 0, 30 * ONE, 30 * ONE), [2]);
+        hash.clear();
+        assert!(hash.is_empty());
+    }
+
+    #[test]
+    fn batch_radius_matches_hash() {
+        let pts = points(100);
+        let xs: Vec<i32> = pts.iter().map(|p| p.1).collect();
+        let mut ys: Vec<i32> = pts.iter().map(|p| p.2).collect();
+        let mut hash = SpatialHash::new(ONE);
+        for (i, &(_, x, y)) in pts.iter().enumerate() {
+            hash.insert(i as u32, x, y);
+        }
+        assert_eq!(indices_within_radius(&xs, &ys, ONE, -ONE, 20 * ONE), hash.query_radius(ONE, -ONE, 20 * ONE));
+        ys.truncate(40);
+        assert_eq!(indices_within_radius(&xs, &ys, 0, 0, 200 * ONE).len(), 40);
+        assert!(indices_within_radius(&xs, &ys, 0, 0, -1).is_empty());
+    }
+}
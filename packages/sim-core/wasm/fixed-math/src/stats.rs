@@ -0,0 +1,250 @@
+//! Running statistics over Q16.16 samples
+//!
+//! `RunningStats` keeps the count, the exact sum and the exact sum of
+//! squares in 128-bit integers. With integers there is no cancellation
+//! to guard against, so this replaces Welford's floating-point update:
+//! every statistic is computed from exact totals when asked for, and the
+//! result is the same whatever order the samples arrived in.
+
+#[cfg(feature = "alloc")]
+use alloc::{vec, vec::Vec};
+
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+use crate::saturate_i128;
+
+/// Accumulator for count, mean, variance, min and max
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RunningStats {
+    count: u64,
+    /// Sum of samples (Q16.16)
+    sum: i128,
+    /// Sum of squared samples (Q32.32)
+    sum_sq: i128,
+    min: i32,
+    max: i32,
+}
+
+impl RunningStats {
+    /// Sum of squared deviations from the mean, floored, in Q32.32
+    ///
+    /// floor((n Σx² - (Σx)²) / n), formed without the n Σx² product
+    /// (which leaves i128 past ~6e9 samples). With Σx = q n + r,
+    /// 0 <= r < n, it equals Σ(x - q)² - ceil(r² / n), where Σ(x - q)² =
+    /// Σx² - q (Σx + r) stays below n * 2^64.
+    fn m2(&self) -> i128 {
+        let n = self.count as i128;
+        let (q, r) = (self.sum.div_euclid(n), self.sum.rem_euclid(n));
+        let r_sq = r as u128 * r as u128;
+        self.sum_sq - q * (self.sum + r) - r_sq.div_ceil(n as u128) as i128
+    }
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl RunningStats {
+    #[cfg_attr(feature = "wasm", wasm_bindgen(constructor))]
+    pub fn new() -> RunningStats {
+        RunningStats::default()
+    }
+
+    /// Add one sample
+    pub fn push(&mut self, x: i32) {
+        if self.count == 0 {
+            self.min = x;
+            self.max = x;
+        } else {
+            self.min = self.min.min(x);
+            self.max = self.max.max(x);
+        }
+        self.count += 1;
+        self.sum += x as i128;
+        self.sum_sq += x as i128 * x as i128;
+    }
+
+    /// Add every sample of an array
+    pub fn push_all(&mut self, xs: &[i32]) {
+        for &x in xs {
+            self.push(x);
+        }
+    }
+
+    /// Samples seen so far
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    /// Mean, rounded toward -∞ (0 when empty)
+    pub fn mean(&self) -> i32 {
+        if self.count == 0 {
+            return 0;
+        }
+        self.sum.div_euclid(self.count as i128) as i32
+    }
+
+    /// Population variance (divide by n), Q16.16, saturated
+    ///
+    /// (n Σx² - (Σx)²) / n², exact until the final division, which
+    /// rounds down, for any sample count. 0 when empty.
+    pub fn variance(&self) -> i32 {
+        if self.count == 0 {
+            return 0;
+        }
+        saturate_i128((self.m2() / self.count as i128) >> 16)
+    }
+
+    /// Sample variance (divide by n - 1), Q16.16, saturated
+    ///
+    /// 0 with fewer than two samples.
+    pub fn sample_variance(&self) -> i32 {
+        if self.count < 2 {
+            return 0;
+        }
+        saturate_i128((self.m2() / (self.count as i128 - 1)) >> 16)
+    }
+
+    /// Population standard deviation, Q16.16
+    ///
+    /// Integer square root of the exact Q32.32 variance, so it is
+    /// accurate to the last bit rather than sqrt of a rounded variance.
+    pub fn stddev(&self) -> i32 {
+        if self.count == 0 {
+            return 0;
+        }
+        saturate_i128(((self.m2() / self.count as i128) as u128).isqrt() as i128)
+    }
+
+    /// Smallest sample (0 when empty)
+    pub fn min(&self) -> i32 {
+        self.min
+    }
+
+    /// Largest sample (0 when empty)
+    pub fn max(&self) -> i32 {
+        self.max
+    }
+
+    pub fn reset(&mut self) {
+        *self = RunningStats::default();
+    }
+}
+
+/// Count values into `buckets` equal bins covering [min, max]
+///
+/// Both ends are inclusive: value v lands in bin
+/// (v - min) * buckets / (max - min + 1), computed exactly in 64-bit, so
+/// bins split the range as evenly as integers allow. Values outside the
+/// range are not counted. An empty range (max < min) gives all-zero
+/// bins.
+#[cfg(feature = "alloc")]
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn histogram(values: &[i32], min: i32, max: i32, buckets: u32) -> Vec<u32> {
+    let mut bins = vec![0u32; buckets as usize];
+    if buckets == 0 || max < min {
+        return bins;
+    }
+    let span = max as i64 - min as i64 + 1;
+    for &v in values {
+        if v < min || v > max {
+            continue;
+        }
+        let bin = ((v as i64 - min as i64) as i128 * buckets as i128 / span as i128) as usize;
+        bins[bin] = bins[bin].saturating_add(1);
+    }
+    bins
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ONE: i32 = 1 << 16;
+
+    #[test]
+    fn stats_of_a_small_sample() {
+        let mut s = RunningStats::new();
+        assert_eq!((s.count(), s.mean(), s.variance(), s.stddev()), (0, 0, 0, 0));
+        s.push_all(&[2 * ONE, 4 * ONE, 4 * ONE, 4 * ONE, 5 * ONE, 5 * ONE, 7 * ONE, 9 * ONE]);
+        assert_eq!(s.count(), 8);
+        assert_eq!(s.mean(), 5 * ONE);
+        assert_eq!(s.variance(), 4 * ONE);
+        assert_eq!(s.stddev(), 2 * ONE);
+        // 32 / 7
+        assert_eq!(s.sample_variance(), 299593);
+        assert_eq!((s.min(), s.max()), (2 * ONE, 9 * ONE));
+
+        s.reset();
+        assert_eq!(s, RunningStats::new());
+    }
+
+    #[test]
+    fn mean_and_variance_round_down() {
+        let mut s = RunningStats::new();
+        s.push_all(&[-1, 0, 0]);
+        assert_eq!(s.mean(), -1);
+        // One sample gives no sample variance
+        let mut one = RunningStats::new();
+        one.push(-3 * ONE);
+        assert_eq!((one.variance(), one.sample_variance()), (0, 0));
+        assert_eq!((one.min(), one.max()), (-3 * ONE, -3 * ONE));
+    }
+
+    #[test]
+    fn stats_match_a_direct_two_pass_reference() {
+        let mut seed = 99u32;
+        let xs: Vec<i32> = (0..500)
+            .map(|_| {
+                seed = seed.wrapping_mul(1664525).wrapping_add(1013904223);
+                seed as i32 >> 12
+            })
+            .collect();
+        let mut s = RunningStats::new();
+        s.push_all(&xs);
+
+        let n = xs.len() as i128;
+        let sum: i128 = xs.iter().map(|&x| x as i128).sum();
+        let sum_sq: i128 = xs.iter().map(|&x| x as i128 * x as i128).sum();
+        let m2 = (n * sum_sq - sum * sum).div_euclid(n);
+        assert_eq!(s.mean() as i128, sum.div_euclid(n));
+        assert_eq!(s.variance() as i128, (m2 / n) >> 16);
+        assert_eq!(s.sample_variance() as i128, (m2 / (n - 1)) >> 16);
+
+        // Order does not matter
+        let mut rev = RunningStats::new();
+        for &x in xs.iter().rev() {
+            rev.push(x);
+        }
+        assert_eq!(rev, s);
+    }
+
+    #[test]
+    fn variance_of_extreme_samples_saturates() {
+        let mut s = RunningStats::new();
+        s.push_all(&[i32::MIN, i32::MAX]);
+        assert_eq!(s.mean(), -1);
+        // A variance near 2^30 is far outside Q16.16; the stddev of just
+        // under 32768.0 still fits exactly
+        assert_eq!(s.variance(), i32::MAX);
+        assert_eq!(s.stddev(), i32::MAX);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn histogram_bins_are_inclusive_and_even() {
+        let values: Vec<i32> = (0..10).collect();
+        assert_eq!(histogram(&values, 0, 9, 5), [2, 2, 2, 2, 2]);
+        assert_eq!(histogram(&values, 0, 9, 3), [4, 3, 3]);
+        // Out-of-range values are dropped, the max lands in the last bin
+        assert_eq!(histogram(&[-1, 0, 9, 10], 0, 9, 2), [1, 1]);
+        assert_eq!(histogram(&values, 5, 4, 2), [0, 0]);
+        assert!(histogram(&values, 0, 9, 0).is_empty());
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn histogram_covers_the_full_i32_range() {
+        let bins = histogram(&[i32::MIN, -1, 0, i32::MAX], i32::MIN, i32::MAX, 2);
+        assert_eq!(bins, [2, 2]);
+    }
+}
@@ -0,0 +1,272 @@
+//! Steering for guided projectiles and crowds
+//!
+//! Velocity updates built on the crate's angle and vector math, so
+//! missile curvature and crowd movement replay bit-exactly.
+
+#[cfg(feature = "alloc")]
+use alloc::{collections::BTreeMap, vec::Vec};
+
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+use crate::angle::delta_angle;
+use crate::collide::unit_q32;
+use crate::geom::atan2_wide;
+use crate::trig::atan2;
+use crate::vec2::FixedVec2;
+use crate::{saturate_i64, truncate_i128};
+
+/// Velocity after turning toward a target at a limited rate
+///
+/// The velocity keeps its speed and turns by the shortest signed angle
+/// toward the target (`delta_angle` of the two `atan2` headings),
+/// clamped to ±max_turn_rate * dt (radians per time unit times the
+/// tick length, both Q16.16). If the target is within that step the
+/// new velocity points straight at it with the old speed (`hypot`);
+/// otherwise the velocity is rotated by the clamped step like
+/// `FixedVec2::rotate`. A zero velocity or a target at the current
+/// position leaves the velocity unchanged.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn steer_homing(pos: &FixedVec2, vel: &FixedVec2, target: &FixedVec2, max_turn_rate: i32, dt: i32) -> FixedVec2 {
+    let to_x = target.x as i128 - pos.x as i128;
+    let to_y = target.y as i128 - pos.y as i128;
+    if (vel.x == 0 && vel.y == 0) || (to_x == 0 && to_y == 0) {
+        return *vel;
+    }
+
+    let heading = atan2(vel.y, vel.x);
+    let turn = delta_angle(heading, atan2_wide(to_y, to_x));
+    let max_step = saturate_i64((max_turn_rate.max(0) as i64 * dt.max(0) as i64) >> 16);
+    if turn.unsigned_abs() <= max_step as u32 {
+        let speed = vel.length() as i128;
+        let (ux, uy) = unit_q32(to_x, to_y);
+        return FixedVec2 {
+            x: truncate_i128((ux as i128 * speed) >> 16),
+            y: truncate_i128((uy as i128 * speed) >> 16),
+        };
+    }
+    vel.rotate(if turn < 0 { -max_step } else { max_step })
+}
+
+/// Tuning for `steering_forces` (all Q16.16)
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SteeringParams {
+    /// Desired speed when seeking or fleeing
+    pub max_speed: i32,
+    /// Cap on the length of the combined force (0 = uncapped)
+    pub max_force: i32,
+    /// Inside this distance of the goal the desired speed ramps down
+    /// linearly to 0 (arrive); 0 disables arriving
+    pub arrive_radius: i32,
+    /// Neighbors closer than this push each other apart
+    pub separation_radius: i32,
+    /// Weight of the seek/arrive force; negative flees from the goal
+    pub seek_weight: i32,
+    /// Weight of the separation force
+    pub separation_weight: i32,
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl SteeringParams {
+    #[cfg_attr(feature = "wasm", wasm_bindgen(constructor))]
+    pub fn new(
+        max_speed: i32,
+        max_force: i32,
+        arrive_radius: i32,
+        separation_radius: i32,
+        seek_weight: i32,
+        separation_weight: i32,
+    ) -> SteeringParams {
+        SteeringParams { max_speed, max_force, arrive_radius, separation_radius, seek_weight, separation_weight }
+    }
+}
+
+/// Desired velocity toward (or away from) the goal, minus the velocity
+#[cfg(feature = "alloc")]
+fn seek_force(px: i32, py: i32, vx: i32, vy: i32, gx: i32, gy: i32, params: &SteeringParams) -> (i128, i128) {
+    let flee = params.seek_weight < 0;
+    let (mut dx, mut dy) = (gx as i128 - px as i128, gy as i128 - py as i128);
+    if flee {
+        (dx, dy) = (-dx, -dy);
+    }
+    let dist = ((dx * dx + dy * dy) as u128).isqrt() as i128;
+    let mut speed = params.max_speed as i128;
+    if !flee && dist < params.arrive_radius as i128 {
+        speed = speed * dist / params.arrive_radius as i128;
+    }
+    let (ux, uy) = if dist == 0 { (0, 0) } else { unit_q32(dx, dy) };
+    (((ux as i128 * speed) >> 16) - vx as i128, ((uy as i128 * speed) >> 16) - vy as i128)
+}
+
+/// Per-entity steering forces for a crowd, in one call
+///
+/// `positions`, `velocities` and `goals` are packed `[x0, y0, x1, y1,
+/// ...]` per entity (the shortest array sets the count); returns packed
+/// forces in the same layout.
+///
+/// Each force is seek_weight * seek + separation_weight * separation,
+/// then capped at max_force:
+/// - seek: desired velocity (max_speed toward the goal, ramped down
+///   inside arrive_radius; away from it at full speed when seek_weight
+///   is negative) minus the current velocity
+/// - separation: for every other entity within separation_radius, a
+///   push away from it scaled by (radius - distance) / radius; entities
+///   at the same position push apart along x, lower index toward -x
+///
+/// Neighbors come from a uniform grid with separation_radius cells and
+/// are visited in ascending index order; their contributions are summed
+/// exactly in 128-bit, so the result does not depend on input order
+/// beyond the indices themselves.
+#[cfg(feature = "alloc")]
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn steering_forces(positions: &[i32], velocities: &[i32], goals: &[i32], params: &SteeringParams) -> Vec<i32> {
+    let n = positions.len().min(velocities.len()).min(goals.len()) / 2;
+    let pos = |i: usize| (positions[2 * i], positions[2 * i + 1]);
+    let sep_r = params.separation_radius as i128;
+
+    let cell = params.separation_radius.max(1);
+    let mut grid: BTreeMap<(i32, i32), Vec<usize>> = BTreeMap::new();
+    if sep_r > 0 {
+        for i in 0..n {
+            let (x, y) = pos(i);
+            grid.entry((x.div_euclid(cell), y.div_euclid(cell))).or_default().push(i);
+        }
+    }
+
+    let mut out = Vec::with_capacity(2 * n);
+    let mut neighbors = Vec::new();
+    for i in 0..n {
+        let (px, py) = pos(i);
+        let (sx, sy) = seek_force(px, py, velocities[2 * i], velocities[2 * i + 1], goals[2 * i], goals[2 * i + 1], params);
+
+        let (mut ax, mut ay) = (0i128, 0i128);
+        if sep_r > 0 {
+            let (cx, cy) = (px.div_euclid(cell), py.div_euclid(cell));
+            neighbors.clear();
+            for gx in cx.saturating_sub(1)..=cx.saturating_add(1) {
+                for gy in cy.saturating_sub(1)..=cy.saturating_add(1) {
+                    if let Some(bucket) = grid.get(&(gx, gy)) {
+                        neighbors.extend(bucket.iter().copied().filter(|&j| j != i));
+                    }
+                }
+            }
+            neighbors.sort_unstable();
+            for &j in &neighbors {
+                let (qx, qy) = pos(j);
+                let (dx, dy) = (px as i128 - qx as i128, py as i128 - qy as i128);
+                let d2 = dx * dx + dy * dy;
+                if d2 >= sep_r * sep_r {
+                    continue;
+                }
+                let dist = (d2 as u128).isqrt() as i128;
+                let (ux, uy) = if dist == 0 {
+                    (if i < j { -(1 << 16) } else { 1 << 16 }, 0)
+                } else {
+                    unit_q32(dx, dy)
+                };
+                let strength = ((sep_r - dist) << 16) / sep_r;
+                ax += (ux as i128 * strength) >> 16;
+                ay += (uy as i128 * strength) >> 16;
+            }
+        }
+
+        let (seek_w, sep_w) = (params.seek_weight.unsigned_abs() as i128, params.separation_weight as i128);
+        let mut fx = (sx * seek_w + ax * sep_w) >> 16;
+        let mut fy = (sy * seek_w + ay * sep_w) >> 16;
+        let cap = params.max_force as i128;
+        if cap > 0 {
+            let len = ((fx * fx + fy * fy) as u128).isqrt() as i128;
+            if len > cap {
+                fx = fx * cap / len;
+                fy = fy * cap / len;
+            }
+        }
+        out.push(truncate_i128(fx));
+        out.push(truncate_i128(fy));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ONE: i32 = 1 << 16;
+
+    fn v(x: i32, y: i32) -> FixedVec2 {
+        FixedVec2::new(x * ONE, y * ONE)
+    }
+
+    fn close(a: FixedVec2, x: f64, y: f64, tol: i32) -> bool {
+        (a.x - (x * ONE as f64).round() as i32).abs() <= tol && (a.y - (y * ONE as f64).round() as i32).abs() <= tol
+    }
+
+    #[test]
+    fn homing_turn_is_rate_limited() {
+        let o = FixedVec2::zero();
+        let vel = v(2, 0);
+        let left = steer_homing(&o, &vel, &v(0, 10), ONE, ONE / 2);
+        assert!(close(left, 2.0 * 0.5f64.cos(), 2.0 * 0.5f64.sin(), 8));
+        let right = steer_homing(&o, &vel, &v(0, -10), ONE, ONE / 2);
+        assert_eq!(right, FixedVec2::new(left.x, -left.y));
+        // Behind across the seam: still turns the short way
+        let back = steer_homing(&o, &v(-2, 0), &v(-10, -1), ONE, ONE / 32);
+        assert!(back.y < 0 && back.x < 0);
+    }
+
+    #[test]
+    fn homing_snaps_within_one_step() {
+        let snapped = steer_homing(&v(1, 1), &v(2, 0), &v(11, 2), ONE, ONE / 2);
+        let (ux, uy) = (10.0 / 101f64.sqrt(), 1.0 / 101f64.sqrt());
+        assert!(close(snapped, 2.0 * ux, 2.0 * uy, 8));
+    }
+
+    #[test]
+    fn homing_degenerate_inputs_keep_velocity() {
+        let vel = v(1, 2);
+        assert_eq!(steer_homing(&v(3, 3), &FixedVec2::zero(), &v(0, 0), ONE, ONE), FixedVec2::zero());
+        assert_eq!(steer_homing(&v(3, 3), &vel, &v(3, 3), ONE, ONE), vel);
+        assert_eq!(steer_homing(&v(0, 0), &vel, &v(-5, 0), 0, ONE), vel);
+    }
+
+    #[cfg(feature = "alloc")]
+    fn params(max_force: i32, arrive: i32, sep: i32, seek_w: i32, sep_w: i32) -> SteeringParams {
+        SteeringParams::new(4 * ONE, max_force, arrive, sep, seek_w, sep_w)
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn seek_arrive_and_flee() {
+        let pos = [0, 0];
+        let vel = [0, ONE];
+        let goal = [10 * ONE, 0];
+        assert_eq!(steering_forces(&pos, &vel, &goal, &params(0, 0, 0, ONE, 0)), [4 * ONE, -ONE]);
+        // Inside arrive_radius the desired speed ramps with distance
+        assert_eq!(steering_forces(&pos, &vel, &goal, &params(0, 20 * ONE, 0, ONE, 0)), [2 * ONE, -ONE]);
+        assert_eq!(steering_forces(&pos, &vel, &goal, &params(0, 20 * ONE, 0, -ONE, 0)), [-4 * ONE, -ONE]);
+        assert_eq!(steering_forces(&pos, &vel, &goal, &params(0, 0, 0, ONE / 2, 0)), [2 * ONE, -ONE / 2]);
+        assert_eq!(steering_forces(&pos, &[0, 0], &pos, &params(0, 0, 0, ONE, 0)), [0, 0]);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn separation_pushes_neighbors_apart() {
+        let p = params(0, 0, 4 * ONE, 0, ONE);
+        let pos = [0, 0, ONE, 0, 20 * ONE, 0];
+        let f = steering_forces(&pos, &[0; 6], &pos, &p);
+        assert_eq!(f, [-3 * ONE / 4, 0, 3 * ONE / 4, 0, 0, 0]);
+        // Coincident entities split along x, lower index toward -x
+        let f = steering_forces(&[ONE, ONE, ONE, ONE], &[0; 4], &[0; 4], &p);
+        assert_eq!(f, [-ONE, 0, ONE, 0]);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn force_is_capped_and_count_follows_shortest_array() {
+        let f = steering_forces(&[0, 0], &[0, 0], &[30 * ONE, 40 * ONE], &params(ONE, 0, 0, ONE, 0));
+        assert_eq!(f, [39321, 52428]);
+        assert!(steering_forces(&[0, 0, ONE, ONE], &[0, 0], &[0], &params(0, 0, 0, ONE, 0)).is_empty());
+        assert_eq!(steering_forces(&[0, 0, ONE, ONE], &[0, 0], &[0, 0, 0], &params(0, 0, 0, ONE, 0)).len(), 2);
+    }
+}
@@ -0,0 +1,347 @@
+//! Weapon targeting queries
+//!
+//! Batch hit tests over packed coordinate arrays (one wasm call per
+//! attack), using the crate's angle conventions: Q16.16 radians,
+//! counter-clockwise from +x, differences wrapped by `delta_angle`.
+
+#[cfg(feature = "alloc")]
+use alloc::{vec, vec::Vec};
+
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+#[cfg(feature = "alloc")]
+use crate::angle::delta_angle;
+use crate::collide::unit_q32;
+#[cfg(feature = "alloc")]
+use crate::geom::atan2_wide;
+use crate::vec2::FixedVec2;
+
+/// Indices i with (xs[i], ys[i]) inside a cone (circular sector)
+///
+/// The cone starts at `origin`, faces the Q16.16 angle `dir` and spans
+/// `half_angle` to each side, out to distance `range`. A point is inside
+/// when its exact squared distance is at most range² and
+/// |delta_angle(dir, bearing)| <= half_angle, with the bearing from
+/// `atan2` on the 64-bit offset. Boundaries are included; a point at the
+/// origin itself is always inside; half_angle >= π is a full circle.
+/// Returns ascending indices; extra elements of the longer array are
+/// ignored.
+#[cfg(feature = "alloc")]
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn in_cone(xs: &[i32], ys: &[i32], origin: &FixedVec2, dir: i32, half_angle: i32, range: i32) -> Vec<u32> {
+    if range < 0 || half_angle < 0 {
+        return Vec::new();
+    }
+    let r2 = range as i128 * range as i128;
+    xs.iter()
+        .zip(ys)
+        .enumerate()
+        .filter(|&(_, (&x, &y))| {
+            let dx = x as i128 - origin.x as i128;
+            let dy = y as i128 - origin.y as i128;
+            let d2 = dx * dx + dy * dy;
+            if d2 > r2 {
+                return false;
+            }
+            d2 == 0 || delta_angle(dir, atan2_wide(dy, dx)).unsigned_abs() <= half_angle as u32
+        })
+        .map(|(i, _)| i as u32)
+        .collect()
+}
+
+/// Selection rule for `select_target`
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TargetMode {
+    /// Smallest distance to the origin
+    Nearest = 0,
+    /// Largest distance to the origin
+    Farthest = 1,
+    /// Smallest value (e.g. lowest HP)
+    LowestValue = 2,
+    /// Largest value (e.g. most path progress)
+    HighestValue = 3,
+}
+
+/// Index of the winning candidate, or -1 if there are none
+///
+/// Distances are compared as exact 128-bit squares; values as plain
+/// i32. Ties always go to the lowest index, independent of how the
+/// candidates came to be in that order, so the result never depends on
+/// sort stability. Distance modes read only `xs` / `ys` (`values` may be
+/// empty) and value modes only `values`; the candidate count is the
+/// length of the arrays the mode reads (the shorter of `xs` and `ys`).
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn select_target(xs: &[i32], ys: &[i32], values: &[i32], origin: &FixedVec2, mode: TargetMode) -> i32 {
+    let dist2 = |i: usize| {
+        let dx = xs[i] as i128 - origin.x as i128;
+        let dy = ys[i] as i128 - origin.y as i128;
+        dx * dx + dy * dy
+    };
+    // Keys are minimized; strict comparison keeps the first of equals
+    let key = |i: usize| -> i128 {
+        match mode {
+            TargetMode::Nearest => dist2(i),
+            TargetMode::Farthest => -dist2(i),
+            TargetMode::LowestValue => values[i] as i128,
+            TargetMode::HighestValue => -(values[i] as i128),
+        }
+    };
+    let n = match mode {
+        TargetMode::Nearest | TargetMode::Farthest => xs.len().min(ys.len()),
+        TargetMode::LowestValue | TargetMode::HighestValue => values.len(),
+    };
+
+    let mut best: Option<(usize, i128)> = None;
+    for i in 0..n {
+        let k = key(i);
+        if best.is_none_or(|(_, b)| k < b) {
+            best = Some((i, k));
+        }
+    }
+    best.map_or(-1, |(i, _)| i as i32)
+}
+
+/// Aim solution returned by `intercept`
+///
+/// When `hit` is false (the projectile can never catch the target) the
+/// other fields are 0.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Intercept {
+    pub hit: bool,
+    /// Unit aim direction (Q16.16)
+    pub dir_x: i32,
+    pub dir_y: i32,
+    /// Time until the projectile meets the target (Q16.16), in the time
+    /// unit of the velocities
+    pub time: i32,
+}
+
+/// Aim direction for a projectile to meet a moving target
+///
+/// Solves |d + v t| = s t for the earliest t >= 0, with d the offset
+/// from shooter to target, v the target velocity and s the projectile
+/// speed: (v·v - s²) t² + 2 (d·v) t + d·d = 0. The coefficients are
+/// exact in 128-bit and shifted down together only when they would not
+/// leave room for the discriminant, so every client takes the same
+/// branch. The aim direction points at the predicted position d + v t.
+/// A target already at the shooter gives t = 0 and the target's
+/// heading (or (1, 0) if it is standing still). Meeting times beyond the
+/// Q16.16 range count as no solution.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn intercept(shooter: &FixedVec2, target_pos: &FixedVec2, target_vel: &FixedVec2, projectile_speed: i32) -> Intercept {
+    let miss = Intercept { hit: false, dir_x: 0, dir_y: 0, time: 0 };
+    let (dx, dy) = (target_pos.x as i128 - shooter.x as i128, target_pos.y as i128 - shooter.y as i128);
+    let (vx, vy) = (target_vel.x as i128, target_vel.y as i128);
+    let s = projectile_speed as i128;
+
+    if dx == 0 && dy == 0 {
+        let (dir_x, dir_y) = unit_q32(vx, vy);
+        return Intercept { hit: true, dir_x, dir_y, time: 0 };
+    }
+
+    // Q32.32 coefficients, scaled down together until b² - 4ac fits
+    let mut a = vx * vx + vy * vy - s * s;
+    let mut b = 2 * (dx * vx + dy * vy);
+    let mut c = dx * dx + dy * dy;
+    while a.abs().max(b.abs()).max(c) >= 1 << 61 {
+        a >>= 1;
+        b >>= 1;
+        c >>= 1;
+    }
+
+    // Smallest non-negative root, Q16.16 in i128
+    let t = if a == 0 {
+        if b >= 0 {
+            return miss;
+        }
+        (c << 16) / -b
+    } else {
+        let disc = b * b - 4 * a * c;
+        if disc < 0 {
+            return miss;
+        }
+        let root = (disc as u128).isqrt() as i128;
+        // Cancellation-free pair: x1 = q / a, x2 = c / q
+        let q = if b < 0 { (root - b) / 2 } else { -(b + root) / 2 };
+        let mut roots = [(q << 16) / a, if q != 0 { (c << 16) / q } else { -1 }];
+        roots.sort_unstable();
+        match roots.iter().find(|&&t| t >= 0) {
+            Some(&t) => t,
+            None => return miss,
+        }
+    };
+    if t > i32::MAX as i128 {
+        return miss;
+    }
+
+    let (dir_x, dir_y) = unit_q32((dx << 16) + vx * t, (dy << 16) + vy * t);
+    Intercept { hit: true, dir_x, dir_y, time: t as i32 }
+}
+
+/// Hit order for a chaining attack that starts on `start_index`
+///
+/// Returns the indices hit, starting with start_index itself, followed by
+/// up to max_jumps jumps. Each jump goes from the last target hit to the
+/// nearest candidate not yet hit with hps[i] > 0 and an exact 128-bit
+/// squared distance of at most max_range²; ties go to the lowest index.
+/// The chain stops early when nothing is in range. The candidate count is
+/// the shortest of the three arrays; a start index past it gives an empty
+/// list and a negative max_range gives just the start.
+#[cfg(feature = "alloc")]
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn chain_targets(xs: &[i32], ys: &[i32], hps: &[i32], start_index: u32, max_jumps: u32, max_range: i32) -> Vec<u32> {
+    let n = xs.len().min(ys.len()).min(hps.len());
+    let start = start_index as usize;
+    if start >= n {
+        return Vec::new();
+    }
+    let mut chain = vec![start_index];
+    if max_range < 0 {
+        return chain;
+    }
+
+    let r2 = max_range as i128 * max_range as i128;
+    let mut hit = vec![false; n];
+    hit[start] = true;
+    let mut current = start;
+    for _ in 0..max_jumps {
+        let mut best: Option<(usize, i128)> = None;
+        for i in 0..n {
+            if hit[i] || hps[i] <= 0 {
+                continue;
+            }
+            let dx = xs[i] as i128 - xs[current] as i128;
+            let dy = ys[i] as i128 - ys[current] as i128;
+            let d2 = dx * dx + dy * dy;
+            // Strict comparison keeps the lowest index among equals
+            if d2 <= r2 && best.is_none_or(|(_, b)| d2 < b) {
+                best = Some((i, d2));
+            }
+        }
+        let Some((next, _)) = best else { break };
+        hit[next] = true;
+        chain.push(next as u32);
+        current = next;
+    }
+    chain
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "alloc")]
+    use crate::consts::PI;
+
+    const ONE: i32 = 1 << 16;
+
+    fn v(x: i32, y: i32) -> FixedVec2 {
+        FixedVec2::new(x * ONE, y * ONE)
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn in_cone_checks_range_and_angle() {
+        let xs = [5, 5, 5, 10, 11, 0, -5, 3].map(|c| c * ONE);
+        let ys = [0, 4, 6, 0, 0, 0, 0, -2].map(|c| c * ONE);
+        let quarter = PI / 4;
+        assert_eq!(in_cone(&xs, &ys, &FixedVec2::zero(), 0, quarter, 10 * ONE), [0, 1, 3, 5, 7]);
+        assert_eq!(in_cone(&xs, &ys, &FixedVec2::zero(), 0, PI, 10 * ONE), [0, 1, 2, 3, 5, 6, 7]);
+        // Facing -x across the angle seam
+        assert_eq!(in_cone(&xs, &ys, &v(10, 0), PI, quarter, 8 * ONE), [0, 1, 3, 7]);
+        assert!(in_cone(&xs, &ys, &FixedVec2::zero(), 0, quarter, -1).is_empty());
+        assert_eq!(in_cone(&xs[..2], &ys, &FixedVec2::zero(), 0, quarter, 10 * ONE), [0, 1]);
+    }
+
+    #[test]
+    fn select_target_ties_go_to_lowest_index() {
+        let xs = [3, -3, 0, 1].map(|c| c * ONE);
+        let ys = [0, 0, 5, 0].map(|c| c * ONE);
+        let hp = [40, 10, 90, 10];
+        let o = FixedVec2::zero();
+        assert_eq!(select_target(&xs, &ys, &[], &o, TargetMode::Nearest), 3);
+        assert_eq!(select_target(&xs, &ys, &[], &o, TargetMode::Farthest), 2);
+        assert_eq!(select_target(&xs[..2], &ys, &[], &o, TargetMode::Nearest), 0);
+        assert_eq!(select_target(&[], &[], &hp, &o, TargetMode::LowestValue), 1);
+        assert_eq!(select_target(&[], &[], &hp, &o, TargetMode::HighestValue), 2);
+        assert_eq!(select_target(&[], &[], &[], &o, TargetMode::Nearest), -1);
+        assert_eq!(select_target(&xs, &ys, &[], &o, TargetMode::LowestValue), -1);
+    }
+
+    #[test]
+    fn intercept_static_and_head_on() {
+        let r = intercept(&FixedVec2::zero(), &v(10, 0), &FixedVec2::zero(), 2 * ONE);
+        assert_eq!(r, Intercept { hit: true, dir_x: ONE, dir_y: 0, time: 5 * ONE });
+        // Equal speeds (a = 0): meet halfway
+        let r = intercept(&FixedVec2::zero(), &v(10, 0), &v(-1, 0), ONE);
+        assert_eq!(r, Intercept { hit: true, dir_x: ONE, dir_y: 0, time: 5 * ONE });
+    }
+
+    #[test]
+    fn intercept_crossing_target() {
+        // 4t² = 100 + t²: t = 10 / √3, aim at 30° above +x
+        let r = intercept(&FixedVec2::zero(), &v(10, 0), &v(0, 1), 2 * ONE);
+        assert!(r.hit);
+        assert!((r.time as f64 / ONE as f64 - 10.0 / 3f64.sqrt()).abs() < 2e-4);
+        assert!((r.dir_x - 56756).abs() <= 2 && (r.dir_y - 32768).abs() <= 2);
+        // The projectile lands where the target will be
+        let t = r.time as f64 / ONE as f64;
+        let hit = (r.dir_x as f64 * 2.0 * t / ONE as f64, r.dir_y as f64 * 2.0 * t / ONE as f64);
+        assert!((hit.0 - 10.0).abs() < 1e-3 && (hit.1 - t).abs() < 1e-3);
+    }
+
+    #[test]
+    fn intercept_misses_and_degenerate_cases() {
+        let miss = Intercept { hit: false, dir_x: 0, dir_y: 0, time: 0 };
+        assert_eq!(intercept(&FixedVec2::zero(), &v(10, 0), &v(3, 0), 2 * ONE), miss);
+        assert_eq!(intercept(&FixedVec2::zero(), &v(10, 0), &v(1, 0), ONE), miss);
+        assert_eq!(intercept(&FixedVec2::zero(), &v(10, 0), &FixedVec2::zero(), 0), miss);
+        let r = intercept(&v(2, 2), &v(2, 2), &v(0, -3), ONE);
+        assert_eq!(r, Intercept { hit: true, dir_x: 0, dir_y: -ONE, time: 0 });
+        let r = intercept(&v(2, 2), &v(2, 2), &FixedVec2::zero(), ONE);
+        assert_eq!((r.dir_x, r.dir_y), (ONE, 0));
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn chain_jumps_to_the_nearest_unhit_target() {
+        // A line of targets 0..5 at x = 0, 2, 3, 7, 8 with target 2 dead
+        let xs = [0, 2 * ONE, 3 * ONE, 7 * ONE, 8 * ONE];
+        let ys = [0; 5];
+        let hps = [ONE, ONE, 0, ONE, ONE];
+        assert_eq!(chain_targets(&xs, &ys, &hps, 0, 10, 5 * ONE), [0, 1, 3, 4]);
+        // Target 3 is out of range once the dead target is skipped
+        assert_eq!(chain_targets(&xs, &ys, &hps, 0, 10, 4 * ONE), [0, 1]);
+        assert_eq!(chain_targets(&xs, &ys, &hps, 0, 2, 5 * ONE), [0, 1, 3]);
+        // The start is hit even when dead, and never revisited
+        assert_eq!(chain_targets(&xs, &ys, &hps, 2, 10, 5 * ONE), [2, 1, 0]);
+        // The range bound is inclusive
+        assert_eq!(chain_targets(&xs, &ys, &hps, 4, 1, ONE), [4, 3]);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn chain_ties_go_to_the_lowest_index() {
+        let xs = [0, -ONE, ONE, 0, 0];
+        let ys = [0, 0, 0, ONE, -ONE];
+        assert_eq!(chain_targets(&xs, &ys, &[ONE; 5], 0, 1, ONE), [0, 1]);
+        assert_eq!(chain_targets(&xs, &ys, &[ONE, 0, ONE, ONE, ONE], 0, 1, ONE), [0, 2]);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn chain_edge_cases() {
+        let (xs, ys, hps) = ([0, ONE], [0, 0], [ONE, ONE]);
+        assert!(chain_targets(&xs, &ys, &hps, 2, 3, ONE).is_empty());
+        assert_eq!(chain_targets(&xs, &ys, &hps, 1, 3, -1), [1]);
+        assert_eq!(chain_targets(&xs, &ys, &hps, 1, 0, ONE), [1]);
+        // Candidates are limited to the shortest array
+        assert_eq!(chain_targets(&xs, &ys, &[ONE], 0, 3, ONE), [0]);
+        // Far corners of the i32 plane without overflow
+        let xs = [i32::MIN, i32::MAX];
+        let ys = [i32::MIN, i32::MAX];
+        assert_eq!(chain_targets(&xs, &ys, &hps, 0, 1, i32::MAX), [0]);
+    }
+}
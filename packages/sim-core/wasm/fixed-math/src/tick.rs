@@ -0,0 +1,345 @@
+//! Fixed-timestep bookkeeping
+//!
+//! `TickAccumulator` turns variable frame times into a whole number of
+//! sim ticks plus a render interpolation factor. Times are Q16.16
+//! milliseconds (quantize `performance.now()` deltas first), so how many
+//! catch-up steps a frame runs is decided identically everywhere.
+//!
+//! `FracAccumulator` does the same for per-tick amounts such as poison
+//! damage or regen: it emits whole units and carries the fraction, so the
+//! total after N ticks is the same however the ticks are batched.
+//!
+//! Cooldowns and attack speeds are converted to whole tick counts once,
+//! with an explicit `Rounding`, so attack cadence is a pure function of
+//! the tick number instead of each client's frame pacing.
+
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+use crate::flags::{self, StatusFlag};
+use crate::round::{div_round, Rounding};
+use crate::saturate_i64;
+
+/// Frame-time accumulator for a fixed tick length
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TickAccumulator {
+    /// Tick length in Q16.16 ms (at least 1 LSB)
+    tick: i64,
+    /// Most whole ticks kept pending; 0 for no limit
+    max_pending: u32,
+    /// Unconsumed time in Q16.16 ms
+    accumulated: i64,
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl TickAccumulator {
+    /// Accumulator for ticks of `tick_ms` (Q16.16 ms)
+    ///
+    /// `max_pending` caps the ticks a long stall can queue (dropping the
+    /// excess whole ticks, keeping the fractional remainder) so a slow
+    /// frame cannot snowball; 0 disables the cap.
+    #[cfg_attr(feature = "wasm", wasm_bindgen(constructor))]
+    pub fn new(tick_ms: i32, max_pending: u32) -> TickAccumulator {
+        TickAccumulator {
+            tick: tick_ms.max(1) as i64,
+            max_pending,
+            accumulated: 0,
+        }
+    }
+
+    /// Add elapsed frame time (Q16.16 ms); negative times are ignored
+    pub fn add(&mut self, elapsed_ms: i32) {
+        self.accumulated += elapsed_ms.max(0) as i64;
+        if self.max_pending > 0 {
+            let cap = self.max_pending as i64 * self.tick + self.accumulated % self.tick;
+            self.accumulated = self.accumulated.min(cap);
+        }
+    }
+
+    /// Whole ticks waiting to run
+    pub fn pending(&self) -> u32 {
+        (self.accumulated / self.tick).min(u32::MAX as i64) as u32
+    }
+
+    /// Consume one tick if a whole one is pending
+    pub fn pop_tick(&mut self) -> bool {
+        if self.accumulated < self.tick {
+            return false;
+        }
+        self.accumulated -= self.tick;
+        true
+    }
+
+    /// Consume every pending tick and return how many there were
+    pub fn pop_ticks(&mut self) -> u32 {
+        let n = self.pending();
+        self.accumulated -= n as i64 * self.tick;
+        n
+    }
+
+    /// Fraction of the next tick already elapsed, Q16.16 in [0, 1.0)
+    ///
+    /// Only meaningful once pending ticks are popped; rounds down.
+    pub fn alpha(&self) -> i32 {
+        ((((self.accumulated % self.tick) as i128) << 16) / self.tick as i128) as i32
+    }
+
+    /// Drop all accumulated time
+    pub fn reset(&mut self) {
+        self.accumulated = 0;
+    }
+}
+
+/// Whole-unit emitter for fractional per-tick amounts
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct FracAccumulator {
+    /// Carried fraction, Q16.16 in [0, 1.0)
+    remainder: i64,
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl FracAccumulator {
+    /// Empty accumulator
+    #[cfg_attr(feature = "wasm", wasm_bindgen(constructor))]
+    pub fn new() -> FracAccumulator {
+        FracAccumulator { remainder: 0 }
+    }
+
+    /// Add one tick's Q16.16 amount and return the whole units it completes
+    ///
+    /// Units are floored, so a negative amount emits negative units and
+    /// the carried fraction always stays in [0, 1.0).
+    pub fn add(&mut self, amount: i32) -> i32 {
+        self.add_ticks(amount, 1)
+    }
+
+    /// Add `ticks` ticks of the same amount at once
+    ///
+    /// Emits exactly the sum of `ticks` separate `add` calls and leaves the
+    /// same remainder. A unit count outside the i32 range saturates and
+    /// raises `StatusFlag::Overflow` (the remainder is still exact).
+    pub fn add_ticks(&mut self, amount: i32, ticks: u32) -> i32 {
+        let total = self.remainder + amount as i64 * ticks as i64;
+        self.remainder = total & 0xFFFF;
+        saturate_i64(total >> 16)
+    }
+
+    /// Carried fraction, Q16.16 in [0, 1.0)
+    pub fn remainder(&self) -> i32 {
+        self.remainder as i32
+    }
+
+    /// Drop the carried fraction
+    pub fn reset(&mut self) {
+        self.remainder = 0;
+    }
+}
+
+/// Clamp an exact tick count to u32, at least 1 tick
+fn tick_count(ticks: i128) -> u32 {
+    ticks.clamp(1, u32::MAX as i128) as u32
+}
+
+/// Ticks between uses of an ability with a hasted cooldown
+///
+/// Formula: round(cooldown_ms / (haste * tick_ms))
+/// cooldown_ms and tick_ms are Q16.16 ms; haste is a Q16.16 speed
+/// multiplier (1.0 = base speed, 1.25 = 25% faster). The ratio is exact
+/// in 128-bit and rounded once by `mode`, with a minimum of one tick. A
+/// non-positive cooldown gives 0 (always ready); non-positive haste or
+/// tick_ms returns 0 and raises `StatusFlag::Domain`.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn cooldown_ticks(cooldown_ms: i32, haste: i32, tick_ms: i32, mode: Rounding) -> u32 {
+    if haste <= 0 || tick_ms <= 0 {
+        flags::raise(StatusFlag::Domain);
+        return 0;
+    }
+    if cooldown_ms <= 0 {
+        return 0;
+    }
+    let num = (cooldown_ms as i128) << 16;
+    tick_count(div_round(num, haste as i128 * tick_ms as i128, mode))
+}
+
+/// Ticks between attacks at a hasted attack speed
+///
+/// Formula: round(1000 / (attacks_per_sec * haste * tick_ms))
+/// attacks_per_sec and haste are Q16.16, tick_ms is Q16.16 ms. Exact in
+/// 128-bit, rounded once by `mode`, with a minimum of one tick.
+/// Non-positive inputs return 0 and raise `StatusFlag::Domain`.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn attack_interval_ticks(attacks_per_sec: i32, haste: i32, tick_ms: i32, mode: Rounding) -> u32 {
+    if attacks_per_sec <= 0 || haste <= 0 || tick_ms <= 0 {
+        flags::raise(StatusFlag::Domain);
+        return 0;
+    }
+    let num = 1000i128 << 48;
+    let den = attacks_per_sec as i128 * haste as i128 * tick_ms as i128;
+    tick_count(div_round(num, den, mode))
+}
+
+/// Ticks left before an action used at `last_tick` is ready again
+///
+/// Ready (0) from tick last_tick + cooldown_ticks on; the sum is taken in
+/// 64-bit so it cannot wrap.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn ticks_until_ready(last_tick: u32, cooldown_ticks: u32, now_tick: u32) -> u32 {
+    let ready = last_tick as u64 + cooldown_ticks as u64;
+    ready.saturating_sub(now_tick as u64).min(u32::MAX as u64) as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ONE: i32 = 1 << 16;
+
+    #[test]
+    fn tick_accumulator_splits_frames_into_ticks() {
+        let mut acc = TickAccumulator::new(16 * ONE, 0);
+        acc.add(40 * ONE);
+        assert_eq!(acc.pending(), 2);
+        assert!(acc.pop_tick());
+        assert!(acc.pop_tick());
+        assert!(!acc.pop_tick());
+        // 8 of the 16 ms tick left over
+        assert_eq!(acc.alpha(), ONE / 2);
+
+        acc.add(-5 * ONE);
+        assert_eq!(acc.alpha(), ONE / 2);
+        acc.add(24 * ONE);
+        assert_eq!(acc.pop_ticks(), 2);
+        assert_eq!(acc.alpha(), 0);
+    }
+
+    #[test]
+    fn tick_accumulator_is_independent_of_frame_split() {
+        let tick = 16 * ONE + ONE * 2 / 3;
+        let mut whole = TickAccumulator::new(tick, 0);
+        whole.add(1000 * ONE);
+        let mut frames = TickAccumulator::new(tick, 0);
+        let mut ticks = 0;
+        for _ in 0..100 {
+            frames.add(10 * ONE);
+            ticks += frames.pop_ticks();
+        }
+        assert_eq!(ticks, whole.pop_ticks());
+        assert_eq!(frames.alpha(), whole.alpha());
+    }
+
+    #[test]
+    fn tick_accumulator_caps_pending_and_keeps_fraction() {
+        let mut acc = TickAccumulator::new(10 * ONE, 3);
+        acc.add(1005 * ONE);
+        assert_eq!(acc.pending(), 3);
+        assert_eq!(acc.pop_ticks(), 3);
+        assert_eq!(acc.alpha(), ONE / 2);
+
+        acc.reset();
+        assert_eq!(acc.pending(), 0);
+        assert_eq!(acc.alpha(), 0);
+
+        // A zero tick length is treated as 1 LSB
+        let mut tiny = TickAccumulator::new(0, 0);
+        tiny.add(5);
+        assert_eq!(tiny.pending(), 5);
+    }
+
+    #[test]
+    fn frac_accumulator_carries_the_fraction() {
+        let mut acc = FracAccumulator::new();
+        // 0.4 rounds down to 26214 LSB, so five ticks fall 2 LSB short
+        // of the second unit
+        let emitted: Vec<i32> = (0..5).map(|_| acc.add(ONE * 2 / 5)).collect();
+        assert_eq!(emitted, [0, 0, 1, 0, 0]);
+        assert_eq!(acc.remainder(), ONE - 2);
+        assert_eq!(acc.add(2), 1);
+        acc.reset();
+        assert_eq!(acc, FracAccumulator::default());
+
+        // Negative amounts floor, keeping the remainder in [0, 1.0)
+        assert_eq!(acc.add(-ONE / 4), -1);
+        assert_eq!(acc.remainder(), 3 * ONE / 4);
+    }
+
+    #[test]
+    fn frac_accumulator_is_independent_of_batching() {
+        for amount in [ONE / 3, 7 * ONE + 12345, -ONE / 7, 1, i32::MIN] {
+            let mut single = FracAccumulator::new();
+            let one_by_one: i64 = (0..1000).map(|_| single.add(amount) as i64).sum();
+            let mut batched = FracAccumulator::new();
+            let mut total = 0i64;
+            for ticks in [1, 10, 100, 389, 500] {
+                total += batched.add_ticks(amount, ticks) as i64;
+            }
+            assert_eq!(total, one_by_one, "{amount}");
+            assert_eq!(batched.remainder(), single.remainder(), "{amount}");
+        }
+    }
+
+    #[test]
+    fn frac_accumulator_saturates_with_exact_remainder() {
+        let mut acc = FracAccumulator::new();
+        flags::clear_flags();
+        assert_eq!(acc.add_ticks(i32::MAX, u32::MAX), i32::MAX);
+        assert_eq!(flags::get_flags(), StatusFlag::Overflow as u32);
+        assert_eq!(acc.remainder() as i64, (i32::MAX as i64 * u32::MAX as i64) & 0xFFFF);
+        assert_eq!(acc.add_ticks(ONE, 0), 0);
+    }
+
+    const MODES: [Rounding; 5] = [Rounding::Floor, Rounding::Ceil, Rounding::Trunc, Rounding::HalfUp, Rounding::HalfEven];
+
+    #[test]
+    fn cooldown_ticks_rounds_the_exact_ratio() {
+        let tick = 50 * ONE;
+        assert_eq!(cooldown_ticks(1000 * ONE, ONE, tick, Rounding::Floor), 20);
+        // 1000 / (1.25 * 50) = 16 exactly in every mode
+        for mode in MODES {
+            assert_eq!(cooldown_ticks(1000 * ONE, 5 * ONE / 4, tick, mode), 16);
+        }
+        // 1000 / (1.5 * 50) = 13.33
+        assert_eq!(cooldown_ticks(1000 * ONE, 3 * ONE / 2, tick, Rounding::Floor), 13);
+        assert_eq!(cooldown_ticks(1000 * ONE, 3 * ONE / 2, tick, Rounding::Ceil), 14);
+        // 125 / 50 = 2.5 ties
+        assert_eq!(cooldown_ticks(125 * ONE, ONE, tick, Rounding::HalfUp), 3);
+        assert_eq!(cooldown_ticks(125 * ONE, ONE, tick, Rounding::HalfEven), 2);
+        // At least one tick, and a non-positive cooldown is always ready
+        assert_eq!(cooldown_ticks(1, i32::MAX, tick, Rounding::Floor), 1);
+        assert_eq!(cooldown_ticks(0, ONE, tick, Rounding::Ceil), 0);
+        assert_eq!(cooldown_ticks(i32::MAX, 1, 1, Rounding::Floor), u32::MAX);
+    }
+
+    #[test]
+    fn attack_interval_ticks_rounds_the_exact_ratio() {
+        // 1000 / (2 * 1.0 * 50) = 10
+        assert_eq!(attack_interval_ticks(2 * ONE, ONE, 50 * ONE, Rounding::Floor), 10);
+        // 1000 / (3 * 1.1 * (1000 / 60)) = 18.18
+        let tick = 1000 * ONE / 60;
+        assert_eq!(attack_interval_ticks(3 * ONE, ONE + ONE / 10, tick, Rounding::HalfUp), 18);
+        assert_eq!(attack_interval_ticks(3 * ONE, ONE + ONE / 10, tick, Rounding::Ceil), 19);
+        assert_eq!(attack_interval_ticks(i32::MAX, i32::MAX, i32::MAX, Rounding::Floor), 1);
+    }
+
+    #[test]
+    fn bad_rates_raise_domain() {
+        flags::clear_flags();
+        assert_eq!(cooldown_ticks(ONE, 0, ONE, Rounding::Floor), 0);
+        assert_eq!(cooldown_ticks(ONE, ONE, -ONE, Rounding::Floor), 0);
+        assert_eq!(attack_interval_ticks(0, ONE, ONE, Rounding::Floor), 0);
+        assert_eq!(attack_interval_ticks(ONE, -1, ONE, Rounding::Floor), 0);
+        assert_eq!(flags::get_flags(), StatusFlag::Domain as u32);
+    }
+
+    #[test]
+    fn ticks_until_ready_counts_down() {
+        assert_eq!(ticks_until_ready(100, 16, 100), 16);
+        assert_eq!(ticks_until_ready(100, 16, 115), 1);
+        assert_eq!(ticks_until_ready(100, 16, 116), 0);
+        assert_eq!(ticks_until_ready(100, 16, 50), 66);
+        // No wrap near the end of the tick range
+        assert_eq!(ticks_until_ready(u32::MAX - 1, 10, u32::MAX), 9);
+        assert_eq!(ticks_until_ready(u32::MAX, u32::MAX, 0), u32::MAX);
+    }
+}
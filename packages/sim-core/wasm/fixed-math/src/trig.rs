@@ -0,0 +1,374 @@
+//! Trigonometry for Q16.16 angles
+//!
+//! Angles are Q16.16 radians. Internally everything runs in Q2.30 on
+//! 64-bit integers using CORDIC, so results are bit-identical on every
+//! platform (no floating point, no platform libm).
+
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+use crate::flags::{self, StatusFlag};
+use crate::{q16_to_q30, q30_to_q16, Q30_FRAC_BITS};
+
+/// 1.0 in Q16.16
+const ONE_Q16: i32 = 1 << 16;
+
+/// π/2 in Q2.30
+pub(crate) const HALF_PI_Q30: i64 = 1_686_629_713;
+/// π in Q2.30
+pub(crate) const PI_Q30: i64 = 3_373_259_426;
+/// 2π in Q2.30
+pub(crate) const TAU_Q30: i64 = 6_746_518_852;
+
+/// CORDIC gain compensation 1/K = Π cos(atan(2^-i)) in Q2.30
+const CORDIC_GAIN_Q30: i64 = 652_032_874;
+
+/// Number of CORDIC iterations (one per fractional bit of the table)
+const CORDIC_ITERATIONS: usize = 30;
+
+/// atan(2^-i) in Q2.30 for i = 0..30
+pub(crate) const ATAN_TABLE_Q30: [i64; CORDIC_ITERATIONS] = [
+    843_314_857, 497_837_829, 263_043_837, 133_525_159, 67_021_687,
+    33_543_516, 16_775_851, 8_388_437, 4_194_283, 2_097_149,
+    1_048_576, 524_288, 262_144, 131_072, 65_536,
+    32_768, 16_384, 8_192, 4_096, 2_048,
+    1_024, 512, 256, 128, 64,
+    32, 16, 8, 4, 2,
+];
+
+/// CORDIC rotation mode
+///
+/// Input: angle z in Q2.30, must lie in [-π/2, π/2]
+/// Output: (cos z, sin z) in Q2.30
+pub(crate) fn cordic_rotate(mut z: i64) -> (i64, i64) {
+    // Start with the gain pre-applied so no final multiply is needed
+    let mut x = CORDIC_GAIN_Q30;
+    let mut y = 0i64;
+
+    for (i, &atan) in ATAN_TABLE_Q30.iter().enumerate() {
+        let dx = y >> i;
+        let dy = x >> i;
+        if z >= 0 {
+            x -= dx;
+            y += dy;
+            z -= atan;
+        } else {
+            x += dx;
+            y -= dy;
+            z += atan;
+        }
+    }
+
+    (x, y)
+}
+
+/// Reduce a Q16.16 angle and compute (cos, sin) in Q2.30
+///
+/// The angle is wrapped into (-π, π] and then folded into [-π/2, π/2]
+/// using cos(π - a) = -cos(a), sin(π - a) = sin(a).
+pub(crate) fn sincos_q30(angle: i32) -> (i64, i64) {
+    let mut r = q16_to_q30(angle).rem_euclid(TAU_Q30);
+    if r > PI_Q30 {
+        r -= TAU_Q30;
+    }
+
+    if r > HALF_PI_Q30 {
+        let (c, s) = cordic_rotate(PI_Q30 - r);
+        (-c, s)
+    } else if r < -HALF_PI_Q30 {
+        let (c, s) = cordic_rotate(-PI_Q30 - r);
+        (-c, s)
+    } else {
+        cordic_rotate(r)
+    }
+}
+
+/// Sine of a Q16.16 angle in radians
+///
+/// Result is Q16.16 in [-1.0, 1.0] (i.e. [-65536, 65536])
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn sin(angle: i32) -> i32 {
+    let (_, s) = sincos_q30(angle);
+    q30_to_q16(s)
+}
+
+/// Cosine of a Q16.16 angle in radians
+///
+/// Result is Q16.16 in [-1.0, 1.0] (i.e. [-65536, 65536])
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn cos(angle: i32) -> i32 {
+    let (c, _) = sincos_q30(angle);
+    q30_to_q16(c)
+}
+
+/// Result of `sincos`, both Q16.16
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SinCos {
+    pub sin: i32,
+    pub cos: i32,
+}
+
+/// Sine and cosine of a Q16.16 angle in one call
+///
+/// Bit-identical to calling `sin` and `cos` separately, but only runs the
+/// CORDIC loop once and crosses the wasm boundary once.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn sincos(angle: i32) -> SinCos {
+    let (c, s) = sincos_q30(angle);
+    SinCos {
+        sin: q30_to_q16(s),
+        cos: q30_to_q16(c),
+    }
+}
+
+/// CORDIC vectoring mode
+///
+/// Input: vector (x, y) with x >= 0, y >= 0, both already scaled up
+/// Output: atan(y / x) in Q2.30
+pub(crate) fn cordic_vector(mut x: i64, mut y: i64) -> i64 {
+    let mut z = 0i64;
+
+    for (i, &atan) in ATAN_TABLE_Q30.iter().enumerate() {
+        let dx = y >> i;
+        let dy = x >> i;
+        if y > 0 {
+            x += dx;
+            y -= dy;
+            z += atan;
+        } else {
+            x -= dx;
+            y += dy;
+            z -= atan;
+        }
+    }
+
+    z
+}
+
+/// atan2 magnitude for the upper half-plane (y >= 0) in Q2.30
+///
+/// The vector is folded into the first octant (0 <= b <= a) so CORDIC
+/// only ever sees angles in [0, π/4], then unfolded with exact Q2.30
+/// identities: atan(a/b) = π/2 - atan(b/a), atan2(y, -x) = π - atan2(y, x).
+pub(crate) fn atan2_upper_q30(y: i64, x: i64) -> i64 {
+    let ax = x.abs();
+    let ay = y.abs();
+    let swapped = ay > ax;
+    let (a, b) = if swapped { (ay, ax) } else { (ax, ay) };
+
+    // Scale up so small inputs keep precision through the shifts.
+    // |input| <= 2^31, so 2^59 * sqrt(2) * CORDIC gain still fits in i64.
+    let mut t = cordic_vector(a << 28, b << 28);
+    if swapped {
+        t = HALF_PI_Q30 - t;
+    }
+    if x < 0 {
+        t = PI_Q30 - t;
+    }
+    t
+}
+
+/// Four-quadrant arctangent of y / x
+///
+/// Inputs are Q16.16 (any common scale works, atan2 is scale-invariant).
+/// Result is a Q16.16 angle in radians in (-π, π].
+///
+/// Defined results:
+/// - atan2(0, 0) = 0
+/// - atan2(0, x > 0) = 0, atan2(0, x < 0) = π
+/// - atan2(y > 0, 0) = π/2, atan2(y < 0, 0) = -π/2
+///
+/// Symmetry: atan2(-y, x) == -atan2(y, x) exactly for y != 0 (the
+/// magnitude is rounded first, then the sign applied). Octant folding
+/// makes swapping |x| and |y| mirror the result around π/4 in Q2.30
+/// before the single final rounding.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn atan2(y: i32, x: i32) -> i32 {
+    if y == 0 {
+        return if x < 0 { q30_to_q16(PI_Q30) } else { 0 };
+    }
+
+    let magnitude = q30_to_q16(atan2_upper_q30(y as i64, x as i64));
+    if y < 0 {
+        -magnitude
+    } else {
+        magnitude
+    }
+}
+
+/// asin magnitude for |x| in [0, 1] (Q16.16) as a Q2.30 angle
+///
+/// asin(x) = atan2(x, sqrt(1 - x²)); the cosine leg is computed from
+/// the exact Q60 square 2^60 - x² so no precision is lost before CORDIC.
+fn asin_abs_q30(ax: i32) -> i64 {
+    if ax == 0 {
+        return 0;
+    }
+    if ax >= ONE_Q16 {
+        return HALF_PI_Q30;
+    }
+
+    let x = q16_to_q30(ax);
+    let one_q60 = 1u64 << (2 * Q30_FRAC_BITS);
+    let c = (one_q60 - (x * x) as u64).isqrt() as i64;
+    atan2_upper_q30(x, c)
+}
+
+/// |x| clamped to 1.0, raising `StatusFlag::Domain` if it had to clamp
+fn clamp_unit_abs(x: i32) -> i32 {
+    let ax = x.unsigned_abs();
+    if ax > ONE_Q16 as u32 {
+        flags::raise(StatusFlag::Domain);
+        return ONE_Q16;
+    }
+    ax as i32
+}
+
+/// Arcsine of a Q16.16 value
+///
+/// The input is clamped (saturated) to [-1.0, 1.0], so out-of-range
+/// values return ±π/2 (raising `StatusFlag::Domain`) instead of panicking. Result is a Q16.16 angle in
+/// [-π/2, π/2], rounded to nearest once from the Q2.30 CORDIC result.
+/// asin(-x) == -asin(x) exactly.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn asin(x: i32) -> i32 {
+    let ax = clamp_unit_abs(x);
+    let magnitude = q30_to_q16(asin_abs_q30(ax));
+    if x < 0 {
+        -magnitude
+    } else {
+        magnitude
+    }
+}
+
+/// Arccosine of a Q16.16 value
+///
+/// The input is clamped (saturated) to [-1.0, 1.0], raising
+/// `StatusFlag::Domain` when out of range. Result is a Q16.16
+/// angle in [0, π], computed as π/2 - asin(x) in Q2.30 and rounded to
+/// nearest once at the end.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn acos(x: i32) -> i32 {
+    let ax = clamp_unit_abs(x);
+    let magnitude = asin_abs_q30(ax);
+    let angle = if x < 0 {
+        HALF_PI_Q30 + magnitude
+    } else {
+        HALF_PI_Q30 - magnitude
+    };
+    q30_to_q16(angle)
+}
+
+/// Tangent of a Q16.16 angle in radians
+///
+/// Computed as sin/cos from the same Q2.30 CORDIC pass, rounded to
+/// nearest. Pole behavior: near ±π/2 (and their periodic repeats) the
+/// quotient exceeds the Q16.16 range and saturates to i32::MAX or
+/// i32::MIN following the sign of sin/cos, so approaching π/2 from below
+/// gives i32::MAX and from above gives i32::MIN. If cos rounds to exactly
+/// zero in Q2.30 the result is i32::MAX for sin > 0 and i32::MIN otherwise.
+/// Saturated results raise `StatusFlag::Overflow`.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn tan(angle: i32) -> i32 {
+    let (c, s) = sincos_q30(angle);
+    if c == 0 {
+        flags::raise(StatusFlag::Overflow);
+        return if s > 0 { i32::MAX } else { i32::MIN };
+    }
+
+    // (s / c) in Q16.17, then round half up into Q16.16
+    let q = (s << 17) / c;
+    crate::saturate_i64((q + 1) >> 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::flags::{clear_flags, get_flags};
+
+    const PI: f64 = core::f64::consts::PI;
+
+    fn fp(x: f64) -> i32 {
+        (x * 65536.0).round() as i32
+    }
+
+    fn lsb_error(got: i32, want: f64) -> f64 {
+        (got as f64 - want * 65536.0).abs()
+    }
+
+    #[test]
+    fn sin_cos_match_f64() {
+        for i in -4000..=4000 {
+            let a = fp(i as f64 * 0.01 * PI);
+            let x = a as f64 / 65536.0;
+            assert!(lsb_error(sin(a), x.sin()) <= 1.0, "sin({x})");
+            assert!(lsb_error(cos(a), x.cos()) <= 1.0, "cos({x})");
+        }
+    }
+
+    #[test]
+    fn sin_cos_exact_points() {
+        assert_eq!(sin(0), 0);
+        assert_eq!(cos(0), ONE_Q16);
+        assert_eq!(sin(fp(PI / 2.0)), ONE_Q16);
+        assert_eq!(cos(fp(PI)), -ONE_Q16);
+    }
+
+    #[test]
+    fn sin_is_odd_and_cos_even() {
+        for a in (-400_000..400_000).step_by(997) {
+            assert_eq!(sin(-a), -sin(a));
+            assert_eq!(cos(-a), cos(a));
+        }
+    }
+
+    #[test]
+    fn sincos_matches_separate_calls() {
+        for a in (i32::MIN..i32::MAX).step_by(7_654_321) {
+            assert_eq!(sincos(a), SinCos { sin: sin(a), cos: cos(a) });
+        }
+    }
+
+    #[test]
+    fn atan2_quadrants_and_axes() {
+        assert_eq!(atan2(0, 0), 0);
+        assert_eq!(atan2(0, ONE_Q16), 0);
+        assert_eq!(atan2(0, -ONE_Q16), fp(PI));
+        assert_eq!(atan2(ONE_Q16, 0), fp(PI / 2.0));
+        assert_eq!(atan2(-ONE_Q16, 0), -fp(PI / 2.0));
+        assert_eq!(atan2(ONE_Q16, ONE_Q16), fp(PI / 4.0));
+        for (y, x) in [(3, 4), (-5, 2), (7, -9), (-1, -1)] {
+            let (y, x) = (y * ONE_Q16, x * ONE_Q16);
+            assert_eq!(atan2(-y, x), -atan2(y, x));
+            let want = (y as f64).atan2(x as f64);
+            assert!(lsb_error(atan2(y, x), want) <= 1.0);
+        }
+    }
+
+    #[test]
+    fn asin_acos_clamp_out_of_range() {
+        assert_eq!(asin(ONE_Q16), fp(PI / 2.0));
+        assert_eq!(acos(ONE_Q16), 0);
+        assert_eq!(acos(-ONE_Q16), fp(PI));
+        for x in (-ONE_Q16..=ONE_Q16).step_by(101) {
+            assert_eq!(asin(-x), -asin(x));
+            assert!(lsb_error(asin(x), (x as f64 / 65536.0).asin()) <= 1.0);
+        }
+
+        clear_flags();
+        assert_eq!(asin(2 * ONE_Q16), fp(PI / 2.0));
+        assert_eq!(get_flags(), StatusFlag::Domain as u32);
+    }
+
+    #[test]
+    fn tan_saturates_at_poles() {
+        assert_eq!(tan(0), 0);
+        assert_eq!(tan(fp(PI / 4.0)), ONE_Q16);
+
+        clear_flags();
+        assert_eq!(tan(fp(PI / 2.0) - 1), i32::MAX);
+        assert_eq!(tan(fp(PI / 2.0) + 1), i32::MIN);
+        assert_eq!(get_flags(), StatusFlag::Overflow as u32);
+    }
+}
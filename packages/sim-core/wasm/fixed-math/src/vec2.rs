@@ -0,0 +1,172 @@
+//! 2D vector type for Q16.16
+//!
+//! `FixedVec2` bundles an (x, y) pair so entity math crosses the JS
+//! boundary as one object. Every method is built from the crate's scalar
+//! ops (same truncation and flag behavior); methods take other vectors
+//! by reference so JS keeps ownership of its arguments.
+
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+use crate::trig::sincos_q30;
+use crate::{truncate_i128, truncate_i64, Q30_FRAC_BITS};
+
+/// 2D vector with Q16.16 components
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct FixedVec2 {
+    pub x: i32,
+    pub y: i32,
+}
+
+/// Result of `FixedVec2::normalize_with_length`: the unit vector (x, y)
+/// and the length it was divided by, all Q16.16
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct NormalizedVec2 {
+    pub x: i32,
+    pub y: i32,
+    pub length: i32,
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl FixedVec2 {
+    #[cfg_attr(feature = "wasm", wasm_bindgen(constructor))]
+    pub fn new(x: i32, y: i32) -> FixedVec2 {
+        FixedVec2 { x, y }
+    }
+
+    /// The zero vector
+    pub fn zero() -> FixedVec2 {
+        FixedVec2 { x: 0, y: 0 }
+    }
+
+    /// Component-wise sum, wrapping like `FP.add`
+    pub fn add(&self, other: &FixedVec2) -> FixedVec2 {
+        FixedVec2 {
+            x: self.x.wrapping_add(other.x),
+            y: self.y.wrapping_add(other.y),
+        }
+    }
+
+    /// Component-wise difference, wrapping like `FP.sub`
+    pub fn sub(&self, other: &FixedVec2) -> FixedVec2 {
+        FixedVec2 {
+            x: self.x.wrapping_sub(other.x),
+            y: self.y.wrapping_sub(other.y),
+        }
+    }
+
+    /// Multiply both components by a Q16.16 scalar (`mul` per component)
+    pub fn scale(&self, s: i32) -> FixedVec2 {
+        FixedVec2 {
+            x: crate::mul(self.x, s),
+            y: crate::mul(self.y, s),
+        }
+    }
+
+    /// Dot product, with the sum of products kept in 128-bit (two
+    /// i32::MIN squares overflow i64) and truncated once
+    pub fn dot(&self, other: &FixedVec2) -> i32 {
+        let sum = self.x as i128 * other.x as i128 + self.y as i128 * other.y as i128;
+        truncate_i128(sum >> 16)
+    }
+
+    /// Euclidean length, same as `hypot(x, y)`
+    pub fn length(&self) -> i32 {
+        crate::hypot(self.x, self.y)
+    }
+
+    /// Unit vector in the same direction (`div` per component by the
+    /// length); the zero vector stays zero
+    pub fn normalize(&self) -> FixedVec2 {
+        let n = self.normalize_with_length();
+        FixedVec2 { x: n.x, y: n.y }
+    }
+
+    /// `normalize` and `length` in one call, computing the length once
+    ///
+    /// The zero vector gives a zero direction and length 0.
+    pub fn normalize_with_length(&self) -> NormalizedVec2 {
+        let length = self.length();
+        if length == 0 {
+            return NormalizedVec2 { x: 0, y: 0, length };
+        }
+        NormalizedVec2 {
+            x: crate::div(self.x, length),
+            y: crate::div(self.y, length),
+            length,
+        }
+    }
+
+    /// Rotate counter-clockwise by a Q16.16 angle in radians
+    ///
+    /// Uses the Q2.30 CORDIC sine and cosine from `trig`; each component
+    /// is summed in 64-bit and rounded to nearest once.
+    pub fn rotate(&self, angle: i32) -> FixedVec2 {
+        let (c, s) = sincos_q30(angle);
+        let (x, y) = (self.x as i64, self.y as i64);
+        let half = 1i64 << (Q30_FRAC_BITS - 1);
+        FixedVec2 {
+            x: truncate_i64((x * c - y * s + half) >> Q30_FRAC_BITS),
+            y: truncate_i64((x * s + y * c + half) >> Q30_FRAC_BITS),
+        }
+    }
+
+    /// Distance to another point, same as the free `distance`
+    pub fn distance(&self, other: &FixedVec2) -> i32 {
+        crate::distance(self.x, self.y, other.x, other.y)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::consts::{HALF_PI, PI};
+
+    const ONE: i32 = 1 << 16;
+
+    fn v(x: i32, y: i32) -> FixedVec2 {
+        FixedVec2::new(x * ONE, y * ONE)
+    }
+
+    #[test]
+    fn arithmetic() {
+        assert_eq!(v(1, 2).add(&v(3, -5)), v(4, -3));
+        assert_eq!(v(1, 2).sub(&v(3, -5)), v(-2, 7));
+        assert_eq!(v(2, -4).scale(ONE / 2), v(1, -2));
+        assert_eq!(v(1, 2).dot(&v(3, -5)), -7 * ONE);
+        assert_eq!(FixedVec2::new(i32::MAX, 0).add(&FixedVec2::new(1, 0)).x, i32::MIN);
+    }
+
+    #[test]
+    fn dot_does_not_overflow_i64() {
+        // 2 * 2^31 raw, i.e. 2^32 >> 16
+        let m = FixedVec2::new(i32::MIN, i32::MIN);
+        assert_eq!(m.dot(&FixedVec2::new(-1, -1)), ONE);
+    }
+
+    #[test]
+    fn length_and_normalize() {
+        assert_eq!(v(3, 4).length(), 5 * ONE);
+        assert_eq!(v(3, 4).normalize(), FixedVec2::new(3 * ONE / 5, 4 * ONE / 5));
+        let n = v(-6, 8).normalize_with_length();
+        assert_eq!(n, NormalizedVec2 { x: -3 * ONE / 5, y: 4 * ONE / 5, length: 10 * ONE });
+        assert_eq!(FixedVec2::zero().normalize(), FixedVec2::zero());
+        assert_eq!(v(1, 1).distance(&v(4, 5)), 5 * ONE);
+    }
+
+    #[test]
+    fn rotate_quarter_and_half_turns() {
+        // HALF_PI and PI are rounded, so turns land within an LSB
+        let close = |a: FixedVec2, b: FixedVec2| (a.x - b.x).abs() <= 1 && (a.y - b.y).abs() <= 1;
+        assert!(close(v(1, 0).rotate(HALF_PI), v(0, 1)));
+        assert!(close(v(2, 3).rotate(PI), v(-2, -3)));
+        assert!(close(v(2, 3).rotate(-HALF_PI), v(3, -2)));
+        assert_eq!(v(2, 3).rotate(0), v(2, 3));
+        // Rotation preserves length
+        for angle in (-PI..PI).step_by(4099) {
+            assert!((v(5, 0).rotate(angle).length() - 5 * ONE).abs() <= 2);
+        }
+    }
+}
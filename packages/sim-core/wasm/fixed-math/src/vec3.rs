@@ -0,0 +1,116 @@
+//! 3D vector type for Q16.16
+//!
+//! `FixedVec3` is the height-aware companion to `FixedVec2`, with the
+//! same conventions: scalar-op semantics per component and arguments
+//! taken by reference.
+
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+use crate::truncate_i128;
+
+/// 3D vector with Q16.16 components
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct FixedVec3 {
+    pub x: i32,
+    pub y: i32,
+    pub z: i32,
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl FixedVec3 {
+    #[cfg_attr(feature = "wasm", wasm_bindgen(constructor))]
+    pub fn new(x: i32, y: i32, z: i32) -> FixedVec3 {
+        FixedVec3 { x, y, z }
+    }
+
+    /// The zero vector
+    pub fn zero() -> FixedVec3 {
+        FixedVec3 { x: 0, y: 0, z: 0 }
+    }
+
+    /// Dot product, with the sum of products kept in 128-bit and
+    /// truncated once
+    pub fn dot(&self, other: &FixedVec3) -> i32 {
+        let sum = self.x as i128 * other.x as i128
+            + self.y as i128 * other.y as i128
+            + self.z as i128 * other.z as i128;
+        truncate_i128(sum >> 16)
+    }
+
+    /// Cross product (right-handed); each component is a 128-bit
+    /// difference of products truncated once
+    pub fn cross(&self, other: &FixedVec3) -> FixedVec3 {
+        let (ax, ay, az) = (self.x as i128, self.y as i128, self.z as i128);
+        let (bx, by, bz) = (other.x as i128, other.y as i128, other.z as i128);
+        FixedVec3 {
+            x: truncate_i128((ay * bz - az * by) >> 16),
+            y: truncate_i128((az * bx - ax * bz) >> 16),
+            z: truncate_i128((ax * by - ay * bx) >> 16),
+        }
+    }
+
+    /// Euclidean length, same as `hypot3(x, y, z)`
+    pub fn length(&self) -> i32 {
+        crate::hypot3(self.x, self.y, self.z)
+    }
+
+    /// Unit vector in the same direction (`div` per component by the
+    /// length); the zero vector stays zero
+    pub fn normalize(&self) -> FixedVec3 {
+        let len = self.length();
+        if len == 0 {
+            return FixedVec3::zero();
+        }
+        FixedVec3 {
+            x: crate::div(self.x, len),
+            y: crate::div(self.y, len),
+            z: crate::div(self.z, len),
+        }
+    }
+
+    /// Linear interpolation toward `other`, `lerp` per component
+    pub fn lerp(&self, other: &FixedVec3, t: i32) -> FixedVec3 {
+        FixedVec3 {
+            x: crate::interp::lerp(self.x, other.x, t),
+            y: crate::interp::lerp(self.y, other.y, t),
+            z: crate::interp::lerp(self.z, other.z, t),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ONE: i32 = 1 << 16;
+
+    fn v(x: i32, y: i32, z: i32) -> FixedVec3 {
+        FixedVec3::new(x * ONE, y * ONE, z * ONE)
+    }
+
+    #[test]
+    fn dot_and_cross() {
+        assert_eq!(v(1, 2, 3).dot(&v(4, -5, 6)), 12 * ONE);
+        assert_eq!(v(1, 0, 0).cross(&v(0, 1, 0)), v(0, 0, 1));
+        assert_eq!(v(0, 1, 0).cross(&v(1, 0, 0)), v(0, 0, -1));
+        let (a, b) = (v(2, -3, 1), v(4, 1, -2));
+        let c = a.cross(&b);
+        assert_eq!(c.dot(&a), 0);
+        assert_eq!(c.dot(&b), 0);
+    }
+
+    #[test]
+    fn length_and_normalize() {
+        assert_eq!(v(2, 3, 6).length(), 7 * ONE);
+        assert_eq!(v(0, 0, -4).normalize(), v(0, 0, -1));
+        assert_eq!(FixedVec3::zero().normalize(), FixedVec3::zero());
+    }
+
+    #[test]
+    fn lerp_per_component() {
+        assert_eq!(v(0, 2, -4).lerp(&v(4, 2, 4), ONE / 4), v(1, 2, -2));
+        assert_eq!(v(0, 2, -4).lerp(&v(4, 2, 4), ONE), v(4, 2, 4));
+    }
+}
@@ -0,0 +1,174 @@
+//! Wave difficulty scaling
+//!
+//! Each wave's enemy HP multiplier, enemy count and speed multiplier come
+//! from one `WaveScaling` parameter set, evaluated with `powi` and 128-bit
+//! products instead of float exponentiation. Waves are indexed from 0,
+//! where both multipliers are exactly 1.0 (before any cap).
+
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+use crate::exp::powi;
+use crate::saturate_i128;
+
+/// 1.0 in Q16.16
+const ONE: i128 = 1 << 16;
+
+/// Wave scaling curves (all Q16.16 except the caps on count)
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct WaveScaling {
+    /// HP multiplier growth per wave (1.0 = flat)
+    pub hp_growth: i32,
+    /// Linear HP term: the multiplier is (1.0 + hp_linear * w) * hp_growth^w
+    pub hp_linear: i32,
+    /// Enemies in wave 0
+    pub count_base: i32,
+    /// Enemies added per wave
+    pub count_per_wave: i32,
+    /// Most enemies in one wave (0 = uncapped)
+    pub count_max: u32,
+    /// Speed multiplier growth per wave (1.0 = flat)
+    pub speed_growth: i32,
+    /// Highest speed multiplier (0 = uncapped)
+    pub speed_max: i32,
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl WaveScaling {
+    #[cfg_attr(feature = "wasm", wasm_bindgen(constructor))]
+    pub fn new(
+        hp_growth: i32,
+        hp_linear: i32,
+        count_base: i32,
+        count_per_wave: i32,
+        count_max: u32,
+        speed_growth: i32,
+        speed_max: i32,
+    ) -> WaveScaling {
+        WaveScaling { hp_growth, hp_linear, count_base, count_per_wave, count_max, speed_growth, speed_max }
+    }
+}
+
+/// Scaled values for one wave, returned by `wave_scaling`
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct WaveValues {
+    /// Enemy HP multiplier, Q16.16
+    pub hp: i32,
+    /// Whole number of enemies
+    pub count: u32,
+    /// Enemy speed multiplier, Q16.16
+    pub speed: i32,
+}
+
+/// Scaling for wave `wave` (from 0)
+///
+/// - hp = ((1.0 + hp_linear * w) * powi(hp_growth, w)) >> 16, saturating
+/// - count = floor(count_base + count_per_wave * w), at least 0 and at
+///   most count_max
+/// - speed = powi(speed_growth, w), at most speed_max
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn wave_scaling(params: &WaveScaling, wave: u32) -> WaveValues {
+    let w = wave.min(i32::MAX as u32) as i32;
+
+    let linear = ONE + params.hp_linear as i128 * w as i128;
+    let hp = saturate_i128((linear * powi(params.hp_growth, w) as i128) >> 16);
+
+    let enemies = (params.count_base as i128 + params.count_per_wave as i128 * w as i128) >> 16;
+    let mut count = enemies.clamp(0, u32::MAX as i128) as u32;
+    if params.count_max > 0 {
+        count = count.min(params.count_max);
+    }
+
+    let mut speed = powi(params.speed_growth, w);
+    if params.speed_max > 0 {
+        speed = speed.min(params.speed_max);
+    }
+
+    WaveValues { hp, count, speed }
+}
+
+/// `wave_scaling` for `waves` consecutive waves starting at first_wave
+///
+/// Returns `[hp, count, speed, ...]` per wave, each triple identical to
+/// the single-wave call.
+#[cfg(feature = "alloc")]
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn wave_schedule(params: &WaveScaling, first_wave: u32, waves: u32) -> Vec<i32> {
+    let mut out = Vec::with_capacity(waves as usize * 3);
+    for i in 0..waves {
+        let v = wave_scaling(params, first_wave.saturating_add(i));
+        out.extend([v.hp, v.count.min(i32::MAX as u32) as i32, v.speed]);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ONE: i32 = 1 << 16;
+
+    fn params() -> WaveScaling {
+        WaveScaling::new(ONE + ONE / 10, ONE / 20, 5 * ONE, 3 * ONE / 2, 40, ONE + ONE / 50, 2 * ONE)
+    }
+
+    #[test]
+    fn wave_zero_is_the_base() {
+        let v = wave_scaling(&params(), 0);
+        assert_eq!(v, WaveValues { hp: ONE, count: 5, speed: ONE });
+    }
+
+    #[test]
+    fn waves_follow_the_formulas() {
+        let p = params();
+        for w in 0..60u32 {
+            let v = wave_scaling(&p, w);
+            let linear = ONE as i128 + p.hp_linear as i128 * w as i128;
+            assert_eq!(v.hp as i128, (linear * powi(p.hp_growth, w as i32) as i128) >> 16, "wave {w}");
+            // 5 + 1.5w, floored and capped at 40
+            assert_eq!(v.count, ((10 + 3 * w) / 2).min(40), "wave {w}");
+            assert_eq!(v.speed, powi(p.speed_growth, w as i32).min(2 * ONE), "wave {w}");
+        }
+        assert_eq!(wave_scaling(&p, 3).count, 9);
+    }
+
+    #[test]
+    fn zero_caps_mean_uncapped_and_extremes_saturate() {
+        let p = WaveScaling::new(2 * ONE, 0, ONE, i32::MAX, 0, 3 * ONE, 0);
+        let v = wave_scaling(&p, 1_000_000);
+        assert_eq!(v.hp, i32::MAX);
+        assert_eq!(v.speed, i32::MAX);
+        assert_eq!(v.count, u32::MAX);
+        #[cfg(feature = "alloc")]
+        assert_eq!(wave_schedule(&p, 1_000_000, 1)[1], i32::MAX);
+
+        // Shrinking waves never go below zero enemies
+        let p = WaveScaling::new(ONE, -ONE, 2 * ONE, -ONE, 0, ONE / 2, 0);
+        let v = wave_scaling(&p, 5);
+        assert_eq!(v.count, 0);
+        assert_eq!(v.hp, -4 * ONE);
+        assert_eq!(v.speed, ONE / 32);
+        assert_eq!(wave_scaling(&p, u32::MAX).count, 0);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn schedule_matches_single_waves() {
+        let p = params();
+        let schedule = wave_schedule(&p, 7, 20);
+        assert_eq!(schedule.len(), 60);
+        for (i, triple) in schedule.chunks(3).enumerate() {
+            let v = wave_scaling(&p, 7 + i as u32);
+            assert_eq!(triple, [v.hp, v.count as i32, v.speed]);
+        }
+        assert!(wave_schedule(&p, 0, 0).is_empty());
+        // The wave index stops at u32::MAX instead of wrapping
+        let end = wave_schedule(&p, u32::MAX - 1, 3);
+        assert_eq!(end[6..], end[3..6]);
+    }
+}
@@ -0,0 +1,202 @@
+//! Weighted random selection
+//!
+//! `WeightedTable` is Vose's alias method built with exact integer
+//! arithmetic: every column holds an acceptance threshold out of the
+//! total weight, so each entry is picked with probability exactly
+//! weight / total, and a roll costs two bounded draws however many
+//! entries the table has.
+
+use alloc::{vec, vec::Vec};
+
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+use crate::flags::{self, StatusFlag};
+use crate::rng::FixedRng;
+
+/// Alias table over non-negative Q16.16 weights
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WeightedTable {
+    /// Sum of all weights (0 for an empty or all-zero table)
+    total: u64,
+    /// Per column: keep the column when a draw in [0, total) is below this
+    prob: Vec<u64>,
+    /// Per column: entry picked when the column is rejected
+    alias: Vec<u32>,
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl WeightedTable {
+    /// Build the table from per-entry weights
+    ///
+    /// Negative weights count as 0. Column i keeps i with threshold
+    /// w_i * n and hands the rest of its share to a heavier entry; the
+    /// small and large worklists are processed in index order, so the
+    /// layout depends only on the weights.
+    #[cfg_attr(feature = "wasm", wasm_bindgen(constructor))]
+    pub fn new(weights: &[i32]) -> WeightedTable {
+        let n = weights.len() as u64;
+        let total: u64 = weights.iter().map(|&w| w.max(0) as u64).sum();
+        let mut scaled: Vec<u64> = weights.iter().map(|&w| w.max(0) as u64 * n).collect();
+        let mut prob = vec![total; weights.len()];
+        let mut alias: Vec<u32> = (0..weights.len() as u32).collect();
+
+        let (mut small, mut large): (Vec<usize>, Vec<usize>) = (0..weights.len()).partition(|&i| scaled[i] < total);
+        small.reverse();
+        large.reverse();
+        while let (Some(&s), Some(&l)) = (small.last(), large.last()) {
+            small.pop();
+            prob[s] = scaled[s];
+            alias[s] = l as u32;
+            scaled[l] -= total - scaled[s];
+            if scaled[l] < total {
+                large.pop();
+                small.push(l);
+            }
+        }
+
+        WeightedTable { total, prob, alias }
+    }
+
+    /// Number of entries
+    pub fn len(&self) -> u32 {
+        self.prob.len() as u32
+    }
+
+    /// Whether the table has no entries (`roll` then returns -1)
+    pub fn is_empty(&self) -> bool {
+        self.prob.is_empty()
+    }
+
+    /// Draw one entry index
+    ///
+    /// Picks a column uniformly, then keeps it or takes its alias with a
+    /// second draw. Returns -1 without drawing when the table is empty
+    /// or every weight is 0.
+    pub fn roll(&self, rng: &mut FixedRng) -> i32 {
+        if self.prob.is_empty() || self.total == 0 {
+            return -1;
+        }
+        let column = rng.below(self.prob.len() as u32) as usize;
+        if rng.below_u64(self.total) < self.prob[column] {
+            column as i32
+        } else {
+            self.alias[column] as i32
+        }
+    }
+
+    /// Table as `[total, prob_0, alias_0, prob_1, alias_1, ...]`
+    pub fn get_state(&self) -> Vec<u64> {
+        let mut words = Vec::with_capacity(1 + 2 * self.prob.len());
+        words.push(self.total);
+        for (&p, &a) in self.prob.iter().zip(&self.alias) {
+            words.push(p);
+            words.push(a as u64);
+        }
+        words
+    }
+
+    /// Restore a table captured by `get_state`
+    ///
+    /// Malformed input (even length, an alias out of range, a threshold
+    /// above the total, or a non-zero total with no columns) leaves the
+    /// table unchanged and raises `StatusFlag::Domain`.
+    pub fn set_state(&mut self, words: &[u64]) {
+        let Some((&total, columns)) = words.split_first() else {
+            flags::raise(StatusFlag::Domain);
+            return;
+        };
+        let n = columns.len() as u64 / 2;
+        let valid = columns.len() % 2 == 0
+            && (n > 0 || total == 0)
+            && columns.chunks_exact(2).all(|c| c[0] <= total && c[1] < n);
+        if !valid {
+            flags::raise(StatusFlag::Domain);
+            return;
+        }
+        self.total = total;
+        self.prob = columns.iter().step_by(2).copied().collect();
+        self.alias = columns.iter().skip(1).step_by(2).map(|&a| a as u32).collect();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::flags::{clear_flags, get_flags};
+
+    /// Probability mass of each entry across all columns, out of n * total
+    fn mass(table: &WeightedTable) -> Vec<u64> {
+        let mut m = vec![0; table.prob.len()];
+        for (c, (&p, &a)) in table.prob.iter().zip(&table.alias).enumerate() {
+            m[c] += p;
+            m[a as usize] += table.total - p;
+        }
+        m
+    }
+
+    #[test]
+    fn columns_carry_exact_weights() {
+        for weights in [&[1, 2, 3, 4][..], &[65536, 1, 0, 99999, 7], &[5], &[3, 3, 3], &[0, 0, 10]] {
+            let table = WeightedTable::new(weights);
+            let n = weights.len() as u64;
+            let expected: Vec<u64> = weights.iter().map(|&w| w as u64 * n).collect();
+            assert_eq!(mass(&table), expected, "{weights:?}");
+        }
+        assert_eq!(mass(&WeightedTable::new(&[-5, 2])), [0, 4]);
+    }
+
+    #[test]
+    fn rolls_follow_the_weights() {
+        let table = WeightedTable::new(&[1, 0, 3, 6]);
+        let mut rng = FixedRng::new(4);
+        let mut counts = [0u32; 4];
+        for _ in 0..10_000 {
+            counts[table.roll(&mut rng) as usize] += 1;
+        }
+        assert_eq!(counts[1], 0);
+        assert!((850..1150).contains(&counts[0]));
+        assert!((2800..3200).contains(&counts[2]));
+        assert!((5700..6300).contains(&counts[3]));
+    }
+
+    #[test]
+    fn empty_and_zero_tables_roll_minus_one_without_drawing() {
+        let mut rng = FixedRng::new(4);
+        let before = rng.clone();
+        let empty = WeightedTable::new(&[]);
+        assert!(empty.is_empty());
+        assert_eq!(empty.roll(&mut rng), -1);
+        let zero = WeightedTable::new(&[0, -1]);
+        assert_eq!(zero.len(), 2);
+        assert_eq!(zero.roll(&mut rng), -1);
+        assert_eq!(rng, before);
+    }
+
+    #[test]
+    fn state_round_trips() {
+        let table = WeightedTable::new(&[4, 1, 9]);
+        let mut restored = WeightedTable::new(&[]);
+        restored.set_state(&table.get_state());
+        assert_eq!(restored, table);
+        let (mut a, mut b) = (FixedRng::new(2), FixedRng::new(2));
+        for _ in 0..50 {
+            assert_eq!(table.roll(&mut a), restored.roll(&mut b));
+        }
+        restored.set_state(&[0]);
+        assert!(restored.is_empty());
+    }
+
+    #[test]
+    fn set_state_rejects_malformed_input() {
+        let mut table = WeightedTable::new(&[1, 2]);
+        let before = table.clone();
+        for words in [&[][..], &[6, 3], &[6, 7, 0, 3, 1], &[6, 3, 2, 3, 0], &[6]] {
+            clear_flags();
+            table.set_state(words);
+            assert_eq!(table, before, "{words:?}");
+            assert_eq!(get_flags(), StatusFlag::Domain as u32);
+        }
+    }
+}
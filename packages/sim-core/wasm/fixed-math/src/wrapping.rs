@@ -0,0 +1,85 @@
+//! Wrapping arithmetic for Q16.16
+//!
+//! Explicit two's-complement wraparound: the 64-bit intermediate keeps
+//! its low 32 bits, exactly like the `| 0` truncation on the JS side.
+//! Use these where wraparound is intended (hash-style mixing of fixed
+//! values) so the intent is visible at the call site.
+
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+use crate::div_by_zero;
+
+/// Wrapping addition: (a + b) mod 2^32
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn wrapping_add(a: i32, b: i32) -> i32 {
+    a.wrapping_add(b)
+}
+
+/// Wrapping subtraction: (a - b) mod 2^32
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn wrapping_sub(a: i32, b: i32) -> i32 {
+    a.wrapping_sub(b)
+}
+
+/// Wrapping negation: wrapping_neg(i32::MIN) = i32::MIN
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn wrapping_neg(a: i32) -> i32 {
+    a.wrapping_neg()
+}
+
+/// Wrapping multiply of two fixed-point numbers (Q16.16)
+///
+/// Formula: ((a * b) >> 16) mod 2^32
+/// Bit-identical to `mul`.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn wrapping_mul(a: i32, b: i32) -> i32 {
+    ((a as i64 * b as i64) >> 16) as i32
+}
+
+/// Wrapping divide of two fixed-point numbers (Q16.16)
+///
+/// Formula: ((a << 16) / b) mod 2^32
+/// Bit-identical to `div`; division by zero is not an overflow and
+/// follows the same policy as `div`.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn wrapping_div(a: i32, b: i32) -> i32 {
+    if b == 0 {
+        return div_by_zero(a < 0);
+    }
+    (((a as i64) << 16) / b as i64) as i32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::flags::{clear_flags, get_flags, StatusFlag};
+
+    const ONE: i32 = 1 << 16;
+
+    #[test]
+    fn wraps_without_raising_flags() {
+        clear_flags();
+        assert_eq!(wrapping_add(i32::MAX, 1), i32::MIN);
+        assert_eq!(wrapping_sub(i32::MIN, 1), i32::MAX);
+        assert_eq!(wrapping_neg(i32::MIN), i32::MIN);
+        assert_eq!(wrapping_mul(i32::MAX, 2 * ONE), -2);
+        assert_eq!(wrapping_div(i32::MIN, -ONE), i32::MIN);
+        assert_eq!(get_flags(), 0);
+    }
+
+    #[test]
+    fn matches_plain_mul_and_div() {
+        for (a, b) in [(3 * ONE, ONE / 2), (-7, 12_345), (i32::MAX, 3 * ONE), (i32::MIN, -5 * ONE)] {
+            assert_eq!(wrapping_mul(a, b), crate::mul(a, b));
+            assert_eq!(wrapping_div(a, b), crate::div(a, b));
+        }
+    }
+
+    #[test]
+    fn divide_by_zero_follows_the_policy() {
+        clear_flags();
+        assert_eq!(wrapping_div(ONE, 0), i32::MAX);
+        assert_eq!(get_flags(), StatusFlag::DivByZero as u32);
+    }
+}